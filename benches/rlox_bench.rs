@@ -3,7 +3,7 @@ extern crate parcel;
 extern crate rlox;
 use rlox::ast::token::Token;
 use rlox::parser::expression_parser::expression;
-use rlox::parser::statement_parser::statements;
+use rlox::parser::{parse, Recovery};
 use rlox::scanner::Scanner;
 
 use parcel::prelude::v1::*;
@@ -29,7 +29,7 @@ fn parse_expr_benchmark(c: &mut Criterion) {
 
     c.bench_function("parse expressions", |b| {
         b.iter(|| {
-            let _expr = expression().parse(&tokens);
+            let _expr = expression(&Recovery::default()).parse(&tokens);
         })
     });
 }
@@ -46,7 +46,7 @@ fn parse_statement_benchmark(c: &mut Criterion) {
 
     c.bench_function("parse statement", |b| {
         b.iter(|| {
-            let _expr = statements().parse(&tokens);
+            let _stmts = parse(&tokens);
         })
     });
 }
@@ -0,0 +1,546 @@
+use crate::ast::expression::{
+    AdditionExpr, ComparisonExpr, EqualityExpr, Expr, LogicalExpr, MultiplicationExpr, UnaryExpr,
+};
+use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::diagnostic::Diagnostic;
+use crate::object::{Literal, Object};
+use crate::pass::*;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// Rule identifies a check performed by the Linter. Each rule has a stable
+/// id, reported as the code of its warnings, and a name, and may be
+/// suppressed by either.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Rule {
+    UnusedVariable,
+    Shadowing,
+    UnreachableCode,
+    SelfInitializer,
+    AssignToDeclaration,
+    ConstantCondition,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Rule::UnusedVariable,
+        Rule::Shadowing,
+        Rule::UnreachableCode,
+        Rule::SelfInitializer,
+        Rule::AssignToDeclaration,
+        Rule::ConstantCondition,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::UnusedVariable => "W0001",
+            Self::Shadowing => "W0002",
+            Self::UnreachableCode => "W0003",
+            Self::SelfInitializer => "W0004",
+            Self::AssignToDeclaration => "W0005",
+            Self::ConstantCondition => "W0006",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UnusedVariable => "unused-variable",
+            Self::Shadowing => "shadowing",
+            Self::UnreachableCode => "unreachable-code",
+            Self::SelfInitializer => "self-initializer",
+            Self::AssignToDeclaration => "assign-to-declaration",
+            Self::ConstantCondition => "constant-condition",
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// from_str parses a rule from either its id or its name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|rule| rule.id() == s || rule.name() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown lint rule '{}'", s))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// LintWarning reports a rule broken by a program, pointing at the span of
+/// source that broke it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LintWarning {
+    pub rule: Rule,
+    pub message: String,
+    pub span: Span,
+}
+
+impl LintWarning {
+    pub fn new(rule: Rule, message: &str, span: Span) -> Self {
+        LintWarning {
+            rule,
+            message: message.to_string(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<LintWarning> for Diagnostic {
+    fn from(warning: LintWarning) -> Self {
+        Diagnostic::warning(warning.rule.id(), &warning.message, warning.span)
+    }
+}
+
+/// Kind tracks what declared a name, determining the rules that apply to
+/// it.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Kind {
+    Variable,
+    Parameter,
+    Function,
+    Class,
+    Binding,
+}
+
+/// Local is a name declared within a scope. A local is uninitialized while
+/// its own initializer is being linted.
+#[derive(Debug)]
+struct Local {
+    name: String,
+    kind: Kind,
+    span: Span,
+    used: bool,
+    initialized: bool,
+}
+
+/// Linter checks a parsed program for likely mistakes that are nonetheless
+/// valid lox, returning a warning for each broken rule that hasn't been
+/// allowed. The linter tracks scopes by name itself, and so runs on the AST
+/// as parsed, before it is resolved by the ScopeAnalyzer.
+pub struct Linter {
+    allowed: Vec<Rule>,
+    scopes: Vec<Vec<Local>>,
+    warnings: Vec<LintWarning>,
+}
+
+impl Linter {
+    pub fn new() -> Linter {
+        Self::default()
+    }
+
+    /// allow suppresses the warnings of a rule.
+    pub fn allow(mut self, rule: Rule) -> Self {
+        self.allowed.push(rule);
+        self
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            allowed: Vec::new(),
+            scopes: vec![Vec::new()],
+            warnings: Vec::new(),
+        }
+    }
+}
+
+type LintResult = Result<(), Infallible>;
+
+impl PassMut<Vec<Stmt>, Vec<LintWarning>> for Linter {
+    type Error = Infallible;
+
+    fn tree_pass(&mut self, input: Vec<Stmt>) -> Result<Vec<LintWarning>, Infallible> {
+        self.scopes = vec![Vec::new()];
+        self.lint_stmts(input)?;
+
+        let mut warnings = std::mem::take(&mut self.warnings);
+        warnings.sort_by_key(|warning| warning.span.start);
+        Ok(warnings)
+    }
+}
+
+impl PassMut<Stmt, ()> for Linter {
+    type Error = Infallible;
+
+    fn tree_pass(&mut self, input: Stmt) -> LintResult {
+        match input {
+            Stmt::Expression(expr, _) | Stmt::Print(expr, _) | Stmt::Throw(expr, _) => {
+                self.tree_pass(expr)
+            }
+            Stmt::If(cond, tb, eb, _) => {
+                self.lint_condition(cond)?;
+                self.tree_pass(tb)?;
+                match eb {
+                    Some(branch) => self.tree_pass(branch),
+                    None => Ok(()),
+                }
+            }
+            Stmt::While(cond, body, inc, _) => {
+                // `while (true)`, as `for (;;)` desugars into, is the idiomatic
+                // infinite loop.
+                match cond {
+                    Expr::Primary(Object::Literal(Literal::Bool(true)), _) => (),
+                    cond => self.lint_condition(cond)?,
+                }
+                self.tree_pass(body)?;
+                match inc {
+                    Some(inc) => self.tree_pass(inc),
+                    None => Ok(()),
+                }
+            }
+            Stmt::ForIn(id, iterable, body, span) => {
+                self.tree_pass(iterable)?;
+                self.scopes.push(Vec::new());
+                self.declare(id, Kind::Binding, span);
+                self.tree_pass(body)?;
+                self.end_scope();
+                Ok(())
+            }
+            Stmt::Function(id, _, params, body, span) => {
+                self.declare(id, Kind::Function, span);
+                self.lint_function(params, *body, span)
+            }
+            Stmt::Declaration(id, expr, span) => self.lint_declaration(id, expr, span),
+            Stmt::Return(Some(expr), _) => self.tree_pass(expr),
+            Stmt::Return(None, _) | Stmt::Break(_) | Stmt::Continue(_) => Ok(()),
+            Stmt::Class(id, _, superclass, methods, span) => {
+                if let Some(superclass) = superclass {
+                    self.tree_pass(superclass)?;
+                }
+                self.declare(id, Kind::Class, span);
+
+                // method names are looked up on an instance and aren't
+                // declared in any scope.
+                for method in methods {
+                    if let Stmt::Function(_, _, params, body, span) = method {
+                        self.lint_function(params, *body, span)?;
+                    }
+                }
+                Ok(())
+            }
+            Stmt::Block(stmts, _) => {
+                self.scopes.push(Vec::new());
+                self.lint_stmts(stmts)?;
+                self.end_scope();
+                Ok(())
+            }
+            Stmt::Import(_, id, span) => {
+                self.declare(id, Kind::Binding, span);
+                Ok(())
+            }
+            Stmt::Try(body, catch, finally, span) => {
+                self.tree_pass(body)?;
+                if let Some((id, handler)) = catch {
+                    self.scopes.push(Vec::new());
+                    self.declare(id, Kind::Binding, span);
+                    self.tree_pass(handler)?;
+                    self.end_scope();
+                }
+                match finally {
+                    Some(finally) => self.tree_pass(finally),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl PassMut<Box<Stmt>, ()> for Linter {
+    type Error = Infallible;
+
+    fn tree_pass(&mut self, input: Box<Stmt>) -> LintResult {
+        self.tree_pass(*input)
+    }
+}
+
+impl PassMut<Expr, ()> for Linter {
+    type Error = Infallible;
+
+    fn tree_pass(&mut self, input: Expr) -> LintResult {
+        match input {
+            Expr::Variable(Identifier::Name(name), span) => {
+                self.read(&name, span);
+                Ok(())
+            }
+            Expr::Assignment(id, value, span) => {
+                self.tree_pass(value)?;
+                if let Identifier::Name(name) = id {
+                    self.assign(&name, span);
+                }
+                Ok(())
+            }
+            Expr::Lambda(params, body, span) => self.lint_function(params, *body, span),
+            Expr::Grouping(expr, _) | Expr::Get(expr, _, _) => self.tree_pass(expr),
+            Expr::Set(instance, _, value, _) => {
+                self.tree_pass(value)?;
+                self.tree_pass(instance)
+            }
+            Expr::Call(callee, args, _) => {
+                self.tree_pass(callee)?;
+                args.into_iter().try_for_each(|arg| self.tree_pass(arg))
+            }
+            Expr::List(elems, _) => elems.into_iter().try_for_each(|elem| self.tree_pass(elem)),
+            Expr::Map(entries, _) => entries.into_iter().try_for_each(|(k, v)| {
+                self.tree_pass(k)?;
+                self.tree_pass(v)
+            }),
+            Expr::Index(list, index, _) => {
+                self.tree_pass(list)?;
+                self.tree_pass(index)
+            }
+            Expr::SetIndex(list, index, value, _) => {
+                self.tree_pass(value)?;
+                self.tree_pass(list)?;
+                self.tree_pass(index)
+            }
+            Expr::Unary(UnaryExpr::Bang(expr), _) | Expr::Unary(UnaryExpr::Minus(expr), _) => {
+                self.tree_pass(expr)
+            }
+            Expr::Logical(LogicalExpr::Or(left, right), _)
+            | Expr::Logical(LogicalExpr::And(left, right), _)
+            | Expr::Equality(EqualityExpr::Equal(left, right), _)
+            | Expr::Equality(EqualityExpr::NotEqual(left, right), _)
+            | Expr::Comparison(ComparisonExpr::Less(left, right), _)
+            | Expr::Comparison(ComparisonExpr::LessEqual(left, right), _)
+            | Expr::Comparison(ComparisonExpr::Greater(left, right), _)
+            | Expr::Comparison(ComparisonExpr::GreaterEqual(left, right), _)
+            | Expr::Addition(AdditionExpr::Add(left, right), _)
+            | Expr::Addition(AdditionExpr::Subtract(left, right), _)
+            | Expr::Multiplication(MultiplicationExpr::Multiply(left, right), _)
+            | Expr::Multiplication(MultiplicationExpr::Divide(left, right), _) => {
+                self.tree_pass(left)?;
+                self.tree_pass(right)
+            }
+            Expr::Variable(_, _) | Expr::Primary(_, _) | Expr::This(_) | Expr::Super(_, _) => {
+                Ok(())
+            }
+        }
+    }
+}
+
+impl PassMut<Box<Expr>, ()> for Linter {
+    type Error = Infallible;
+
+    fn tree_pass(&mut self, input: Box<Expr>) -> LintResult {
+        self.tree_pass(*input)
+    }
+}
+
+impl Linter {
+    fn warn(&mut self, rule: Rule, message: &str, span: Span) {
+        if !self.allowed.contains(&rule) {
+            self.warnings.push(LintWarning::new(rule, message, span));
+        }
+    }
+
+    /// lint_stmts lints a sequence of statements, warning on the first
+    /// statement following one that always jumps away.
+    fn lint_stmts(&mut self, stmts: Vec<Stmt>) -> LintResult {
+        let mut reachable = true;
+        for stmt in stmts {
+            if !reachable {
+                self.warn(Rule::UnreachableCode, "unreachable statement", stmt.span());
+                reachable = true;
+            } else if terminates(&stmt) {
+                reachable = false;
+            }
+            self.tree_pass(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// lint_function lints a function's body within a scope declaring its
+    /// parameters.
+    fn lint_function(&mut self, params: Vec<Identifier>, body: Stmt, span: Span) -> LintResult {
+        self.scopes.push(Vec::new());
+        for param in params {
+            self.declare(param, Kind::Parameter, span);
+        }
+        self.tree_pass(body)?;
+        self.end_scope();
+        Ok(())
+    }
+
+    /// lint_declaration lints a variable's initializer. Outside the global
+    /// scope the variable is declared, uninitialized, beforehand, so that
+    /// reads of it from its own initializer can be caught. Such reads refer
+    /// to an enclosing variable, or to none at all.
+    fn lint_declaration(&mut self, id: Identifier, expr: Expr, span: Span) -> LintResult {
+        let redeclared = match &id {
+            Identifier::Name(name) => self.scope().iter().any(|local| local.name == *name),
+            _ => true,
+        };
+        if self.scopes.len() == 1 || redeclared {
+            self.tree_pass(expr)?;
+            self.declare(id, Kind::Variable, span);
+            return Ok(());
+        }
+
+        self.declare(id, Kind::Variable, span);
+        self.scope().last_mut().unwrap().initialized = false;
+        self.tree_pass(expr)?;
+        self.scope().last_mut().unwrap().initialized = true;
+        Ok(())
+    }
+
+    fn lint_condition(&mut self, cond: Expr) -> LintResult {
+        if is_constant(&cond) {
+            self.warn(
+                Rule::ConstantCondition,
+                "condition is constant",
+                cond.span(),
+            );
+        }
+        self.tree_pass(cond)
+    }
+
+    fn scope(&mut self) -> &mut Vec<Local> {
+        self.scopes.last_mut().unwrap()
+    }
+
+    /// declare adds a name to the innermost scope, warning if it shadows a
+    /// name declared by an enclosing scope. Redeclaring a name within the
+    /// same scope reuses its existing declaration.
+    fn declare(&mut self, id: Identifier, kind: Kind, span: Span) {
+        let name = match id {
+            Identifier::Name(name) => name,
+            _ => return,
+        };
+        if self.scope().iter().any(|local| local.name == name) {
+            return;
+        }
+
+        let (_, enclosing) = self.scopes.split_last().unwrap();
+        if enclosing.iter().flatten().any(|local| local.name == name) {
+            self.warn(
+                Rule::Shadowing,
+                &format!("'{}' shadows a declaration in an enclosing scope", name),
+                span,
+            );
+        }
+
+        self.scope().push(Local {
+            name,
+            kind,
+            span,
+            used: false,
+            initialized: true,
+        });
+    }
+
+    /// end_scope leaves the innermost scope, warning on each variable and
+    /// parameter declared by it that was never read.
+    fn end_scope(&mut self) {
+        for local in self.scopes.pop().unwrap_or_default() {
+            let kind = match local.kind {
+                Kind::Variable => "variable",
+                Kind::Parameter => "parameter",
+                _ => continue,
+            };
+            if !local.used {
+                self.warn(
+                    Rule::UnusedVariable,
+                    &format!("unused {} '{}'", kind, local.name),
+                    local.span,
+                );
+            }
+        }
+    }
+
+    fn resolve(&mut self, name: &str) -> Option<&mut Local> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|local| local.name == name))
+    }
+
+    fn read(&mut self, name: &str, span: Span) {
+        let initialized = match self.resolve(name) {
+            Some(local) if local.initialized => {
+                local.used = true;
+                true
+            }
+            Some(_) => false,
+            None => true,
+        };
+
+        if !initialized {
+            self.warn(
+                Rule::SelfInitializer,
+                &format!("'{}' is read in its own initializer", name),
+                span,
+            );
+        }
+    }
+
+    fn assign(&mut self, name: &str, span: Span) {
+        let kind = match self.resolve(name).map(|local| local.kind) {
+            Some(Kind::Function) => "function",
+            Some(Kind::Class) => "class",
+            _ => return,
+        };
+
+        self.warn(
+            Rule::AssignToDeclaration,
+            &format!("assignment to {} '{}'", kind, name),
+            span,
+        );
+    }
+}
+
+/// terminates returns true if a statement always jumps away, leaving any
+/// statements following it unreachable.
+fn terminates(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Return(_, _) | Stmt::Break(_) | Stmt::Continue(_) | Stmt::Throw(_, _) => true,
+        Stmt::Block(stmts, _) => stmts.iter().any(terminates),
+        Stmt::If(_, tb, Some(eb), _) => terminates(tb) && terminates(eb),
+        _ => false,
+    }
+}
+
+/// is_constant returns true if an expression is built from literals alone,
+/// evaluating to the same value every time.
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Primary(Object::Literal(_), _) => true,
+        Expr::Grouping(expr, _)
+        | Expr::Unary(UnaryExpr::Bang(expr), _)
+        | Expr::Unary(UnaryExpr::Minus(expr), _) => is_constant(expr),
+        Expr::Logical(LogicalExpr::Or(left, right), _)
+        | Expr::Logical(LogicalExpr::And(left, right), _)
+        | Expr::Equality(EqualityExpr::Equal(left, right), _)
+        | Expr::Equality(EqualityExpr::NotEqual(left, right), _)
+        | Expr::Comparison(ComparisonExpr::Less(left, right), _)
+        | Expr::Comparison(ComparisonExpr::LessEqual(left, right), _)
+        | Expr::Comparison(ComparisonExpr::Greater(left, right), _)
+        | Expr::Comparison(ComparisonExpr::GreaterEqual(left, right), _)
+        | Expr::Addition(AdditionExpr::Add(left, right), _)
+        | Expr::Addition(AdditionExpr::Subtract(left, right), _)
+        | Expr::Multiplication(MultiplicationExpr::Multiply(left, right), _)
+        | Expr::Multiplication(MultiplicationExpr::Divide(left, right), _) => {
+            is_constant(left) && is_constant(right)
+        }
+        _ => false,
+    }
+}
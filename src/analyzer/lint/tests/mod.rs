@@ -0,0 +1,174 @@
+use crate::analyzer::lint::{LintWarning, Linter, Rule};
+use crate::ast::span::Span;
+use crate::pass::*;
+use crate::runtime;
+
+fn lint_with(linter: Linter, source: &str) -> Vec<(&'static str, String)> {
+    let stmts = runtime::parse(source).unwrap();
+    let mut linter = linter;
+    linter
+        .tree_pass(stmts)
+        .unwrap()
+        .into_iter()
+        .map(|warning| (warning.rule.id(), warning.message))
+        .collect()
+}
+
+fn lint(source: &str) -> Vec<(&'static str, String)> {
+    lint_with(Linter::new(), source)
+}
+
+fn warning(id: &'static str, message: &str) -> (&'static str, String) {
+    (id, message.to_string())
+}
+
+#[test]
+fn unread_locals_and_parameters_should_warn() {
+    assert_eq!(
+        vec![
+            warning("W0001", "unused parameter 'b'"),
+            warning("W0001", "unused variable 'unread'"),
+        ],
+        lint(
+            "var global = 1;
+fun add(a, b) {
+  var unread = a;
+  unread = 2;
+  var read = a;
+  return read;
+}"
+        )
+    );
+}
+
+#[test]
+fn declarations_shadowing_an_enclosing_scope_should_warn() {
+    assert_eq!(
+        vec![
+            warning("W0002", "'a' shadows a declaration in an enclosing scope"),
+            warning("W0002", "'b' shadows a declaration in an enclosing scope"),
+        ],
+        lint(
+            "var a = 1;
+var b = 2;
+fun f(a) {
+  { var b = a; print b; }
+}
+var a = 3;"
+        )
+    );
+}
+
+#[test]
+fn statements_after_a_jump_should_warn_once() {
+    assert_eq!(
+        vec![
+            warning("W0003", "unreachable statement"),
+            warning("W0003", "unreachable statement"),
+        ],
+        lint(
+            "fun f(x) {
+  if (x) return 1; else { return 2; }
+  print 3;
+  print 4;
+}
+while (f(1)) { break; print 5; }"
+        )
+    );
+}
+
+#[test]
+fn locals_read_in_their_own_initializer_should_warn() {
+    assert_eq!(
+        vec![
+            warning("W0004", "'x' is read in its own initializer"),
+            warning("W0004", "'g' is read in its own initializer"),
+        ],
+        lint_with(
+            Linter::new().allow(Rule::Shadowing),
+            "var x = 1;
+x = x + 1;
+{
+  var x = x + 1;
+  var g = fun () { return g; };
+  print x;
+  print g;
+}"
+        )
+    );
+}
+
+#[test]
+fn assignment_to_functions_and_classes_should_warn() {
+    assert_eq!(
+        vec![
+            warning("W0005", "assignment to function 'f'"),
+            warning("W0005", "assignment to class 'C'"),
+        ],
+        lint(
+            "fun f() { return 1; }
+class C {}
+var v = f;
+f = nil;
+C = nil;
+v = nil;"
+        )
+    );
+}
+
+#[test]
+fn constant_conditions_other_than_while_true_should_warn() {
+    assert_eq!(
+        vec![
+            warning("W0006", "condition is constant"),
+            warning("W0006", "condition is constant"),
+        ],
+        lint(
+            "var x = 1;
+if (1 < (2 + 3)) print x;
+while (!nil) print x;
+while (true) { break; }
+for (;;) { break; }
+if (x == 1) print x;"
+        )
+    );
+}
+
+#[test]
+fn allowed_rules_should_not_warn() {
+    let source = "fun f(a) { return 1; print a; }";
+    assert_eq!(
+        vec![warning("W0003", "unreachable statement")],
+        lint_with(Linter::new().allow(Rule::UnusedVariable), source)
+    );
+    assert_eq!(
+        Vec::<(&str, String)>::new(),
+        lint_with(
+            Linter::new()
+                .allow(Rule::UnusedVariable)
+                .allow(Rule::UnreachableCode),
+            source
+        )
+    );
+}
+
+#[test]
+fn rules_should_parse_from_either_their_id_or_name() {
+    assert_eq!(Ok(Rule::Shadowing), "W0002".parse());
+    assert_eq!(Ok(Rule::ConstantCondition), "constant-condition".parse());
+    assert!("W9999".parse::<Rule>().is_err());
+}
+
+#[test]
+fn warnings_should_locate_the_statement_breaking_a_rule() {
+    let stmts = runtime::parse("{\n  var unused = 1;\n}").unwrap();
+
+    assert_eq!(
+        Ok(vec![LintWarning::new(
+            Rule::UnusedVariable,
+            "unused variable 'unused'",
+            Span::new(4, 19, 2, 3)
+        )]),
+        Linter::new().tree_pass(stmts)
+    );
+}
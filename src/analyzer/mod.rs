@@ -15,4 +15,5 @@ pub trait SemanticAnalyzerMut<A, B> {
     fn analyze(&mut self, input: A) -> Result<B, Self::Error>;
 }
 
+pub mod lint;
 pub mod scope;
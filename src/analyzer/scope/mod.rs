@@ -2,8 +2,15 @@ use crate::ast::expression::{
     AdditionExpr, ComparisonExpr, EqualityExpr, Expr, LogicalExpr, MultiplicationExpr, UnaryExpr,
 };
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
+use crate::diagnostic::Diagnostic;
+use crate::module;
 use crate::pass::*;
+use crate::statics;
+use crate::stdlib;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 mod stack;
 use stack::{Scope, ScopeStack};
@@ -13,33 +20,182 @@ mod tests;
 
 #[derive(PartialEq, Debug)]
 pub enum ScopeAnalyzerErr {
-    Undefined,
+    Undefined(Identifier, Span),
     TypeMismatch,
+    ThisOutsideClass(Span),
+    ReturnFromInitializer(Span),
+    InheritFromSelf(Span),
+    SuperOutsideClass(Span),
+    SuperWithoutSuperclass(Span),
+    BreakOutsideLoop(Span),
+    ContinueOutsideLoop(Span),
+    ImportOutsideTopLevel(Span),
+    AssignToBuiltin(Identifier, Span),
+    RedeclareBuiltin(Identifier, Span),
+    UndefinedExport(Identifier, Identifier, Span),
+}
+
+impl ScopeAnalyzerErr {
+    /// span returns the span of source the error occurred at, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Undefined(_, span)
+            | Self::ThisOutsideClass(span)
+            | Self::ReturnFromInitializer(span)
+            | Self::InheritFromSelf(span)
+            | Self::SuperOutsideClass(span)
+            | Self::SuperWithoutSuperclass(span)
+            | Self::BreakOutsideLoop(span)
+            | Self::ContinueOutsideLoop(span)
+            | Self::ImportOutsideTopLevel(span)
+            | Self::AssignToBuiltin(_, span)
+            | Self::RedeclareBuiltin(_, span)
+            | Self::UndefinedExport(_, _, span) => Some(*span),
+            Self::TypeMismatch => None,
+        }
+    }
 }
 
 impl fmt::Display for ScopeAnalyzerErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Undefined => write!(f, "undefined error"),
+            Self::Undefined(id, _) => write!(f, "undefined variable '{}'", id),
             Self::TypeMismatch => write!(f, "invalid type passed to analyzer method"),
+            Self::ThisOutsideClass(_) => write!(f, "can't use 'this' outside of a class"),
+            Self::ReturnFromInitializer(_) => write!(f, "can't return a value from an initializer"),
+            Self::InheritFromSelf(_) => write!(f, "a class can't inherit from itself"),
+            Self::SuperOutsideClass(_) => write!(f, "can't use 'super' outside of a class"),
+            Self::SuperWithoutSuperclass(_) => {
+                write!(f, "can't use 'super' in a class with no superclass")
+            }
+            Self::BreakOutsideLoop(_) => write!(f, "can't use 'break' outside of a loop"),
+            Self::ContinueOutsideLoop(_) => write!(f, "can't use 'continue' outside of a loop"),
+            Self::ImportOutsideTopLevel(_) => {
+                write!(f, "can't use 'import' outside the top level of a file")
+            }
+            Self::AssignToBuiltin(id, _) => write!(f, "can't assign to builtin '{}'", id),
+            Self::RedeclareBuiltin(id, _) => {
+                write!(f, "can't redeclare builtin '{}' at the top level", id)
+            }
+            Self::UndefinedExport(module, member, _) => {
+                write!(f, "module '{}' has no member '{}'", module, member)
+            }
         }
     }
 }
 
+impl From<ScopeAnalyzerErr> for Diagnostic {
+    fn from(err: ScopeAnalyzerErr) -> Self {
+        let code = match err {
+            ScopeAnalyzerErr::Undefined(_, _) => "E0200",
+            ScopeAnalyzerErr::ThisOutsideClass(_) => "E0201",
+            ScopeAnalyzerErr::ReturnFromInitializer(_) => "E0202",
+            ScopeAnalyzerErr::InheritFromSelf(_) => "E0203",
+            ScopeAnalyzerErr::SuperOutsideClass(_) => "E0204",
+            ScopeAnalyzerErr::SuperWithoutSuperclass(_) => "E0205",
+            ScopeAnalyzerErr::TypeMismatch => "E0206",
+            ScopeAnalyzerErr::BreakOutsideLoop(_) => "E0207",
+            ScopeAnalyzerErr::ContinueOutsideLoop(_) => "E0208",
+            ScopeAnalyzerErr::ImportOutsideTopLevel(_) => "E0209",
+            ScopeAnalyzerErr::AssignToBuiltin(_, _) => "E0210",
+            ScopeAnalyzerErr::RedeclareBuiltin(_, _) => "E0211",
+            ScopeAnalyzerErr::UndefinedExport(_, _, _) => "E0212",
+        };
+
+        Diagnostic::error(code, &err.to_string(), err.span().unwrap_or_default())
+    }
+}
+
+/// ClassType tracks whether the analyzer is currently within a class body,
+/// and whether that class has a superclass.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// FunctionType tracks the kind of function body the analyzer is currently
+/// within.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum FunctionType {
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+#[derive(Clone)]
 pub struct ScopeAnalyzer {
     stack: ScopeStack,
+    builtins: HashSet<String>,
+    script: Option<PathBuf>,
+    /// exports holds the alias and exported names of each imported module,
+    /// by the id of the variable the module is bound to.
+    exports: HashMap<usize, (Identifier, HashSet<String>)>,
+    current_class: ClassType,
+    current_function: FunctionType,
+    loop_depth: usize,
 }
 
 impl ScopeAnalyzer {
     pub fn new() -> ScopeAnalyzer {
         Self::default()
     }
+
+    /// declare_builtin tells the analyzer of a global defined by the host
+    /// rather than the program, such as a native function registered with
+    /// `register_native`. Builtins may be read, and shadowed within a block,
+    /// but not assigned to or redeclared at the top level.
+    pub fn declare_builtin(&mut self, name: &str) {
+        self.builtins.insert(name.to_string());
+    }
+
+    /// set_script records the file being analyzed, locating the modules it
+    /// imports relative to it rather than the working directory.
+    pub fn set_script(&mut self, path: &Path) {
+        self.script = Some(path.to_path_buf());
+    }
+
+    /// builtins returns the name of each builtin, sorted.
+    pub fn builtins(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.builtins.iter().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// builtin returns the identifier a builtin is looked up by, if the
+    /// passed identifier names one.
+    fn builtin(&self, id: &Identifier) -> Option<Identifier> {
+        match id {
+            Identifier::Name(name) if self.builtins.contains(name) => {
+                Some(Identifier::Builtin(name.clone()))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Default for ScopeAnalyzer {
+    /// default returns an analyzer aware of the standard natives and each
+    /// enabled stdlib namespace.
     fn default() -> Self {
+        let natives = statics::natives()
+            .iter()
+            .map(|native| native.name().to_string())
+            .collect::<Vec<String>>();
+
         Self {
             stack: ScopeStack::new(),
+            builtins: natives
+                .into_iter()
+                .chain(stdlib::NAMESPACES.iter().map(|name| name.to_string()))
+                .collect(),
+            script: None,
+            exports: HashMap::new(),
+            current_class: ClassType::None,
+            current_function: FunctionType::None,
+            loop_depth: 0,
         }
     }
 }
@@ -51,19 +207,48 @@ impl PassMut<Expr, Expr> for ScopeAnalyzer {
 
     fn tree_pass(&mut self, expr: Expr) -> ExprSemanticAnalyzerResult {
         match expr {
-            Expr::Grouping(e) => Ok(Expr::Grouping(Box::new(self.tree_pass(e)?))),
-            Expr::Lambda(params, body) => self.analyze_lambda(params, *body),
-            Expr::Variable(id) => self.analyze_variable(id),
-            e @ Expr::Primary(_) => Ok(e),
-            Expr::Call(callee, args) => self.analyze_call(*callee, args),
-            Expr::Get(instance, param) => self.analyze_get(*instance, *param),
-            Expr::Unary(expr) => self.analyze_unary(expr),
-            Expr::Multiplication(me) => self.analyze_multiplication(me),
-            Expr::Addition(ae) => self.analyze_addition(ae),
-            Expr::Comparison(ce) => self.analyze_comparison(ce),
-            Expr::Equality(ee) => self.analyze_equality(ee),
-            Expr::Logical(le) => self.analyze_logical(le),
-            Expr::Assignment(id, v) => self.analyze_assignment(id, v),
+            Expr::Grouping(e, span) => Ok(Expr::Grouping(Box::new(self.tree_pass(e)?), span)),
+            Expr::Lambda(params, body, span) => self.analyze_lambda(params, *body, span),
+            Expr::Variable(id, span) => self.analyze_variable(id, span),
+            Expr::This(span) => self.analyze_this(span),
+            Expr::Super(method, span) => self.analyze_super(method, span),
+            e @ Expr::Primary(_, _) => Ok(e),
+            Expr::Call(callee, args, span) => self.analyze_call(*callee, args, span),
+            Expr::Get(instance, param, span) => self.analyze_get(*instance, *param, span),
+            Expr::Set(instance, param, value, span) => {
+                self.analyze_set(*instance, param, *value, span)
+            }
+            Expr::List(elems, span) => Ok(Expr::List(
+                elems
+                    .into_iter()
+                    .map(|e| self.tree_pass(e))
+                    .collect::<Result<Vec<Expr>, ScopeAnalyzerErr>>()?,
+                span,
+            )),
+            Expr::Map(entries, span) => Ok(Expr::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| Ok((self.tree_pass(k)?, self.tree_pass(v)?)))
+                    .collect::<Result<Vec<(Expr, Expr)>, ScopeAnalyzerErr>>()?,
+                span,
+            )),
+            Expr::Index(list, index, span) => Ok(Expr::Index(
+                Box::new(self.tree_pass(list)?),
+                Box::new(self.tree_pass(index)?),
+                span,
+            )),
+            Expr::SetIndex(list, index, value, span) => {
+                self.analyze_set_index(*list, *index, *value, span)
+            }
+            Expr::Unary(expr, span) => Ok(Expr::Unary(self.analyze_unary(expr)?, span)),
+            Expr::Multiplication(me, span) => {
+                Ok(Expr::Multiplication(self.analyze_multiplication(me)?, span))
+            }
+            Expr::Addition(ae, span) => Ok(Expr::Addition(self.analyze_addition(ae)?, span)),
+            Expr::Comparison(ce, span) => Ok(Expr::Comparison(self.analyze_comparison(ce)?, span)),
+            Expr::Equality(ee, span) => Ok(Expr::Equality(self.analyze_equality(ee)?, span)),
+            Expr::Logical(le, span) => Ok(Expr::Logical(self.analyze_logical(le)?, span)),
+            Expr::Assignment(id, v, span) => self.analyze_assignment(id, v, span),
         }
     }
 }
@@ -73,17 +258,27 @@ impl ScopeAnalyzer {
         &mut self,
         id: Identifier,
         expr: Box<Expr>,
+        span: Span,
     ) -> ExprSemanticAnalyzerResult {
         let rhv = self.tree_pass(expr)?;
 
-        match self.stack.get_offset(&id) {
-            Some(offset) => Ok(Expr::Assignment(Identifier::Id(offset), Box::new(rhv))),
-            None => Err(ScopeAnalyzerErr::Undefined),
+        match self.stack.resolve(&id) {
+            Some(binding) => {
+                // a reassigned module no longer has known exports.
+                self.exports.remove(&binding.id);
+                Ok(Expr::Assignment(
+                    Identifier::Resolved(binding),
+                    Box::new(rhv),
+                    span,
+                ))
+            }
+            None if self.builtin(&id).is_some() => Err(ScopeAnalyzerErr::AssignToBuiltin(id, span)),
+            None => Err(ScopeAnalyzerErr::Undefined(id, span)),
         }
     }
 
-    fn analyze_logical(&mut self, expr: LogicalExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Logical(match expr {
+    fn analyze_logical(&mut self, expr: LogicalExpr) -> Result<LogicalExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             LogicalExpr::Or(left, right) => LogicalExpr::Or(
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
@@ -92,11 +287,11 @@ impl ScopeAnalyzer {
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
             ),
-        }))
+        })
     }
 
-    fn analyze_equality(&mut self, expr: EqualityExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Equality(match expr {
+    fn analyze_equality(&mut self, expr: EqualityExpr) -> Result<EqualityExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             EqualityExpr::Equal(left, right) => EqualityExpr::Equal(
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
@@ -105,11 +300,14 @@ impl ScopeAnalyzer {
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
             ),
-        }))
+        })
     }
 
-    fn analyze_comparison(&mut self, expr: ComparisonExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Comparison(match expr {
+    fn analyze_comparison(
+        &mut self,
+        expr: ComparisonExpr,
+    ) -> Result<ComparisonExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             ComparisonExpr::Greater(left, right) => ComparisonExpr::Greater(
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
@@ -126,11 +324,11 @@ impl ScopeAnalyzer {
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
             ),
-        }))
+        })
     }
 
-    fn analyze_addition(&mut self, expr: AdditionExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Addition(match expr {
+    fn analyze_addition(&mut self, expr: AdditionExpr) -> Result<AdditionExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             AdditionExpr::Add(left, right) => AdditionExpr::Add(
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
@@ -139,11 +337,14 @@ impl ScopeAnalyzer {
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
             ),
-        }))
+        })
     }
 
-    fn analyze_multiplication(&mut self, expr: MultiplicationExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Multiplication(match expr {
+    fn analyze_multiplication(
+        &mut self,
+        expr: MultiplicationExpr,
+    ) -> Result<MultiplicationExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             MultiplicationExpr::Multiply(left, right) => MultiplicationExpr::Multiply(
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
@@ -152,17 +353,22 @@ impl ScopeAnalyzer {
                 Box::new(self.tree_pass(left)?),
                 Box::new(self.tree_pass(right)?),
             ),
-        }))
+        })
     }
 
-    fn analyze_unary(&mut self, expr: UnaryExpr) -> ExprSemanticAnalyzerResult {
-        Ok(Expr::Unary(match expr {
+    fn analyze_unary(&mut self, expr: UnaryExpr) -> Result<UnaryExpr, ScopeAnalyzerErr> {
+        Ok(match expr {
             UnaryExpr::Bang(expr) => UnaryExpr::Bang(Box::new(self.tree_pass(expr)?)),
             UnaryExpr::Minus(expr) => UnaryExpr::Minus(Box::new(self.tree_pass(expr)?)),
-        }))
+        })
     }
 
-    fn analyze_call(&mut self, callee: Expr, args: Vec<Expr>) -> ExprSemanticAnalyzerResult {
+    fn analyze_call(
+        &mut self,
+        callee: Expr,
+        args: Vec<Expr>,
+        span: Span,
+    ) -> ExprSemanticAnalyzerResult {
         let analyzed_callee = self.tree_pass(callee)?;
         let mut analyzed_args: Vec<Expr> = Vec::new();
 
@@ -170,16 +376,74 @@ impl ScopeAnalyzer {
             analyzed_args.push(self.tree_pass(arg)?);
         }
 
-        Ok(Expr::Call(Box::new(analyzed_callee), analyzed_args))
+        Ok(Expr::Call(Box::new(analyzed_callee), analyzed_args, span))
     }
 
-    fn analyze_get(&mut self, instance: Expr, param: Expr) -> ExprSemanticAnalyzerResult {
+    fn analyze_get(
+        &mut self,
+        instance: Expr,
+        param: Expr,
+        span: Span,
+    ) -> ExprSemanticAnalyzerResult {
+        let analyzed_callee = self.tree_pass(instance)?;
+
+        // members of a module are checked against its exports, otherwise
+        // properties are resolved against the instance at runtime and are
+        // left as named identifiers.
+        if let (Expr::Variable(Identifier::Resolved(binding), _), Expr::Variable(member, _)) =
+            (&analyzed_callee, &param)
+        {
+            if let Some((module, exports)) = self.exports.get(&binding.id) {
+                if !exports.contains(&member.to_string()) {
+                    return Err(ScopeAnalyzerErr::UndefinedExport(
+                        module.clone(),
+                        member.clone(),
+                        span,
+                    ));
+                }
+            }
+        }
+
+        match param {
+            p @ Expr::Variable(_, _) => Ok(Expr::Get(Box::new(analyzed_callee), Box::new(p), span)),
+            _ => Err(ScopeAnalyzerErr::TypeMismatch),
+        }
+    }
+
+    fn analyze_set(
+        &mut self,
+        instance: Expr,
+        param: Identifier,
+        value: Expr,
+        span: Span,
+    ) -> ExprSemanticAnalyzerResult {
+        let analyzed_value = self.tree_pass(value)?;
         let analyzed_callee = self.tree_pass(instance)?;
-        let analyzed_param = self.tree_pass(param)?;
 
-        Ok(Expr::Get(
+        Ok(Expr::Set(
             Box::new(analyzed_callee),
-            Box::new(analyzed_param),
+            param,
+            Box::new(analyzed_value),
+            span,
+        ))
+    }
+
+    fn analyze_set_index(
+        &mut self,
+        list: Expr,
+        index: Expr,
+        value: Expr,
+        span: Span,
+    ) -> ExprSemanticAnalyzerResult {
+        let analyzed_value = self.tree_pass(value)?;
+        let analyzed_list = self.tree_pass(list)?;
+        let analyzed_index = self.tree_pass(index)?;
+
+        Ok(Expr::SetIndex(
+            Box::new(analyzed_list),
+            Box::new(analyzed_index),
+            Box::new(analyzed_value),
+            span,
         ))
     }
 
@@ -187,26 +451,36 @@ impl ScopeAnalyzer {
         &mut self,
         params: Vec<Identifier>,
         body: Stmt,
+        span: Span,
     ) -> ExprSemanticAnalyzerResult {
-        // enter scope
-        self.stack.push(Scope::new());
-        let param_ids: Vec<Identifier> = params
-            .into_iter()
-            .map(|param| self.declare_or_assign(param))
-            .collect();
+        let (param_ids, analyzed_body) =
+            self.analyze_function_body(FunctionType::Function, params, body)?;
 
-        let analyzed_body = self.tree_pass(body)?;
+        Ok(Expr::Lambda(param_ids, Box::new(analyzed_body), span))
+    }
 
-        // exit scope
-        self.stack.pop();
+    fn analyze_this(&mut self, span: Span) -> ExprSemanticAnalyzerResult {
+        match self.current_class {
+            ClassType::None => Err(ScopeAnalyzerErr::ThisOutsideClass(span)),
+            ClassType::Class | ClassType::Subclass => Ok(Expr::This(span)),
+        }
+    }
 
-        Ok(Expr::Lambda(param_ids, Box::new(analyzed_body)))
+    fn analyze_super(&mut self, method: Identifier, span: Span) -> ExprSemanticAnalyzerResult {
+        match self.current_class {
+            ClassType::None => Err(ScopeAnalyzerErr::SuperOutsideClass(span)),
+            ClassType::Class => Err(ScopeAnalyzerErr::SuperWithoutSuperclass(span)),
+            ClassType::Subclass => Ok(Expr::Super(method, span)),
+        }
     }
 
-    fn analyze_variable(&mut self, id: Identifier) -> ExprSemanticAnalyzerResult {
-        match self.stack.get_offset(&id) {
-            Some(offset) => Ok(Expr::Variable(Identifier::Id(offset))),
-            None => Err(ScopeAnalyzerErr::Undefined),
+    fn analyze_variable(&mut self, id: Identifier, span: Span) -> ExprSemanticAnalyzerResult {
+        match self.stack.resolve(&id) {
+            Some(binding) => Ok(Expr::Variable(Identifier::Resolved(binding), span)),
+            None => match self.builtin(&id) {
+                Some(builtin) => Ok(Expr::Variable(builtin, span)),
+                None => Err(ScopeAnalyzerErr::Undefined(id, span)),
+            },
         }
     }
 }
@@ -235,18 +509,33 @@ impl PassMut<Stmt, Stmt> for ScopeAnalyzer {
 
     fn tree_pass(&mut self, input: Stmt) -> StmtSemanticAnalyzerResult {
         match input {
-            Stmt::Expression(e) => Ok(Stmt::Expression(self.tree_pass(e)?)),
-            Stmt::If(cond, tb, eb) => self.analyze_if(cond, tb, eb),
-            Stmt::While(e, b) => Ok(Stmt::While(
-                self.tree_pass(e)?,
-                Box::new(self.tree_pass(b)?),
-            )),
-            Stmt::Print(e) => Ok(Stmt::Print(self.tree_pass(e)?)),
-            Stmt::Function(name, params, body) => self.analyze_function(name, params, body),
-            Stmt::Declaration(id, expr) => self.analyze_declaration(id, expr),
-            Stmt::Return(e) => Ok(Stmt::Return(self.tree_pass(e)?)),
-            Stmt::Class(id, stmts) => self.analyze_class(id, stmts),
-            Stmt::Block(stmts) => self.analyze_block(stmts),
+            Stmt::Expression(e, span) => Ok(Stmt::Expression(self.tree_pass(e)?, span)),
+            Stmt::If(cond, tb, eb, span) => self.analyze_if(cond, tb, eb, span),
+            Stmt::While(e, b, inc, span) => self.analyze_while(e, b, inc, span),
+            Stmt::ForIn(id, iterable, b, span) => self.analyze_for_in(id, iterable, *b, span),
+            Stmt::Print(e, span) => Ok(Stmt::Print(self.tree_pass(e)?, span)),
+            Stmt::Function(id, name, params, body, span) => {
+                self.analyze_function(id, name, params, *body, span)
+            }
+            Stmt::Declaration(id, expr, span) => self.analyze_declaration(id, expr, span),
+            Stmt::Return(e, span) => self.analyze_return(e, span),
+            Stmt::Class(id, name, superclass, stmts, span) => {
+                self.analyze_class(id, name, superclass, stmts, span)
+            }
+            Stmt::Block(stmts, span) => self.analyze_block(stmts, span),
+            Stmt::Break(span) if self.loop_depth == 0 => {
+                Err(ScopeAnalyzerErr::BreakOutsideLoop(span))
+            }
+            Stmt::Continue(span) if self.loop_depth == 0 => {
+                Err(ScopeAnalyzerErr::ContinueOutsideLoop(span))
+            }
+            s @ Stmt::Break(_) | s @ Stmt::Continue(_) => Ok(s),
+            Stmt::Import(_, _, span) if self.stack.depth() > 1 => {
+                Err(ScopeAnalyzerErr::ImportOutsideTopLevel(span))
+            }
+            Stmt::Import(path, id, span) => self.analyze_import(path, id, span),
+            Stmt::Throw(e, span) => Ok(Stmt::Throw(self.tree_pass(e)?, span)),
+            Stmt::Try(body, catch, finally, span) => self.analyze_try(*body, catch, finally, span),
         }
     }
 }
@@ -263,7 +552,7 @@ impl ScopeAnalyzer {
     pub fn has_key(&mut self, id: &Identifier) -> bool {
         match self.stack.pop() {
             Some(s) => {
-                let has_key = s.contains(id);
+                let has_key = s.iter().any(|(ident, _)| ident == id);
                 self.stack.push(s);
                 has_key
             }
@@ -271,50 +560,195 @@ impl ScopeAnalyzer {
         }
     }
 
+    /// globals returns the name of each top-level declaration, paired with
+    /// the key its value is stored under in the global environment.
+    pub fn globals(&self) -> HashMap<String, Identifier> {
+        self.stack
+            .globals()
+            .map(|scope| {
+                scope
+                    .iter()
+                    .enumerate()
+                    .map(|(slot, (id, _))| (id.to_string(), Identifier::Id(slot)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// resolve returns the binding of a named identifier visible from the
+    /// current scope, or its builtin identifier, as used by the interpreter
+    /// to look up its value.
+    pub fn resolve(&self, id: &Identifier) -> Option<Identifier> {
+        self.stack
+            .resolve(id)
+            .map(Identifier::Resolved)
+            .or_else(|| self.builtin(id))
+    }
+
     fn declare_or_assign(&mut self, id: Identifier) -> Identifier {
-        if self.has_key(&id) {
-            self.stack.get_offset(&id).map(Identifier::Id).unwrap()
-        } else {
-            self.stack.push_elem(id);
-            Identifier::Id(self.stack.len() - 1)
+        let binding = self.stack.declare(id);
+        // a redeclared module no longer has known exports.
+        self.exports.remove(&binding.id);
+        Identifier::Resolved(binding)
+    }
+
+    /// declare declares a variable in the current scope, failing if it would
+    /// replace a builtin at the top level.
+    fn declare(&mut self, id: Identifier, span: Span) -> Result<Identifier, ScopeAnalyzerErr> {
+        if self.stack.depth() == 1 && self.builtin(&id).is_some() {
+            return Err(ScopeAnalyzerErr::RedeclareBuiltin(id, span));
+        }
+
+        Ok(self.declare_or_assign(id))
+    }
+
+    /// analyze_import declares the module's alias, recording what the module
+    /// exports when it can be read so that its members are checked statically.
+    fn analyze_import(
+        &mut self,
+        path: String,
+        id: Identifier,
+        span: Span,
+    ) -> StmtSemanticAnalyzerResult {
+        let alias = id.clone();
+        let resolved = self.declare(id, span)?;
+
+        let base = self
+            .script
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new("."));
+        if let (Identifier::Resolved(binding), Some(exports)) =
+            (&resolved, module::exports(&base.join(&path)))
+        {
+            self.exports.insert(binding.id, (alias, exports));
         }
+
+        Ok(Stmt::Import(path, resolved, span))
     }
 
-    fn analyze_block(&mut self, stmts: Vec<Stmt>) -> StmtSemanticAnalyzerResult {
+    fn analyze_block(&mut self, stmts: Vec<Stmt>, span: Span) -> StmtSemanticAnalyzerResult {
         // enter scope
         self.stack.push(Scope::new());
-        let analyzed_block = self.tree_pass(stmts)?;
+        let analyzed_block = self.tree_pass(stmts);
         // leave scope
         self.stack.pop();
 
-        Ok(Stmt::Block(analyzed_block))
+        Ok(Stmt::Block(analyzed_block?, span))
     }
 
     fn analyze_class(
         &mut self,
         cname: Identifier,
+        name: Identifier,
+        superclass: Option<Expr>,
         methods: Vec<Stmt>,
+        span: Span,
     ) -> StmtSemanticAnalyzerResult {
-        let cid = self.declare_or_assign(cname);
+        let analyzed_superclass = match superclass {
+            Some(Expr::Variable(ref sc, sc_span)) if *sc == cname => {
+                return Err(ScopeAnalyzerErr::InheritFromSelf(sc_span))
+            }
+            Some(sc) => Some(self.tree_pass(sc)?),
+            None => None,
+        };
 
-        // enter scope
+        let cid = self.declare(cname, span)?;
+        let enclosing_class = self.current_class;
+        self.current_class = match analyzed_superclass {
+            Some(_) => ClassType::Subclass,
+            None => ClassType::Class,
+        };
+
+        // methods close over a scope defining `super` for subclasses, and
+        // are bound to a scope defining `this` when accessed on an instance.
+        if analyzed_superclass.is_some() {
+            self.stack.push(Scope::new());
+            self.stack.declare(identifier_name!("super"));
+        }
         self.stack.push(Scope::new());
+        self.stack.declare(identifier_name!("this"));
 
-        let method_ids = self.tree_pass(methods)?;
+        let analyzed_methods: Result<Vec<Stmt>, ScopeAnalyzerErr> = methods
+            .into_iter()
+            .map(|method| self.analyze_method(method))
+            .collect();
 
-        // leave scope
         self.stack.pop();
+        if analyzed_superclass.is_some() {
+            self.stack.pop();
+        }
+        self.current_class = enclosing_class;
+
+        Ok(Stmt::Class(
+            cid,
+            name,
+            analyzed_superclass,
+            analyzed_methods?,
+            span,
+        ))
+    }
 
-        Ok(Stmt::Class(cid, method_ids))
+    /// analyze_method resolves the parameters and body of a method. Method
+    /// names are looked up on an instance at runtime and are not declared in
+    /// the enclosing scope.
+    fn analyze_method(&mut self, method: Stmt) -> StmtSemanticAnalyzerResult {
+        match method {
+            Stmt::Function(id, name, params, body, span) => {
+                let function_type = if name == identifier_name!("init") {
+                    FunctionType::Initializer
+                } else {
+                    FunctionType::Method
+                };
+                let (param_ids, analyzed_body) =
+                    self.analyze_function_body(function_type, params, *body)?;
+
+                Ok(Stmt::Function(
+                    id,
+                    name,
+                    param_ids,
+                    Box::new(analyzed_body),
+                    span,
+                ))
+            }
+            _ => Err(ScopeAnalyzerErr::TypeMismatch),
+        }
     }
 
     fn analyze_function(
         &mut self,
-        fname: Identifier,
+        fid: Identifier,
+        name: Identifier,
         params: Vec<Identifier>,
-        body: Box<Stmt>,
+        body: Stmt,
+        span: Span,
     ) -> StmtSemanticAnalyzerResult {
-        let fid = self.declare_or_assign(fname);
+        let fid = self.declare(fid, span)?;
+        let (param_ids, analyzed_body) =
+            self.analyze_function_body(FunctionType::Function, params, body)?;
+
+        Ok(Stmt::Function(
+            fid,
+            name,
+            param_ids,
+            Box::new(analyzed_body),
+            span,
+        ))
+    }
+
+    /// analyze_function_body resolves a functions parameters and body within
+    /// a new scope, tracking the kind of function being analyzed.
+    fn analyze_function_body(
+        &mut self,
+        function_type: FunctionType,
+        params: Vec<Identifier>,
+        body: Stmt,
+    ) -> Result<(Vec<Identifier>, Stmt), ScopeAnalyzerErr> {
+        let enclosing_function = self.current_function;
+        let enclosing_loop_depth = self.loop_depth;
+        self.current_function = function_type;
+        // loops don't extend into a function declared within them.
+        self.loop_depth = 0;
 
         // enter scope
         self.stack.push(Scope::new());
@@ -323,11 +757,24 @@ impl ScopeAnalyzer {
             .into_iter()
             .map(|param| self.declare_or_assign(param))
             .collect();
-        let analyzed_body = self.tree_pass(body)?;
+        let analyzed_body = self.tree_pass(body);
+
         // leave scope
         self.stack.pop();
+        self.current_function = enclosing_function;
+        self.loop_depth = enclosing_loop_depth;
+
+        Ok((param_ids, analyzed_body?))
+    }
 
-        Ok(Stmt::Function(fid, param_ids, Box::new(analyzed_body)))
+    fn analyze_return(&mut self, expr: Option<Expr>, span: Span) -> StmtSemanticAnalyzerResult {
+        match (self.current_function, expr) {
+            (FunctionType::Initializer, Some(_)) => {
+                Err(ScopeAnalyzerErr::ReturnFromInitializer(span))
+            }
+            (_, Some(e)) => Ok(Stmt::Return(Some(self.tree_pass(e)?), span)),
+            (_, None) => Ok(Stmt::Return(None, span)),
+        }
     }
 
     fn analyze_if(
@@ -335,6 +782,7 @@ impl ScopeAnalyzer {
         cond: Expr,
         tb: Box<Stmt>,
         eb: Option<Box<Stmt>>,
+        span: Span,
     ) -> StmtSemanticAnalyzerResult {
         let c = self.tree_pass(cond)?;
         let then_branch = Box::new(self.tree_pass(tb)?);
@@ -343,12 +791,99 @@ impl ScopeAnalyzer {
             None => None,
         };
 
-        Ok(Stmt::If(c, then_branch, else_branch))
+        Ok(Stmt::If(c, then_branch, else_branch, span))
     }
 
-    fn analyze_declaration(&mut self, id: Identifier, expr: Expr) -> StmtSemanticAnalyzerResult {
+    fn analyze_while(
+        &mut self,
+        cond: Expr,
+        body: Box<Stmt>,
+        increment: Option<Expr>,
+        span: Span,
+    ) -> StmtSemanticAnalyzerResult {
+        let c = self.tree_pass(cond)?;
+
+        self.loop_depth += 1;
+        let analyzed_body = self.tree_pass(body);
+        self.loop_depth -= 1;
+
+        let inc = match increment {
+            Some(e) => Some(self.tree_pass(e)?),
+            None => None,
+        };
+
+        Ok(Stmt::While(c, Box::new(analyzed_body?), inc, span))
+    }
+
+    fn analyze_for_in(
+        &mut self,
+        id: Identifier,
+        iterable: Expr,
+        body: Stmt,
+        span: Span,
+    ) -> StmtSemanticAnalyzerResult {
+        let analyzed_iterable = self.tree_pass(iterable)?;
+
+        // the loop variable is declared in a scope of its own, entered anew
+        // on each iteration.
+        self.stack.push(Scope::new());
+        let var = self.declare(id, span)?;
+        self.loop_depth += 1;
+        let analyzed_body = self.tree_pass(body);
+        self.loop_depth -= 1;
+        self.stack.pop();
+
+        Ok(Stmt::ForIn(
+            var,
+            analyzed_iterable,
+            Box::new(analyzed_body?),
+            span,
+        ))
+    }
+
+    fn analyze_try(
+        &mut self,
+        body: Stmt,
+        catch: Option<(Identifier, Box<Stmt>)>,
+        finally: Option<Box<Stmt>>,
+        span: Span,
+    ) -> StmtSemanticAnalyzerResult {
+        let analyzed_body = self.tree_pass(body)?;
+
+        // the caught error is declared in a scope of its own, enclosing the
+        // handler.
+        let analyzed_catch = match catch {
+            Some((id, handler)) => {
+                self.stack.push(Scope::new());
+                let var = self.declare(id, span)?;
+                let analyzed_handler = self.tree_pass(handler);
+                self.stack.pop();
+                Some((var, Box::new(analyzed_handler?)))
+            }
+            None => None,
+        };
+
+        let analyzed_finally = match finally {
+            Some(finally) => Some(Box::new(self.tree_pass(finally)?)),
+            None => None,
+        };
+
+        Ok(Stmt::Try(
+            Box::new(analyzed_body),
+            analyzed_catch,
+            analyzed_finally,
+            span,
+        ))
+    }
+
+    fn analyze_declaration(
+        &mut self,
+        id: Identifier,
+        expr: Expr,
+        span: Span,
+    ) -> StmtSemanticAnalyzerResult {
         match self.tree_pass(expr) {
-            Ok(e) => Ok(Stmt::Declaration(self.declare_or_assign(id), e)),
+            Ok(e) => Ok(Stmt::Declaration(self.declare(id, span)?, e, span)),
             Err(e) => Err(e),
         }
     }
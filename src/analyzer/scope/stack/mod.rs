@@ -1,4 +1,4 @@
-use crate::ast::identifier::Identifier;
+use crate::ast::identifier::{Binding, Identifier};
 use std::fmt;
 
 #[cfg(test)]
@@ -19,18 +19,25 @@ impl fmt::Display for StackErr {
     }
 }
 
-/// Scope represents an array of Identifiers signifiying a scope
-pub type Scope = Vec<Identifier>;
+/// Scope represents the identifiers declared within a scope, in order of
+/// declaration, each paired with the unique id of its declaration. An
+/// identifier's slot is its index within the scope.
+pub type Scope = Vec<(Identifier, usize)>;
 
 // ScopeStack represents a stack of Scopes implemented using a vector.
 #[derive(Clone)]
 pub struct ScopeStack {
     stack: Vec<Scope>,
+    declared: usize,
 }
 
 impl From<Vec<Scope>> for ScopeStack {
     fn from(source: Vec<Scope>) -> Self {
-        ScopeStack { stack: source }
+        let declared = source.iter().flatten().map(|(_, id)| id + 1).max();
+        ScopeStack {
+            stack: source,
+            declared: declared.unwrap_or(0),
+        }
     }
 }
 
@@ -44,6 +51,7 @@ impl ScopeStack {
     pub fn new() -> Self {
         ScopeStack {
             stack: vec![Scope::new()],
+            declared: 0,
         }
     }
 
@@ -55,46 +63,49 @@ impl ScopeStack {
         self.stack.pop()
     }
 
-    pub fn push_elem(&mut self, elem: Identifier) {
-        match self.pop() {
-            Some(mut scope) => {
-                scope.push(elem);
-                self.push(scope);
-            }
-            None => self.push(vec![elem]),
-        }
+    /// depth returns the number of scopes on the stack, with the global scope
+    /// at a depth of 1.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
     }
 
-    #[allow(dead_code)]
-    pub fn pop_elem(&mut self) -> Option<Identifier> {
-        match self.pop() {
-            Some(mut scope) => {
-                let elem = scope.pop();
-                self.push(scope);
-                elem
+    /// globals returns the outermost scope.
+    pub fn globals(&self) -> Option<&Scope> {
+        self.stack.first()
+    }
+
+    /// declare adds an identifier to the innermost scope under a new id,
+    /// returning its binding. Redeclaring an identifier within the same scope
+    /// returns its existing binding.
+    pub fn declare(&mut self, elem: Identifier) -> Binding {
+        if self.stack.is_empty() {
+            self.push(Scope::new());
+        }
+
+        let id = self.declared;
+        let scope = self.stack.last_mut().unwrap();
+        match scope.iter().position(|(ident, _)| *ident == elem) {
+            Some(slot) => Binding::new(scope[slot].1, 0, slot),
+            None => {
+                scope.push((elem, id));
+                self.declared += 1;
+                Binding::new(id, 0, scope.len() - 1)
             }
-            None => None,
         }
     }
 
-    /// get_offset returns the uzise offset for a given Identifier.
-    pub fn get_offset(&self, id: &Identifier) -> Option<usize> {
-        let stack_len = self.len();
-
-        let offset = self
-            .stack
-            .clone()
+    /// resolve returns the binding of the innermost declaration of an
+    /// identifier, relative to the innermost scope.
+    pub fn resolve(&self, elem: &Identifier) -> Option<Binding> {
+        self.stack
             .iter()
-            .flatten()
             .rev()
-            .position(|elem| elem == id);
-
-        offset.map(|i| stack_len - i - 1)
-    }
-
-    /// len returns the number of unique IDs exist in the scope, this is useful
-    /// for fetching the next Identifier offset.
-    pub fn len(&self) -> usize {
-        self.stack.iter().flatten().count()
+            .enumerate()
+            .find_map(|(distance, scope)| {
+                scope
+                    .iter()
+                    .rposition(|(ident, _)| ident == elem)
+                    .map(|slot| Binding::new(scope[slot].1, distance, slot))
+            })
     }
 }
@@ -1,26 +1,54 @@
 use crate::analyzer::scope::stack::{Scope, ScopeStack};
+use crate::ast::identifier::Binding;
 
 #[test]
 fn push_should_append_to_stack() {
     let mut ss = ScopeStack::new();
 
-    ss.push(vec![identifier_name!("a")]);
+    ss.push(vec![(identifier_name!("a"), 0)]);
     let ssv: Vec<Scope> = ss.into();
 
-    assert_eq!(ssv, vec![vec![], vec![identifier_name!("a")]])
+    assert_eq!(ssv, vec![vec![], vec![(identifier_name!("a"), 0)]])
 }
 
 #[test]
-fn get_offset_should_return_correct_variable_offset() {
+fn resolve_should_return_distance_and_slot_of_variable() {
     let mut ss = ScopeStack::new();
     ss.push(vec![
-        identifier_name!("a"),
-        identifier_name!("b"),
-        identifier_name!("c"),
+        (identifier_name!("a"), 0),
+        (identifier_name!("b"), 1),
+        (identifier_name!("c"), 2),
     ]);
-    ss.push(vec![identifier_name!("d")]);
-    ss.push(vec![identifier_name!("e"), identifier_name!("f")]);
-    ss.push(vec![identifier_name!("g")]);
+    ss.push(vec![(identifier_name!("d"), 3)]);
+    ss.push(vec![(identifier_name!("e"), 4), (identifier_name!("f"), 5)]);
+    ss.push(vec![(identifier_name!("g"), 6)]);
 
-    assert_eq!(Some(2), ss.get_offset(&identifier_name!("c")))
+    assert_eq!(
+        Some(Binding::new(2, 3, 2)),
+        ss.resolve(&identifier_name!("c"))
+    )
+}
+
+#[test]
+fn resolve_should_return_innermost_declaration_of_shadowed_variable() {
+    let mut ss = ScopeStack::new();
+    ss.declare(identifier_name!("a"));
+    ss.push(Scope::new());
+    ss.declare(identifier_name!("b"));
+    ss.declare(identifier_name!("a"));
+
+    assert_eq!(
+        Some(Binding::new(2, 0, 1)),
+        ss.resolve(&identifier_name!("a"))
+    )
+}
+
+#[test]
+fn declare_should_reuse_binding_of_variable_declared_in_same_scope() {
+    let mut ss = ScopeStack::new();
+    let first = ss.declare(identifier_name!("a"));
+    ss.declare(identifier_name!("b"));
+
+    assert_eq!(first, ss.declare(identifier_name!("a")));
+    assert_eq!(Binding::new(2, 0, 2), ss.declare(identifier_name!("c")));
 }
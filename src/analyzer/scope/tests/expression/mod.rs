@@ -1,11 +1,13 @@
-use crate::analyzer::scope::ScopeAnalyzer;
+use crate::analyzer::scope::{ScopeAnalyzer, ScopeAnalyzerErr};
 use crate::ast::expression::Expr;
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
+use crate::diagnostic::Diagnostic;
 use crate::pass::*;
 
 #[test]
 fn primary_expression_should_return_ok() {
-    let input = Expr::Primary(obj_bool!(true));
+    let input = Expr::Primary(obj_bool!(true), Span::default());
     let output = input.clone();
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -16,9 +18,14 @@ fn assignment_expression_should_match_predefined_value() {
     let mut sa = ScopeAnalyzer::new();
     let input = Expr::Assignment(
         identifier_name!("test"),
-        Box::new(Expr::Primary(obj_bool!(true))),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    );
+    let output = Expr::Assignment(
+        identifier_resolved!(0, 0, 0),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
     );
-    let output = Expr::Assignment(identifier_id!(0), Box::new(Expr::Primary(obj_bool!(true))));
 
     // Pre-declare a test variable for the above assignment to assign to
     sa.declare_or_assign(identifier_name!("test"));
@@ -30,31 +37,148 @@ fn assignment_expression_should_match_predefined_value() {
 fn assignment_expression_should_err_if_variable_is_undeclared() {
     let input = Expr::Assignment(
         identifier_name!("test"),
-        Box::new(Expr::Primary(obj_bool!(true))),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
     );
 
     assert!(ScopeAnalyzer::new().tree_pass(input).is_err());
 }
 
 #[test]
-fn variable_analyze_should_resolve_offset() {
+fn set_index_expression_should_resolve_each_operand() {
+    let mut sa = ScopeAnalyzer::new();
+    let var = |name: &str| Box::new(Expr::Variable(identifier_name!(name), Span::default()));
+    let input = Expr::SetIndex(
+        var("list"),
+        var("index"),
+        Box::new(Expr::List(vec![*var("list")], Span::default())),
+        Span::default(),
+    );
+    let resolved = |id| {
+        Box::new(Expr::Variable(
+            identifier_resolved!(id, 0, id),
+            Span::default(),
+        ))
+    };
+    let output = Expr::SetIndex(
+        resolved(0),
+        resolved(1),
+        Box::new(Expr::List(vec![*resolved(0)], Span::default())),
+        Span::default(),
+    );
+
+    sa.declare_or_assign(identifier_name!("list"));
+    sa.declare_or_assign(identifier_name!("index"));
+
+    assert_eq!(Ok(output), sa.tree_pass(input));
+}
+
+#[test]
+fn undefined_variable_should_err_with_its_span() {
+    let span = Span::new(4, 8, 2, 3);
+    let input = Expr::Variable(identifier_name!("test"), span);
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::Undefined(identifier_name!("test"), span)),
+        ScopeAnalyzer::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn analyzer_errors_should_convert_to_a_diagnostic() {
+    let span = Span::new(4, 8, 2, 3);
+    let err = ScopeAnalyzer::new()
+        .tree_pass(Expr::Variable(identifier_name!("test"), span))
+        .unwrap_err();
+
+    assert_eq!(
+        Diagnostic::error("E0200", "undefined variable 'test'", span),
+        Diagnostic::from(err)
+    );
+}
+
+#[test]
+fn variable_analyze_should_resolve_binding() {
     let mut sa = ScopeAnalyzer::new();
     sa.declare_or_assign(identifier_name!("a"));
 
-    let input = Expr::Variable(identifier_name!("a"));
-    let output = Expr::Variable(identifier_id!(0));
+    let input = Expr::Variable(identifier_name!("a"), Span::default());
+    let output = Expr::Variable(identifier_resolved!(0, 0, 0), Span::default());
 
     assert_eq!(Ok(output), sa.tree_pass(input));
 }
 
+#[cfg(feature = "math")]
+#[test]
+fn builtins_should_be_looked_up_by_name_unless_shadowed() {
+    let mut sa = ScopeAnalyzer::new();
+    let input = Expr::Variable(identifier_name!("math"), Span::default());
+
+    assert_eq!(
+        Ok(Expr::Variable(identifier_builtin!("math"), Span::default())),
+        sa.tree_pass(input.clone())
+    );
+
+    sa.declare_or_assign(identifier_name!("math"));
+    assert_eq!(
+        Ok(Expr::Variable(
+            identifier_resolved!(0, 0, 0),
+            Span::default()
+        )),
+        sa.tree_pass(input)
+    );
+}
+
+#[test]
+fn assignment_to_a_builtin_should_error() {
+    let mut sa = ScopeAnalyzer::new();
+    sa.declare_builtin("host");
+    let input = Expr::Assignment(
+        identifier_name!("host"),
+        Box::new(Expr::Primary(obj_nil!(), Span::default())),
+        Span::new(0, 11, 1, 1),
+    );
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::AssignToBuiltin(
+            identifier_name!("host"),
+            Span::new(0, 11, 1, 1)
+        )),
+        sa.tree_pass(input)
+    );
+}
+
+#[cfg(feature = "math")]
+#[test]
+fn assignment_to_a_stdlib_namespace_should_error() {
+    let input = Expr::Assignment(
+        identifier_name!("math"),
+        Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+        Span::new(0, 8, 1, 1),
+    );
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::AssignToBuiltin(
+            identifier_name!("math"),
+            Span::new(0, 8, 1, 1)
+        )),
+        ScopeAnalyzer::new().tree_pass(input)
+    );
+}
+
 #[test]
 fn call_expression_should_match_predefined_value() {
     let mut sa = ScopeAnalyzer::new();
     let input = Expr::Assignment(
         identifier_name!("test"),
-        Box::new(Expr::Primary(obj_bool!(true))),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    );
+    let output = Expr::Assignment(
+        identifier_resolved!(0, 0, 0),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
     );
-    let output = Expr::Assignment(identifier_id!(0), Box::new(Expr::Primary(obj_bool!(true))));
 
     // Pre-declare a test variable for the above assignment to assign to
     sa.declare_or_assign(identifier_name!("test"));
@@ -63,18 +187,26 @@ fn call_expression_should_match_predefined_value() {
 }
 
 #[test]
-fn call_analyze_should_resolve_identifiers_to_ids() {
+fn call_analyze_should_resolve_identifiers_to_bindings() {
     let mut sa = ScopeAnalyzer::new();
     sa.declare_or_assign(identifier_name!("a"));
     sa.declare_or_assign(identifier_name!("b"));
 
     let input = Expr::Call(
-        Box::new(Expr::Variable(identifier_name!("a"))),
-        vec![Expr::Variable(identifier_name!("b"))],
+        Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+        vec![Expr::Variable(identifier_name!("b"), Span::default())],
+        Span::default(),
     );
     let output = Expr::Call(
-        Box::new(Expr::Variable(identifier_id!(0))),
-        vec![Expr::Variable(identifier_id!(1))],
+        Box::new(Expr::Variable(
+            identifier_resolved!(0, 0, 0),
+            Span::default(),
+        )),
+        vec![Expr::Variable(
+            identifier_resolved!(1, 0, 1),
+            Span::default(),
+        )],
+        Span::default(),
     );
 
     assert_eq!(Ok(output), sa.tree_pass(input));
@@ -85,15 +217,25 @@ fn lambda_expression_should_match_predefined_value() {
     let mut sa = ScopeAnalyzer::new();
     let input = Expr::Lambda(
         vec![identifier_name!("test"), identifier_name!("test_again")],
-        Box::new(Stmt::Block(vec![Stmt::Print(Expr::Variable(
-            identifier_name!("test"),
-        ))])),
+        Box::new(Stmt::Block(
+            vec![Stmt::Print(
+                Expr::Variable(identifier_name!("test"), Span::default()),
+                Span::default(),
+            )],
+            Span::default(),
+        )),
+        Span::default(),
     );
     let output = Expr::Lambda(
-        vec![identifier_id!(1), identifier_id!(2)],
-        Box::new(Stmt::Block(vec![Stmt::Print(Expr::Variable(
-            identifier_id!(1),
-        ))])),
+        vec![identifier_resolved!(1, 0, 0), identifier_resolved!(2, 0, 1)],
+        Box::new(Stmt::Block(
+            vec![Stmt::Print(
+                Expr::Variable(identifier_resolved!(1, 1, 0), Span::default()),
+                Span::default(),
+            )],
+            Span::default(),
+        )),
+        Span::default(),
     );
 
     // Pre-declare a test variable for the above assignment to assign to
@@ -101,3 +243,80 @@ fn lambda_expression_should_match_predefined_value() {
 
     assert_eq!(Ok(output), sa.tree_pass(input));
 }
+
+#[test]
+fn this_expression_should_err_outside_of_a_class() {
+    assert_eq!(
+        Err(ScopeAnalyzerErr::ThisOutsideClass(Span::default())),
+        ScopeAnalyzer::new().tree_pass(Expr::This(Span::default()))
+    );
+}
+
+#[test]
+fn this_expression_should_resolve_within_a_method() {
+    let input = vec![Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("test_func"),
+            identifier_name!("test_func"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(
+                    Some(Expr::This(Span::default())),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
+
+    let output = vec![Stmt::Class(
+        identifier_resolved!(0, 0, 0),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("test_func"),
+            identifier_name!("test_func"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(
+                    Some(Expr::This(Span::default())),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
+
+    assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
+}
+
+#[test]
+fn set_expression_should_resolve_instance_and_preserve_property_name() {
+    let mut sa = ScopeAnalyzer::new();
+    sa.declare_or_assign(identifier_name!("a"));
+
+    let input = Expr::Set(
+        Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+        identifier_name!("field"),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    );
+    let output = Expr::Set(
+        Box::new(Expr::Variable(
+            identifier_resolved!(0, 0, 0),
+            Span::default(),
+        )),
+        identifier_name!("field"),
+        Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    );
+
+    assert_eq!(Ok(output), sa.tree_pass(input));
+}
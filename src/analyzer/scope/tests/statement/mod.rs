@@ -1,18 +1,26 @@
-use crate::analyzer::scope::ScopeAnalyzer;
+use crate::analyzer::scope::{ScopeAnalyzer, ScopeAnalyzerErr};
 use crate::ast::expression::Expr;
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
 use crate::pass::*;
+use std::path::Path;
 
 #[test]
 fn expression_stmt_should_return_ok() {
-    let stmts = vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))];
+    let stmts = vec![Stmt::Expression(
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Span::default(),
+    )];
 
     assert_eq!(Ok(stmts.clone()), ScopeAnalyzer::new().tree_pass(stmts));
 }
 
 #[test]
 fn print_stmt_should_return_self() {
-    let stmts = vec![Stmt::Print(Expr::Primary(obj_bool!(true)))];
+    let stmts = vec![Stmt::Print(
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Span::default(),
+    )];
 
     assert_eq!(Ok(stmts.clone()), ScopeAnalyzer::new().tree_pass(stmts));
 }
@@ -21,12 +29,14 @@ fn print_stmt_should_return_self() {
 fn declaration_statement_should_return_id_def() {
     let input = vec![Stmt::Declaration(
         identifier_name!("test"),
-        Expr::Primary(obj_bool!(true)),
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Span::default(),
     )];
 
     let output = vec![Stmt::Declaration(
-        identifier_id!(0),
-        Expr::Primary(obj_bool!(true)),
+        identifier_resolved!(0, 0, 0),
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Span::default(),
     )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -35,16 +45,29 @@ fn declaration_statement_should_return_id_def() {
 #[test]
 fn multiple_unique_declaration_statements_should_increment_id() {
     let input = vec![
-        Stmt::Declaration(identifier_name!("test"), Expr::Primary(obj_bool!(true))),
+        Stmt::Declaration(
+            identifier_name!("test"),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        ),
         Stmt::Declaration(
             identifier_name!("test_again"),
-            Expr::Primary(obj_bool!(false)),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
         ),
     ];
 
     let output = vec![
-        Stmt::Declaration(identifier_id!(0), Expr::Primary(obj_bool!(true))),
-        Stmt::Declaration(identifier_id!(1), Expr::Primary(obj_bool!(false))),
+        Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Declaration(
+            identifier_resolved!(1, 0, 1),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
+        ),
     ];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -53,13 +76,29 @@ fn multiple_unique_declaration_statements_should_increment_id() {
 #[test]
 fn multiple_matching_declaration_statements_should_increment_id() {
     let input = vec![
-        Stmt::Declaration(identifier_name!("test"), Expr::Primary(obj_bool!(true))),
-        Stmt::Declaration(identifier_name!("test"), Expr::Primary(obj_bool!(false))),
+        Stmt::Declaration(
+            identifier_name!("test"),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Declaration(
+            identifier_name!("test"),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
+        ),
     ];
 
     let output = vec![
-        Stmt::Declaration(identifier_id!(0), Expr::Primary(obj_bool!(true))),
-        Stmt::Declaration(identifier_id!(0), Expr::Primary(obj_bool!(false))),
+        Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
+        ),
     ];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -67,47 +106,72 @@ fn multiple_matching_declaration_statements_should_increment_id() {
 
 #[test]
 fn return_statement_should_return_self() {
-    let stmts = vec![Stmt::Return(Expr::Primary(obj_bool!(true)))];
+    let stmts = vec![Stmt::Return(
+        Some(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    )];
 
     assert_eq!(Ok(stmts.clone()), ScopeAnalyzer::new().tree_pass(stmts));
 }
 
 #[test]
 fn block_statement_should_return_self() {
-    let stmts = vec![Stmt::Block(vec![Stmt::Expression(Expr::Primary(
-        obj_bool!(true),
-    ))])];
+    let stmts = vec![Stmt::Block(
+        vec![Stmt::Expression(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
 
     assert_eq!(Ok(stmts.clone()), ScopeAnalyzer::new().tree_pass(stmts));
 }
 
 #[test]
 fn block_statement_should_analyze_child_stmts() {
-    let input = vec![Stmt::Block(vec![Stmt::Declaration(
-        identifier_name!("test"),
-        Expr::Primary(obj_bool!(true)),
-    )])];
+    let input = vec![Stmt::Block(
+        vec![Stmt::Declaration(
+            identifier_name!("test"),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
 
-    let output = vec![Stmt::Block(vec![Stmt::Declaration(
-        identifier_id!(0),
-        Expr::Primary(obj_bool!(true)),
-    )])];
+    let output = vec![Stmt::Block(
+        vec![Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
 }
 
 #[test]
 fn function_declaration_statement_should_return_self() {
-    let block = Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]);
+    let block = Stmt::Block(
+        vec![Stmt::Expression(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    );
     let input = vec![Stmt::Function(
+        identifier_name!("test"),
         identifier_name!("test"),
         vec![],
         Box::new(block.clone()),
+        Span::default(),
     )];
     let output = vec![Stmt::Function(
-        identifier_id!(0),
+        identifier_resolved!(0, 0, 0),
+        identifier_name!("test"),
         vec![],
         Box::new(block.clone()),
+        Span::default(),
     )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -116,27 +180,33 @@ fn function_declaration_statement_should_return_self() {
 #[test]
 fn if_statement_should_return_self() {
     let input = vec![Stmt::If(
-        Expr::Primary(obj_bool!(true)),
+        Expr::Primary(obj_bool!(true), Span::default()),
         Box::new(Stmt::Declaration(
             identifier_name!("test"),
-            Expr::Primary(obj_bool!(true)),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
         Option::Some(Box::new(Stmt::Declaration(
             identifier_name!("test"),
-            Expr::Primary(obj_bool!(false)),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
         ))),
+        Span::default(),
     )];
 
     let output = vec![Stmt::If(
-        Expr::Primary(obj_bool!(true)),
+        Expr::Primary(obj_bool!(true), Span::default()),
         Box::new(Stmt::Declaration(
-            identifier_id!(0),
-            Expr::Primary(obj_bool!(true)),
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
         Option::Some(Box::new(Stmt::Declaration(
-            identifier_id!(0),
-            Expr::Primary(obj_bool!(false)),
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
         ))),
+        Span::default(),
     )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
@@ -145,46 +215,70 @@ fn if_statement_should_return_self() {
 #[test]
 fn while_statement_should_return_self() {
     let input = vec![Stmt::While(
-        Expr::Primary(obj_bool!(false)),
+        Expr::Primary(obj_bool!(false), Span::default()),
         Box::new(Stmt::Declaration(
             identifier_name!("test"),
-            Expr::Primary(obj_bool!(true)),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
+        None,
+        Span::default(),
     )];
 
     let output = vec![Stmt::While(
-        Expr::Primary(obj_bool!(false)),
+        Expr::Primary(obj_bool!(false), Span::default()),
         Box::new(Stmt::Declaration(
-            identifier_id!(0),
-            Expr::Primary(obj_bool!(true)),
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
+        None,
+        Span::default(),
     )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input))
 }
 
 #[test]
-fn class_statement_should_return_self() {
+fn class_statement_should_resolve_class_name_and_preserve_method_names() {
     let input = vec![Stmt::Class(
         identifier_name!("test"),
+        identifier_name!("test"),
+        None,
         vec![Stmt::Function(
+            identifier_name!("test_func"),
             identifier_name!("test_func"),
             vec![],
-            Box::new(Stmt::Block(vec![Stmt::Print(Expr::Primary(obj_number!(
-                5.0
-            )))])),
+            Box::new(Stmt::Block(
+                vec![Stmt::Print(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+            Span::default(),
         )],
+        Span::default(),
     )];
 
     let output = vec![Stmt::Class(
-        identifier_id!(0),
+        identifier_resolved!(0, 0, 0),
+        identifier_name!("test"),
+        None,
         vec![Stmt::Function(
-            identifier_id!(1),
+            identifier_name!("test_func"),
+            identifier_name!("test_func"),
             vec![],
-            Box::new(Stmt::Block(vec![Stmt::Print(Expr::Primary(obj_number!(
-                5.0
-            )))])),
+            Box::new(Stmt::Block(
+                vec![Stmt::Print(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+            Span::default(),
         )],
+        Span::default(),
     )];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input))
@@ -195,58 +289,637 @@ fn scope_should_shade_correctly() {
     let input = vec![
         Stmt::Declaration(
             identifier_name!("a"),
-            Expr::Primary(obj_str!("global".to_string())),
+            Expr::Primary(obj_str!("global".to_string()), Span::default()),
+            Span::default(),
         ),
-        Stmt::Block(vec![
-            Stmt::Function(
-                identifier_name!("showA"),
-                vec![],
-                Box::new(Stmt::Block(vec![Stmt::Print(Expr::Variable(
+        Stmt::Block(
+            vec![
+                Stmt::Function(
+                    identifier_name!("showA"),
+                    identifier_name!("showA"),
+                    vec![],
+                    Box::new(Stmt::Block(
+                        vec![Stmt::Print(
+                            Expr::Variable(identifier_name!("a"), Span::default()),
+                            Span::default(),
+                        )],
+                        Span::default(),
+                    )),
+                    Span::default(),
+                ),
+                Stmt::Expression(
+                    Expr::Call(
+                        Box::new(Expr::Variable(identifier_name!("showA"), Span::default())),
+                        vec![],
+                        Span::default(),
+                    ),
+                    Span::default(),
+                ),
+                Stmt::Declaration(
                     identifier_name!("a"),
-                ))])),
-            ),
-            Stmt::Expression(Expr::Call(
-                Box::new(Expr::Variable(identifier_name!("showA"))),
-                vec![],
+                    Expr::Primary(obj_str!("block".to_string()), Span::default()),
+                    Span::default(),
+                ),
+                Stmt::Expression(
+                    Expr::Call(
+                        Box::new(Expr::Variable(identifier_name!("showA"), Span::default())),
+                        vec![],
+                        Span::default(),
+                    ),
+                    Span::default(),
+                ),
+            ],
+            Span::default(),
+        ),
+    ];
+
+    let output = vec![
+        Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_str!("global".to_string()), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Block(
+            vec![
+                Stmt::Function(
+                    identifier_resolved!(1, 0, 0),
+                    identifier_name!("showA"),
+                    vec![],
+                    Box::new(Stmt::Block(
+                        vec![Stmt::Print(
+                            Expr::Variable(identifier_resolved!(0, 3, 0), Span::default()),
+                            Span::default(),
+                        )],
+                        Span::default(),
+                    )),
+                    Span::default(),
+                ),
+                Stmt::Expression(
+                    Expr::Call(
+                        Box::new(Expr::Variable(
+                            identifier_resolved!(1, 0, 0),
+                            Span::default(),
+                        )),
+                        vec![],
+                        Span::default(),
+                    ),
+                    Span::default(),
+                ),
+                Stmt::Declaration(
+                    identifier_resolved!(2, 0, 1),
+                    Expr::Primary(obj_str!("block".to_string()), Span::default()),
+                    Span::default(),
+                ),
+                Stmt::Expression(
+                    Expr::Call(
+                        Box::new(Expr::Variable(
+                            identifier_resolved!(1, 0, 0),
+                            Span::default(),
+                        )),
+                        vec![],
+                        Span::default(),
+                    ),
+                    Span::default(),
+                ),
+            ],
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input))
+}
+
+#[test]
+fn initializer_should_allow_bare_return() {
+    let input = vec![Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("init"),
+            identifier_name!("init"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(None, Span::default())],
+                Span::default(),
             )),
-            Stmt::Declaration(
-                identifier_name!("a"),
-                Expr::Primary(obj_str!("block".to_string())),
-            ),
-            Stmt::Expression(Expr::Call(
-                Box::new(Expr::Variable(identifier_name!("showA"))),
-                vec![],
+            Span::default(),
+        )],
+        Span::default(),
+    )];
+
+    let output = vec![Stmt::Class(
+        identifier_resolved!(0, 0, 0),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("init"),
+            identifier_name!("init"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(None, Span::default())],
+                Span::default(),
+            )),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
+
+    assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input))
+}
+
+#[test]
+fn initializer_should_err_on_return_with_value() {
+    let input = vec![Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("init"),
+            identifier_name!("init"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(
+                    Some(Expr::Primary(obj_number!(5.0), Span::default())),
+                    Span::default(),
+                )],
+                Span::default(),
             )),
-        ]),
+            Span::default(),
+        )],
+        Span::default(),
+    )];
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::ReturnFromInitializer(Span::default())),
+        ScopeAnalyzer::new().tree_pass(input)
+    )
+}
+
+#[test]
+fn class_should_err_when_inheriting_from_itself() {
+    let input = vec![Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        Some(Expr::Variable(identifier_name!("test"), Span::default())),
+        vec![],
+        Span::default(),
+    )];
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::InheritFromSelf(Span::default())),
+        ScopeAnalyzer::new().tree_pass(input)
+    )
+}
+
+fn super_method_class_declaration(superclass: Option<Expr>) -> Stmt {
+    Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        superclass,
+        vec![Stmt::Function(
+            identifier_name!("test_func"),
+            identifier_name!("test_func"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Return(
+                    Some(Expr::Super(identifier_name!("test_func"), Span::default())),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+            Span::default(),
+        )],
+        Span::default(),
+    )
+}
+
+#[test]
+fn super_should_resolve_within_a_subclass() {
+    let input = vec![
+        Stmt::Class(
+            identifier_name!("parent"),
+            identifier_name!("parent"),
+            None,
+            vec![],
+            Span::default(),
+        ),
+        super_method_class_declaration(Some(Expr::Variable(
+            identifier_name!("parent"),
+            Span::default(),
+        ))),
     ];
 
     let output = vec![
-        Stmt::Declaration(
-            identifier_id!(0),
-            Expr::Primary(obj_str!("global".to_string())),
+        Stmt::Class(
+            identifier_resolved!(0, 0, 0),
+            identifier_name!("parent"),
+            None,
+            vec![],
+            Span::default(),
         ),
-        Stmt::Block(vec![
-            Stmt::Function(
-                identifier_id!(1),
-                vec![],
-                Box::new(Stmt::Block(vec![Stmt::Print(Expr::Variable(
-                    identifier_id!(0),
-                ))])),
-            ),
-            Stmt::Expression(Expr::Call(
-                Box::new(Expr::Variable(identifier_id!(1))),
-                vec![],
+        Stmt::Class(
+            identifier_resolved!(2, 0, 1),
+            identifier_name!("test"),
+            Some(Expr::Variable(
+                identifier_resolved!(0, 0, 0),
+                Span::default(),
             )),
-            Stmt::Declaration(
-                identifier_id!(2),
-                Expr::Primary(obj_str!("block".to_string())),
-            ),
-            Stmt::Expression(Expr::Call(
-                Box::new(Expr::Variable(identifier_id!(1))),
+            vec![Stmt::Function(
+                identifier_name!("test_func"),
+                identifier_name!("test_func"),
                 vec![],
-            )),
-        ]),
+                Box::new(Stmt::Block(
+                    vec![Stmt::Return(
+                        Some(Expr::Super(identifier_name!("test_func"), Span::default())),
+                        Span::default(),
+                    )],
+                    Span::default(),
+                )),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
     ];
 
     assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input))
 }
+
+#[test]
+fn super_should_err_in_a_class_without_a_superclass() {
+    assert_eq!(
+        Err(ScopeAnalyzerErr::SuperWithoutSuperclass(Span::default())),
+        ScopeAnalyzer::new().tree_pass(vec![super_method_class_declaration(None)])
+    )
+}
+
+#[test]
+fn super_should_err_outside_of_a_class() {
+    assert_eq!(
+        Err(ScopeAnalyzerErr::SuperOutsideClass(Span::default())),
+        ScopeAnalyzer::new().tree_pass(vec![Stmt::Expression(
+            Expr::Super(identifier_name!("test_func"), Span::default()),
+            Span::default()
+        )])
+    )
+}
+
+#[test]
+fn failed_block_should_leave_its_scope() {
+    let mut analyzer = ScopeAnalyzer::new();
+    let declare_a = Stmt::Declaration(
+        identifier_name!("a"),
+        Expr::Primary(obj_nil!(), Span::default()),
+        Span::default(),
+    );
+    let input = vec![
+        declare_a.clone(),
+        Stmt::Block(
+            vec![
+                Stmt::Declaration(
+                    identifier_name!("b"),
+                    Expr::Primary(obj_nil!(), Span::default()),
+                    Span::default(),
+                ),
+                Stmt::Print(
+                    Expr::Variable(identifier_name!("undefined"), Span::default()),
+                    Span::default(),
+                ),
+            ],
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::Undefined(
+            identifier_name!("undefined"),
+            Span::default()
+        )),
+        analyzer.tree_pass(input)
+    );
+    assert_eq!(
+        Some(identifier_resolved!(0, 0, 0)),
+        analyzer.resolve(&identifier_name!("a"))
+    );
+    assert_eq!(None, analyzer.resolve(&identifier_name!("b")));
+}
+
+#[test]
+fn method_should_resolve_variables_through_super_and_this_scopes() {
+    let input = vec![
+        Stmt::Declaration(
+            identifier_name!("a"),
+            Expr::Primary(obj_nil!(), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Class(
+            identifier_name!("parent"),
+            identifier_name!("parent"),
+            None,
+            vec![],
+            Span::default(),
+        ),
+        Stmt::Class(
+            identifier_name!("test"),
+            identifier_name!("test"),
+            Some(Expr::Variable(identifier_name!("parent"), Span::default())),
+            vec![Stmt::Function(
+                identifier_name!("test_func"),
+                identifier_name!("test_func"),
+                vec![identifier_name!("b")],
+                Box::new(Stmt::Block(
+                    vec![
+                        Stmt::Print(
+                            Expr::Variable(identifier_name!("a"), Span::default()),
+                            Span::default(),
+                        ),
+                        Stmt::Print(
+                            Expr::Variable(identifier_name!("b"), Span::default()),
+                            Span::default(),
+                        ),
+                    ],
+                    Span::default(),
+                )),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
+    ];
+
+    let method = ScopeAnalyzer::new()
+        .tree_pass(input)
+        .map(|stmts| stmts[2].clone());
+
+    // body, parameters, this and super scopes lie between a method body and
+    // the scope its class is declared in.
+    assert_eq!(
+        Ok(Stmt::Class(
+            identifier_resolved!(3, 0, 2),
+            identifier_name!("test"),
+            Some(Expr::Variable(
+                identifier_resolved!(1, 0, 1),
+                Span::default()
+            )),
+            vec![Stmt::Function(
+                identifier_name!("test_func"),
+                identifier_name!("test_func"),
+                vec![identifier_resolved!(6, 0, 0)],
+                Box::new(Stmt::Block(
+                    vec![
+                        Stmt::Print(
+                            Expr::Variable(identifier_resolved!(0, 4, 0), Span::default()),
+                            Span::default(),
+                        ),
+                        Stmt::Print(
+                            Expr::Variable(identifier_resolved!(6, 1, 0), Span::default()),
+                            Span::default(),
+                        ),
+                    ],
+                    Span::default(),
+                )),
+                Span::default(),
+            )],
+            Span::default(),
+        )),
+        method
+    )
+}
+
+#[test]
+fn break_and_continue_should_err_outside_of_a_loop() {
+    assert_eq!(
+        Err(ScopeAnalyzerErr::BreakOutsideLoop(Span::default())),
+        ScopeAnalyzer::new().tree_pass(vec![Stmt::Break(Span::default())])
+    );
+    assert_eq!(
+        Err(ScopeAnalyzerErr::ContinueOutsideLoop(Span::default())),
+        ScopeAnalyzer::new().tree_pass(vec![Stmt::Continue(Span::default())])
+    );
+}
+
+#[test]
+fn break_should_be_allowed_within_a_loop() {
+    let stmts = vec![Stmt::While(
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Box::new(Stmt::Block(
+            vec![Stmt::Break(Span::default())],
+            Span::default(),
+        )),
+        None,
+        Span::default(),
+    )];
+
+    assert_eq!(Ok(stmts.clone()), ScopeAnalyzer::new().tree_pass(stmts));
+}
+
+#[test]
+fn break_should_err_in_a_function_declared_within_a_loop() {
+    let input = vec![Stmt::While(
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Box::new(Stmt::Function(
+            identifier_name!("escape"),
+            identifier_name!("escape"),
+            vec![],
+            Box::new(Stmt::Block(
+                vec![Stmt::Break(Span::default())],
+                Span::default(),
+            )),
+            Span::default(),
+        )),
+        None,
+        Span::default(),
+    )];
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::BreakOutsideLoop(Span::default())),
+        ScopeAnalyzer::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn for_in_statement_should_scope_its_variable_to_the_loop() {
+    let input = vec![
+        Stmt::Declaration(
+            identifier_name!("items"),
+            Expr::Primary(obj_nil!(), Span::default()),
+            Span::default(),
+        ),
+        Stmt::ForIn(
+            identifier_name!("item"),
+            Expr::Variable(identifier_name!("items"), Span::default()),
+            Box::new(Stmt::Block(
+                vec![
+                    Stmt::Print(
+                        Expr::Variable(identifier_name!("item"), Span::default()),
+                        Span::default(),
+                    ),
+                    Stmt::Break(Span::default()),
+                ],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+    let output = vec![
+        Stmt::Declaration(
+            identifier_resolved!(0, 0, 0),
+            Expr::Primary(obj_nil!(), Span::default()),
+            Span::default(),
+        ),
+        Stmt::ForIn(
+            identifier_resolved!(1, 0, 0),
+            Expr::Variable(identifier_resolved!(0, 0, 0), Span::default()),
+            Box::new(Stmt::Block(
+                vec![
+                    Stmt::Print(
+                        Expr::Variable(identifier_resolved!(1, 1, 0), Span::default()),
+                        Span::default(),
+                    ),
+                    Stmt::Break(Span::default()),
+                ],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(Ok(output), ScopeAnalyzer::new().tree_pass(input));
+
+    let escaped = vec![
+        Stmt::ForIn(
+            identifier_name!("item"),
+            Expr::Primary(obj_nil!(), Span::default()),
+            Box::new(Stmt::Break(Span::default())),
+            Span::default(),
+        ),
+        Stmt::Print(
+            Expr::Variable(identifier_name!("item"), Span::default()),
+            Span::default(),
+        ),
+    ];
+    assert!(ScopeAnalyzer::new().tree_pass(escaped).is_err());
+}
+
+#[test]
+fn import_should_declare_its_namespace_at_the_top_level_only() {
+    let import = || {
+        Stmt::Import(
+            "lib.lox".to_string(),
+            identifier_name!("lib"),
+            Span::default(),
+        )
+    };
+    let mut sa = ScopeAnalyzer::new();
+
+    assert_eq!(
+        Ok(vec![Stmt::Import(
+            "lib.lox".to_string(),
+            identifier_resolved!(0, 0, 0),
+            Span::default()
+        )]),
+        sa.tree_pass(vec![import()])
+    );
+    assert_eq!(Some(&identifier_id!(0)), sa.globals().get("lib"));
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::ImportOutsideTopLevel(Span::default())),
+        sa.tree_pass(vec![Stmt::Block(vec![import()], Span::default())])
+    );
+}
+
+#[test]
+fn module_members_should_be_checked_against_its_exports() {
+    let get = |member: &str| {
+        Stmt::Expression(
+            Expr::Get(
+                Box::new(Expr::Variable(
+                    identifier_name!("greeting"),
+                    Span::default(),
+                )),
+                Box::new(Expr::Variable(identifier_name!(member), Span::default())),
+                Span::default(),
+            ),
+            Span::default(),
+        )
+    };
+    let mut sa = ScopeAnalyzer::new();
+    sa.set_script(Path::new("tests/corpus/imports.lox"));
+    sa.tree_pass(vec![Stmt::Import(
+        "modules/greeting.lox".to_string(),
+        identifier_name!("greeting"),
+        Span::default(),
+    )])
+    .unwrap();
+
+    assert!(sa.tree_pass(vec![get("greet")]).is_ok());
+    assert_eq!(
+        Err(ScopeAnalyzerErr::UndefinedExport(
+            identifier_name!("greeting"),
+            identifier_name!("farewell"),
+            Span::default()
+        )),
+        sa.tree_pass(vec![get("farewell")])
+    );
+}
+
+#[test]
+fn builtins_should_only_be_redeclared_within_a_block() {
+    let declaration = || {
+        Stmt::Declaration(
+            identifier_name!("clock"),
+            Expr::Primary(obj_nil!(), Span::default()),
+            Span::new(0, 16, 1, 1),
+        )
+    };
+    let mut sa = ScopeAnalyzer::new();
+
+    assert_eq!(
+        Err(ScopeAnalyzerErr::RedeclareBuiltin(
+            identifier_name!("clock"),
+            Span::new(0, 16, 1, 1)
+        )),
+        sa.tree_pass(declaration())
+    );
+    assert!(sa
+        .tree_pass(Stmt::Block(vec![declaration()], Span::default()))
+        .is_ok());
+}
+
+#[test]
+fn try_should_declare_the_caught_error_in_a_scope_enclosing_the_handler() {
+    let input = vec![Stmt::Try(
+        Box::new(Stmt::Block(vec![], Span::default())),
+        Some((
+            identifier_name!("e"),
+            Box::new(Stmt::Block(
+                vec![Stmt::Print(
+                    Expr::Variable(identifier_name!("e"), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+        )),
+        None,
+        Span::default(),
+    )];
+
+    let expected = vec![Stmt::Try(
+        Box::new(Stmt::Block(vec![], Span::default())),
+        Some((
+            identifier_resolved!(0, 0, 0),
+            Box::new(Stmt::Block(
+                vec![Stmt::Print(
+                    Expr::Variable(identifier_resolved!(0, 1, 0), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            )),
+        )),
+        None,
+        Span::default(),
+    )];
+
+    let mut sa = ScopeAnalyzer::new();
+    assert_eq!(Ok(expected), sa.tree_pass(input));
+    assert!(sa.globals().is_empty());
+}
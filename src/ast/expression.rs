@@ -1,41 +1,80 @@
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use crate::ast::statement;
 use crate::object;
 use std::fmt;
 
 /// Represents, and encapsulates one of the four types of expressions possible in
-/// lox currently. Further information can be found on each sub-type.
+/// lox currently. Further information can be found on each sub-type. Each
+/// variant carries the Span of source it was parsed from.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
-    Assignment(Identifier, Box<Expr>),
-    Logical(LogicalExpr),
-    Equality(EqualityExpr),
-    Comparison(ComparisonExpr),
-    Addition(AdditionExpr),
-    Multiplication(MultiplicationExpr),
-    Unary(UnaryExpr),
-    Call(Box<Expr>, Vec<Expr>),
-    Get(Box<Expr>, Box<Expr>),
-    Primary(object::Object),
-    Grouping(Box<Expr>),
-    Lambda(Vec<Identifier>, Box<statement::Stmt>),
-    Variable(Identifier),
+    Assignment(Identifier, Box<Expr>, Span),
+    Logical(LogicalExpr, Span),
+    Equality(EqualityExpr, Span),
+    Comparison(ComparisonExpr, Span),
+    Addition(AdditionExpr, Span),
+    Multiplication(MultiplicationExpr, Span),
+    Unary(UnaryExpr, Span),
+    Call(Box<Expr>, Vec<Expr>, Span),
+    Get(Box<Expr>, Box<Expr>, Span),
+    Set(Box<Expr>, Identifier, Box<Expr>, Span),
+    List(Vec<Expr>, Span),
+    Map(Vec<(Expr, Expr)>, Span),
+    Index(Box<Expr>, Box<Expr>, Span),
+    SetIndex(Box<Expr>, Box<Expr>, Box<Expr>, Span),
+    Primary(object::Object, Span),
+    Grouping(Box<Expr>, Span),
+    Lambda(Vec<Identifier>, Box<statement::Stmt>, Span),
+    Variable(Identifier, Span),
+    This(Span),
+    Super(Identifier, Span),
+}
+
+impl Expr {
+    /// span returns the Span of source the expression was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Assignment(_, _, span)
+            | Self::Logical(_, span)
+            | Self::Equality(_, span)
+            | Self::Comparison(_, span)
+            | Self::Addition(_, span)
+            | Self::Multiplication(_, span)
+            | Self::Unary(_, span)
+            | Self::Call(_, _, span)
+            | Self::Get(_, _, span)
+            | Self::Set(_, _, _, span)
+            | Self::List(_, span)
+            | Self::Map(_, span)
+            | Self::Index(_, _, span)
+            | Self::SetIndex(_, _, _, span)
+            | Self::Primary(_, span)
+            | Self::Grouping(_, span)
+            | Self::Lambda(_, _, span)
+            | Self::Variable(_, span)
+            | Self::This(span)
+            | Self::Super(_, span) => *span,
+        }
+    }
 }
 
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Assignment(i, e) => write!(f, "(= {:?} {})", &i, e),
-            Self::Logical(e) => write!(f, "{}", &e),
-            Self::Equality(e) => write!(f, "{}", &e),
-            Self::Comparison(e) => write!(f, "{}", &e),
-            Self::Addition(e) => write!(f, "{}", &e),
-            Self::Multiplication(e) => write!(f, "{}", &e),
-            Self::Unary(e) => write!(f, "{}", &e),
-            Self::Primary(e) => write!(f, "{}", &e),
-            Self::Grouping(e) => write!(f, "(Grouping {})", &e),
-            Self::Variable(i) => write!(f, "(Var {:?})", &i),
-            Self::Lambda(params, body) => write!(
+            Self::Assignment(i, e, _) => write!(f, "(= {:?} {})", &i, e),
+            Self::Logical(e, _) => write!(f, "{}", &e),
+            Self::Equality(e, _) => write!(f, "{}", &e),
+            Self::Comparison(e, _) => write!(f, "{}", &e),
+            Self::Addition(e, _) => write!(f, "{}", &e),
+            Self::Multiplication(e, _) => write!(f, "{}", &e),
+            Self::Unary(e, _) => write!(f, "{}", &e),
+            Self::Primary(e, _) => write!(f, "{}", &e),
+            Self::Grouping(e, _) => write!(f, "(Grouping {})", &e),
+            Self::Variable(i, _) => write!(f, "(Var {:?})", &i),
+            Self::This(_) => write!(f, "(This)"),
+            Self::Super(method, _) => write!(f, "(Super {})", method),
+            Self::Lambda(params, body, _) => write!(
                 f,
                 "(Lambda ({}) {})",
                 params
@@ -45,7 +84,7 @@ impl fmt::Display for Expr {
                     .join(","),
                 body
             ),
-            Self::Call(callee, args) => write!(
+            Self::Call(callee, args, _) => write!(
                 f,
                 "{}({})",
                 callee,
@@ -54,7 +93,32 @@ impl fmt::Display for Expr {
                     .collect::<Vec<String>>()
                     .join(",")
             ),
-            Self::Get(callee, prop) => write!(f, "GET ({}.{})", callee, prop),
+            Self::Get(callee, prop, _) => write!(f, "GET ({}.{})", callee, prop),
+            Self::Set(callee, prop, value, _) => {
+                write!(f, "SET ({}.{} {})", callee, prop, value)
+            }
+            Self::List(elems, _) => write!(
+                f,
+                "[{}]",
+                elems
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Map(entries, _) => write!(
+                f,
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(k, v)| format!("{}:{}", k, v))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Index(list, index, _) => write!(f, "INDEX ({}[{}])", list, index),
+            Self::SetIndex(list, index, value, _) => {
+                write!(f, "SET ({}[{}] {})", list, index, value)
+            }
         }
     }
 }
@@ -64,6 +128,7 @@ impl fmt::Display for Expr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let Logical = Expr::Logical(
@@ -72,17 +137,20 @@ impl fmt::Display for Expr {
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Bool(false)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
 ///         Box::new(
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Bool(true)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
@@ -105,6 +173,7 @@ impl fmt::Display for LogicalExpr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let comparison = Expr::Equality(
@@ -113,17 +182,20 @@ impl fmt::Display for LogicalExpr {
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
 ///         Box::new(
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
@@ -146,6 +218,7 @@ impl fmt::Display for EqualityExpr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let comparison = Expr::Comparison(
@@ -154,17 +227,20 @@ impl fmt::Display for EqualityExpr {
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
 ///         Box::new(
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
@@ -191,6 +267,7 @@ impl fmt::Display for ComparisonExpr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let addition = Expr::Addition(
@@ -199,17 +276,20 @@ impl fmt::Display for ComparisonExpr {
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
 ///         Box::new(
 ///             Expr::Primary(
 ///                 object::Object::Literal(
 ///                     object::Literal::Number(5.0)
-///                 )
+///                 ),
+///                 Span::default()
 ///             )
 ///         ),
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
@@ -232,21 +312,25 @@ impl fmt::Display for AdditionExpr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let multiplication = Expr::Multiplication(
 ///     MultiplicationExpr::Multiply(
 ///         Box::new(
 ///             Expr::Primary(
-///                 object::Object::Literal(object::Literal::Number(5.0))
+///                 object::Object::Literal(object::Literal::Number(5.0)),
+///                 Span::default()
 ///             )
 ///         ),
 ///         Box::new(
 ///             Expr::Primary(
-///                 object::Object::Literal(object::Literal::Number(5.0))
+///                 object::Object::Literal(object::Literal::Number(5.0)),
+///                 Span::default()
 ///             )
 ///         ),
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
@@ -269,16 +353,19 @@ impl fmt::Display for MultiplicationExpr {
 /// # Examples
 /// ```
 /// use rlox::ast::expression::*;
+/// use rlox::ast::span::Span;
 /// use rlox::object;
 ///
 /// let unary = Expr::Unary(
 ///     UnaryExpr::Minus(
 ///         Box::new(
 ///             Expr::Primary(
-///                 object::Object::Literal(object::Literal::Number(5.0))
+///                 object::Object::Literal(object::Literal::Number(5.0)),
+///                 Span::default()
 ///             )
 ///         )
-///     )
+///     ),
+///     Span::default()
 /// );
 /// ```
 #[derive(Debug, PartialEq, Clone)]
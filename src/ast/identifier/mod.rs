@@ -5,13 +5,46 @@ use std::fmt;
 #[cfg(test)]
 mod tests;
 
+/// Binding locates the declaration a resolved variable refers to. The id is
+/// unique to the declaration, while distance counts the scopes between the
+/// reference and the declaring scope, and slot is the variable's index within
+/// that scope.
+#[derive(Debug, PartialEq, Hash, Eq, Clone, Copy)]
+pub struct Binding {
+    pub id: usize,
+    pub distance: usize,
+    pub slot: usize,
+}
+
+impl Binding {
+    pub fn new(id: usize, distance: usize, slot: usize) -> Self {
+        Binding { id, distance, slot }
+    }
+}
+
 /// Identifier functions as a replacement for variable names, offering a raw
-/// name corresponding to a variable name, and an Id functioning as a numeric
-/// reference.
+/// name corresponding to a variable name, an Id functioning as a numeric
+/// reference and a Resolved binding produced by scope analysis. Builtin names
+/// a global defined by the host rather than the program, such as a native
+/// function or stdlib namespace, which is looked up by name.
 #[derive(Debug, PartialEq, Hash, Eq, Clone)]
 pub enum Identifier {
     Name(String),
     Id(usize),
+    Resolved(Binding),
+    Builtin(String),
+}
+
+impl Identifier {
+    /// key returns the identifier a variable is stored under within its
+    /// environment. Resolved identifiers are stored by slot, all others by
+    /// themselves.
+    pub fn key(&self) -> Identifier {
+        match self {
+            Self::Resolved(binding) => Self::Id(binding.slot),
+            other => other.clone(),
+        }
+    }
 }
 
 impl PartialEq<usize> for Identifier {
@@ -26,8 +59,9 @@ impl PartialEq<usize> for Identifier {
 impl fmt::Display for Identifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Name(ref s) => write!(f, "{}", s),
+            Self::Name(ref s) | Self::Builtin(ref s) => write!(f, "{}", s),
             Self::Id(ref u) => write!(f, "{}", u),
+            Self::Resolved(ref binding) => write!(f, "{}", binding.id),
         }
     }
 }
@@ -68,3 +102,19 @@ macro_rules! identifier_id {
         $crate::ast::identifier::Identifier::Id($id)
     };
 }
+
+#[allow(unused_macros)]
+macro_rules! identifier_builtin {
+    ($name:expr) => {
+        $crate::ast::identifier::Identifier::Builtin($name.to_string())
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! identifier_resolved {
+    ($id:expr, $distance:expr, $slot:expr) => {
+        $crate::ast::identifier::Identifier::Resolved($crate::ast::identifier::Binding::new(
+            $id, $distance, $slot,
+        ))
+    };
+}
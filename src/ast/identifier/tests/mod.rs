@@ -1,4 +1,5 @@
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use crate::ast::token;
 use std::convert::TryFrom;
 
@@ -6,7 +7,7 @@ use std::convert::TryFrom;
 fn should_convert_identfier_token_with_lexeme_to_identfier() {
     let tok = token::Token::new(
         token::TokenType::Identifier,
-        0,
+        Span::default(),
         Some("test".to_string()),
         None,
     );
@@ -21,7 +22,7 @@ fn should_convert_identfier_token_with_lexeme_to_identfier() {
 fn should_throw_an_error_if_token_not_an_identifer_on_conversion() {
     let tok = token::Token::new(
         token::TokenType::LeftParen,
-        0,
+        Span::default(),
         Some("test".to_string()),
         None,
     );
@@ -31,7 +32,7 @@ fn should_throw_an_error_if_token_not_an_identifer_on_conversion() {
 
 #[test]
 fn should_throw_an_error_if_token_has_no_lexeme() {
-    let tok = token::Token::new(token::TokenType::Identifier, 0, None, None);
+    let tok = token::Token::new(token::TokenType::Identifier, Span::default(), None, None);
 
     assert!(Identifier::try_from(tok).is_err())
 }
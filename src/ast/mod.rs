@@ -1,3 +1,5 @@
+pub mod span;
+
 #[macro_use]
 pub mod token;
 
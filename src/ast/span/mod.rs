@@ -0,0 +1,57 @@
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// Span represents a range of source characters, with start being inclusive
+/// and end exclusive, along with the line and column the range begins at.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, col: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            col,
+        }
+    }
+
+    /// merge returns a span covering both spans, positioned at whichever span
+    /// begins first.
+    pub fn merge(&self, other: &Span) -> Span {
+        let (first, _) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        Span {
+            start: first.start,
+            end: std::cmp::max(self.end, other.end),
+            line: first.line,
+            col: first.col,
+        }
+    }
+
+    /// len returns the number of characters covered by the span.
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::ast::span::Span;
+
+#[test]
+fn merge_should_cover_both_spans() {
+    let first = Span::new(0, 3, 1, 1);
+    let second = Span::new(6, 9, 1, 7);
+
+    assert_eq!(Span::new(0, 9, 1, 1), first.merge(&second));
+    assert_eq!(Span::new(0, 9, 1, 1), second.merge(&first));
+}
+
+#[test]
+fn merge_should_position_at_the_earliest_span() {
+    let first = Span::new(4, 5, 2, 1);
+    let second = Span::new(0, 2, 1, 1);
+
+    assert_eq!(Span::new(0, 5, 1, 1), first.merge(&second));
+}
+
+#[test]
+fn len_should_return_character_count() {
+    assert_eq!(3, Span::new(2, 5, 1, 3).len());
+    assert!(Span::new(2, 2, 1, 3).is_empty());
+}
@@ -1,39 +1,109 @@
 use crate::ast::expression::Expr;
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use std::fmt;
 
 /// Represents, and encapsulates statement types possiblepossible in
-/// lox currently. Further information can be found on each sub-type.
+/// lox currently. Further information can be found on each sub-type. Each
+/// variant carries the Span of source it was parsed from.
+///
+/// A Function carries both the identifier it is bound to, which is resolved
+/// by analysis, and the name it was declared with for reporting. A While
+/// carries an optional increment, evaluated after the body on each iteration,
+/// including those ended early by a `continue`, which a `for` loop desugars
+/// into. A ForIn binds its variable afresh on each iteration over the
+/// values produced by its iterable. An Import binds the namespace of the
+/// module at its path, relative to the importing file. A Try carries an
+/// optional catch clause, binding the caught error to its identifier, and an
+/// optional finally block, with at least one of the two present.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Stmt {
-    Expression(Expr),
-    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
-    While(Expr, Box<Stmt>),
-    Print(Expr),
-    Function(Identifier, Vec<Identifier>, Box<Stmt>),
-    Declaration(Identifier, Expr),
-    Return(Expr),
-    Class(Identifier, Vec<Stmt>),
-    Block(Vec<Stmt>),
+    Expression(Expr, Span),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>, Span),
+    While(Expr, Box<Stmt>, Option<Expr>, Span),
+    ForIn(Identifier, Expr, Box<Stmt>, Span),
+    Print(Expr, Span),
+    Function(Identifier, Identifier, Vec<Identifier>, Box<Stmt>, Span),
+    Declaration(Identifier, Expr, Span),
+    Return(Option<Expr>, Span),
+    Class(Identifier, Identifier, Option<Expr>, Vec<Stmt>, Span),
+    Block(Vec<Stmt>, Span),
+    Break(Span),
+    Continue(Span),
+    Import(String, Identifier, Span),
+    Throw(Expr, Span),
+    Try(
+        Box<Stmt>,
+        Option<(Identifier, Box<Stmt>)>,
+        Option<Box<Stmt>>,
+        Span,
+    ),
+}
+
+impl Stmt {
+    /// span returns the Span of source the statement was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Expression(_, span)
+            | Self::If(_, _, _, span)
+            | Self::While(_, _, _, span)
+            | Self::ForIn(_, _, _, span)
+            | Self::Print(_, span)
+            | Self::Function(_, _, _, _, span)
+            | Self::Declaration(_, _, span)
+            | Self::Return(_, span)
+            | Self::Class(_, _, _, _, span)
+            | Self::Block(_, span)
+            | Self::Break(span)
+            | Self::Continue(span)
+            | Self::Import(_, _, span)
+            | Self::Throw(_, span)
+            | Self::Try(_, _, _, span) => *span,
+        }
+    }
 }
 
 impl fmt::Display for Stmt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Expression(e) => write!(f, "(Expression {})", &e),
-            Self::If(e, tb, eb) => match eb {
+            Self::Expression(e, _) => write!(f, "(Expression {})", &e),
+            Self::If(e, tb, eb, _) => match eb {
                 Some(eb) => write!(f, "(if ({}) ({}) ({}))", &e, &tb, &eb),
                 None => write!(f, "(if ({}) ({}))", &e, &tb),
             },
-            Self::While(e, stmt) => write!(f, "(While ({}) ({})", e, stmt),
-            Self::Print(e) => write!(f, "(Print {})", &e),
-            Self::Function(name, params, block) => {
+            Self::While(e, stmt, None, _) => write!(f, "(While ({}) ({})", e, stmt),
+            Self::While(e, stmt, Some(inc), _) => {
+                write!(f, "(While ({}) ({}) ({})", e, stmt, inc)
+            }
+            Self::ForIn(id, iterable, stmt, _) => {
+                write!(f, "(ForIn {} ({}) ({}))", id, iterable, stmt)
+            }
+            Self::Print(e, _) => write!(f, "(Print {})", &e),
+            Self::Function(_, name, params, block, _) => {
                 write!(f, "(Fun {}({:?}) {}", &name, &params, &block)
             }
-            Self::Declaration(name, e) => write!(f, "(Declaration {} {}", &name, &e),
-            Self::Return(e) => write!(f, "(Return {})", &e),
-            Self::Class(id, stmts) => write!(f, "(Class {} {:?})", id, stmts),
-            Self::Block(stmts) => write!(f, "(Block {:?})", stmts),
+            Self::Declaration(name, e, _) => write!(f, "(Declaration {} {}", &name, &e),
+            Self::Return(Some(e), _) => write!(f, "(Return {})", &e),
+            Self::Return(None, _) => write!(f, "(Return)"),
+            Self::Class(_, name, Some(superclass), stmts, _) => {
+                write!(f, "(Class {} < {} {:?})", name, superclass, stmts)
+            }
+            Self::Class(_, name, None, stmts, _) => write!(f, "(Class {} {:?})", name, stmts),
+            Self::Block(stmts, _) => write!(f, "(Block {:?})", stmts),
+            Self::Break(_) => write!(f, "(Break)"),
+            Self::Continue(_) => write!(f, "(Continue)"),
+            Self::Import(path, id, _) => write!(f, "(Import {:?} {})", path, id),
+            Self::Throw(e, _) => write!(f, "(Throw {})", e),
+            Self::Try(body, catch, finally, _) => {
+                write!(f, "(Try {}", body)?;
+                if let Some((id, handler)) = catch {
+                    write!(f, " (Catch {} {})", id, handler)?;
+                }
+                if let Some(finally) = finally {
+                    write!(f, " (Finally {})", finally)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 #[macro_use]
 pub mod token_type;
 
+use crate::ast::span::Span;
 use crate::object;
 use std::fmt;
 use std::option::Option;
@@ -22,14 +23,28 @@ const RESERVED_KEYWORDS: &[(&str, TokenType)] = &[
     ("fun", TokenType::Fun),
     ("while", TokenType::While),
     ("for", TokenType::For),
+    ("in", TokenType::In),
+    ("break", TokenType::Break),
+    ("continue", TokenType::Continue),
+    ("import", TokenType::Import),
+    ("as", TokenType::As),
+    ("throw", TokenType::Throw),
+    ("try", TokenType::Try),
+    ("catch", TokenType::Catch),
+    ("finally", TokenType::Finally),
     ("if", TokenType::If),
     ("else", TokenType::Else),
 ];
 
+/// keywords returns each reserved word of the language.
+pub fn keywords() -> impl Iterator<Item = &'static str> {
+    RESERVED_KEYWORDS.iter().map(|(kw, _)| *kw)
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub token_type: TokenType,
-    pub line: usize,
+    pub span: Span,
     pub lexeme: Option<String>,
     pub object: Option<object::Object>,
 }
@@ -37,13 +52,13 @@ pub struct Token {
 impl Token {
     pub fn new(
         token_type: TokenType,
-        line: usize,
+        span: Span,
         lexeme: Option<String>,
         object: Option<object::Object>,
     ) -> Token {
         Token {
             token_type,
-            line,
+            span,
             lexeme,
             object,
         }
@@ -9,6 +9,9 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
@@ -34,19 +37,28 @@ pub enum TokenType {
 
     // Keywords
     And,
+    As,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
+    In,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -65,6 +77,9 @@ impl fmt::Display for TokenType {
                 TokenType::RightParen => ")".to_string(),
                 TokenType::LeftBrace => "{".to_string(),
                 TokenType::RightBrace => "}".to_string(),
+                TokenType::LeftBracket => "[".to_string(),
+                TokenType::RightBracket => "]".to_string(),
+                TokenType::Colon => ":".to_string(),
                 TokenType::Comma => ",".to_string(),
                 TokenType::Dot => ".".to_string(),
                 TokenType::Minus => "-".to_string(),
@@ -80,6 +95,32 @@ impl fmt::Display for TokenType {
                 TokenType::GreaterEqual => ">=".to_string(),
                 TokenType::Less => "<".to_string(),
                 TokenType::LessEqual => "<=".to_string(),
+                TokenType::And => "and".to_string(),
+                TokenType::As => "as".to_string(),
+                TokenType::Break => "break".to_string(),
+                TokenType::Catch => "catch".to_string(),
+                TokenType::Class => "class".to_string(),
+                TokenType::Continue => "continue".to_string(),
+                TokenType::Else => "else".to_string(),
+                TokenType::False => "false".to_string(),
+                TokenType::Finally => "finally".to_string(),
+                TokenType::Fun => "fun".to_string(),
+                TokenType::For => "for".to_string(),
+                TokenType::If => "if".to_string(),
+                TokenType::Import => "import".to_string(),
+                TokenType::In => "in".to_string(),
+                TokenType::Nil => "nil".to_string(),
+                TokenType::Or => "or".to_string(),
+                TokenType::Print => "print".to_string(),
+                TokenType::Return => "return".to_string(),
+                TokenType::Super => "super".to_string(),
+                TokenType::This => "this".to_string(),
+                TokenType::Throw => "throw".to_string(),
+                TokenType::True => "true".to_string(),
+                TokenType::Try => "try".to_string(),
+                TokenType::Var => "var".to_string(),
+                TokenType::While => "while".to_string(),
+                TokenType::EOF => "end of file".to_string(),
                 _ => format!("{:?}", self),
             }
         )
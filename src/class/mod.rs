@@ -1,36 +1,77 @@
 use crate::ast::identifier::Identifier;
-use crate::environment::Environment;
-use crate::functions::CallResult;
+use crate::functions::{CallResult, Function};
 use crate::instance::Instance;
+use crate::interpreter::StatefulInterpreter;
 use crate::object::Object;
+use std::collections::HashMap;
 use std::fmt;
 use std::rc::Rc;
 
+/// Methods represents a classes method table, keyed by the method name.
+pub type Methods = HashMap<Identifier, Function>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Class {
     id: Identifier,
+    name: String,
+    superclass: Option<Box<Class>>,
+    methods: Rc<Methods>,
 }
 
 impl Class {
-    pub fn new(id: &Identifier) -> Self {
-        Class { id: id.to_owned() }
+    pub fn new(id: &Identifier, superclass: Option<Class>, methods: Methods) -> Self {
+        Class {
+            id: id.to_owned(),
+            name: id.to_string(),
+            superclass: superclass.map(Box::new),
+            methods: Rc::new(methods),
+        }
+    }
+
+    /// named returns the class with the name it was declared with.
+    pub fn named(self, name: &Identifier) -> Self {
+        Class {
+            name: name.to_string(),
+            ..self
+        }
+    }
+
+    /// find_method returns the method matching the passed name if it is
+    /// defined on the class, walking the superclass chain when it is not.
+    pub fn find_method(&self, name: &Identifier) -> Option<Function> {
+        self.methods.get(name).cloned().or_else(|| {
+            self.superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name))
+        })
+    }
+
+    /// is returns true if both classes are the same class object.
+    pub fn is(&self, other: &Class) -> bool {
+        Rc::ptr_eq(&self.methods, &other.methods)
     }
 
+    /// arity returns the arity of the class's initializer, or 0 if no
+    /// initializer is defined.
     pub fn arity(&self) -> usize {
-        0
+        self.find_method(&identifier_name!("init"))
+            .map_or(0, |init| init.arity())
     }
 
-    pub fn call(
-        &self,
-        _env: Rc<Environment<Identifier, Object>>,
-        _args: Vec<Object>,
-    ) -> CallResult {
-        Ok(obj_instance!(Instance::new(self)))
+    /// call instantiates a new instance of the class, invoking the
+    /// initializer with the passed arguments if one is defined.
+    pub fn call(&self, interpreter: &StatefulInterpreter, args: Vec<Object>) -> CallResult {
+        let instance = Instance::new(self);
+        if let Some(init) = self.find_method(&identifier_name!("init")) {
+            init.bind(&instance).call(interpreter, args)?;
+        }
+
+        Ok(obj_instance!(instance))
     }
 }
 
 impl fmt::Display for Class {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Class {}", self.id)
+        write!(f, "Class {}", self.name)
     }
 }
@@ -0,0 +1,266 @@
+use crate::ast::span::Span;
+use crate::vm::value::Value;
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// OpCode represents a single instruction for the virtual machine. Operands
+/// are carried inline, with constants and names referenced by their index
+/// into the chunk's constant pool and jumps by their absolute target.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OpCode {
+    Constant(usize),
+    Nil,
+    True,
+    False,
+    Pop,
+    GetLocal(usize),
+    SetLocal(usize),
+    GetUpvalue(usize),
+    SetUpvalue(usize),
+    GetBuiltin(usize),
+    GetProperty(usize),
+    SetProperty(usize),
+    GetSuper(usize),
+    Equal,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Not,
+    Negate,
+    Print,
+    Jump(usize),
+    JumpIfFalse(usize),
+    Call(usize),
+    Closure(usize),
+    CloseUpvalue,
+    Return,
+    Class(usize),
+    Inherit,
+    Method(usize),
+    List(usize),
+    Map(usize),
+    Index,
+    SetIndex,
+    Iterate,
+    IterNext(usize),
+    Import(usize),
+    Throw,
+    Rethrow,
+}
+
+impl fmt::Display for OpCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Constant(i) => write!(f, "CONSTANT {}", i),
+            Self::Nil => write!(f, "NIL"),
+            Self::True => write!(f, "TRUE"),
+            Self::False => write!(f, "FALSE"),
+            Self::Pop => write!(f, "POP"),
+            Self::GetLocal(i) => write!(f, "GET_LOCAL {}", i),
+            Self::SetLocal(i) => write!(f, "SET_LOCAL {}", i),
+            Self::GetUpvalue(i) => write!(f, "GET_UPVALUE {}", i),
+            Self::SetUpvalue(i) => write!(f, "SET_UPVALUE {}", i),
+            Self::GetBuiltin(i) => write!(f, "GET_BUILTIN {}", i),
+            Self::GetProperty(i) => write!(f, "GET_PROPERTY {}", i),
+            Self::SetProperty(i) => write!(f, "SET_PROPERTY {}", i),
+            Self::GetSuper(i) => write!(f, "GET_SUPER {}", i),
+            Self::Equal => write!(f, "EQUAL"),
+            Self::Greater => write!(f, "GREATER"),
+            Self::GreaterEqual => write!(f, "GREATER_EQUAL"),
+            Self::Less => write!(f, "LESS"),
+            Self::LessEqual => write!(f, "LESS_EQUAL"),
+            Self::Add => write!(f, "ADD"),
+            Self::Subtract => write!(f, "SUBTRACT"),
+            Self::Multiply => write!(f, "MULTIPLY"),
+            Self::Divide => write!(f, "DIVIDE"),
+            Self::Not => write!(f, "NOT"),
+            Self::Negate => write!(f, "NEGATE"),
+            Self::Print => write!(f, "PRINT"),
+            Self::Jump(target) => write!(f, "JUMP {}", target),
+            Self::JumpIfFalse(target) => write!(f, "JUMP_IF_FALSE {}", target),
+            Self::Call(argc) => write!(f, "CALL {}", argc),
+            Self::Closure(i) => write!(f, "CLOSURE {}", i),
+            Self::CloseUpvalue => write!(f, "CLOSE_UPVALUE"),
+            Self::Return => write!(f, "RETURN"),
+            Self::Class(i) => write!(f, "CLASS {}", i),
+            Self::Inherit => write!(f, "INHERIT"),
+            Self::Method(i) => write!(f, "METHOD {}", i),
+            Self::List(len) => write!(f, "LIST {}", len),
+            Self::Map(len) => write!(f, "MAP {}", len),
+            Self::Index => write!(f, "INDEX"),
+            Self::SetIndex => write!(f, "SET_INDEX"),
+            Self::Iterate => write!(f, "ITERATE"),
+            Self::IterNext(target) => write!(f, "ITER_NEXT {}", target),
+            Self::Import(i) => write!(f, "IMPORT {}", i),
+            Self::Throw => write!(f, "THROW"),
+            Self::Rethrow => write!(f, "RETHROW"),
+        }
+    }
+}
+
+/// HandlerKind distinguishes the handler of a catch clause, which binds the
+/// error raised, from that of a finally block, which raises the error again
+/// once the block has run.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HandlerKind {
+    Catch,
+    Finally,
+}
+
+/// Handler covers a range of instructions protected by a try statement. An
+/// error raised by an instruction in the range unwinds the stack to the
+/// number of slots the frame held when the try statement was entered, pushes
+/// the error and jumps to the target. Handlers are ordered innermost first.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Handler {
+    pub start: usize,
+    pub end: usize,
+    pub target: usize,
+    pub depth: usize,
+    pub kind: HandlerKind,
+}
+
+impl Handler {
+    /// covers returns whether the instruction at the passed offset is
+    /// protected by the handler.
+    pub fn covers(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+}
+
+/// Chunk represents a sequence of instructions along with the constants they
+/// reference. Each instruction records the span of source it was compiled
+/// from for error reporting, and the handlers table records the ranges of
+/// instructions protected by try statements.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Chunk {
+    pub code: Vec<OpCode>,
+    pub spans: Vec<Span>,
+    pub constants: Vec<Value>,
+    pub handlers: Vec<Handler>,
+}
+
+impl Chunk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// write appends an instruction to the chunk, returning its offset.
+    pub fn write(&mut self, op: OpCode, span: Span) -> usize {
+        self.code.push(op);
+        self.spans.push(span);
+        self.code.len() - 1
+    }
+
+    /// add_constant appends a value to the constant pool, returning its
+    /// index. String constants are deduplicated.
+    pub fn add_constant(&mut self, value: Value) -> usize {
+        let existing = match value {
+            Value::Str(_) => self.constants.iter().position(|c| *c == value),
+            _ => None,
+        };
+
+        existing.unwrap_or_else(|| {
+            self.constants.push(value);
+            self.constants.len() - 1
+        })
+    }
+
+    /// len returns the number of instructions in the chunk.
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+}
+
+impl fmt::Display for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (offset, op) in self.code.iter().enumerate() {
+            match op {
+                OpCode::Constant(i)
+                | OpCode::GetBuiltin(i)
+                | OpCode::GetProperty(i)
+                | OpCode::SetProperty(i)
+                | OpCode::GetSuper(i)
+                | OpCode::Closure(i)
+                | OpCode::Class(i)
+                | OpCode::Method(i)
+                | OpCode::Import(i) => writeln!(
+                    f,
+                    "{:04} {:<16} ; {}",
+                    offset,
+                    op.to_string(),
+                    self.constants[*i]
+                )?,
+                _ => writeln!(f, "{:04} {}", offset, op)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// UpvalueRef describes a variable captured by a closure, either directly
+/// from a local slot of the enclosing function or from one of the enclosing
+/// function's own upvalues.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UpvalueRef {
+    pub index: usize,
+    pub is_local: bool,
+}
+
+/// FunctionKind tracks how a compiled function is invoked, which determines
+/// what occupies its first slot and what it implicitly returns.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FunctionKind {
+    Script,
+    Function,
+    Method,
+    Initializer,
+}
+
+/// Function represents a compiled function prototype. Closures over the
+/// prototype are created at runtime by the Closure instruction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Function {
+    pub name: String,
+    pub kind: FunctionKind,
+    pub arity: usize,
+    pub chunk: Chunk,
+    pub upvalues: Vec<UpvalueRef>,
+    /// globals holds the number of top-level variables defined once a
+    /// script has run. It is unused for other kinds of functions.
+    pub globals: usize,
+}
+
+impl Function {
+    pub fn new(name: &str, kind: FunctionKind, arity: usize) -> Self {
+        Function {
+            name: name.to_string(),
+            kind,
+            arity,
+            chunk: Chunk::new(),
+            upvalues: Vec::new(),
+            globals: 0,
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            FunctionKind::Script => write!(f, "<script>"),
+            _ => write!(f, "<fn {}>", self.name),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::ast::span::Span;
+use crate::compiler::chunk::*;
+use crate::vm::value::Value;
+
+#[test]
+fn write_should_return_the_offset_of_the_instruction() {
+    let mut chunk = Chunk::new();
+
+    assert_eq!(0, chunk.write(OpCode::Nil, Span::default()));
+    assert_eq!(1, chunk.write(OpCode::Return, Span::new(1, 2, 1, 2)));
+    assert_eq!(vec![Span::default(), Span::new(1, 2, 1, 2)], chunk.spans);
+}
+
+#[test]
+fn add_constant_should_deduplicate_strings() {
+    let mut chunk = Chunk::new();
+
+    assert_eq!(0, chunk.add_constant(Value::Str("name".into())));
+    assert_eq!(1, chunk.add_constant(Value::Number(1.0)));
+    assert_eq!(2, chunk.add_constant(Value::Number(1.0)));
+    assert_eq!(0, chunk.add_constant(Value::Str("name".into())));
+}
+
+#[test]
+fn chunk_should_disassemble_with_constants() {
+    let mut chunk = Chunk::new();
+    let constant = chunk.add_constant(Value::Number(1.5));
+    chunk.write(OpCode::Constant(constant), Span::default());
+    chunk.write(OpCode::Print, Span::default());
+
+    assert_eq!(
+        "0000 CONSTANT 0       ; 1.5\n0001 PRINT\n",
+        chunk.to_string()
+    );
+}
@@ -0,0 +1,1085 @@
+use crate::ast::expression::{
+    AdditionExpr, ComparisonExpr, EqualityExpr, Expr, LogicalExpr, MultiplicationExpr, UnaryExpr,
+};
+use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::diagnostic::Diagnostic;
+use crate::pass::*;
+use crate::vm::value::Value;
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+
+pub mod chunk;
+use chunk::{Function, FunctionKind, Handler, HandlerKind, OpCode, UpvalueRef};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(PartialEq, Debug)]
+pub enum CompileErr {
+    Unresolved(Identifier, Span),
+    Unsupported(&'static str, Span),
+}
+
+impl CompileErr {
+    /// span returns the span of source the error occurred at.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Unresolved(_, span) | Self::Unsupported(_, span) => *span,
+        }
+    }
+}
+
+impl fmt::Display for CompileErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unresolved(id, _) => write!(f, "unresolved variable '{}'", id),
+            Self::Unsupported(e, _) => write!(f, "unsupported {}", e),
+        }
+    }
+}
+
+impl From<CompileErr> for Diagnostic {
+    fn from(err: CompileErr) -> Self {
+        let code = match err {
+            CompileErr::Unresolved(_, _) => "E0400",
+            CompileErr::Unsupported(_, _) => "E0401",
+        };
+
+        Diagnostic::error(code, &err.to_string(), err.span())
+    }
+}
+
+/// Slot identifies what occupies a local slot of a function. Variables are
+/// keyed by the offset assigned by the ScopeAnalyzer, while `this`, `super`,
+/// the iterator of a for-in loop, a value being returned through a finally
+/// block and an error pending the end of a finally block are implicit locals
+/// the compiler introduces itself.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Slot {
+    Callee,
+    Id(usize),
+    This,
+    Super,
+    Iterator,
+    Result,
+    Error,
+}
+
+#[derive(Debug)]
+struct Local {
+    slot: Slot,
+    depth: usize,
+    is_captured: bool,
+}
+
+/// Loop tracks the scope depth a loop was entered at, the number of try
+/// statements enclosing it, and the jumps emitted by each `break` and
+/// `continue` within it, which are patched once the loop's exit and
+/// increment have been compiled.
+struct Loop {
+    depth: usize,
+    trys: usize,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+/// Try tracks a try statement whose protected instructions are being
+/// compiled. The instructions of a finally block inlined where a `return`,
+/// `break` or `continue` leaves the statement are not protected by it, so
+/// its protected ranges are closed around them and reopened afterwards.
+struct Try {
+    depth: usize,
+    finally: Option<Stmt>,
+    ranges: Vec<(usize, usize)>,
+    opened: Option<usize>,
+}
+
+impl Try {
+    fn close(&mut self, offset: usize) {
+        if let Some(start) = self.opened.take().filter(|start| *start < offset) {
+            self.ranges.push((start, offset));
+        }
+    }
+}
+
+/// FunctionState tracks the locals, scope depth and enclosing loops and try
+/// statements of a function while its body is being compiled.
+struct FunctionState {
+    function: Function,
+    locals: Vec<Local>,
+    scope_depth: usize,
+    loops: Vec<Loop>,
+    trys: Vec<Try>,
+}
+
+impl FunctionState {
+    fn new(function: Function) -> Self {
+        let receiver = match function.kind {
+            FunctionKind::Method | FunctionKind::Initializer => Slot::This,
+            FunctionKind::Script | FunctionKind::Function => Slot::Callee,
+        };
+
+        FunctionState {
+            function,
+            locals: vec![Local {
+                slot: receiver,
+                depth: 0,
+                is_captured: false,
+            }],
+            scope_depth: 0,
+            loops: Vec::new(),
+            trys: Vec::new(),
+        }
+    }
+
+    fn resolve_local(&self, slot: Slot) -> Option<usize> {
+        self.locals.iter().rposition(|local| local.slot == slot)
+    }
+
+    fn add_upvalue(&mut self, upvalue: UpvalueRef) -> usize {
+        let upvalues = &mut self.function.upvalues;
+        upvalues
+            .iter()
+            .position(|u| *u == upvalue)
+            .unwrap_or_else(|| {
+                upvalues.push(upvalue);
+                upvalues.len() - 1
+            })
+    }
+}
+
+/// Compiler lowers a program that has been resolved by the ScopeAnalyzer into
+/// a chunk of bytecode for the virtual machine. Top-level variables are kept
+/// as locals of the script and persist between passes, so that successive
+/// programs compiled by the same Compiler can refer to each other's
+/// variables.
+pub struct Compiler {
+    functions: Vec<FunctionState>,
+}
+
+impl Compiler {
+    pub fn new() -> Compiler {
+        Self::default()
+    }
+
+    /// global returns the stack slot a top-level variable, resolved by the
+    /// ScopeAnalyzer, is held in by the virtual machine.
+    pub fn global(&self, id: &Identifier) -> Option<usize> {
+        match id {
+            Identifier::Resolved(binding) => self.functions[0].resolve_local(Slot::Id(binding.id)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self {
+            functions: vec![FunctionState::new(Function::new(
+                "script",
+                FunctionKind::Script,
+                0,
+            ))],
+        }
+    }
+}
+
+type CompileResult = Result<(), CompileErr>;
+
+impl PassMut<Vec<Stmt>, Rc<Function>> for Compiler {
+    type Error = CompileErr;
+
+    fn tree_pass(&mut self, input: Vec<Stmt>) -> Result<Rc<Function>, CompileErr> {
+        let defined = self.functions[0].locals.len();
+        let compiled = input.into_iter().try_for_each(|stmt| self.tree_pass(stmt));
+
+        // discard any state left over from a failed compilation.
+        self.functions.truncate(1);
+        let script = &mut self.functions[0];
+        script.scope_depth = 0;
+        script.loops.clear();
+        script.trys.clear();
+        match compiled {
+            Ok(_) => script.locals.retain(|local| local.depth == 0),
+            Err(_) => script.locals.truncate(defined),
+        }
+
+        let mut function = std::mem::replace(
+            &mut script.function,
+            Function::new("script", FunctionKind::Script, 0),
+        );
+        function.globals = script.locals.len() - 1;
+        compiled?;
+
+        function.chunk.write(OpCode::Nil, Span::default());
+        function.chunk.write(OpCode::Return, Span::default());
+
+        Ok(Rc::new(function))
+    }
+}
+
+impl PassMut<Stmt, ()> for Compiler {
+    type Error = CompileErr;
+
+    fn tree_pass(&mut self, input: Stmt) -> CompileResult {
+        match input {
+            Stmt::Expression(expr, span) => {
+                self.tree_pass(expr)?;
+                self.emit(OpCode::Pop, span);
+                Ok(())
+            }
+            Stmt::If(cond, tb, eb, span) => self.compile_if(cond, *tb, eb.map(|b| *b), span),
+            Stmt::While(cond, body, inc, span) => self.compile_while(cond, *body, inc, span),
+            Stmt::ForIn(id, iterable, body, span) => self.compile_for_in(id, iterable, *body, span),
+            Stmt::Import(path, id, span) => {
+                let path = self.make_constant(Value::Str(path.into()));
+                self.emit(OpCode::Import(path), span);
+                self.define_variable(&id, span)
+            }
+            Stmt::Throw(expr, span) => {
+                self.tree_pass(expr)?;
+                self.emit(OpCode::Throw, span);
+                Ok(())
+            }
+            Stmt::Try(body, catch, finally, span) => {
+                self.compile_try(*body, catch, finally.map(|f| *f), span)
+            }
+            Stmt::Print(expr, span) => {
+                self.tree_pass(expr)?;
+                self.emit(OpCode::Print, span);
+                Ok(())
+            }
+            Stmt::Function(id, name, params, body, span) => {
+                self.compile_function_decl(id, name, params, *body, span)
+            }
+            Stmt::Declaration(name, expr, span) => self.compile_declaration(name, expr, span),
+            Stmt::Return(expr, span) => self.compile_return(expr, span),
+            Stmt::Class(id, name, superclass, methods, span) => {
+                self.compile_class(id, name, superclass, methods, span)
+            }
+            Stmt::Block(stmts, span) => {
+                self.begin_scope();
+                let compiled = stmts.into_iter().try_for_each(|stmt| self.tree_pass(stmt));
+                self.end_scope(span);
+                compiled
+            }
+            Stmt::Break(span) => self.compile_loop_jump(true, span),
+            Stmt::Continue(span) => self.compile_loop_jump(false, span),
+        }
+    }
+}
+
+impl PassMut<Box<Stmt>, ()> for Compiler {
+    type Error = CompileErr;
+
+    fn tree_pass(&mut self, input: Box<Stmt>) -> CompileResult {
+        self.tree_pass(*input)
+    }
+}
+
+impl Compiler {
+    fn current(&mut self) -> &mut FunctionState {
+        self.functions.last_mut().unwrap()
+    }
+
+    fn emit(&mut self, op: OpCode, span: Span) -> usize {
+        self.current().function.chunk.write(op, span)
+    }
+
+    fn emit_constant(&mut self, value: Value, span: Span) {
+        let constant = self.make_constant(value);
+        self.emit(OpCode::Constant(constant), span);
+    }
+
+    fn make_constant(&mut self, value: Value) -> usize {
+        self.current().function.chunk.add_constant(value)
+    }
+
+    fn make_name(&mut self, id: &Identifier) -> usize {
+        self.make_constant(Value::Str(id.to_string().into()))
+    }
+
+    /// patch_jump points a previously emitted jump at the next instruction
+    /// to be emitted.
+    fn patch_jump(&mut self, offset: usize) {
+        let chunk = &mut self.current().function.chunk;
+        let target = chunk.len();
+        chunk.code[offset] = match chunk.code[offset] {
+            OpCode::Jump(_) => OpCode::Jump(target),
+            OpCode::JumpIfFalse(_) => OpCode::JumpIfFalse(target),
+            OpCode::IterNext(_) => OpCode::IterNext(target),
+            op => op,
+        };
+    }
+
+    fn begin_scope(&mut self) {
+        self.current().scope_depth += 1;
+    }
+
+    /// end_scope discards the locals declared in the current scope, closing
+    /// over any that were captured by a closure.
+    fn end_scope(&mut self, span: Span) {
+        self.current().scope_depth -= 1;
+
+        let depth = self.current().scope_depth;
+        self.discard_locals(depth, span);
+
+        let state = self.current();
+        let retained = state
+            .locals
+            .iter()
+            .position(|local| local.depth > depth)
+            .unwrap_or(state.locals.len());
+        state.locals.truncate(retained);
+    }
+
+    /// discard_locals emits the instructions discarding every local declared
+    /// deeper than the passed scope depth, closing over any that were
+    /// captured by a closure, without forgetting the locals themselves.
+    fn discard_locals(&mut self, depth: usize, span: Span) {
+        let ops: Vec<OpCode> = self
+            .current()
+            .locals
+            .iter()
+            .rev()
+            .take_while(|local| local.depth > depth)
+            .map(|local| match local.is_captured {
+                true => OpCode::CloseUpvalue,
+                false => OpCode::Pop,
+            })
+            .collect();
+
+        for op in ops {
+            self.emit(op, span);
+        }
+    }
+
+    fn add_local(&mut self, slot: Slot) {
+        let state = self.current();
+        let depth = state.scope_depth;
+        state.locals.push(Local {
+            slot,
+            depth,
+            is_captured: false,
+        });
+    }
+
+    /// declared_in_scope returns the local slot of a variable if it has
+    /// already been declared in the current scope.
+    fn declared_in_scope(&mut self, slot: Slot) -> Option<usize> {
+        let state = self.current();
+        state
+            .resolve_local(slot)
+            .filter(|local| state.locals[*local].depth == state.scope_depth)
+    }
+
+    /// define_variable binds the value on top of the stack to a variable. A
+    /// new variable takes ownership of the stack slot, while a variable
+    /// redeclared in the same scope is assigned to.
+    fn define_variable(&mut self, id: &Identifier, span: Span) -> CompileResult {
+        let slot = Self::slot_of(id, span)?;
+        match self.declared_in_scope(slot) {
+            Some(local) => {
+                self.emit(OpCode::SetLocal(local), span);
+                self.emit(OpCode::Pop, span);
+            }
+            None => self.add_local(slot),
+        }
+
+        Ok(())
+    }
+
+    fn slot_of(id: &Identifier, span: Span) -> Result<Slot, CompileErr> {
+        match id {
+            Identifier::Id(offset) => Ok(Slot::Id(*offset)),
+            Identifier::Resolved(binding) => Ok(Slot::Id(binding.id)),
+            Identifier::Name(_) | Identifier::Builtin(_) => {
+                Err(CompileErr::Unresolved(id.clone(), span))
+            }
+        }
+    }
+
+    /// resolve_upvalue captures a variable from the functions enclosing the
+    /// function at the passed depth, returning the upvalue index it can be
+    /// referenced by.
+    fn resolve_upvalue(&mut self, function: usize, slot: Slot) -> Option<usize> {
+        if function == 0 {
+            return None;
+        }
+
+        let enclosing = function - 1;
+        if let Some(local) = self.functions[enclosing].resolve_local(slot) {
+            self.functions[enclosing].locals[local].is_captured = true;
+            return Some(self.functions[function].add_upvalue(UpvalueRef {
+                index: local,
+                is_local: true,
+            }));
+        }
+
+        self.resolve_upvalue(enclosing, slot).map(|upvalue| {
+            self.functions[function].add_upvalue(UpvalueRef {
+                index: upvalue,
+                is_local: false,
+            })
+        })
+    }
+
+    fn resolve(&mut self, slot: Slot) -> Option<(OpCode, OpCode)> {
+        let current = self.functions.len() - 1;
+        if let Some(local) = self.functions[current].resolve_local(slot) {
+            return Some((OpCode::GetLocal(local), OpCode::SetLocal(local)));
+        }
+
+        self.resolve_upvalue(current, slot)
+            .map(|upvalue| (OpCode::GetUpvalue(upvalue), OpCode::SetUpvalue(upvalue)))
+    }
+
+    fn get_variable(&mut self, slot: Slot, id: Identifier, span: Span) -> CompileResult {
+        let (get, _) = self.resolve(slot).ok_or(CompileErr::Unresolved(id, span))?;
+        self.emit(get, span);
+        Ok(())
+    }
+
+    /// get_builtin looks up a global defined by the host, such as a native
+    /// function or stdlib namespace, by name.
+    fn get_builtin(&mut self, name: &str, span: Span) -> CompileResult {
+        let name = self.make_constant(Value::Str(name.into()));
+        self.emit(OpCode::GetBuiltin(name), span);
+        Ok(())
+    }
+
+    fn compile_declaration(&mut self, id: Identifier, expr: Expr, span: Span) -> CompileResult {
+        self.tree_pass(expr)?;
+        self.define_variable(&id, span)
+    }
+
+    fn compile_function_decl(
+        &mut self,
+        id: Identifier,
+        name: Identifier,
+        params: Vec<Identifier>,
+        body: Stmt,
+        span: Span,
+    ) -> CompileResult {
+        // a new function is declared before its body is compiled so that it
+        // may refer to itself.
+        let slot = Self::slot_of(&id, span)?;
+        let redeclared = self.declared_in_scope(slot);
+        if redeclared.is_none() {
+            self.add_local(slot);
+        }
+
+        self.compile_function(FunctionKind::Function, &name, params, body, span)?;
+        if let Some(local) = redeclared {
+            self.emit(OpCode::SetLocal(local), span);
+            self.emit(OpCode::Pop, span);
+        }
+
+        Ok(())
+    }
+
+    /// compile_function compiles a function body into its own prototype,
+    /// emitting a Closure instruction to the enclosing function.
+    fn compile_function(
+        &mut self,
+        kind: FunctionKind,
+        name: &Identifier,
+        params: Vec<Identifier>,
+        body: Stmt,
+        span: Span,
+    ) -> CompileResult {
+        let function = Function::new(&name.to_string(), kind, params.len());
+        self.functions.push(FunctionState::new(function));
+        self.begin_scope();
+
+        for param in params.iter() {
+            let slot = Self::slot_of(param, span)?;
+            self.add_local(slot);
+        }
+
+        self.tree_pass(body)?;
+        self.emit_implicit_return(span);
+
+        let function = self.functions.pop().unwrap().function;
+        let constant = self.make_constant(Value::Function(Rc::new(function)));
+        self.emit(OpCode::Closure(constant), span);
+        Ok(())
+    }
+
+    /// emit_implicit_return returns `this` from initializers, and nil from
+    /// any other function.
+    fn emit_implicit_return(&mut self, span: Span) {
+        self.emit_implicit_result(span);
+        self.emit(OpCode::Return, span);
+    }
+
+    fn emit_implicit_result(&mut self, span: Span) {
+        match self.current().function.kind {
+            FunctionKind::Initializer => self.emit(OpCode::GetLocal(0), span),
+            _ => self.emit(OpCode::Nil, span),
+        };
+    }
+
+    /// compile_return runs the finally blocks of each try statement the
+    /// return leaves, with the result held in an implicit local meanwhile.
+    fn compile_return(&mut self, expr: Option<Expr>, span: Span) -> CompileResult {
+        match expr {
+            Some(e) => self.tree_pass(e)?,
+            None => self.emit_implicit_result(span),
+        };
+
+        self.add_local(Slot::Result);
+        let compiled = self.leave_trys(0);
+        self.emit(OpCode::Return, span);
+        self.current().locals.pop();
+        self.reopen_trys(0);
+        compiled
+    }
+
+    fn compile_class(
+        &mut self,
+        id: Identifier,
+        name: Identifier,
+        superclass: Option<Expr>,
+        methods: Vec<Stmt>,
+        span: Span,
+    ) -> CompileResult {
+        let slot = Self::slot_of(&id, span)?;
+        let name = self.make_name(&name);
+        self.emit(OpCode::Class(name), span);
+        self.define_variable(&id, span)?;
+
+        // a subclass's methods close over an implicit `super` local holding
+        // the superclass.
+        let has_superclass = superclass.is_some();
+        if let Some(sc) = superclass {
+            let sc_span = sc.span();
+            self.begin_scope();
+            self.tree_pass(sc)?;
+            self.add_local(Slot::Super);
+            self.get_variable(slot, id.clone(), span)?;
+            self.emit(OpCode::Inherit, sc_span);
+        }
+
+        self.get_variable(slot, id.clone(), span)?;
+        for method in methods {
+            match method {
+                Stmt::Function(_, name, params, body, method_span) => {
+                    let kind = if name == identifier_name!("init") {
+                        FunctionKind::Initializer
+                    } else {
+                        FunctionKind::Method
+                    };
+                    self.compile_function(kind, &name, params, *body, method_span)?;
+
+                    let name = self.make_name(&name);
+                    self.emit(OpCode::Method(name), method_span);
+                }
+                _ => return Err(CompileErr::Unsupported("class member", method.span())),
+            }
+        }
+        self.emit(OpCode::Pop, span);
+
+        if has_superclass {
+            self.end_scope(span);
+        }
+
+        Ok(())
+    }
+
+    fn compile_if(&mut self, cond: Expr, tb: Stmt, eb: Option<Stmt>, span: Span) -> CompileResult {
+        self.tree_pass(cond)?;
+        let then_jump = self.emit(OpCode::JumpIfFalse(0), span);
+        self.emit(OpCode::Pop, span);
+        self.tree_pass(tb)?;
+
+        let else_jump = self.emit(OpCode::Jump(0), span);
+        self.patch_jump(then_jump);
+        self.emit(OpCode::Pop, span);
+        if let Some(stmt) = eb {
+            self.tree_pass(stmt)?;
+        }
+        self.patch_jump(else_jump);
+
+        Ok(())
+    }
+
+    fn compile_while(
+        &mut self,
+        cond: Expr,
+        body: Stmt,
+        increment: Option<Expr>,
+        span: Span,
+    ) -> CompileResult {
+        let loop_start = self.current().function.chunk.len();
+        self.tree_pass(cond)?;
+
+        let exit_jump = self.emit(OpCode::JumpIfFalse(0), span);
+        self.emit(OpCode::Pop, span);
+
+        let depth = self.current().scope_depth;
+        let trys = self.current().trys.len();
+        self.current().loops.push(Loop {
+            depth,
+            trys,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+        let compiled = self.tree_pass(body);
+        let compiled_loop = self.current().loops.pop().unwrap();
+        compiled?;
+
+        for jump in compiled_loop.continues {
+            self.patch_jump(jump);
+        }
+        if let Some(inc) = increment {
+            let inc_span = inc.span();
+            self.tree_pass(inc)?;
+            self.emit(OpCode::Pop, inc_span);
+        }
+        self.emit(OpCode::Jump(loop_start), span);
+
+        self.patch_jump(exit_jump);
+        self.emit(OpCode::Pop, span);
+        for jump in compiled_loop.breaks {
+            self.patch_jump(jump);
+        }
+
+        Ok(())
+    }
+
+    /// compile_for_in holds the loop's iterator in an implicit local while
+    /// the loop runs. Each item is bound to the loop variable in a scope of
+    /// its own, so that closures capture the item of their own iteration.
+    fn compile_for_in(
+        &mut self,
+        id: Identifier,
+        iterable: Expr,
+        body: Stmt,
+        span: Span,
+    ) -> CompileResult {
+        let iterable_span = iterable.span();
+        self.begin_scope();
+        self.tree_pass(iterable)?;
+        self.emit(OpCode::Iterate, iterable_span);
+        self.add_local(Slot::Iterator);
+
+        let loop_start = self.current().function.chunk.len();
+        let exit_jump = self.emit(OpCode::IterNext(0), iterable_span);
+
+        let depth = self.current().scope_depth;
+        let trys = self.current().trys.len();
+        self.current().loops.push(Loop {
+            depth,
+            trys,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+        self.begin_scope();
+        let compiled = Self::slot_of(&id, span).and_then(|slot| {
+            self.add_local(slot);
+            self.tree_pass(body)
+        });
+        self.end_scope(span);
+        let compiled_loop = self.current().loops.pop().unwrap();
+        compiled?;
+
+        for jump in compiled_loop.continues {
+            self.patch_jump(jump);
+        }
+        self.emit(OpCode::Jump(loop_start), span);
+
+        self.patch_jump(exit_jump);
+        for jump in compiled_loop.breaks {
+            self.patch_jump(jump);
+        }
+        self.end_scope(span);
+
+        Ok(())
+    }
+
+    /// compile_loop_jump discards the locals declared within the innermost
+    /// loop and jumps out of it for a `break`, or on to its increment for a
+    /// `continue`. Both jumps are patched once the loop has been compiled.
+    fn compile_loop_jump(&mut self, is_break: bool, span: Span) -> CompileResult {
+        let (depth, trys) = match self.current().loops.last() {
+            Some(compiled_loop) => (compiled_loop.depth, compiled_loop.trys),
+            None if is_break => {
+                return Err(CompileErr::Unsupported("break outside of a loop", span))
+            }
+            None => return Err(CompileErr::Unsupported("continue outside of a loop", span)),
+        };
+
+        self.leave_trys(trys)?;
+        self.discard_locals(depth, span);
+        let jump = self.emit(OpCode::Jump(0), span);
+        self.reopen_trys(trys);
+        let compiled_loop = self.current().loops.last_mut().unwrap();
+        if is_break {
+            compiled_loop.breaks.push(jump);
+        } else {
+            compiled_loop.continues.push(jump);
+        }
+
+        Ok(())
+    }
+
+    /// compile_try compiles a try statement with both a catch clause and a
+    /// finally block as a try-catch protected by a try-finally.
+    fn compile_try(
+        &mut self,
+        body: Stmt,
+        catch: Option<(Identifier, Box<Stmt>)>,
+        finally: Option<Stmt>,
+        span: Span,
+    ) -> CompileResult {
+        match (catch, finally) {
+            (Some((id, handler)), Some(finally)) => {
+                self.compile_finally(|c| c.compile_catch(body, id, *handler, span), finally, span)
+            }
+            (Some((id, handler)), None) => self.compile_catch(body, id, *handler, span),
+            (None, Some(finally)) => self.compile_finally(|c| c.tree_pass(body), finally, span),
+            (None, None) => self.tree_pass(body),
+        }
+    }
+
+    /// compile_catch jumps over the catch clause once the body completes. An
+    /// error raised within the body is bound to the caught variable, in a
+    /// scope of its own enclosing the handler.
+    fn compile_catch(
+        &mut self,
+        body: Stmt,
+        id: Identifier,
+        handler: Stmt,
+        span: Span,
+    ) -> CompileResult {
+        let protected = self.protect(None, |c| c.tree_pass(body))?;
+        let exit_jump = self.emit(OpCode::Jump(0), span);
+        self.add_handlers(protected, HandlerKind::Catch);
+
+        self.begin_scope();
+        let compiled = Self::slot_of(&id, span).and_then(|slot| {
+            self.add_local(slot);
+            self.tree_pass(handler)
+        });
+        self.end_scope(span);
+        compiled?;
+
+        self.patch_jump(exit_jump);
+        Ok(())
+    }
+
+    /// compile_finally runs the finally block once the body completes, and
+    /// again on an error raised within the body, raising the error once the
+    /// block completes.
+    fn compile_finally<F>(&mut self, body: F, finally: Stmt, span: Span) -> CompileResult
+    where
+        F: FnOnce(&mut Self) -> CompileResult,
+    {
+        let protected = self.protect(Some(finally.clone()), body)?;
+        self.tree_pass(finally.clone())?;
+        let exit_jump = self.emit(OpCode::Jump(0), span);
+        self.add_handlers(protected, HandlerKind::Finally);
+
+        self.add_local(Slot::Error);
+        let compiled = self.tree_pass(finally);
+        self.emit(OpCode::Rethrow, span);
+        self.current().locals.pop();
+        compiled?;
+
+        self.patch_jump(exit_jump);
+        Ok(())
+    }
+
+    /// protect compiles the instructions protected by a try statement,
+    /// returning the statement's protected ranges.
+    fn protect<F>(&mut self, finally: Option<Stmt>, body: F) -> Result<Try, CompileErr>
+    where
+        F: FnOnce(&mut Self) -> CompileResult,
+    {
+        let state = self.current();
+        let offset = state.function.chunk.len();
+        let depth = state.locals.len();
+        state.trys.push(Try {
+            depth,
+            finally,
+            ranges: Vec::new(),
+            opened: Some(offset),
+        });
+
+        let compiled = body(self);
+        let offset = self.current().function.chunk.len();
+        let mut protected = self.current().trys.pop().unwrap();
+        protected.close(offset);
+        compiled.map(|_| protected)
+    }
+
+    /// add_handlers records a handler jumping to the next instruction to be
+    /// emitted for each of a try statement's protected ranges.
+    fn add_handlers(&mut self, protected: Try, kind: HandlerKind) {
+        let chunk = &mut self.current().function.chunk;
+        let target = chunk.len();
+        for (start, end) in protected.ranges {
+            chunk.handlers.push(Handler {
+                start,
+                end,
+                target,
+                depth: protected.depth,
+                kind,
+            });
+        }
+    }
+
+    /// leave_trys inlines the finally blocks of each try statement a
+    /// `return`, `break` or `continue` leaves, from the innermost out,
+    /// closing the protected ranges of each statement before its block. A
+    /// finally block is compiled as if its try statement were already left,
+    /// so that a jump within the block leaves only the enclosing statements.
+    fn leave_trys(&mut self, from: usize) -> CompileResult {
+        for index in (from..self.current().trys.len()).rev() {
+            let offset = self.current().function.chunk.len();
+            let left = &mut self.current().trys[index];
+            left.close(offset);
+
+            if let Some(finally) = left.finally.clone() {
+                let left = self.current().trys.split_off(index);
+                let compiled = self.tree_pass(finally);
+                self.current().trys.extend(left);
+                compiled?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// reopen_trys reopens the protected ranges closed by leave_trys, once
+    /// the jump out of the try statements has been emitted.
+    fn reopen_trys(&mut self, from: usize) {
+        let state = self.current();
+        let offset = state.function.chunk.len();
+        for protected in state.trys[from..].iter_mut() {
+            protected.opened = Some(offset);
+        }
+    }
+}
+
+impl PassMut<Expr, ()> for Compiler {
+    type Error = CompileErr;
+
+    fn tree_pass(&mut self, expr: Expr) -> CompileResult {
+        match expr {
+            Expr::Grouping(expr, _) => self.tree_pass(expr),
+            Expr::Lambda(params, body, span) => self.compile_function(
+                FunctionKind::Function,
+                &identifier_name!("lambda"),
+                params,
+                *body,
+                span,
+            ),
+            Expr::Variable(Identifier::Builtin(name), span) => self.get_builtin(&name, span),
+            Expr::Variable(id, span) => self.get_variable(Self::slot_of(&id, span)?, id, span),
+            Expr::This(span) => self.get_variable(Slot::This, identifier_name!("this"), span),
+            Expr::Super(method, span) => self.compile_super(method, span),
+            Expr::Primary(obj, span) => self.compile_primary(obj, span),
+            Expr::Call(callee, args, span) => self.compile_call(*callee, args, span),
+            Expr::Get(instance, param, span) => self.compile_get(*instance, *param, span),
+            Expr::Set(instance, param, value, span) => {
+                self.compile_set(*instance, param, *value, span)
+            }
+            Expr::List(items, span) => self.compile_list(items, span),
+            Expr::Map(entries, span) => self.compile_map(entries, span),
+            Expr::Index(collection, index, span) => {
+                self.tree_pass(collection)?;
+                self.tree_pass(index)?;
+                self.emit(OpCode::Index, span);
+                Ok(())
+            }
+            Expr::SetIndex(collection, index, value, span) => {
+                self.tree_pass(collection)?;
+                self.tree_pass(index)?;
+                self.tree_pass(value)?;
+                self.emit(OpCode::SetIndex, span);
+                Ok(())
+            }
+            Expr::Unary(expr, span) => self.compile_unary(expr, span),
+            Expr::Multiplication(expr, span) => match expr {
+                MultiplicationExpr::Multiply(l, r) => {
+                    self.compile_binary(*l, *r, OpCode::Multiply, span)
+                }
+                MultiplicationExpr::Divide(l, r) => {
+                    self.compile_binary(*l, *r, OpCode::Divide, span)
+                }
+            },
+            Expr::Addition(expr, span) => match expr {
+                AdditionExpr::Add(l, r) => self.compile_binary(*l, *r, OpCode::Add, span),
+                AdditionExpr::Subtract(l, r) => self.compile_binary(*l, *r, OpCode::Subtract, span),
+            },
+            Expr::Comparison(expr, span) => match expr {
+                ComparisonExpr::Less(l, r) => self.compile_binary(*l, *r, OpCode::Less, span),
+                ComparisonExpr::LessEqual(l, r) => {
+                    self.compile_binary(*l, *r, OpCode::LessEqual, span)
+                }
+                ComparisonExpr::Greater(l, r) => self.compile_binary(*l, *r, OpCode::Greater, span),
+                ComparisonExpr::GreaterEqual(l, r) => {
+                    self.compile_binary(*l, *r, OpCode::GreaterEqual, span)
+                }
+            },
+            Expr::Equality(expr, span) => match expr {
+                EqualityExpr::Equal(l, r) => self.compile_binary(*l, *r, OpCode::Equal, span),
+                EqualityExpr::NotEqual(l, r) => {
+                    self.compile_binary(*l, *r, OpCode::Equal, span)?;
+                    self.emit(OpCode::Not, span);
+                    Ok(())
+                }
+            },
+            Expr::Logical(expr, span) => self.compile_logical(expr, span),
+            Expr::Assignment(id, expr, span) => self.compile_assignment(id, *expr, span),
+        }
+    }
+}
+
+impl PassMut<Box<Expr>, ()> for Compiler {
+    type Error = CompileErr;
+
+    fn tree_pass(&mut self, expr: Box<Expr>) -> CompileResult {
+        self.tree_pass(*expr)
+    }
+}
+
+impl Compiler {
+    fn compile_primary(&mut self, obj: crate::object::Object, span: Span) -> CompileResult {
+        match Value::try_from(obj) {
+            Ok(Value::Nil) => self.emit(OpCode::Nil, span),
+            Ok(Value::Bool(true)) => self.emit(OpCode::True, span),
+            Ok(Value::Bool(false)) => self.emit(OpCode::False, span),
+            Ok(value) => {
+                self.emit_constant(value, span);
+                return Ok(());
+            }
+            Err(_) => return Err(CompileErr::Unsupported("object literal", span)),
+        };
+
+        Ok(())
+    }
+
+    fn compile_binary(&mut self, left: Expr, right: Expr, op: OpCode, span: Span) -> CompileResult {
+        self.tree_pass(left)?;
+        self.tree_pass(right)?;
+        self.emit(op, span);
+        Ok(())
+    }
+
+    fn compile_unary(&mut self, expr: UnaryExpr, span: Span) -> CompileResult {
+        let (expr, op) = match expr {
+            UnaryExpr::Bang(e) => (e, OpCode::Not),
+            UnaryExpr::Minus(e) => (e, OpCode::Negate),
+        };
+
+        self.tree_pass(expr)?;
+        self.emit(op, span);
+        Ok(())
+    }
+
+    /// compile_logical short-circuits, leaving the left operand on the stack
+    /// when it determines the result.
+    fn compile_logical(&mut self, expr: LogicalExpr, span: Span) -> CompileResult {
+        match expr {
+            LogicalExpr::And(left, right) => {
+                self.tree_pass(left)?;
+                let end_jump = self.emit(OpCode::JumpIfFalse(0), span);
+                self.emit(OpCode::Pop, span);
+                self.tree_pass(right)?;
+                self.patch_jump(end_jump);
+            }
+            LogicalExpr::Or(left, right) => {
+                self.tree_pass(left)?;
+                let else_jump = self.emit(OpCode::JumpIfFalse(0), span);
+                let end_jump = self.emit(OpCode::Jump(0), span);
+                self.patch_jump(else_jump);
+                self.emit(OpCode::Pop, span);
+                self.tree_pass(right)?;
+                self.patch_jump(end_jump);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compile_assignment(&mut self, id: Identifier, expr: Expr, span: Span) -> CompileResult {
+        self.tree_pass(expr)?;
+
+        let (_, set) = self
+            .resolve(Self::slot_of(&id, span)?)
+            .ok_or(CompileErr::Unresolved(id, span))?;
+        self.emit(set, span);
+        Ok(())
+    }
+
+    fn compile_call(&mut self, callee: Expr, args: Vec<Expr>, span: Span) -> CompileResult {
+        let argc = args.len();
+        self.tree_pass(callee)?;
+        for arg in args {
+            self.tree_pass(arg)?;
+        }
+
+        self.emit(OpCode::Call(argc), span);
+        Ok(())
+    }
+
+    fn compile_list(&mut self, items: Vec<Expr>, span: Span) -> CompileResult {
+        let len = items.len();
+        for item in items {
+            self.tree_pass(item)?;
+        }
+
+        self.emit(OpCode::List(len), span);
+        Ok(())
+    }
+
+    fn compile_map(&mut self, entries: Vec<(Expr, Expr)>, span: Span) -> CompileResult {
+        let len = entries.len();
+        for (key, value) in entries {
+            self.tree_pass(key)?;
+            self.tree_pass(value)?;
+        }
+
+        self.emit(OpCode::Map(len), span);
+        Ok(())
+    }
+
+    fn compile_get(&mut self, instance: Expr, param: Expr, span: Span) -> CompileResult {
+        let name = match param {
+            Expr::Variable(id @ Identifier::Name(_), _) => self.make_name(&id),
+            _ => return Err(CompileErr::Unsupported("property name", span)),
+        };
+
+        self.tree_pass(instance)?;
+        self.emit(OpCode::GetProperty(name), span);
+        Ok(())
+    }
+
+    fn compile_set(
+        &mut self,
+        instance: Expr,
+        param: Identifier,
+        value: Expr,
+        span: Span,
+    ) -> CompileResult {
+        let name = self.make_name(&param);
+        self.tree_pass(instance)?;
+        self.tree_pass(value)?;
+        self.emit(OpCode::SetProperty(name), span);
+        Ok(())
+    }
+
+    fn compile_super(&mut self, method: Identifier, span: Span) -> CompileResult {
+        let name = self.make_name(&method);
+        self.get_variable(Slot::This, identifier_name!("this"), span)?;
+        self.get_variable(Slot::Super, identifier_name!("super"), span)?;
+        self.emit(OpCode::GetSuper(name), span);
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::expression::Expr;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::compiler::chunk::{Function, Handler, HandlerKind, OpCode, UpvalueRef};
+use crate::compiler::{CompileErr, Compiler};
+use crate::parser;
+use crate::pass::*;
+use crate::scanner::Scanner;
+use crate::vm::value::Value;
+use std::rc::Rc;
+
+fn compile(compiler: &mut Compiler, source: &str) -> Result<Rc<Function>, CompileErr> {
+    let tokens: Vec<_> = Scanner::new(source.to_string())
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap())
+        .collect();
+    let (stmts, errors) = parser::parse(&tokens);
+    assert!(errors.is_empty());
+
+    let analyzed = ScopeAnalyzer::new().tree_pass(stmts).unwrap();
+    compiler.tree_pass(analyzed)
+}
+
+#[test]
+fn should_compile_expressions_to_stack_operations() {
+    let script = compile(&mut Compiler::new(), "print 1 + 2 * 3;").unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::Constant(0),
+            OpCode::Constant(1),
+            OpCode::Constant(2),
+            OpCode::Multiply,
+            OpCode::Add,
+            OpCode::Print,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+    assert_eq!(
+        vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)],
+        script.chunk.constants
+    );
+}
+
+#[test]
+fn should_record_the_span_of_each_instruction() {
+    let script = compile(&mut Compiler::new(), "print -1;").unwrap();
+
+    assert_eq!(Span::new(7, 8, 1, 8), script.chunk.spans[0]);
+    assert_eq!(Span::new(6, 8, 1, 7), script.chunk.spans[1]);
+}
+
+#[test]
+fn should_assign_locals_to_stack_slots() {
+    let script = compile(&mut Compiler::new(), "var a = 1; { var b = a; print b; }").unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::Constant(0),
+            OpCode::GetLocal(1),
+            OpCode::GetLocal(2),
+            OpCode::Print,
+            OpCode::Pop,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+    assert_eq!(1, script.globals);
+}
+
+#[test]
+fn should_assign_to_a_variable_redeclared_in_the_same_scope() {
+    let script = compile(&mut Compiler::new(), "var a = 1; var a = 2;").unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::Constant(0),
+            OpCode::Constant(1),
+            OpCode::SetLocal(1),
+            OpCode::Pop,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+}
+
+#[test]
+fn should_patch_jumps_over_branches() {
+    let script = compile(&mut Compiler::new(), "if (true) print 1; else print 2;").unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::True,
+            OpCode::JumpIfFalse(6),
+            OpCode::Pop,
+            OpCode::Constant(0),
+            OpCode::Print,
+            OpCode::Jump(9),
+            OpCode::Pop,
+            OpCode::Constant(1),
+            OpCode::Print,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+}
+
+#[test]
+fn should_capture_enclosing_variables_as_upvalues() {
+    let source = "fun outer() { var x = 1; fun inner() { return x; } return inner; }";
+    let script = compile(&mut Compiler::new(), source).unwrap();
+
+    let outer = match &script.chunk.constants[0] {
+        Value::Function(f) => f.clone(),
+        v => panic!("expected function constant, found {:?}", v),
+    };
+    let inner = outer
+        .chunk
+        .constants
+        .iter()
+        .find_map(|c| match c {
+            Value::Function(f) => Some(f.clone()),
+            _ => None,
+        })
+        .unwrap();
+
+    assert_eq!(
+        vec![UpvalueRef {
+            index: 1,
+            is_local: true
+        }],
+        inner.upvalues
+    );
+    assert_eq!(
+        vec![
+            OpCode::GetUpvalue(0),
+            OpCode::Return,
+            OpCode::Nil,
+            OpCode::Return
+        ],
+        inner.chunk.code
+    );
+    // the captured local is closed over when the function's scope ends.
+    assert!(outer.chunk.code.contains(&OpCode::CloseUpvalue));
+}
+
+#[test]
+fn should_protect_try_bodies_with_handlers() {
+    let script = compile(
+        &mut Compiler::new(),
+        "try { throw 1; } catch (e) { print e; }",
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::Constant(0),
+            OpCode::Throw,
+            OpCode::Jump(6),
+            OpCode::GetLocal(1),
+            OpCode::Print,
+            OpCode::Pop,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+    assert_eq!(
+        vec![Handler {
+            start: 0,
+            end: 2,
+            target: 3,
+            depth: 1,
+            kind: HandlerKind::Catch,
+        }],
+        script.chunk.handlers
+    );
+}
+
+#[test]
+fn should_inline_finally_blocks_left_by_a_return() {
+    let source = "fun f() { try { return 1; } finally { print 2; } }";
+    let script = compile(&mut Compiler::new(), source).unwrap();
+
+    let f = match &script.chunk.constants[0] {
+        Value::Function(f) => f.clone(),
+        v => panic!("expected function constant, found {:?}", v),
+    };
+    assert_eq!(
+        vec![
+            OpCode::Constant(0),
+            OpCode::Constant(1),
+            OpCode::Print,
+            OpCode::Return,
+            OpCode::Constant(2),
+            OpCode::Print,
+            OpCode::Jump(10),
+            OpCode::Constant(3),
+            OpCode::Print,
+            OpCode::Rethrow,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        f.chunk.code
+    );
+    // the finally block inlined before the return is left unprotected.
+    assert_eq!(
+        vec![Handler {
+            start: 0,
+            end: 1,
+            target: 7,
+            depth: 1,
+            kind: HandlerKind::Finally,
+        }],
+        f.chunk.handlers
+    );
+}
+
+#[test]
+fn should_persist_top_level_variables_between_passes() {
+    let mut compiler = Compiler::new();
+    compile(&mut compiler, "var a = 1;").unwrap();
+
+    let analyzed = vec![Stmt::Print(
+        Expr::Variable(identifier_id!(0), Span::default()),
+        Span::default(),
+    )];
+    let script = compiler.tree_pass(analyzed).unwrap();
+
+    assert_eq!(OpCode::GetLocal(1), script.chunk.code[0]);
+    assert_eq!(1, script.globals);
+}
+
+#[test]
+fn should_error_on_unresolved_variables() {
+    let unresolved = vec![Stmt::Print(
+        Expr::Variable(identifier_name!("a"), Span::new(6, 7, 1, 7)),
+        Span::default(),
+    )];
+
+    assert_eq!(
+        Err(CompileErr::Unresolved(
+            identifier_name!("a"),
+            Span::new(6, 7, 1, 7)
+        )),
+        Compiler::new().tree_pass(unresolved)
+    );
+}
+
+#[cfg(feature = "math")]
+#[test]
+fn should_look_up_stdlib_namespaces_as_builtins() {
+    let script = compile(&mut Compiler::new(), "print math.pi;").unwrap();
+
+    assert_eq!(
+        vec![
+            OpCode::GetBuiltin(1),
+            OpCode::GetProperty(0),
+            OpCode::Print,
+            OpCode::Nil,
+            OpCode::Return,
+        ],
+        script.chunk.code
+    );
+    assert_eq!(
+        vec![Value::Str("pi".into()), Value::Str("math".into())],
+        script.chunk.constants
+    );
+}
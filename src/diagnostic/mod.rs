@@ -0,0 +1,203 @@
+use crate::ast::span::Span;
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// Severity represents how serious a reported diagnostic is.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// Label attaches a message to a secondary span of source related to a
+/// diagnostic.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: &str) -> Self {
+        Label {
+            span,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// StackFrame names a function that was executing when a runtime error was
+/// raised, along with the span of source it was executing.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StackFrame {
+    pub name: String,
+    pub span: Span,
+}
+
+impl StackFrame {
+    pub fn new(name: &str, span: Span) -> Self {
+        StackFrame {
+            name: name.to_string(),
+            span,
+        }
+    }
+}
+
+/// Diagnostic represents a single problem found in lox source, from any stage
+/// of the pipeline, pointing at the primary span of source it occurred at.
+/// A primary span with a line of 0 denotes an unknown location. Runtime
+/// errors additionally carry a backtrace, from the innermost frame out.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub backtrace: Vec<StackFrame>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: &str, span: Span) -> Self {
+        Diagnostic {
+            severity,
+            code,
+            message: message.to_string(),
+            span,
+            labels: Vec::new(),
+            backtrace: Vec::new(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: &str, span: Span) -> Self {
+        Self::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: &'static str, message: &str, span: Span) -> Self {
+        Self::new(Severity::Warning, code, message, span)
+    }
+
+    /// with_label returns the diagnostic with an additional secondary label.
+    pub fn with_label(mut self, span: Span, message: &str) -> Self {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+
+    /// with_frame returns the diagnostic with an additional frame appended to
+    /// its backtrace.
+    pub fn with_frame(mut self, name: &str, span: Span) -> Self {
+        self.backtrace.push(StackFrame::new(name, span));
+        self
+    }
+
+    /// render formats the diagnostic in the style of rustc, printing the
+    /// offending source lines with the primary span underlined by `^` and any
+    /// secondary labels underlined by `-`, followed by the backtrace.
+    ///
+    /// # Examples
+    /// ```
+    /// use rlox::ast::span::Span;
+    /// use rlox::diagnostic::Diagnostic;
+    ///
+    /// let source = "print a + b;";
+    /// let diagnostic = Diagnostic::error("E0200", "undefined variable 'b'", Span::new(10, 11, 1, 11));
+    ///
+    /// assert_eq!(
+    ///     "error[E0200]: undefined variable 'b'
+    ///  --> main.lox:1:11
+    ///   |
+    /// 1 | print a + b;
+    ///   |           ^
+    /// ",
+    ///     diagnostic.render("main.lox", source)
+    /// );
+    /// ```
+    pub fn render(&self, filename: &str, source: &str) -> String {
+        let mut out = format!("{}\n", self);
+        if self.span.line != 0 {
+            out.push_str(&self.render_snippet(filename, source));
+        }
+
+        for frame in self.backtrace.iter() {
+            out.push_str(&format!(
+                "  at {} ({}:{})\n",
+                frame.name, filename, frame.span.line
+            ));
+        }
+
+        out
+    }
+
+    /// render_snippet formats the source lines annotated by the diagnostic.
+    fn render_snippet(&self, filename: &str, source: &str) -> String {
+        let mut out = String::new();
+        let lines: Vec<&str> = source.lines().collect();
+        let mut annotations: Vec<(Span, char, &str)> = vec![(self.span, '^', "")];
+        annotations.extend(
+            self.labels
+                .iter()
+                .filter(|label| label.span.line != 0)
+                .map(|label| (label.span, '-', label.message.as_str())),
+        );
+
+        let mut line_numbers: Vec<usize> =
+            annotations.iter().map(|(span, _, _)| span.line).collect();
+        line_numbers.sort_unstable();
+        line_numbers.dedup();
+
+        let gutter = " ".repeat(line_numbers.last().unwrap_or(&0).to_string().len());
+        out.push_str(&format!(
+            "{}--> {}:{}:{}\n",
+            gutter, filename, self.span.line, self.span.col
+        ));
+        out.push_str(&format!("{} |\n", gutter));
+
+        for line_number in line_numbers {
+            let line = lines.get(line_number - 1).copied().unwrap_or("");
+            let source_line = format!("{:>width$} | {}", line_number, line, width = gutter.len());
+            out.push_str(source_line.trim_end());
+            out.push('\n');
+
+            for (span, marker, message) in
+                annotations.iter().filter(|(s, _, _)| s.line == line_number)
+            {
+                let line_len = line.chars().count();
+                let col = std::cmp::max(span.col, 1);
+                let remaining = (line_len + 1).saturating_sub(col);
+                let width = std::cmp::max(std::cmp::min(span.len(), remaining), 1);
+                let underline = format!(
+                    "{}{}",
+                    " ".repeat(col - 1),
+                    marker.to_string().repeat(width)
+                );
+
+                if message.is_empty() {
+                    out.push_str(&format!("{} | {}\n", gutter, underline));
+                } else {
+                    out.push_str(&format!("{} | {} {}\n", gutter, underline, message));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
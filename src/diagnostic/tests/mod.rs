@@ -0,0 +1,116 @@
+use crate::ast::span::Span;
+use crate::diagnostic::{Diagnostic, Severity};
+
+#[test]
+fn diagnostic_should_display_its_header() {
+    let diagnostic = Diagnostic::warning("W0001", "unused variable 'a'", Span::default());
+
+    assert_eq!(Severity::Warning, diagnostic.severity);
+    assert_eq!(
+        "warning[W0001]: unused variable 'a'",
+        diagnostic.to_string()
+    );
+}
+
+#[test]
+fn render_should_underline_the_primary_span() {
+    let source = "var a = 1;\nprint a + \"b\";\n";
+    let diagnostic = Diagnostic::error("E0300", "invalid operands", Span::new(17, 24, 2, 7));
+
+    assert_eq!(
+        "error[E0300]: invalid operands
+ --> test.lox:2:7
+  |
+2 | print a + \"b\";
+  |       ^^^^^^^
+",
+        diagnostic.render("test.lox", source)
+    );
+}
+
+#[test]
+fn render_should_include_secondary_labels() {
+    let source = "class A < A {}";
+    let diagnostic = Diagnostic::error(
+        "E0203",
+        "a class can't inherit from itself",
+        Span::new(10, 11, 1, 11),
+    )
+    .with_label(Span::new(6, 7, 1, 7), "class declared here");
+
+    assert_eq!(
+        "error[E0203]: a class can't inherit from itself
+ --> test.lox:1:11
+  |
+1 | class A < A {}
+  |           ^
+  |       - class declared here
+",
+        diagnostic.render("test.lox", source)
+    );
+}
+
+#[test]
+fn render_should_widen_the_gutter_for_large_line_numbers() {
+    let source = format!("{}print x;", "\n".repeat(9));
+    let diagnostic = Diagnostic::error("E0200", "undefined variable 'x'", Span::new(15, 16, 10, 7))
+        .with_label(Span::new(0, 0, 1, 1), "file starts here");
+
+    assert_eq!(
+        "error[E0200]: undefined variable 'x'
+  --> test.lox:10:7
+   |
+ 1 |
+   | - file starts here
+10 | print x;
+   |       ^
+",
+        diagnostic.render("test.lox", &source)
+    );
+}
+
+#[test]
+fn render_should_omit_snippets_for_unknown_locations() {
+    let diagnostic = Diagnostic::error("E0303", "unspecified error", Span::default());
+
+    assert_eq!(
+        "error[E0303]: unspecified error\n",
+        diagnostic.render("test.lox", "print 1;")
+    );
+}
+
+#[test]
+fn render_should_clamp_spans_that_cross_lines() {
+    let source = "print \"abc\ndef\";";
+    let diagnostic = Diagnostic::error("E0002", "unterminated string", Span::new(6, 15, 1, 7));
+
+    assert_eq!(
+        "error[E0002]: unterminated string
+ --> test.lox:1:7
+  |
+1 | print \"abc
+  |       ^^^^
+",
+        diagnostic.render("test.lox", source)
+    );
+}
+
+#[test]
+fn render_should_print_the_backtrace_after_the_snippet() {
+    let source = "fun f() {\n  return nil + 1;\n}\nf();\n";
+    let diagnostic = Diagnostic::error("E0300", "invalid operands", Span::new(19, 26, 2, 10))
+        .with_frame("f", Span::new(19, 26, 2, 10))
+        .with_frame("<script>", Span::new(31, 34, 4, 1));
+
+    assert_eq!(
+        "error[E0300]: invalid operands
+ --> test.lox:2:10
+  |
+2 |   return nil + 1;
+  |          ^^^^^^^
+  at f (test.lox:2)
+  at <script> (test.lox:4)
+",
+        diagnostic.render("test.lox", source)
+    );
+}
@@ -37,6 +37,14 @@ impl<K, V> Environment<K, V> {
         self.symbols_table.borrow().contains_key(name)
     }
 
+    /// keys returns each symbol defined directly within the environment.
+    pub fn keys(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.symbols_table.borrow().keys().cloned().collect()
+    }
+
     pub fn assign(&self, name: &K, value: V) -> Option<V>
     where
         K: Eq + Hash + Clone,
@@ -74,4 +82,34 @@ impl<K, V> Environment<K, V> {
             (None, None) => None,
         }
     }
+
+    /// get_at returns the value of a symbol defined in the environment
+    /// `distance` hops up the parent chain, without consulting any other
+    /// environment.
+    pub fn get_at(&self, distance: usize, name: &K) -> Option<V>
+    where
+        K: Eq + Hash + Clone,
+        V: Clone,
+    {
+        match (distance, self.parent.as_ref()) {
+            (0, _) => self.symbols_table.borrow().get(name).cloned(),
+            (_, Some(parent)) => parent.get_at(distance - 1, name),
+            (_, None) => None,
+        }
+    }
+
+    /// assign_at assigns a symbol already defined in the environment
+    /// `distance` hops up the parent chain.
+    pub fn assign_at(&self, distance: usize, name: &K, value: V) -> Option<V>
+    where
+        K: Eq + Hash + Clone,
+        V: Clone,
+    {
+        match (distance, self.parent.as_ref()) {
+            (0, _) if self.has_key(name) => self.define(name, value),
+            (0, _) => None,
+            (_, Some(parent)) => parent.assign_at(distance - 1, name, value),
+            (_, None) => None,
+        }
+    }
 }
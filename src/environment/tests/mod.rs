@@ -100,3 +100,33 @@ fn child_environment_should_be_able_to_assign_symbols_to_parents() {
 
     assert_eq!(child.get(&key), Option::Some(obj_bool!(false)))
 }
+
+#[test]
+fn get_at_should_only_read_from_the_environment_at_distance() {
+    let parent: Rc<Environment<Identifier, Object>> = Environment::new();
+    let child = Environment::from(&parent);
+    let key = identifier_id!(0);
+
+    parent.define(&key, obj_bool!(true));
+    child.define(&key, obj_bool!(false));
+
+    assert_eq!(child.get_at(1, &key), Option::Some(obj_bool!(true)));
+    assert_eq!(child.get_at(0, &key), Option::Some(obj_bool!(false)));
+    assert_eq!(child.get_at(2, &key), Option::None);
+}
+
+#[test]
+fn assign_at_should_only_assign_symbols_defined_at_distance() {
+    let parent: Rc<Environment<Identifier, Object>> = Environment::new();
+    let child = Environment::from(&parent);
+    let key = identifier_id!(0);
+
+    parent.define(&key, obj_bool!(true));
+
+    assert_eq!(child.assign_at(0, &key, obj_bool!(false)), Option::None);
+    assert_eq!(
+        child.assign_at(1, &key, obj_bool!(false)),
+        Option::Some(obj_bool!(false))
+    );
+    assert_eq!(parent.get(&key), Option::Some(obj_bool!(false)));
+}
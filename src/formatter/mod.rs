@@ -0,0 +1,804 @@
+use crate::ast::expression::*;
+use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::ast::token::{Token, TokenType};
+use crate::diagnostic::Diagnostic;
+use crate::object::{Literal, Object};
+use crate::parser;
+use crate::scanner::Scanner;
+
+#[cfg(test)]
+mod tests;
+
+/// Config controls the layout of formatted source: the number of spaces each
+/// level of nesting is indented by, and the line width beyond which calls,
+/// lists and maps are broken over multiple lines.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Config {
+    pub indent: usize,
+    pub width: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            indent: 4,
+            width: 80,
+        }
+    }
+}
+
+/// format returns source printed in the canonical style, keeping its
+/// comments. The formatted source is reparsed before being returned, failing
+/// rather than returning source that parses to a different program or holds
+/// its comments in different places.
+///
+/// # Examples
+/// ```
+/// use rlox::formatter::{format, Config};
+///
+/// let formatted = format("var   x=1+2 ;  // three", &Config::default()).unwrap();
+/// assert_eq!("var x = 1 + 2; // three\n", formatted);
+/// ```
+pub fn format(source: &str, config: &Config) -> Result<String, Vec<Diagnostic>> {
+    let (stmts, tokens, comments) = parse(source)?;
+    let placed = placements(&tokens, &comments);
+    let formatted = Printer::new(source, tokens.clone(), comments.clone(), config).program(&stmts);
+
+    let message = match parse(&formatted) {
+        Ok((reparsed, _, _)) if erase_all(&reparsed) != erase_all(&stmts) => {
+            "formatted source does not parse to the same program"
+        }
+        Ok((_, tokens, comments)) if placements(&tokens, &comments) != placed => {
+            "formatted source does not keep the comments in place"
+        }
+        Ok(_) => return Ok(formatted),
+        Err(_) => "formatted source does not parse to the same program",
+    };
+    Err(vec![Diagnostic::error("E0500", message, Span::default())])
+}
+
+/// Parsed holds the statements parsed from source, the tokens they were
+/// parsed from and the comment tokens found between them.
+type Parsed = (Vec<Stmt>, Vec<Token>, Vec<Token>);
+
+/// parse scans and parses source, returning its statements along with their
+/// tokens and comments.
+fn parse(source: &str) -> Result<Parsed, Vec<Diagnostic>> {
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for lex_result in Scanner::new(source.to_string()).scan_tokens_with_comments() {
+        match lex_result {
+            Ok(tok) if tok.token_type == TokenType::Comment => comments.push(tok),
+            Ok(tok) => tokens.push(tok),
+            Err(e) => diagnostics.push(e.into()),
+        }
+    }
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    match parser::parse(&tokens) {
+        (stmts, errors) if errors.is_empty() => Ok((stmts, tokens, comments)),
+        (_, errors) => Err(errors.into_iter().map(Diagnostic::from).collect()),
+    }
+}
+
+/// Printer prints statements as source, placing each comment before the
+/// statement that follows it or, when it shares a line with the end of a
+/// statement, after that statement. Comments between the elements of a call,
+/// list, map or parameter list are placed beside the nearest element instead.
+struct Printer<'a> {
+    config: &'a Config,
+    line_starts: Vec<usize>,
+    tokens: Vec<Token>,
+    comments: Vec<Token>,
+    next: usize,
+    flat: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(source: &str, tokens: Vec<Token>, comments: Vec<Token>, config: &'a Config) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+
+        Printer {
+            config,
+            line_starts,
+            tokens,
+            comments,
+            next: 0,
+            flat: false,
+        }
+    }
+
+    fn program(&mut self, stmts: &[Stmt]) -> String {
+        let items = self.items(stmts, usize::MAX, 0, false);
+        match items.is_empty() {
+            true => String::new(),
+            false => format!("{}\n", items.join("\n")),
+        }
+    }
+
+    /// items prints each statement of a block, or each method of a class,
+    /// along with the comments before the block ends. Single blank lines
+    /// between statements are kept.
+    fn items(&mut self, stmts: &[Stmt], end: usize, depth: usize, methods: bool) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut last_line = None;
+        for (i, stmt) in stmts.iter().enumerate() {
+            let span = stmt.span();
+            self.leading_comments(span.start, depth, &mut items, &mut last_line);
+            if last_line.is_some_and(|last| span.line > last + 1) {
+                items.push(String::new());
+            }
+
+            let text = match methods {
+                true => self.method(stmt, depth),
+                false => self.stmt(stmt, depth),
+            };
+
+            // comments left within the statement, such as those between the
+            // elements of a list, trail it along with any comment following
+            // it on its last line.
+            let mut end_line = self.line_of(span.end.saturating_sub(1));
+            let limit = stmts.get(i + 1).map_or(end, |next| next.span().start);
+            let mut trailing = Vec::new();
+            while let Some(comment) = self.comments.get(self.next) {
+                let within = comment.span.start < span.end;
+                let same_line = comment.span.line == end_line && comment.span.start < limit;
+                if !within && !same_line {
+                    break;
+                }
+                trailing.push(lexeme(comment).to_string());
+                end_line = std::cmp::max(end_line, self.line_of(comment.span.end - 1));
+                self.next += 1;
+            }
+
+            let mut trailing = trailing.into_iter();
+            match trailing.next() {
+                Some(comment) => items.push(format!("{} {}", text, comment)),
+                None => items.push(text),
+            }
+            items.extend(trailing.map(|comment| format!("{}{}", self.pad(depth), comment)));
+            last_line = Some(end_line);
+        }
+
+        self.leading_comments(end, depth, &mut items, &mut last_line);
+        items
+    }
+
+    /// leading_comments prints each comment beginning before the passed
+    /// offset on lines of their own.
+    fn leading_comments(
+        &mut self,
+        before: usize,
+        depth: usize,
+        items: &mut Vec<String>,
+        last_line: &mut Option<usize>,
+    ) {
+        while let Some(comment) = self.comments.get(self.next) {
+            if comment.span.start >= before {
+                break;
+            }
+            if last_line.is_some_and(|last| comment.span.line > last + 1) {
+                items.push(String::new());
+            }
+
+            items.push(format!("{}{}", self.pad(depth), lexeme(comment)));
+            *last_line = Some(self.line_of(comment.span.end.saturating_sub(1)));
+            self.next += 1;
+        }
+    }
+
+    /// stmt prints a statement indented to the passed depth.
+    fn stmt(&mut self, stmt: &Stmt, depth: usize) -> String {
+        let pad = self.pad(depth);
+        let column = pad.len();
+        match stmt {
+            Stmt::Expression(expr, _) => format!("{}{};", pad, self.expr(expr, depth, column)),
+            Stmt::Print(expr, _) => {
+                format!("{}print {};", pad, self.expr(expr, depth, column + 6))
+            }
+            Stmt::Throw(expr, _) => {
+                format!("{}throw {};", pad, self.expr(expr, depth, column + 6))
+            }
+            Stmt::Return(Some(expr), _) => {
+                format!("{}return {};", pad, self.expr(expr, depth, column + 7))
+            }
+            Stmt::Return(None, _) => format!("{}return;", pad),
+            Stmt::Break(_) => format!("{}break;", pad),
+            Stmt::Continue(_) => format!("{}continue;", pad),
+            Stmt::Declaration(id, expr, _) => {
+                let prefix = format!("var {} = ", id);
+                let value = self.expr(expr, depth, column + prefix.len());
+                format!("{}{}{};", pad, prefix, value)
+            }
+            Stmt::Import(path, id, _) => format!("{}import \"{}\" as {};", pad, path, id),
+            Stmt::Block(stmts, span) => match for_loop(stmts, *span) {
+                Some((init, cond, step, body)) => {
+                    let init = match init {
+                        Stmt::Expression(Expr::Primary(Object::Literal(Literal::Nil), _), _) => {
+                            ";".to_string()
+                        }
+                        init => self.stmt(init, depth).trim_start().to_string(),
+                    };
+                    let cond = match cond {
+                        Expr::Primary(Object::Literal(Literal::Bool(true)), s) if *s == *span => {
+                            ";".to_string()
+                        }
+                        cond => format!(" {};", self.expr(cond, depth, column)),
+                    };
+                    let step = match step {
+                        Some(step) => format!(" {}", self.expr(step, depth, column)),
+                        None => String::new(),
+                    };
+                    let body = self.body(body, depth);
+                    format!("{}for ({}{}{}) {}", pad, init, cond, step, body)
+                }
+                None => format!("{}{}", pad, self.block(stmts, *span, depth)),
+            },
+            Stmt::If(cond, then, otherwise, _) => {
+                let cond = self.expr(cond, depth, column + 4);
+                let then = self.body(then, depth);
+                match otherwise {
+                    Some(otherwise) => {
+                        let separator = match then.ends_with('}') {
+                            true => " ".to_string(),
+                            false => format!("\n{}", pad),
+                        };
+                        let otherwise = match otherwise.as_ref() {
+                            stmt @ Stmt::If(..) => self.stmt(stmt, depth).trim_start().to_string(),
+                            stmt => self.body(stmt, depth),
+                        };
+                        format!(
+                            "{}if ({}) {}{}else {}",
+                            pad, cond, then, separator, otherwise
+                        )
+                    }
+                    None => format!("{}if ({}) {}", pad, cond, then),
+                }
+            }
+            Stmt::While(cond, body, _, _) => {
+                let cond = self.expr(cond, depth, column + 7);
+                format!("{}while ({}) {}", pad, cond, self.body(body, depth))
+            }
+            Stmt::ForIn(id, iterable, body, _) => {
+                let iterable = self.expr(iterable, depth, column);
+                let body = self.body(body, depth);
+                format!("{}for ({} in {}) {}", pad, id, iterable, body)
+            }
+            Stmt::Function(_, name, params, body, span) => {
+                let prefix = format!("fun {}", name);
+                let params = self.params(&prefix, params, span.start, depth, column);
+                format!("{}{} {}", pad, params, self.body(body, depth))
+            }
+            Stmt::Class(_, name, superclass, methods, span) => {
+                let superclass = match superclass {
+                    Some(superclass) => format!(" < {}", self.expr(superclass, depth, column)),
+                    None => String::new(),
+                };
+                let methods = self.items(methods, span.end, depth + 1, true);
+                let body = match methods.is_empty() {
+                    true => "{}".to_string(),
+                    false => format!("{{\n{}\n{}}}", methods.join("\n"), pad),
+                };
+                format!("{}class {}{} {}", pad, name, superclass, body)
+            }
+            Stmt::Try(body, catch, finally, _) => {
+                let mut text = format!("{}try {}", pad, self.body(body, depth));
+                if let Some((id, handler)) = catch {
+                    text = format!("{} catch ({}) {}", text, id, self.body(handler, depth));
+                }
+                if let Some(finally) = finally {
+                    text = format!("{} finally {}", text, self.body(finally, depth));
+                }
+                text
+            }
+        }
+    }
+
+    /// method prints a method of a class, which is declared without the
+    /// `fun` keyword.
+    fn method(&mut self, method: &Stmt, depth: usize) -> String {
+        match method {
+            Stmt::Function(_, name, params, body, span) => {
+                let pad = self.pad(depth);
+                let params = self.params(&name.to_string(), params, span.start, depth, pad.len());
+                format!("{}{} {}", pad, params, self.body(body, depth))
+            }
+            stmt => self.stmt(stmt, depth),
+        }
+    }
+
+    /// body prints the body of a compound statement, following its header on
+    /// the same line.
+    fn body(&mut self, stmt: &Stmt, depth: usize) -> String {
+        match stmt {
+            Stmt::Block(stmts, span) if for_loop(stmts, *span).is_none() => {
+                self.block(stmts, *span, depth)
+            }
+            stmt => self.stmt(stmt, depth).trim_start().to_string(),
+        }
+    }
+
+    /// block prints braces enclosing statements indented a level deeper than
+    /// the passed depth.
+    fn block(&mut self, stmts: &[Stmt], span: Span, depth: usize) -> String {
+        // statements within a block are laid out on lines of their own, even
+        // when the block is within elements being printed flat.
+        let flat = std::mem::replace(&mut self.flat, false);
+        let items = self.items(stmts, span.end, depth + 1, false);
+        self.flat = flat;
+        match items.is_empty() {
+            true => "{}".to_string(),
+            false => format!("{{\n{}\n{}}}", items.join("\n"), self.pad(depth)),
+        }
+    }
+
+    /// expr prints an expression beginning at the passed column, breaking
+    /// calls, lists and maps that would overrun the line width with one
+    /// element per line.
+    fn expr(&mut self, expr: &Expr, depth: usize, column: usize) -> String {
+        match expr {
+            Expr::Assignment(id, value, _) => {
+                let prefix = format!("{} = ", id);
+                format!(
+                    "{}{}",
+                    prefix,
+                    self.expr(value, depth, column + prefix.len())
+                )
+            }
+            Expr::Logical(LogicalExpr::Or(l, r), _) => self.binary(l, "or", r, depth, column),
+            Expr::Logical(LogicalExpr::And(l, r), _) => self.binary(l, "and", r, depth, column),
+            Expr::Equality(EqualityExpr::Equal(l, r), _) => self.binary(l, "==", r, depth, column),
+            Expr::Equality(EqualityExpr::NotEqual(l, r), _) => {
+                self.binary(l, "!=", r, depth, column)
+            }
+            Expr::Comparison(ComparisonExpr::Less(l, r), _) => {
+                self.binary(l, "<", r, depth, column)
+            }
+            Expr::Comparison(ComparisonExpr::LessEqual(l, r), _) => {
+                self.binary(l, "<=", r, depth, column)
+            }
+            Expr::Comparison(ComparisonExpr::Greater(l, r), _) => {
+                self.binary(l, ">", r, depth, column)
+            }
+            Expr::Comparison(ComparisonExpr::GreaterEqual(l, r), _) => {
+                self.binary(l, ">=", r, depth, column)
+            }
+            Expr::Addition(AdditionExpr::Add(l, r), _) => self.binary(l, "+", r, depth, column),
+            Expr::Addition(AdditionExpr::Subtract(l, r), _) => {
+                self.binary(l, "-", r, depth, column)
+            }
+            Expr::Multiplication(MultiplicationExpr::Multiply(l, r), _) => {
+                self.binary(l, "*", r, depth, column)
+            }
+            Expr::Multiplication(MultiplicationExpr::Divide(l, r), _) => {
+                self.binary(l, "/", r, depth, column)
+            }
+            Expr::Unary(UnaryExpr::Bang(e), _) => format!("!{}", self.expr(e, depth, column + 1)),
+            Expr::Unary(UnaryExpr::Minus(e), _) => format!("-{}", self.expr(e, depth, column + 1)),
+            Expr::Grouping(e, _) => format!("({})", self.expr(e, depth, column + 1)),
+            Expr::Call(callee, args, span) => {
+                let range = (callee.span().end, span.end);
+                let spans: Vec<Span> = args.iter().map(Expr::span).collect();
+                let callee = self.expr(callee, depth, column);
+                let column = column + callee.len();
+                let delimiters = (callee.as_str(), "(", ")");
+                self.elements(
+                    delimiters,
+                    args,
+                    &spans,
+                    range,
+                    depth,
+                    column,
+                    |p, arg, d, c| p.expr(arg, d, c),
+                )
+            }
+            Expr::List(elems, span) => {
+                let spans: Vec<Span> = elems.iter().map(Expr::span).collect();
+                let range = (span.start, span.end);
+                self.elements(
+                    ("", "[", "]"),
+                    elems,
+                    &spans,
+                    range,
+                    depth,
+                    column,
+                    |p, e, d, c| p.expr(e, d, c),
+                )
+            }
+            Expr::Map(entries, span) => {
+                let spans: Vec<Span> = entries
+                    .iter()
+                    .map(|(k, v)| k.span().merge(&v.span()))
+                    .collect();
+                let range = (span.start, span.end);
+                self.elements(
+                    ("", "{", "}"),
+                    entries,
+                    &spans,
+                    range,
+                    depth,
+                    column,
+                    |p, (k, v), d, c| {
+                        let key = p.expr(k, d, c);
+                        let value = p.expr(v, d, c + key.len() + 2);
+                        format!("{}: {}", key, value)
+                    },
+                )
+            }
+            Expr::Get(object, property, _) => {
+                let object = self.expr(object, depth, column);
+                let column = column + object.len() + 1;
+                format!("{}.{}", object, self.expr(property, depth, column))
+            }
+            Expr::Set(object, name, value, _) => {
+                let object = self.expr(object, depth, column);
+                let prefix = format!("{}.{} = ", object, name);
+                let value = self.expr(value, depth, column + prefix.len());
+                format!("{}{}", prefix, value)
+            }
+            Expr::Index(target, index, _) => {
+                let target = self.expr(target, depth, column);
+                let index = self.expr(index, depth, column + target.len() + 1);
+                format!("{}[{}]", target, index)
+            }
+            Expr::SetIndex(target, index, value, _) => {
+                let target = self.expr(target, depth, column);
+                let index = self.expr(index, depth, column + target.len() + 1);
+                let prefix = format!("{}[{}] = ", target, index);
+                let value = self.expr(value, depth, column + prefix.len());
+                format!("{}{}", prefix, value)
+            }
+            Expr::Primary(Object::Literal(Literal::Str(s)), _) => format!("\"{}\"", s),
+            Expr::Primary(obj, _) => obj.to_string(),
+            Expr::Lambda(params, body, span) => {
+                let params = self.params("fun ", params, span.start, depth, column);
+                format!("{} {}", params, self.body(body, depth))
+            }
+            Expr::Variable(id, _) => id.to_string(),
+            Expr::This(_) => "this".to_string(),
+            Expr::Super(method, _) => format!("super.{}", method),
+        }
+    }
+
+    fn binary(&mut self, l: &Expr, op: &str, r: &Expr, depth: usize, column: usize) -> String {
+        let l = self.expr(l, depth, column);
+        let column = column + last_line_len(&l) + op.len() + 2;
+        format!("{} {} {}", l, op, self.expr(r, depth, column))
+    }
+
+    /// elements prints the delimited elements of a call, list or map after
+    /// the passed prefix, on one line if it fits within the line width. The
+    /// span of each element and the range of source between the delimiters
+    /// place the comments among the elements.
+    #[allow(clippy::too_many_arguments)]
+    fn elements<T, F>(
+        &mut self,
+        (prefix, open, close): (&str, &str, &str),
+        elems: &[T],
+        spans: &[Span],
+        range: (usize, usize),
+        depth: usize,
+        column: usize,
+        mut print: F,
+    ) -> String
+    where
+        F: FnMut(&mut Self, &T, usize, usize) -> String,
+    {
+        // elements are first printed on a single line, with any nested
+        // elements. Comments consumed by an attempt that doesn't fit are
+        // placed again when the elements are broken over lines, as they
+        // always are when a comment between or within the elements was left
+        // unplaced.
+        let (start, end) = range;
+        let next = self.next;
+        let enclosing_flat = std::mem::replace(&mut self.flat, true);
+        let mut flat = Vec::new();
+        let mut offset = column + open.len();
+        for elem in elems {
+            let text = print(self, elem, depth, offset);
+            offset += text.len() + 2;
+            flat.push(text);
+        }
+        self.flat = enclosing_flat;
+
+        let flat = format!("{}{}{}{}", prefix, open, flat.join(", "), close);
+        let first_line = flat.lines().next().unwrap_or_default().len();
+        let fits = column - prefix.len() + first_line <= self.config.width;
+        let commented = self
+            .comments
+            .get(self.next)
+            .is_some_and(|comment| start <= comment.span.start && comment.span.start < end);
+        if !commented && (elems.is_empty() || self.flat || fits) {
+            return flat;
+        }
+
+        self.next = next;
+        let pad = self.pad(depth + 1);
+        let mut lines = Vec::new();
+        for (i, elem) in elems.iter().enumerate() {
+            self.comment_lines(start, spans[i].start, &pad, &mut lines);
+            let mut line = format!("{}{}", pad, print(self, elem, depth + 1, pad.len()));
+            if i + 1 < elems.len() {
+                line.push(',');
+            }
+
+            // comments on the line an element ends on trail it.
+            let limit = spans.get(i + 1).map_or(end, |next| next.start);
+            let end_line = self.line_of(spans[i].end.saturating_sub(1));
+            while let Some(comment) = self.comments.get(self.next) {
+                let span = comment.span;
+                if span.start < start || span.start >= limit || span.line > end_line {
+                    break;
+                }
+                line = format!("{} {}", line, lexeme(comment));
+                self.next += 1;
+            }
+            lines.push(line);
+        }
+        self.comment_lines(start, end, &pad, &mut lines);
+
+        format!(
+            "{}{}\n{}\n{}{}",
+            prefix,
+            open,
+            lines.join("\n"),
+            self.pad(depth),
+            close
+        )
+    }
+
+    /// params prints the parameter list of a function declared from the
+    /// passed offset after the passed prefix, locating each parameter within
+    /// the source so that comments are placed among them as elements.
+    fn params(
+        &mut self,
+        prefix: &str,
+        params: &[Identifier],
+        from: usize,
+        depth: usize,
+        column: usize,
+    ) -> String {
+        let open = self
+            .tokens
+            .iter()
+            .position(|tok| tok.span.start >= from && tok.token_type == TokenType::LeftParen)
+            .unwrap_or(self.tokens.len());
+        let list: Vec<&Token> = self.tokens[open..]
+            .iter()
+            .take_while(|tok| tok.token_type != TokenType::RightParen)
+            .collect();
+        let close = self.tokens.get(open + list.len());
+
+        let spans: Vec<Span> = list
+            .iter()
+            .filter(|tok| tok.token_type == TokenType::Identifier)
+            .map(|tok| tok.span)
+            .collect();
+        let range = match (list.first(), close) {
+            (Some(open), Some(close)) => (open.span.start, close.span.end),
+            _ => (from, from),
+        };
+        self.elements(
+            (prefix, "(", ")"),
+            params,
+            &spans,
+            range,
+            depth,
+            column + prefix.len(),
+            |_, param, _, _| param.to_string(),
+        )
+    }
+
+    /// comment_lines prints each comment from the passed offset up to
+    /// another on lines of their own.
+    fn comment_lines(&mut self, from: usize, before: usize, pad: &str, lines: &mut Vec<String>) {
+        while let Some(comment) = self.comments.get(self.next) {
+            if comment.span.start < from || comment.span.start >= before {
+                break;
+            }
+            lines.push(format!("{}{}", pad, lexeme(comment)));
+            self.next += 1;
+        }
+    }
+
+    fn pad(&self, depth: usize) -> String {
+        " ".repeat(depth * self.config.indent)
+    }
+
+    /// line_of returns the line the character at the passed offset is on.
+    fn line_of(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line + 1,
+            Err(next) => next,
+        }
+    }
+}
+
+/// for_loop returns the initializer, condition, incrementer and body of a
+/// block desugared from a `for` loop, which the parser produces as a block
+/// spanning the same source as the loop it holds.
+fn for_loop(stmts: &[Stmt], span: Span) -> Option<(&Stmt, &Expr, Option<&Expr>, &Stmt)> {
+    match stmts {
+        [init, Stmt::While(cond, body, step, loop_span)] if *loop_span == span => {
+            Some((init, cond, step.as_ref(), body))
+        }
+        _ => None,
+    }
+}
+
+fn lexeme(tok: &Token) -> &str {
+    tok.lexeme.as_deref().unwrap_or_default()
+}
+
+/// placements pairs each comment with the number of parentheses, brackets
+/// and braces before it, so that a comment moved into or out of a call, list,
+/// parameter list or block is told apart from one kept in place.
+fn placements<'t>(tokens: &[Token], comments: &'t [Token]) -> Vec<(usize, &'t str)> {
+    let delimiters: Vec<usize> = tokens
+        .iter()
+        .filter(|tok| {
+            matches!(
+                tok.token_type,
+                TokenType::LeftParen
+                    | TokenType::RightParen
+                    | TokenType::LeftBracket
+                    | TokenType::RightBracket
+                    | TokenType::LeftBrace
+                    | TokenType::RightBrace
+            )
+        })
+        .map(|tok| tok.span.start)
+        .collect();
+
+    comments
+        .iter()
+        .map(|comment| {
+            let before = delimiters.partition_point(|start| *start < comment.span.start);
+            (before, lexeme(comment))
+        })
+        .collect()
+}
+
+fn last_line_len(text: &str) -> usize {
+    text.lines().last().map(str::len).unwrap_or_default()
+}
+
+/// erase_all returns statements with every span cleared, so that programs
+/// parsed from differently laid out source compare equal.
+fn erase_all(stmts: &[Stmt]) -> Vec<Stmt> {
+    stmts.iter().map(erase).collect()
+}
+
+fn erase(stmt: &Stmt) -> Stmt {
+    let boxed = |stmt: &Stmt| Box::new(erase(stmt));
+    let none = Span::default();
+    match stmt {
+        Stmt::Expression(e, _) => Stmt::Expression(erase_expr(e), none),
+        Stmt::If(cond, then, otherwise, _) => Stmt::If(
+            erase_expr(cond),
+            boxed(then),
+            otherwise.as_deref().map(boxed),
+            none,
+        ),
+        Stmt::While(cond, body, step, _) => Stmt::While(
+            erase_expr(cond),
+            boxed(body),
+            step.as_ref().map(erase_expr),
+            none,
+        ),
+        Stmt::ForIn(id, iterable, body, _) => {
+            Stmt::ForIn(id.clone(), erase_expr(iterable), boxed(body), none)
+        }
+        Stmt::Print(e, _) => Stmt::Print(erase_expr(e), none),
+        Stmt::Function(id, name, params, body, _) => {
+            Stmt::Function(id.clone(), name.clone(), params.clone(), boxed(body), none)
+        }
+        Stmt::Declaration(id, e, _) => Stmt::Declaration(id.clone(), erase_expr(e), none),
+        Stmt::Return(e, _) => Stmt::Return(e.as_ref().map(erase_expr), none),
+        Stmt::Class(id, name, superclass, methods, _) => Stmt::Class(
+            id.clone(),
+            name.clone(),
+            superclass.as_ref().map(erase_expr),
+            erase_all(methods),
+            none,
+        ),
+        Stmt::Block(stmts, _) => Stmt::Block(erase_all(stmts), none),
+        Stmt::Break(_) => Stmt::Break(none),
+        Stmt::Continue(_) => Stmt::Continue(none),
+        Stmt::Import(path, id, _) => Stmt::Import(path.clone(), id.clone(), none),
+        Stmt::Throw(e, _) => Stmt::Throw(erase_expr(e), none),
+        Stmt::Try(body, catch, finally, _) => Stmt::Try(
+            boxed(body),
+            catch
+                .as_ref()
+                .map(|(id, handler)| (id.clone(), boxed(handler))),
+            finally.as_deref().map(boxed),
+            none,
+        ),
+    }
+}
+
+fn erase_expr(expr: &Expr) -> Expr {
+    let boxed = |e: &Expr| Box::new(erase_expr(e));
+    let none = Span::default();
+    match expr {
+        Expr::Assignment(id, value, _) => Expr::Assignment(id.clone(), boxed(value), none),
+        Expr::Logical(LogicalExpr::Or(l, r), _) => {
+            Expr::Logical(LogicalExpr::Or(boxed(l), boxed(r)), none)
+        }
+        Expr::Logical(LogicalExpr::And(l, r), _) => {
+            Expr::Logical(LogicalExpr::And(boxed(l), boxed(r)), none)
+        }
+        Expr::Equality(EqualityExpr::Equal(l, r), _) => {
+            Expr::Equality(EqualityExpr::Equal(boxed(l), boxed(r)), none)
+        }
+        Expr::Equality(EqualityExpr::NotEqual(l, r), _) => {
+            Expr::Equality(EqualityExpr::NotEqual(boxed(l), boxed(r)), none)
+        }
+        Expr::Comparison(ComparisonExpr::Less(l, r), _) => {
+            Expr::Comparison(ComparisonExpr::Less(boxed(l), boxed(r)), none)
+        }
+        Expr::Comparison(ComparisonExpr::LessEqual(l, r), _) => {
+            Expr::Comparison(ComparisonExpr::LessEqual(boxed(l), boxed(r)), none)
+        }
+        Expr::Comparison(ComparisonExpr::Greater(l, r), _) => {
+            Expr::Comparison(ComparisonExpr::Greater(boxed(l), boxed(r)), none)
+        }
+        Expr::Comparison(ComparisonExpr::GreaterEqual(l, r), _) => {
+            Expr::Comparison(ComparisonExpr::GreaterEqual(boxed(l), boxed(r)), none)
+        }
+        Expr::Addition(AdditionExpr::Add(l, r), _) => {
+            Expr::Addition(AdditionExpr::Add(boxed(l), boxed(r)), none)
+        }
+        Expr::Addition(AdditionExpr::Subtract(l, r), _) => {
+            Expr::Addition(AdditionExpr::Subtract(boxed(l), boxed(r)), none)
+        }
+        Expr::Multiplication(MultiplicationExpr::Multiply(l, r), _) => {
+            Expr::Multiplication(MultiplicationExpr::Multiply(boxed(l), boxed(r)), none)
+        }
+        Expr::Multiplication(MultiplicationExpr::Divide(l, r), _) => {
+            Expr::Multiplication(MultiplicationExpr::Divide(boxed(l), boxed(r)), none)
+        }
+        Expr::Unary(UnaryExpr::Bang(e), _) => Expr::Unary(UnaryExpr::Bang(boxed(e)), none),
+        Expr::Unary(UnaryExpr::Minus(e), _) => Expr::Unary(UnaryExpr::Minus(boxed(e)), none),
+        Expr::Call(callee, args, _) => {
+            Expr::Call(boxed(callee), args.iter().map(erase_expr).collect(), none)
+        }
+        Expr::Get(object, property, _) => Expr::Get(boxed(object), boxed(property), none),
+        Expr::Set(object, name, value, _) => {
+            Expr::Set(boxed(object), name.clone(), boxed(value), none)
+        }
+        Expr::List(elems, _) => Expr::List(elems.iter().map(erase_expr).collect(), none),
+        Expr::Map(entries, _) => Expr::Map(
+            entries
+                .iter()
+                .map(|(k, v)| (erase_expr(k), erase_expr(v)))
+                .collect(),
+            none,
+        ),
+        Expr::Index(target, index, _) => Expr::Index(boxed(target), boxed(index), none),
+        Expr::SetIndex(target, index, value, _) => {
+            Expr::SetIndex(boxed(target), boxed(index), boxed(value), none)
+        }
+        Expr::Primary(obj, _) => Expr::Primary(obj.clone(), none),
+        Expr::Grouping(e, _) => Expr::Grouping(boxed(e), none),
+        Expr::Lambda(params, body, _) => Expr::Lambda(params.clone(), Box::new(erase(body)), none),
+        Expr::Variable(id, _) => Expr::Variable(id.clone(), none),
+        Expr::This(_) => Expr::This(none),
+        Expr::Super(method, _) => Expr::Super(method.clone(), none),
+    }
+}
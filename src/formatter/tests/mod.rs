@@ -0,0 +1,174 @@
+use crate::formatter::{format, Config};
+use std::fs;
+use std::path::Path;
+
+fn format_default(source: &str) -> String {
+    format(source, &Config::default()).unwrap()
+}
+
+#[test]
+fn statements_should_be_printed_one_per_line_and_indented_by_depth() {
+    assert_eq!(
+        "fun add(a, b) {
+    if (a > b) {
+        return a - b;
+    } else if (a == b) return 0;
+    else return b - a;
+}
+class Pair < Base {
+    init(a, b) {
+        this.a = a;
+    }
+}
+",
+        format_default(
+            "fun add(a,b){if(a>b){return a-b;}else if (a==b) return 0; else return b-a;}
+class Pair<Base{init(a,b){this.a=a;}}"
+        )
+    );
+}
+
+#[test]
+fn comments_and_single_blank_lines_should_be_kept() {
+    assert_eq!(
+        "// counts things
+var count = 0; // starts at zero
+
+/* loops
+   forever */
+while (true) {
+    count = count + 1;
+    // nothing after this
+}
+",
+        format_default(
+            "// counts things
+var count = 0;   // starts at zero
+
+
+
+/* loops
+   forever */
+while (true) {
+  count = count + 1;
+  // nothing after this
+}"
+        )
+    );
+}
+
+#[test]
+fn comments_within_elements_should_stay_beside_the_nearest_element() {
+    assert_eq!(
+        "var total = add(
+    first, // the first
+    // before the second
+    second
+);
+var xs = [
+    1, /* one */
+    2
+    // no more
+];
+print f(
+    a + b, // split
+    c
+);
+",
+        format_default(
+            "var total = add(first, // the first
+  // before the second
+  second);
+var xs = [1 /* one */, 2
+  // no more
+];
+print f(a + // split
+  b, c);"
+        )
+    );
+}
+
+#[test]
+fn comments_within_parameters_should_stay_beside_the_nearest_parameter() {
+    assert_eq!(
+        "fun f(
+    a, /* p */
+    b
+) {
+    return a;
+}
+class A {
+    m(
+        // none
+    ) {
+        return 1;
+    }
+}
+",
+        format_default(
+            "fun f(a, /* p */ b) { return a; }
+class A { m(// none
+) { return 1; } }"
+        )
+    );
+}
+
+#[test]
+fn comments_that_would_be_moved_between_delimiters_should_err() {
+    let diagnostics = format("print (1 + /* c */ 2);", &Config::default()).unwrap_err();
+    assert_eq!("E0500", diagnostics[0].code);
+}
+
+#[test]
+fn for_loops_should_be_printed_as_written_rather_than_desugared() {
+    assert_eq!(
+        "for (var i = 0; i < 3; i = i + 1) print i;\nfor (;;) {\n    break;\n}\n",
+        format_default("for(var i=0;i<3;i=i+1) print i; for (;;) { break; }")
+    );
+}
+
+#[test]
+fn elements_overrunning_the_line_width_should_be_broken_over_lines() {
+    let config = Config {
+        indent: 2,
+        width: 24,
+    };
+
+    assert_eq!(
+        "var short = [1, 2];\nvar long = add(\n  first,\n  [1, 2, 3]\n);\n",
+        format(
+            "var short = [1, 2]; var long = add(first, [1, 2, 3]);",
+            &config
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn invalid_source_should_err_with_its_diagnostics() {
+    let diagnostics = format("var = 1;", &Config::default()).unwrap_err();
+    assert_eq!("E0101", diagnostics[0].code);
+}
+
+#[test]
+fn every_example_program_should_format_idempotently() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for dir in ["examples", "tests/corpus", "tests/corpus/modules"].iter() {
+        for entry in fs::read_dir(root.join(dir)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "lox") {
+                continue;
+            }
+
+            let source = fs::read_to_string(&path).unwrap();
+            let formatted = format(&source, &Config::default())
+                .unwrap_or_else(|e| panic!("{}: {:?}", path.display(), e));
+            assert_eq!(
+                formatted,
+                format_default(&formatted),
+                "{} is not formatted idempotently",
+                path.display()
+            );
+        }
+    }
+}
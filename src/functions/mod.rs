@@ -2,7 +2,8 @@ use crate::ast::identifier::Identifier;
 use crate::ast::statement;
 use crate::class::Class;
 use crate::environment::Environment;
-use crate::interpreter;
+use crate::instance::Instance;
+use crate::interpreter::{self, ExprInterpreterErr, StatefulInterpreter};
 use crate::object::Object;
 use crate::pass::*;
 use std::fmt;
@@ -11,11 +12,47 @@ use std::rc::Rc;
 #[cfg(test)]
 mod tests;
 
+/// Arity represents the number of arguments a function accepts, either an
+/// exact count or a minimum count for variadic functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Fixed(usize),
+    Variadic(usize),
+}
+
+impl Arity {
+    /// accepts returns true if a call with the passed number of arguments
+    /// satisfies the arity.
+    pub fn accepts(&self, argc: usize) -> bool {
+        match self {
+            Self::Fixed(n) => argc == *n,
+            Self::Variadic(min) => argc >= *min,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(n) => write!(f, "{}", n),
+            Self::Variadic(min) => write!(f, "at least {}", min),
+        }
+    }
+}
+
 /// CallError represents an error while attempting to make a function call be
-/// it a runtime error or an arity error.
-#[derive(Debug, Clone, Copy)]
+/// it a runtime error or an arity error. Static functions report invalid
+/// arguments with a Type error and any other failure with a Native error,
+/// while a Runtime error carries an error raised within a lox function body.
+/// An Unhashable error carries a value that cannot be used as a map key.
+#[derive(Debug, Clone, PartialEq)]
 pub enum CallError {
-    Arity,
+    Arity(Arity, usize),
+    Type(String),
+    Native(String),
+    Unhashable(Object),
+    IndexOutOfRange(f64, usize),
+    Runtime(Box<ExprInterpreterErr>),
     Unknown,
 }
 
@@ -23,7 +60,16 @@ impl fmt::Display for CallError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Unknown => write!(f, "unknown call error"),
-            Self::Arity => write!(f, "argument count doesn't match function arity"),
+            Self::Arity(expected, got) => {
+                write!(f, "expected {} arguments but got {}", expected, got)
+            }
+            Self::Type(e) => write!(f, "invalid argument type: {}", e),
+            Self::Native(e) => write!(f, "{}", e),
+            Self::Unhashable(key) => write!(f, "unhashable map key: {}", key),
+            Self::IndexOutOfRange(index, len) => {
+                write!(f, "list index {} out of range for length {}", index, len)
+            }
+            Self::Runtime(e) => write!(f, "{}", e),
         }
     }
 }
@@ -37,6 +83,7 @@ pub type CallResult = Result<Object, CallError>;
 pub enum Callable {
     Func(Function),
     Static(StaticFunc),
+    Method(NativeMethod),
     Class(Class),
 }
 
@@ -46,24 +93,64 @@ impl Callable {
     }
 
     /// arity dispatches to each corresponding functions arity method,
-    /// Returning the usize of the function signature arity.
-    pub fn arity(&self) -> usize {
+    /// Returning the Arity of the function signature.
+    pub fn arity(&self) -> Arity {
         match self {
-            Self::Func(f) => f.arity(),
+            Self::Func(f) => Arity::Fixed(f.arity()),
             Self::Static(sf) => sf.arity(),
-            Self::Class(c) => c.arity(),
+            Self::Method(m) => m.arity(),
+            Self::Class(c) => Arity::Fixed(c.arity()),
+        }
+    }
+
+    /// name returns the name reported for calls to the function. Calling a
+    /// class reports the initializer it runs.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Func(f) => f.name().to_string(),
+            Self::Static(sf) => sf.name().to_string(),
+            Self::Method(m) => m.name().to_string(),
+            Self::Class(_) => "init".to_string(),
+        }
+    }
+
+    /// is returns true if both callables are the same function, native or
+    /// class object.
+    pub fn is(&self, other: &Callable) -> bool {
+        match (self, other) {
+            (Self::Func(l), Self::Func(r)) => l.is(r),
+            (Self::Static(l), Self::Static(r)) => l == r,
+            (Self::Method(l), Self::Method(r)) => l.is(r),
+            (Self::Class(l), Self::Class(r)) => l.is(r),
+            _ => false,
         }
     }
 
-    /// Call attempts to invoke each correspondings call method.
-    pub fn call(&self, env: Rc<Environment<Identifier, Object>>, args: Vec<Object>) -> CallResult {
-        let arity_match = self.arity() == args.len();
+    /// Call attempts to invoke each correspondings call method. Lox
+    /// functions are evaluated by an interpreter sharing the caller's call
+    /// stack.
+    pub fn call(&self, interpreter: &StatefulInterpreter, args: Vec<Object>) -> CallResult {
+        let arity = self.arity();
 
-        match (arity_match, self) {
-            (true, Self::Func(f)) => f.call(env, args),
-            (true, Self::Static(sf)) => sf.call(env, args),
-            (true, Self::Class(c)) => c.call(env, args),
-            (false, _) => Err(CallError::Arity),
+        match (arity.accepts(args.len()), self) {
+            (true, Self::Func(f)) => f.call(interpreter, args),
+            (true, Self::Static(sf)) => sf.call(args),
+            (true, Self::Method(m)) => m.call(interpreter, args),
+            (true, Self::Class(c)) => c.call(interpreter, args),
+            (false, _) => Err(CallError::Arity(arity, args.len())),
+        }
+    }
+}
+
+/// Callables display as the bytecode vm displays them, rather than by their
+/// contents, as a function's closure may refer back to the function itself.
+impl fmt::Display for Callable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Func(func) => write!(f, "<fn {}>", func.name()),
+            Self::Static(sf) => write!(f, "<native fn {}>", sf.name()),
+            Self::Method(m) => write!(f, "<native fn {}>", m.name()),
+            Self::Class(c) => write!(f, "{}", c),
         }
     }
 }
@@ -71,9 +158,11 @@ impl Callable {
 /// Function represents a lox runtime function.
 #[derive(Debug, Clone)]
 pub struct Function {
+    name: String,
     closure: Rc<Environment<Identifier, Object>>,
     params: Vec<Identifier>,
-    body: statement::Stmt,
+    body: Rc<statement::Stmt>,
+    is_initializer: bool,
 }
 
 impl Function {
@@ -83,57 +172,225 @@ impl Function {
         body: statement::Stmt,
     ) -> Self {
         Function {
+            name: "lambda".to_string(),
             closure,
             params,
-            body,
+            body: Rc::new(body),
+            is_initializer: false,
+        }
+    }
+
+    /// named returns the function with the name it was declared with.
+    pub fn named(self, name: &Identifier) -> Self {
+        Function {
+            name: name.to_string(),
+            ..self
+        }
+    }
+
+    /// new_initializer returns a function representing a class's `init`
+    /// method. Initializers always return the bound instance.
+    pub fn new_initializer(
+        closure: Rc<Environment<Identifier, Object>>,
+        params: Vec<Identifier>,
+        body: statement::Stmt,
+    ) -> Self {
+        Function {
+            is_initializer: true,
+            ..Function::new(closure, params, body).named(&identifier_name!("init"))
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn arity(&self) -> usize {
         self.params.len()
     }
 
-    pub fn call(&self, _env: Rc<Environment<Identifier, Object>>, args: Vec<Object>) -> CallResult {
+    /// is returns true if both functions are the same function object.
+    /// Each evaluation of a declaration, and each binding of a method,
+    /// produces a distinct function.
+    pub fn is(&self, other: &Function) -> bool {
+        Rc::ptr_eq(&self.body, &other.body) && Rc::ptr_eq(&self.closure, &other.closure)
+    }
+
+    /// bind returns a copy of the function whose closure defines `this` as
+    /// the passed instance.
+    pub fn bind(&self, instance: &Instance) -> Self {
+        let env = Environment::from(&self.closure);
+        env.define(&identifier_name!("this"), obj_instance!(instance.clone()));
+
+        Function {
+            name: self.name.clone(),
+            closure: env,
+            params: self.params.clone(),
+            body: self.body.clone(),
+            is_initializer: self.is_initializer,
+        }
+    }
+
+    pub fn call(&self, interpreter: &StatefulInterpreter, args: Vec<Object>) -> CallResult {
         let local = Environment::from(&self.closure);
         for (ident, arg) in self.params.iter().zip(args.into_iter()) {
-            local.define(&ident, arg.clone());
+            local.define(&ident.key(), arg.clone());
         }
 
-        let intptr = interpreter::StatefulInterpreter::from(local);
-        match intptr.tree_pass(self.body.clone()) {
+        let intptr = interpreter.with_env(local);
+        match intptr.tree_pass(self.body.as_ref()) {
+            Ok(_) if self.is_initializer => Ok(self
+                .closure
+                .get(&identifier_name!("this"))
+                .unwrap_or(obj_nil!())),
             Ok(rv) => Ok(rv.unwrap_or(obj_nil!())),
-            Err(_) => Err(CallError::Unknown),
+            Err(interpreter::StmtInterpreterErr::Expression(e)) => {
+                Err(CallError::Runtime(Box::new(e)))
+            }
+            Err(interpreter::StmtInterpreterErr::Unspecified)
+            | Err(interpreter::StmtInterpreterErr::Break)
+            | Err(interpreter::StmtInterpreterErr::Continue)
+            | Err(interpreter::StmtInterpreterErr::Import(_, _)) => Err(CallError::Runtime(
+                Box::new(ExprInterpreterErr::Unspecified),
+            )),
         }
     }
 }
 
 impl PartialEq for Function {
     fn eq(&self, other: &Self) -> bool {
-        self.params == other.params && self.body == other.body
+        self.name == other.name
+            && self.params == other.params
+            && self.body == other.body
+            && self.is_initializer == other.is_initializer
     }
 }
 
-/// StaticFuncCallback is a type that all static functions must implement. This
-/// type takes an environment and a vector of objects, representing arguments for
-/// use at call time.
-type StaticFuncCallback = fn(Rc<Environment<Identifier, Object>>, Vec<Object>) -> Object;
+/// StaticFuncCallback is the type that all static functions implement,
+/// taking a vector of objects representing the arguments passed at call time.
+/// Callbacks may capture state from the host embedding the interpreter.
+pub type StaticFuncCallback = dyn Fn(Vec<Object>) -> CallResult;
 
-/// StaticFunc represents a static function to be called at a later date.
-#[derive(Debug, Clone, PartialEq)]
+/// StaticFunc represents a named native function to be called at a later
+/// date. Clones of a StaticFunc share the same callback.
+#[derive(Clone)]
 pub struct StaticFunc {
-    func: StaticFuncCallback,
+    name: String,
+    arity: Arity,
+    func: Rc<StaticFuncCallback>,
 }
 
 impl StaticFunc {
-    pub fn new(func: fn(Rc<Environment<Identifier, Object>>, args: Vec<Object>) -> Object) -> Self {
-        Self { func }
+    pub fn new<F>(name: &str, arity: Arity, func: F) -> Self
+    where
+        F: Fn(Vec<Object>) -> CallResult + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            arity,
+            func: Rc::new(func),
+        }
     }
 
-    pub fn arity(&self) -> usize {
-        0
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    /// call invokes the callback, checking the number of arguments passed
+    /// against the function's arity.
+    pub fn call(&self, args: Vec<Object>) -> CallResult {
+        if self.arity.accepts(args.len()) {
+            (self.func)(args)
+        } else {
+            Err(CallError::Arity(self.arity, args.len()))
+        }
+    }
+}
+
+impl fmt::Debug for StaticFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticFunc")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
     }
+}
+
+/// Static functions are equal only when they share the same callback.
+impl PartialEq for StaticFunc {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Rc::ptr_eq(&self.func, &other.func)
+    }
+}
 
-    pub fn call(&self, env: Rc<Environment<Identifier, Object>>, args: Vec<Object>) -> CallResult {
-        Ok((self.func)(env, args))
+/// NativeMethodCallback is the type that all native methods implement, taking
+/// the interpreter making the call, so that methods may call back into lox,
+/// and the arguments passed at call time.
+pub type NativeMethodCallback = dyn Fn(&StatefulInterpreter, Vec<Object>) -> CallResult;
+
+/// NativeMethod represents a native method bound to the built-in value it
+/// was looked up on.
+#[derive(Clone)]
+pub struct NativeMethod {
+    name: String,
+    arity: Arity,
+    receiver: Object,
+    func: Rc<NativeMethodCallback>,
+}
+
+impl NativeMethod {
+    pub fn new<F>(name: &str, arity: Arity, receiver: Object, func: F) -> Self
+    where
+        F: Fn(&StatefulInterpreter, Vec<Object>) -> CallResult + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            arity,
+            receiver,
+            func: Rc::new(func),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    /// is returns true if both methods share a name and are bound to the
+    /// same receiver.
+    pub fn is(&self, other: &NativeMethod) -> bool {
+        self.name == other.name && self.receiver.is_equal(&other.receiver)
+    }
+
+    /// call invokes the callback, checking the number of arguments passed
+    /// against the method's arity.
+    pub fn call(&self, interpreter: &StatefulInterpreter, args: Vec<Object>) -> CallResult {
+        if self.arity.accepts(args.len()) {
+            (self.func)(interpreter, args)
+        } else {
+            Err(CallError::Arity(self.arity, args.len()))
+        }
+    }
+}
+
+impl fmt::Debug for NativeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeMethod")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+impl PartialEq for NativeMethod {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.receiver == other.receiver
     }
 }
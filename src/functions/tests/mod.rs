@@ -1,20 +1,34 @@
 use crate::ast::expression::Expr;
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
 use crate::environment::Environment;
+use crate::functions::{self, Arity};
 
 macro_rules! gen_func {
     () => {
         $crate::functions::Function::new(
             Environment::new(),
             vec![],
-            Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]),
+            Stmt::Block(
+                vec![Stmt::Expression(
+                    Expr::Primary(obj_bool!(true), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            ),
         )
     };
     ($params:expr) => {
         $crate::functions::Function::new(
             Environment::new(),
             $params,
-            Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]),
+            Stmt::Block(
+                vec![Stmt::Expression(
+                    Expr::Primary(obj_bool!(true), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            ),
         )
     };
 }
@@ -27,13 +41,13 @@ macro_rules! gen_callable {
 
 #[test]
 fn arity_should_return_the_number_of_params_declared() {
-    assert_eq!(0, gen_callable!(gen_func!()).arity());
+    assert_eq!(Arity::Fixed(0), gen_callable!(gen_func!()).arity());
     assert_eq!(
-        1,
+        Arity::Fixed(1),
         gen_callable!(gen_func!(vec![identifier_name!("a"),])).arity()
     );
     assert_eq!(
-        2,
+        Arity::Fixed(2),
         gen_callable!(gen_func!(vec![
             identifier_name!("a"),
             identifier_name!("b")
@@ -41,3 +55,29 @@ fn arity_should_return_the_number_of_params_declared() {
         .arity()
     );
 }
+
+#[test]
+fn arity_should_accept_matching_argument_counts() {
+    assert!(Arity::Fixed(2).accepts(2));
+    assert!(!Arity::Fixed(2).accepts(1));
+    assert!(!Arity::Fixed(2).accepts(3));
+
+    assert!(Arity::Variadic(1).accepts(1));
+    assert!(Arity::Variadic(1).accepts(5));
+    assert!(!Arity::Variadic(1).accepts(0));
+}
+
+#[test]
+fn callable_objects_should_display_by_name() {
+    let func = gen_func!().named(&identifier_name!("test"));
+    let native = functions::StaticFunc::new("clock", Arity::Fixed(0), |_| Ok(obj_nil!()));
+
+    assert_eq!(
+        "<fn test>",
+        obj_call!(Box::new(functions::Callable::Func(func))).to_string()
+    );
+    assert_eq!(
+        "<native fn clock>",
+        obj_call!(Box::new(functions::Callable::Static(native))).to_string()
+    );
+}
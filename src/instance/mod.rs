@@ -1,6 +1,7 @@
 use crate::ast::identifier::Identifier;
 use crate::class::Class;
 use crate::environment::Environment;
+use crate::functions::Callable;
 use crate::object::Object;
 use std::fmt;
 use std::rc::Rc;
@@ -8,22 +9,43 @@ use std::rc::Rc;
 #[cfg(test)]
 mod tests;
 
+/// Instance represents an instantiated class. The class and fields are
+/// reference-counted, so clones of an instance are aliases that share their
+/// fields.
 #[derive(Debug, Clone)]
 pub struct Instance {
-    pub class: Class,
+    pub class: Rc<Class>,
     pub scope: Rc<Environment<Identifier, Object>>,
 }
 
 impl Instance {
     pub fn new(cls: &Class) -> Self {
         Self {
-            class: cls.clone(),
+            class: Rc::new(cls.clone()),
             scope: Environment::new(),
         }
     }
 
+    /// get returns the value of a field on the instance, falling back to
+    /// a method defined on the instance's class, bound to the instance, when
+    /// no field matches.
     pub fn get(&self, id: &Identifier) -> Option<Object> {
-        self.scope.clone().get(id)
+        self.scope.clone().get(id).or_else(|| {
+            self.class
+                .find_method(id)
+                .map(|method| obj_call!(Box::new(Callable::Func(method.bind(self)))))
+        })
+    }
+
+    /// is returns true if both instances are the same object, rather than
+    /// merely instances of the same class.
+    pub fn is(&self, other: &Instance) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+
+    /// set defines or overwrites a field on the instance.
+    pub fn set(&self, id: &Identifier, value: Object) -> Option<Object> {
+        self.scope.define(id, value)
     }
 }
 
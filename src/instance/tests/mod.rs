@@ -1,6 +1,10 @@
+use crate::ast::expression::Expr;
 use crate::ast::identifier::Identifier;
-use crate::class::Class;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::class::{Class, Methods};
 use crate::environment::Environment;
+use crate::functions::Function;
 use crate::instance::Instance;
 use crate::object::Object;
 use std::option::Option;
@@ -8,7 +12,7 @@ use std::rc::Rc;
 
 #[test]
 fn instance_should_allow_getting_of_parameters() {
-    let test_instance = Instance::new(&Class::new(&identifier_name!("test")));
+    let test_instance = Instance::new(&Class::new(&identifier_name!("test"), None, Methods::new()));
     let symtable: Rc<Environment<Identifier, Object>> = test_instance.scope.clone();
     let key = identifier_name!("key");
 
@@ -19,3 +23,43 @@ fn instance_should_allow_getting_of_parameters() {
 
     assert_eq!(test_instance.get(&key), Option::Some(obj_bool!(true)));
 }
+
+#[test]
+fn instance_should_fall_back_to_class_methods_on_missing_field() {
+    let mut methods = Methods::new();
+    methods.insert(
+        identifier_name!("method"),
+        Function::new(
+            Environment::new(),
+            vec![],
+            Stmt::Block(
+                vec![Stmt::Expression(
+                    Expr::Primary(obj_bool!(true), Span::default()),
+                    Span::default(),
+                )],
+                Span::default(),
+            ),
+        ),
+    );
+    let test_instance = Instance::new(&Class::new(&identifier_name!("test"), None, methods));
+
+    assert!(matches!(
+        test_instance.get(&identifier_name!("method")),
+        Option::Some(Object::Call(_))
+    ));
+    assert_eq!(
+        test_instance.get(&identifier_name!("undefined")),
+        Option::None
+    );
+}
+
+#[test]
+fn instance_clones_should_share_fields() {
+    let test_instance = Instance::new(&Class::new(&identifier_name!("test"), None, Methods::new()));
+    let alias = test_instance.clone();
+    let key = identifier_name!("key");
+
+    alias.set(&key, obj_bool!(true));
+
+    assert_eq!(test_instance.get(&key), Option::Some(obj_bool!(true)));
+}
@@ -1,13 +1,27 @@
+use crate::analyzer::scope::ScopeAnalyzer;
 use crate::ast::expression::{
     AdditionExpr, ComparisonExpr, EqualityExpr, Expr, LogicalExpr, MultiplicationExpr, UnaryExpr,
 };
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use crate::class;
+use crate::diagnostic::Diagnostic;
 use crate::environment::Environment;
 use crate::functions;
+use crate::instance::Instance;
+use crate::iterator::Iter;
+use crate::list::List;
+use crate::map::Map;
+use crate::module::{ImportErr, Modules};
 use crate::object::{Literal, Object};
 use crate::pass::*;
+use crate::runtime;
+use crate::statics;
+use crate::stdlib::{self, Namespace};
+use std::cell::RefCell;
 use std::fmt;
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
 
 #[cfg(test)]
@@ -38,14 +52,86 @@ macro_rules! type_error {
     };
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum ExprInterpreterErr {
     Unspecified,
     Type(&'static str),
     BinaryExpr(&'static str, Object, Object),
-    UndefinedVariable(String),
+    UndefinedVariable(String, Span),
+    UndefinedBinding(Span),
     UndefinedFunction,
     CallErr(String),
+    InvalidArgument(String),
+    Unhashable(Object),
+    IndexOutOfRange(f64, usize),
+    DivisionByZero,
+    Thrown(Object),
+    Spanned(Box<ExprInterpreterErr>, Span),
+    Traced(Box<ExprInterpreterErr>, Vec<Frame>),
+}
+
+impl ExprInterpreterErr {
+    /// at attaches the span of the expression that raised the error, unless
+    /// the error already carries a span.
+    fn at(self, span: Span) -> Self {
+        match self {
+            e @ Self::Spanned(_, _)
+            | e @ Self::UndefinedVariable(_, _)
+            | e @ Self::UndefinedBinding(_)
+            | e @ Self::Traced(_, _) => e,
+            e => Self::Spanned(Box::new(e), span),
+        }
+    }
+
+    /// undefined reports a variable without a value. Resolved bindings no
+    /// longer carry their name, but were declared, so are reported as used
+    /// before their definition ran.
+    fn undefined(id: &Identifier, span: Span) -> Self {
+        match id {
+            Identifier::Resolved(_) => Self::UndefinedBinding(span),
+            id => Self::UndefinedVariable(id.to_string(), span),
+        }
+    }
+
+    /// span returns the span of source the error was raised at, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Spanned(_, span)
+            | Self::UndefinedVariable(_, span)
+            | Self::UndefinedBinding(span) => Some(*span),
+            Self::Traced(e, _) => e.span(),
+            _ => None,
+        }
+    }
+
+    /// code returns the diagnostic code for the kind of the error.
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Type(_)
+            | Self::BinaryExpr(_, _, _)
+            | Self::InvalidArgument(_)
+            | Self::Unhashable(_)
+            | Self::DivisionByZero => "E0300",
+            Self::UndefinedVariable(_, _) | Self::UndefinedBinding(_) | Self::UndefinedFunction => {
+                "E0301"
+            }
+            Self::CallErr(_) => "E0302",
+            Self::Unspecified => "E0303",
+            Self::Thrown(_) => "E0308",
+            Self::IndexOutOfRange(_, _) => "E0309",
+            Self::Spanned(e, _) | Self::Traced(e, _) => e.code(),
+        }
+    }
+
+    /// thrown returns the value thrown by a throw statement, if the error was
+    /// raised by one.
+    fn thrown(&self) -> Option<&Object> {
+        match self {
+            Self::Thrown(obj) => Some(obj),
+            Self::Spanned(e, _) | Self::Traced(e, _) => e.thrown(),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ExprInterpreterErr {
@@ -58,27 +144,237 @@ impl fmt::Display for ExprInterpreterErr {
                 "invalid operand for operators: {} {} {}",
                 left, op, right
             ),
-            Self::UndefinedVariable(id) => write!(f, "undefined symbol: {}", id),
+            Self::UndefinedVariable(id, _) => write!(f, "undefined symbol: {}", id),
+            Self::UndefinedBinding(_) => write!(f, "variable is used before it is defined"),
             Self::UndefinedFunction => write!(f, "undefined function"),
             Self::CallErr(o) => write!(f, "{}", o),
+            Self::InvalidArgument(e) => write!(f, "{}", e),
+            Self::Unhashable(key) => write!(f, "unhashable map key: {}", key),
+            Self::IndexOutOfRange(index, len) => {
+                write!(f, "list index {} out of range for length {}", index, len)
+            }
+            Self::DivisionByZero => write!(f, "division by zero"),
+            // a rethrown error instance is reported by its message.
+            Self::Thrown(Object::Instance(i)) if i.get(&identifier_name!("message")).is_some() => {
+                write!(
+                    f,
+                    "uncaught exception: {}",
+                    i.get(&identifier_name!("message")).unwrap()
+                )
+            }
+            Self::Thrown(obj) => write!(f, "uncaught exception: {}", obj),
+            Self::Spanned(e, _) | Self::Traced(e, _) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<functions::CallError> for ExprInterpreterErr {
+    fn from(err: functions::CallError) -> Self {
+        match err {
+            e @ functions::CallError::Type(_) => Self::InvalidArgument(e.to_string()),
+            functions::CallError::Runtime(e) => *e,
+            functions::CallError::Unhashable(key) => Self::Unhashable(key),
+            functions::CallError::IndexOutOfRange(index, len) => Self::IndexOutOfRange(index, len),
+            e => Self::CallErr(e.to_string()),
         }
     }
 }
 
 pub type ExprInterpreterResult = Result<Object, ExprInterpreterErr>;
 
+/// Frame represents a call in progress, recording the function called, its
+/// name and the span of the call expression.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame {
+    pub name: String,
+    pub call_site: Span,
+    pub callee: Rc<functions::Callable>,
+}
+
+/// CallStack holds the frames of each call in progress, shared between the
+/// interpreters created for each function body and block.
+pub type CallStack = Rc<RefCell<Vec<Frame>>>;
+
 pub struct StatefulInterpreter {
     pub env: Rc<Environment<Identifier, Object>>,
+    builtins: Rc<Environment<Identifier, Object>>,
+    frames: CallStack,
+    modules: Rc<RefCell<Modules>>,
 }
 
 impl StatefulInterpreter {
+    /// new returns an interpreter with the standard natives and each enabled
+    /// stdlib namespace defined as builtins. Builtins live in an environment
+    /// enclosing the global environment of the script and of each module it
+    /// imports.
     pub fn new() -> StatefulInterpreter {
+        let builtins = builtins();
+        StatefulInterpreter {
+            env: Environment::from(&builtins),
+            builtins,
+            frames: Rc::new(RefCell::new(Vec::new())),
+            modules: Rc::new(RefCell::new(Modules::new())),
+        }
+    }
+
+    /// with_env returns an interpreter evaluating within the passed
+    /// environment that shares this interpreter's builtins, call stack and
+    /// modules.
+    pub fn with_env(&self, env: Rc<Environment<Identifier, Object>>) -> StatefulInterpreter {
         StatefulInterpreter {
-            env: Environment::new(),
+            env,
+            builtins: self.builtins.clone(),
+            frames: self.frames.clone(),
+            modules: self.modules.clone(),
+        }
+    }
+
+    /// register_native defines a builtin function callable from lox under
+    /// the passed name, replacing any builtin with the same name. Calls are
+    /// checked against the arity before the function is invoked. The name
+    /// must also be declared to the ScopeAnalyzer with `declare_builtin`.
+    ///
+    /// # Examples
+    /// ```
+    /// use rlox::functions::{Arity, CallError};
+    /// use rlox::interpreter::StatefulInterpreter;
+    /// use rlox::object::{Literal, Object};
+    ///
+    /// let interpreter = StatefulInterpreter::new();
+    /// interpreter.register_native("sum", Arity::Variadic(0), |args| {
+    ///     args.into_iter()
+    ///         .try_fold(0.0, |sum, arg| match arg {
+    ///             Object::Literal(Literal::Number(n)) => Ok(sum + n),
+    ///             obj => Err(CallError::Type(format!("expected number, found {}", obj))),
+    ///         })
+    ///         .map(|sum| Object::Literal(Literal::Number(sum)))
+    /// });
+    /// ```
+    pub fn register_native<F>(&self, name: &str, arity: functions::Arity, func: F)
+    where
+        F: Fn(Vec<Object>) -> functions::CallResult + 'static,
+    {
+        let func = functions::StaticFunc::new(name, arity, func);
+        self.builtins.define(
+            &Identifier::Builtin(name.to_string()),
+            obj_call!(Box::new(functions::Callable::Static(func))),
+        );
+    }
+
+    /// analyzer returns a ScopeAnalyzer aware of each of the interpreter's
+    /// builtins, for analyzing source it is about to run.
+    pub fn analyzer(&self) -> ScopeAnalyzer {
+        let mut analyzer = ScopeAnalyzer::new();
+        for key in self.builtins.keys() {
+            analyzer.declare_builtin(&key.to_string());
+        }
+        analyzer
+    }
+
+    /// set_script records the file the interpreter is running, resolving
+    /// imports relative to it rather than the working directory.
+    pub fn set_script(&self, path: &str) -> Result<(), ImportErr> {
+        let mut modules = self.modules.borrow_mut();
+        let path = modules.resolve(path)?;
+        modules.enter(path)
+    }
+
+    /// frames returns a copy of the call stack, from the outermost call in.
+    pub fn frames(&self) -> Vec<Frame> {
+        self.frames.borrow().clone()
+    }
+
+    /// lookup returns the value of a variable visible from the interpreter's
+    /// environment. Resolved identifiers are read from the environment their
+    /// binding's distance away, while unresolved identifiers are searched for
+    /// through each enclosing environment.
+    pub fn lookup(&self, id: &Identifier) -> Option<Object> {
+        match id {
+            Identifier::Resolved(binding) => self.env.get_at(binding.distance, &id.key()),
+            _ => self.env.get(id),
+        }
+    }
+
+    /// call invokes a callable, pushing a frame for the call onto the call
+    /// stack for its duration.
+    pub fn call(
+        &self,
+        callee: Rc<functions::Callable>,
+        args: Vec<Object>,
+        call_site: Span,
+    ) -> ExprInterpreterResult {
+        self.frames.borrow_mut().push(Frame {
+            name: callee.name(),
+            call_site,
+            callee: callee.clone(),
+        });
+
+        // errors raised within the callee are traced with the call stack as
+        // it stood when they first unwound through a call.
+        let result = callee.call(self, args).map_err(|e| match e {
+            functions::CallError::Runtime(e) => match *e {
+                e @ ExprInterpreterErr::Traced(_, _) => e,
+                e => ExprInterpreterErr::Traced(Box::new(e), self.frames()),
+            },
+            e => ExprInterpreterErr::from(e),
+        });
+        self.frames.borrow_mut().pop();
+        result
+    }
+
+    /// call_site returns the span of the innermost call in progress, to
+    /// which calls made by native functions are attributed.
+    pub fn call_site(&self) -> Span {
+        self.frames
+            .borrow()
+            .last()
+            .map_or(Span::default(), |frame| frame.call_site)
+    }
+
+    /// call_from_native invokes a callable on behalf of a native function,
+    /// such as a callback passed to a list method. Having no source of its
+    /// own, the call is attributed to the call site of the innermost call in
+    /// progress.
+    pub fn call_from_native(
+        &self,
+        callee: Rc<functions::Callable>,
+        args: Vec<Object>,
+    ) -> ExprInterpreterResult {
+        self.call(callee, args, self.call_site())
+    }
+
+    /// define defines a variable within the interpreter's environment.
+    fn define(&self, id: &Identifier, value: Object) -> Option<Object> {
+        self.env.define(&id.key(), value)
+    }
+
+    fn assign(&self, id: &Identifier, value: Object) -> Option<Object> {
+        match id {
+            Identifier::Resolved(binding) => self.env.assign_at(binding.distance, &id.key(), value),
+            _ => self.env.assign(id, value),
         }
     }
 }
 
+/// builtins returns an environment defining the standard natives and each
+/// enabled stdlib namespace.
+fn builtins() -> Rc<Environment<Identifier, Object>> {
+    let env = Environment::new();
+    for native in statics::natives() {
+        env.define(
+            &Identifier::Builtin(native.name().to_string()),
+            obj_call!(Box::new(functions::Callable::Static(native))),
+        );
+    }
+    for namespace in stdlib::namespaces() {
+        env.define(
+            &Identifier::Builtin(namespace.name().to_string()),
+            Object::Namespace(namespace),
+        );
+    }
+    env
+}
+
 impl Default for StatefulInterpreter {
     fn default() -> Self {
         Self::new()
@@ -93,153 +389,179 @@ impl From<Rc<Environment<Identifier, Object>>> for StatefulInterpreter {
     }
 }
 
-/// Interpreter<Expr, object::Object> begins implemententing the required state
-/// for interpreting Expressions in a stateful way.
-impl Pass<Expr, Object> for StatefulInterpreter {
+/// Interpreter<&Expr, object::Object> begins implemententing the required
+/// state for interpreting Expressions in a stateful way. Expressions are
+/// borrowed, so that function bodies and loops are evaluated in place each
+/// time they run.
+impl Pass<&Expr, Object> for StatefulInterpreter {
     type Error = ExprInterpreterErr;
 
-    fn tree_pass(&self, expr: Expr) -> ExprInterpreterResult {
+    fn tree_pass(&self, expr: &Expr) -> ExprInterpreterResult {
+        let span = expr.span();
         match expr {
-            Expr::Grouping(expr) => self.tree_pass(expr),
-            Expr::Lambda(params, body) => self.interpret_lambda(params, *body),
-            Expr::Variable(id) => self.interpret_variable(id),
-            Expr::Primary(obj) => self.interpret_primary(obj),
-            Expr::Call(callee, args) => self.interpret_call(*callee, args),
-            Expr::Get(instance, param) => self.interpret_get(*instance, *param),
-            Expr::Unary(expr) => self.interpret_unary(expr),
-            Expr::Multiplication(expr) => self.interpret_multiplication(expr),
-            Expr::Addition(expr) => self.interpret_addition(expr),
-            Expr::Comparison(expr) => self.interpret_comparison(expr),
-            Expr::Equality(expr) => self.interpret_equality(expr),
-            Expr::Logical(expr) => self.interpret_logical(expr),
-            Expr::Assignment(id, expr) => self.interpret_assignment(id, expr),
+            Expr::Grouping(expr, _) => self.tree_pass(expr.as_ref()),
+            Expr::Lambda(params, body, _) => self.interpret_lambda(params, body),
+            Expr::Variable(id, span) => self.interpret_variable(id, *span),
+            Expr::This(span) => self.interpret_variable(&identifier_name!("this"), *span),
+            Expr::Super(method, span) => self.interpret_super(method, *span),
+            Expr::Primary(obj, _) => self.interpret_primary(obj),
+            Expr::Call(callee, args, span) => self.interpret_call(callee, args, *span),
+            Expr::Get(instance, param, _) => self.interpret_get(instance, param),
+            Expr::Set(instance, param, value, _) => self.interpret_set(instance, param, value),
+            Expr::List(elems, _) => self.interpret_list(elems),
+            Expr::Map(entries, _) => self.interpret_map(entries),
+            Expr::Index(list, index, _) => self.interpret_index(list, index),
+            Expr::SetIndex(list, index, value, _) => self.interpret_set_index(list, index, value),
+            Expr::Unary(expr, _) => self.interpret_unary(expr),
+            Expr::Multiplication(expr, _) => self.interpret_multiplication(expr),
+            Expr::Addition(expr, _) => self.interpret_addition(expr),
+            Expr::Comparison(expr, _) => self.interpret_comparison(expr),
+            Expr::Equality(expr, _) => self.interpret_equality(expr),
+            Expr::Logical(expr, _) => self.interpret_logical(expr),
+            Expr::Assignment(id, expr, span) => self.interpret_assignment(id, expr, *span),
         }
+        .map_err(|e| e.at(span))
+    }
+}
+
+/// This functions only to dispatch an owned Expr to the upstream
+/// Interpreter<&Expr, object::Object> implementation.
+impl Pass<Expr, Object> for StatefulInterpreter {
+    type Error = ExprInterpreterErr;
+    fn tree_pass(&self, expr: Expr) -> ExprInterpreterResult {
+        self.tree_pass(&expr)
     }
 }
 
 /// This functions only to unpack an Expr and dispatch to the upstream
-/// Interpreter<Expr, object::Object> implementation.
+/// Interpreter<&Expr, object::Object> implementation.
 impl Pass<Box<Expr>, Object> for StatefulInterpreter {
     type Error = ExprInterpreterErr;
     fn tree_pass(&self, expr: Box<Expr>) -> ExprInterpreterResult {
-        self.tree_pass(*expr)
+        self.tree_pass(expr.as_ref())
     }
 }
 
 impl StatefulInterpreter {
-    fn interpret_assignment(&self, id: Identifier, expr: Box<Expr>) -> ExprInterpreterResult {
-        let lhv = id;
+    fn interpret_assignment(
+        &self,
+        id: &Identifier,
+        expr: &Expr,
+        span: Span,
+    ) -> ExprInterpreterResult {
         let rhv = self.tree_pass(expr)?;
 
-        match self.env.assign(&lhv, rhv) {
+        match self.assign(id, rhv) {
             Some(v) => Ok(v),
-            None => Err(ExprInterpreterErr::UndefinedVariable(format!("{:?}", &lhv))),
+            None => Err(ExprInterpreterErr::undefined(id, span)),
         }
     }
 
-    fn interpret_logical(&self, expr: LogicalExpr) -> ExprInterpreterResult {
+    fn interpret_logical(&self, expr: &LogicalExpr) -> ExprInterpreterResult {
         match expr {
             LogicalExpr::Or(left, right) => {
-                let lho: Object = self.tree_pass(left)?;
+                let lho: Object = self.tree_pass(left.as_ref())?;
                 let lho_bool: bool = lho.clone().into();
                 if lho_bool {
                     Ok(lho)
                 } else {
-                    self.tree_pass(right)
+                    self.tree_pass(right.as_ref())
                 }
             }
             LogicalExpr::And(left, right) => {
-                let lho: Object = self.tree_pass(left)?;
+                let lho: Object = self.tree_pass(left.as_ref())?;
                 let lho_bool: bool = lho.clone().into();
                 if !lho_bool {
                     Ok(lho)
                 } else {
-                    self.tree_pass(right)
+                    self.tree_pass(right.as_ref())
                 }
             }
         }
     }
 
-    fn interpret_equality(&self, expr: EqualityExpr) -> ExprInterpreterResult {
+    fn interpret_equality(&self, expr: &EqualityExpr) -> ExprInterpreterResult {
         match expr {
-            EqualityExpr::Equal(left, right) => match (self.tree_pass(left), self.tree_pass(right))
-            {
-                (
-                    Ok(Object::Literal(Literal::Number(l_val))),
-                    Ok(Object::Literal(Literal::Number(r_val))),
-                ) => Ok(obj_bool!((l_val - r_val).abs() < std::f64::EPSILON)),
-                (
-                    Ok(Object::Literal(Literal::Str(l_val))),
-                    Ok(Object::Literal(Literal::Str(r_val))),
-                ) => Ok(obj_bool!(l_val == r_val)),
-                (Ok(l), Ok(r)) => type_error!(l, "==", r),
-                _ => type_error!(),
-            },
+            EqualityExpr::Equal(left, right) => {
+                let (l, r) = (
+                    self.tree_pass(left.as_ref())?,
+                    self.tree_pass(right.as_ref())?,
+                );
+                Ok(obj_bool!(l.is_equal(&r)))
+            }
             EqualityExpr::NotEqual(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
-                    (
-                        Ok(Object::Literal(Literal::Number(l_val))),
-                        Ok(Object::Literal(Literal::Number(r_val))),
-                    ) => Ok(obj_bool!((l_val - r_val).abs() > std::f64::EPSILON)),
-                    (
-                        Ok(Object::Literal(Literal::Str(l_val))),
-                        Ok(Object::Literal(Literal::Str(r_val))),
-                    ) => Ok(obj_bool!(l_val != r_val)),
-                    (Ok(l), Ok(r)) => type_error!(l, "!=", r),
-                    _ => type_error!(),
-                }
+                let (l, r) = (
+                    self.tree_pass(left.as_ref())?,
+                    self.tree_pass(right.as_ref())?,
+                );
+                Ok(obj_bool!(!l.is_equal(&r)))
             }
         }
     }
 
-    fn interpret_comparison(&self, expr: ComparisonExpr) -> ExprInterpreterResult {
+    fn interpret_comparison(&self, expr: &ComparisonExpr) -> ExprInterpreterResult {
         match expr {
             ComparisonExpr::Less(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_bool!(l_val < r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, "<", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
             ComparisonExpr::LessEqual(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_bool!(l_val <= r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, "<=", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
             ComparisonExpr::Greater(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_bool!(l_val > r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, ">", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
             ComparisonExpr::GreaterEqual(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_bool!(l_val >= r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, ">=", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
         }
     }
 
-    fn interpret_addition(&self, expr: AdditionExpr) -> ExprInterpreterResult {
+    fn interpret_addition(&self, expr: &AdditionExpr) -> ExprInterpreterResult {
         match expr {
-            AdditionExpr::Add(left, right) => match (self.tree_pass(left), self.tree_pass(right)) {
+            AdditionExpr::Add(left, right) => match (
+                self.tree_pass(left.as_ref()),
+                self.tree_pass(right.as_ref()),
+            ) {
                 (
                     Ok(Object::Literal(Literal::Number(l_val))),
                     Ok(Object::Literal(Literal::Number(r_val))),
@@ -249,56 +571,71 @@ impl StatefulInterpreter {
                     Ok(Object::Literal(Literal::Str(r_val))),
                 ) => Ok(obj_str!(format!("{}{}", l_val, r_val))),
                 (Ok(l), Ok(r)) => type_error!(l, "+", r),
-                _ => type_error!(),
+                (Err(e), _) | (_, Err(e)) => Err(e),
             },
             AdditionExpr::Subtract(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_number!(l_val - r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, "-", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
         }
     }
 
-    fn interpret_multiplication(&self, expr: MultiplicationExpr) -> ExprInterpreterResult {
+    fn interpret_multiplication(&self, expr: &MultiplicationExpr) -> ExprInterpreterResult {
         match expr {
             MultiplicationExpr::Multiply(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
                     ) => Ok(obj_number!(l_val * r_val)),
                     (Ok(l), Ok(r)) => type_error!(l, "*", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
             MultiplicationExpr::Divide(left, right) => {
-                match (self.tree_pass(left), self.tree_pass(right)) {
+                match (
+                    self.tree_pass(left.as_ref()),
+                    self.tree_pass(right.as_ref()),
+                ) {
                     (
                         Ok(Object::Literal(Literal::Number(l_val))),
                         Ok(Object::Literal(Literal::Number(r_val))),
-                    ) => Ok(obj_number!(l_val / r_val)),
+                    ) => {
+                        if r_val == 0.0 {
+                            Err(ExprInterpreterErr::DivisionByZero)
+                        } else {
+                            Ok(obj_number!(l_val / r_val))
+                        }
+                    }
                     (Ok(l), Ok(r)) => type_error!(l, "/", r),
-                    _ => type_error!(),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
                 }
             }
         }
     }
 
-    fn interpret_unary(&self, expr: UnaryExpr) -> ExprInterpreterResult {
+    fn interpret_unary(&self, expr: &UnaryExpr) -> ExprInterpreterResult {
         match expr {
-            UnaryExpr::Bang(ue) => match self.tree_pass(ue) {
+            UnaryExpr::Bang(ue) => match self.tree_pass(ue.as_ref()) {
                 Ok(obj) => {
                     let ob: bool = obj.into();
                     Ok(obj_bool!(!ob))
                 }
                 e @ Err(_) => e,
             },
-            UnaryExpr::Minus(ue) => match self.tree_pass(ue) {
+            UnaryExpr::Minus(ue) => match self.tree_pass(ue.as_ref()) {
                 Ok(Object::Literal(Literal::Number(n))) => Ok(obj_number!(n * -1.0)),
                 e @ Err(_) => e,
                 _ => type_error!(),
@@ -306,12 +643,12 @@ impl StatefulInterpreter {
         }
     }
 
-    fn interpret_call(&self, callee: Expr, args: Vec<Expr>) -> ExprInterpreterResult {
+    fn interpret_call(&self, callee: &Expr, args: &[Expr], span: Span) -> ExprInterpreterResult {
         let fun = self.tree_pass(callee)?;
-        let params: Vec<Object> = args
-            .into_iter()
-            .map(|expr| self.tree_pass(expr).unwrap())
-            .collect();
+        let params = args
+            .iter()
+            .map(|expr| self.tree_pass(expr))
+            .collect::<Result<Vec<Object>, ExprInterpreterErr>>()?;
 
         let c = match fun {
             Object::Call(c) => Ok(c),
@@ -321,64 +658,170 @@ impl StatefulInterpreter {
             ))),
         }?;
 
-        match c.call(self.env.clone(), params) {
-            Ok(r) => Ok(r),
-            Err(e) => Err(ExprInterpreterErr::CallErr(format!("{:?}", e))),
+        self.call(Rc::new(*c), params, span)
+    }
+
+    fn interpret_list(&self, elems: &[Expr]) -> ExprInterpreterResult {
+        elems
+            .iter()
+            .map(|expr| self.tree_pass(expr))
+            .collect::<Result<Vec<Object>, ExprInterpreterErr>>()
+            .map(|items| Object::List(List::new(items)))
+    }
+
+    fn interpret_map(&self, entries: &[(Expr, Expr)]) -> ExprInterpreterResult {
+        let map = Map::new();
+        for (key, value) in entries {
+            let k = self.tree_pass(key)?;
+            let v = self.tree_pass(value)?;
+            map.set(&k, v)?;
         }
+
+        Ok(Object::Map(map))
     }
 
-    fn interpret_get(&self, instance: Expr, param: Expr) -> ExprInterpreterResult {
-        let i = self.tree_pass(instance).map(|obj_res| match obj_res {
-            Object::Instance(i) => Ok(i),
-            _ => Err(ExprInterpreterErr::CallErr(format!(
-                "object {} is not callable",
-                obj_res
-            ))),
-        })??;
+    fn interpret_index(&self, collection: &Expr, index: &Expr) -> ExprInterpreterResult {
+        match self.tree_pass(collection)? {
+            Object::List(l) => {
+                let i = l.index_of(&self.tree_pass(index)?)?;
+                l.get(i)
+                    .ok_or_else(|| ExprInterpreterErr::IndexOutOfRange(i as f64, l.len()))
+            }
+            Object::Map(m) => {
+                let key = self.tree_pass(index)?;
+                m.get(&key)?.ok_or_else(|| {
+                    ExprInterpreterErr::CallErr(format!("undefined map key: {}", key))
+                })
+            }
+            _ => type_error!("only lists and maps can be indexed"),
+        }
+    }
+
+    fn interpret_set_index(
+        &self,
+        collection: &Expr,
+        index: &Expr,
+        value: &Expr,
+    ) -> ExprInterpreterResult {
+        match self.tree_pass(collection)? {
+            Object::List(l) => {
+                let i = l.index_of(&self.tree_pass(index)?)?;
+                let v = self.tree_pass(value)?;
+                l.set(i, v)
+                    .ok_or_else(|| ExprInterpreterErr::IndexOutOfRange(i as f64, l.len()))
+            }
+            Object::Map(m) => {
+                let key = self.tree_pass(index)?;
+                let v = self.tree_pass(value)?;
+                Ok(m.set(&key, v)?)
+            }
+            _ => type_error!("only lists and maps can be indexed"),
+        }
+    }
 
-        let param_id = if let Expr::Variable(id) = param {
-            Ok(id)
+    fn interpret_get(&self, instance: &Expr, param: &Expr) -> ExprInterpreterResult {
+        let obj = self.tree_pass(instance)?;
+
+        let (param_id, span) = if let Expr::Variable(id, span) = param {
+            Ok((id, *span))
         } else {
             Err(ExprInterpreterErr::Type(
                 "Expected identifier for parameter",
             ))
         }?;
 
-        i.get(&param_id).map_or(
-            Err(ExprInterpreterErr::UndefinedVariable(param_id.to_string())),
-            Ok,
+        let property = match obj {
+            Object::Instance(i) => i.get(param_id),
+            Object::List(l) => l
+                .method(&param_id.to_string())
+                .map(|method| obj_call!(Box::new(method))),
+            Object::Map(m) => m
+                .method(&param_id.to_string())
+                .map(|method| obj_call!(Box::new(method))),
+            Object::Iterator(i) => i
+                .method(&param_id.to_string())
+                .map(|method| obj_call!(Box::new(method))),
+            Object::Namespace(n) => n.get(&param_id.to_string()),
+            _ => return type_error!("only instances have properties"),
+        };
+
+        property.ok_or_else(|| ExprInterpreterErr::UndefinedVariable(param_id.to_string(), span))
+    }
+
+    fn interpret_set(
+        &self,
+        instance: &Expr,
+        param: &Identifier,
+        value: &Expr,
+    ) -> ExprInterpreterResult {
+        let i = match self.tree_pass(instance)? {
+            Object::Instance(i) => i,
+            _ => return type_error!("only instances have fields"),
+        };
+
+        let v = self.tree_pass(value)?;
+        i.set(param, v.clone());
+
+        Ok(v)
+    }
+
+    fn interpret_super(&self, method: &Identifier, span: Span) -> ExprInterpreterResult {
+        let superclass = match self.interpret_variable(&identifier_name!("super"), span)? {
+            Object::Call(c) => match *c {
+                functions::Callable::Class(c) => c,
+                _ => return type_error!("superclass must be a class"),
+            },
+            _ => return type_error!("superclass must be a class"),
+        };
+        let instance = match self.interpret_variable(&identifier_name!("this"), span)? {
+            Object::Instance(i) => i,
+            _ => return type_error!("only instances have methods"),
+        };
+
+        superclass.find_method(method).map_or(
+            Err(ExprInterpreterErr::UndefinedVariable(
+                method.to_string(),
+                span,
+            )),
+            |m| {
+                let callable = functions::Callable::Func(m.bind(&instance));
+                Ok(obj_call!(Box::new(callable)))
+            },
         )
     }
 
-    fn interpret_lambda(&self, params: Vec<Identifier>, body: Stmt) -> ExprInterpreterResult {
-        let func = functions::Function::new(self.env.clone(), params, body);
+    fn interpret_lambda(&self, params: &[Identifier], body: &Stmt) -> ExprInterpreterResult {
+        let func = functions::Function::new(self.env.clone(), params.to_vec(), body.clone());
         let callable = functions::Callable::Func(func);
         let obj = Object::Call(Box::new(callable));
 
         Ok(obj)
     }
 
-    fn interpret_primary(&self, obj: Object) -> ExprInterpreterResult {
-        Ok(obj)
+    fn interpret_primary(&self, obj: &Object) -> ExprInterpreterResult {
+        Ok(obj.clone())
     }
 
-    fn interpret_variable(&self, identifier: Identifier) -> ExprInterpreterResult {
-        match self.env.get(&identifier) {
+    fn interpret_variable(&self, identifier: &Identifier, span: Span) -> ExprInterpreterResult {
+        match self.lookup(identifier) {
             Some(v) => Ok(v),
-            None => Err(ExprInterpreterErr::UndefinedVariable(format!(
-                "{:?}",
-                &identifier
-            ))),
+            None => Err(ExprInterpreterErr::undefined(identifier, span)),
         }
     }
 }
 
 use crate::ast::statement::Stmt;
 
-#[derive(PartialEq, Debug)]
+/// StmtInterpreterErr represents a failure to execute a statement. Break and
+/// Continue unwind from a `break` or `continue` statement to the innermost
+/// enclosing loop, and only surface as errors when no loop encloses them.
+#[derive(PartialEq, Debug, Clone)]
 pub enum StmtInterpreterErr {
     Unspecified,
     Expression(ExprInterpreterErr),
+    Break,
+    Continue,
+    Import(ImportErr, Span),
 }
 
 impl fmt::Display for StmtInterpreterErr {
@@ -386,16 +829,56 @@ impl fmt::Display for StmtInterpreterErr {
         match self {
             Self::Unspecified => write!(f, "unspecified statement error"),
             Self::Expression(e) => write!(f, "Expression Error: {}", e),
+            Self::Break => write!(f, "break outside of a loop"),
+            Self::Continue => write!(f, "continue outside of a loop"),
+            Self::Import(e, _) => write!(f, "{}", e),
         }
     }
 }
 
+impl From<StmtInterpreterErr> for Diagnostic {
+    fn from(err: StmtInterpreterErr) -> Self {
+        match err {
+            StmtInterpreterErr::Unspecified
+            | StmtInterpreterErr::Break
+            | StmtInterpreterErr::Continue => {
+                Diagnostic::error("E0303", &err.to_string(), Span::default())
+            }
+            StmtInterpreterErr::Import(e, span) => {
+                Diagnostic::error(e.code(), &e.to_string(), span)
+            }
+            StmtInterpreterErr::Expression(e) => {
+                let span = e.span().unwrap_or_default();
+                let diagnostic = Diagnostic::error(e.code(), &e.to_string(), span);
+                match e {
+                    ExprInterpreterErr::Traced(_, frames) => with_backtrace(diagnostic, &frames),
+                    _ => diagnostic,
+                }
+            }
+        }
+    }
+}
+
+/// with_backtrace attaches a frame to the diagnostic for each call the error
+/// unwound through, from the innermost call out. Each frame points at the
+/// source its function was executing, ending with the top-level call site.
+fn with_backtrace(diagnostic: Diagnostic, frames: &[Frame]) -> Diagnostic {
+    let mut span = diagnostic.span;
+    let mut diagnostic = diagnostic;
+    for frame in frames.iter().rev() {
+        diagnostic = diagnostic.with_frame(&frame.name, span);
+        span = frame.call_site;
+    }
+
+    diagnostic.with_frame("<script>", span)
+}
+
 pub type StmtInterpreterResult = Result<Option<Object>, StmtInterpreterErr>;
 
-impl Pass<Vec<Stmt>, Option<Object>> for StatefulInterpreter {
+impl Pass<&[Stmt], Option<Object>> for StatefulInterpreter {
     type Error = StmtInterpreterErr;
 
-    fn tree_pass(&self, input: Vec<Stmt>) -> StmtInterpreterResult {
+    fn tree_pass(&self, input: &[Stmt]) -> StmtInterpreterResult {
         for stmt in input {
             match self.tree_pass(stmt) {
                 Ok(None) => continue,
@@ -407,43 +890,198 @@ impl Pass<Vec<Stmt>, Option<Object>> for StatefulInterpreter {
     }
 }
 
-impl Pass<Stmt, Option<Object>> for StatefulInterpreter {
+/// This functions only to dispatch owned statements to the upstream
+/// Interpreter<&[Stmt], Option<Object>> implementation.
+impl Pass<Vec<Stmt>, Option<Object>> for StatefulInterpreter {
     type Error = StmtInterpreterErr;
 
-    fn tree_pass(&self, input: Stmt) -> StmtInterpreterResult {
+    fn tree_pass(&self, input: Vec<Stmt>) -> StmtInterpreterResult {
+        self.tree_pass(input.as_slice())
+    }
+}
+
+/// Statements, like expressions, are borrowed, so that function bodies and
+/// loops run without copying their statements.
+impl Pass<&Stmt, Option<Object>> for StatefulInterpreter {
+    type Error = StmtInterpreterErr;
+
+    fn tree_pass(&self, input: &Stmt) -> StmtInterpreterResult {
         match input {
-            Stmt::Expression(expr) => self.interpret_expression_stmt(expr),
-            Stmt::If(expr, tb, eb) => self.interpret_if_stmt(expr, tb, eb),
-            Stmt::While(cond, body) => self.interpret_while_stmt(cond, body),
-            Stmt::Print(expr) => self.interpret_print_stmt(expr),
-            Stmt::Function(name, params, body) => {
-                self.interpret_function_decl_stmt(name, params, *body)
+            Stmt::Expression(expr, _) => self.interpret_expression_stmt(expr),
+            Stmt::If(expr, tb, eb, _) => self.interpret_if_stmt(expr, tb, eb.as_deref()),
+            Stmt::While(cond, body, inc, _) => self.interpret_while_stmt(cond, body, inc.as_ref()),
+            Stmt::ForIn(id, iterable, body, _) => self.interpret_for_in_stmt(id, iterable, body),
+            Stmt::Print(expr, _) => self.interpret_print_stmt(expr),
+            Stmt::Function(id, name, params, body, _) => {
+                self.interpret_function_decl_stmt(id, name, params, body)
+            }
+            Stmt::Declaration(name, expr, _) => self.interpret_declaration_stmt(name, expr),
+            Stmt::Return(expr, _) => self.interpret_return_stmt(expr.as_ref()),
+            Stmt::Class(id, name, superclass, stmts, _) => {
+                self.interpret_class_decl_stmt(id, name, superclass.as_ref(), stmts)
+            }
+            Stmt::Block(stmts, _) => self.interpret_block(stmts),
+            Stmt::Break(_) => Err(StmtInterpreterErr::Break),
+            Stmt::Continue(_) => Err(StmtInterpreterErr::Continue),
+            Stmt::Import(path, id, span) => self.interpret_import_stmt(path, id, *span),
+            Stmt::Throw(expr, span) => self.interpret_throw_stmt(expr, *span),
+            Stmt::Try(body, catch, finally, _) => {
+                self.interpret_try_stmt(body, catch.as_ref(), finally.as_deref())
             }
-            Stmt::Declaration(name, expr) => self.interpret_declaration_stmt(name, expr),
-            Stmt::Return(expr) => self.interpret_return_stmt(expr),
-            Stmt::Class(id, stmts) => self.interpret_class_decl_stmt(id, stmts),
-            Stmt::Block(stmts) => self.interpret_block(stmts),
         }
     }
 }
 
-/// This functions only to unpack an Stmt and dispatch to the upstream Interpreter<Stmt, Object)> implementation
+/// This functions only to dispatch an owned Stmt to the upstream
+/// Interpreter<&Stmt, Option<Object>> implementation.
+impl Pass<Stmt, Option<Object>> for StatefulInterpreter {
+    type Error = StmtInterpreterErr;
+    fn tree_pass(&self, input: Stmt) -> StmtInterpreterResult {
+        self.tree_pass(&input)
+    }
+}
+
+/// This functions only to unpack an Stmt and dispatch to the upstream Interpreter<&Stmt, Object)> implementation
 impl Pass<Box<Stmt>, Option<Object>> for StatefulInterpreter {
     type Error = StmtInterpreterErr;
     fn tree_pass(&self, input: Box<Stmt>) -> StmtInterpreterResult {
-        self.tree_pass(*input)
+        self.tree_pass(input.as_ref())
     }
 }
 
 impl StatefulInterpreter {
-    fn interpret_expression_stmt(&self, expr: Expr) -> StmtInterpreterResult {
+    fn interpret_throw_stmt(&self, expr: &Expr, span: Span) -> StmtInterpreterResult {
+        let obj = self
+            .tree_pass(expr)
+            .map_err(StmtInterpreterErr::Expression)?;
+        Err(StmtInterpreterErr::Expression(
+            ExprInterpreterErr::Thrown(obj).at(span),
+        ))
+    }
+
+    /// interpret_try_stmt runs the body of a try statement, handing any error
+    /// it raises to the catch clause. The finally block runs however the body
+    /// and handler complete, with its own return, break or error taking the
+    /// place of theirs.
+    fn interpret_try_stmt(
+        &self,
+        body: &Stmt,
+        catch: Option<&(Identifier, Box<Stmt>)>,
+        finally: Option<&Stmt>,
+    ) -> StmtInterpreterResult {
+        let result = match (self.tree_pass(body), catch) {
+            (Err(StmtInterpreterErr::Expression(e)), Some((id, handler))) => {
+                let intptr = self.with_env(Environment::from(&self.env));
+                intptr.define(id, self.error_object(e));
+                intptr.tree_pass(handler.as_ref())
+            }
+            (result, _) => result,
+        };
+
+        match finally.map(|finally| self.tree_pass(finally)) {
+            None | Some(Ok(None)) => result,
+            Some(completion) => completion,
+        }
+    }
+
+    /// error_object returns the value a catch clause binds for an error.
+    /// Thrown values are bound as they were thrown, while runtime errors are
+    /// bound as an Error instance with `message`, `line` and `stack` fields.
+    fn error_object(&self, err: ExprInterpreterErr) -> Object {
+        if let Some(obj) = err.thrown() {
+            return obj.clone();
+        }
+
+        // errors that have not unwound through a call are traced with the
+        // calls still in progress.
+        let err = match err {
+            e @ ExprInterpreterErr::Traced(_, _) => e,
+            e => ExprInterpreterErr::Traced(Box::new(e), self.frames()),
+        };
+        let diagnostic = Diagnostic::from(StmtInterpreterErr::Expression(err));
+        let stack = diagnostic
+            .backtrace
+            .iter()
+            .map(|frame| obj_str!(format!("{} (line {})", frame.name, frame.span.line)))
+            .collect();
+
+        let error = Instance::new(&class::Class::new(
+            &identifier_name!("Error"),
+            None,
+            class::Methods::new(),
+        ));
+        error.set(&identifier_name!("message"), obj_str!(diagnostic.message));
+        error.set(
+            &identifier_name!("line"),
+            obj_number!(diagnostic.span.line as f64),
+        );
+        error.set(&identifier_name!("stack"), Object::List(List::new(stack)));
+        Object::Instance(error)
+    }
+
+    fn interpret_import_stmt(
+        &self,
+        path: &str,
+        id: &Identifier,
+        span: Span,
+    ) -> StmtInterpreterResult {
+        let namespace = self
+            .import(path)
+            .map_err(|e| StmtInterpreterErr::Import(e, span))?;
+        self.define(id, Object::Namespace(namespace));
+        Ok(None)
+    }
+
+    /// import returns the namespace of the module at the passed path, running
+    /// the module in its own global environment the first time it is
+    /// imported.
+    fn import(&self, path: &str) -> Result<Namespace, ImportErr> {
+        let resolved = self.modules.borrow().resolve(path)?;
+        if let Some(namespace) = self.modules.borrow().get(&resolved) {
+            return Ok(namespace);
+        }
+
+        self.modules.borrow_mut().enter(resolved.clone())?;
+        let namespace = self.load(path, &resolved);
+        self.modules.borrow_mut().exit(namespace.clone().ok());
+        namespace
+    }
+
+    fn load(&self, path: &str, resolved: &Path) -> Result<Namespace, ImportErr> {
+        let source = fs::read_to_string(resolved)
+            .map_err(|e| ImportErr::Read(path.to_string(), e.to_string()))?;
+        let module_err = |diagnostics| ImportErr::Module(path.to_string(), diagnostics);
+
+        let mut analyzer = self.analyzer();
+        analyzer.set_script(resolved);
+        let stmts = runtime::parse(&source).map_err(module_err)?;
+        let analyzed = analyzer
+            .tree_pass(stmts)
+            .map_err(|e| module_err(vec![e.into()]))?;
+
+        // cycles are reported as a whole at the import that closed them,
+        // rather than wrapped by each module along the chain.
+        let module = self.with_env(Environment::from(&self.builtins));
+        module.tree_pass(analyzed).map_err(|e| match e {
+            StmtInterpreterErr::Import(cycle @ ImportErr::Cycle(_), _) => cycle,
+            e => module_err(vec![e.into()]),
+        })?;
+
+        let name = resolved
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        Ok(Namespace::module(&name, module.env, analyzer.globals()))
+    }
+
+    fn interpret_expression_stmt(&self, expr: &Expr) -> StmtInterpreterResult {
         match self.tree_pass(expr) {
             Ok(_) => Ok(None),
             Err(err) => Err(StmtInterpreterErr::Expression(err)),
         }
     }
 
-    fn interpret_print_stmt(&self, expr: Expr) -> StmtInterpreterResult {
+    fn interpret_print_stmt(&self, expr: &Expr) -> StmtInterpreterResult {
         match self.tree_pass(expr) {
             Ok(expr) => {
                 println!("{}", expr);
@@ -453,10 +1091,10 @@ impl StatefulInterpreter {
         }
     }
 
-    fn interpret_declaration_stmt(&self, id: Identifier, expr: Expr) -> StmtInterpreterResult {
+    fn interpret_declaration_stmt(&self, id: &Identifier, expr: &Expr) -> StmtInterpreterResult {
         match self.tree_pass(expr) {
             Ok(obj) => {
-                self.env.define(&id, obj);
+                self.define(id, obj);
                 Ok(None)
             }
             Err(e) => Err(StmtInterpreterErr::Expression(e)),
@@ -465,47 +1103,103 @@ impl StatefulInterpreter {
 
     fn interpret_function_decl_stmt(
         &self,
-        id: Identifier,
-        params: Vec<Identifier>,
-        body: Stmt,
+        id: &Identifier,
+        name: &Identifier,
+        params: &[Identifier],
+        body: &Stmt,
     ) -> StmtInterpreterResult {
-        let func = functions::Function::new(self.env.clone(), params, body);
+        let func =
+            functions::Function::new(self.env.clone(), params.to_vec(), body.clone()).named(name);
         let callable = functions::Callable::Func(func);
         let obj = obj_call!(Box::new(callable));
 
-        self.env.define(&id, obj);
+        self.define(id, obj);
         Ok(None)
     }
 
     fn interpret_class_decl_stmt(
         &self,
-        id: Identifier,
-        _methods: Vec<Stmt>,
+        id: &Identifier,
+        name: &Identifier,
+        superclass: Option<&Expr>,
+        methods: &[Stmt],
     ) -> StmtInterpreterResult {
-        let c = class::Class::new(&id);
+        let sc = match superclass {
+            Some(expr) => {
+                let span = expr.span();
+                let obj = self
+                    .tree_pass(expr)
+                    .map_err(StmtInterpreterErr::Expression)?;
+                let c = match obj {
+                    Object::Call(c) => match *c {
+                        functions::Callable::Class(c) => Some(c),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                Some(c.ok_or_else(|| {
+                    StmtInterpreterErr::Expression(
+                        ExprInterpreterErr::Type("superclass must be a class").at(span),
+                    )
+                })?)
+            }
+            None => None,
+        };
+
+        // methods of a subclass close over an environment defining `super`.
+        let closure = match sc {
+            Some(ref c) => {
+                let env = Environment::from(&self.env);
+                env.define(
+                    &identifier_name!("super"),
+                    obj_call!(Box::new(functions::Callable::Class(c.clone()))),
+                );
+                env
+            }
+            None => self.env.clone(),
+        };
+
+        let mut method_table = class::Methods::new();
+        for method in methods {
+            match method {
+                Stmt::Function(_, name, params, body, _) => {
+                    let (params, body) = (params.to_vec(), body.as_ref().clone());
+                    let func = if *name == identifier_name!("init") {
+                        functions::Function::new_initializer(closure.clone(), params, body)
+                    } else {
+                        functions::Function::new(closure.clone(), params, body).named(name)
+                    };
+                    method_table.insert(name.clone(), func);
+                }
+                _ => return Err(StmtInterpreterErr::Unspecified),
+            }
+        }
+
+        let c = class::Class::new(id, sc, method_table).named(name);
         let callable = functions::Callable::Class(c);
-        self.env.define(&id, obj_call!(Box::new(callable)));
+        self.define(id, obj_call!(Box::new(callable)));
         Ok(None)
     }
 
-    fn interpret_return_stmt(&self, expr: Expr) -> StmtInterpreterResult {
-        match self.tree_pass(expr) {
+    fn interpret_return_stmt(&self, expr: Option<&Expr>) -> StmtInterpreterResult {
+        match expr.map_or(Ok(obj_nil!()), |e| self.tree_pass(e)) {
             Ok(obj) => Ok(Some(obj)),
             Err(e) => Err(StmtInterpreterErr::Expression(e)),
         }
     }
 
-    fn interpret_block(&self, stmts: Vec<Stmt>) -> StmtInterpreterResult {
-        let block_interpreter = StatefulInterpreter::from(Environment::from(&self.env));
+    fn interpret_block(&self, stmts: &[Stmt]) -> StmtInterpreterResult {
+        let block_interpreter = self.with_env(Environment::from(&self.env));
         block_interpreter.tree_pass(stmts)
     }
 
     #[allow(clippy::redundant_closure)]
     fn interpret_if_stmt(
         &self,
-        cond: Expr,
-        tb: Box<Stmt>,
-        eb: Option<Box<Stmt>>,
+        cond: &Expr,
+        tb: &Stmt,
+        eb: Option<&Stmt>,
     ) -> StmtInterpreterResult {
         let condition = self
             .tree_pass(cond)
@@ -518,14 +1212,64 @@ impl StatefulInterpreter {
     }
 
     #[allow(clippy::redundant_closure)]
-    fn interpret_while_stmt(&self, cond: Expr, body: Box<Stmt>) -> StmtInterpreterResult {
+    fn interpret_while_stmt(
+        &self,
+        cond: &Expr,
+        body: &Stmt,
+        increment: Option<&Expr>,
+    ) -> StmtInterpreterResult {
         while self
-            .tree_pass(cond.clone())
+            .tree_pass(cond)
             .map_err(|e| StmtInterpreterErr::Expression(e))?
             .into()
         {
-            match self.tree_pass(body.clone()) {
-                Ok(None) => continue,
+            match self.tree_pass(body) {
+                Ok(None) | Err(StmtInterpreterErr::Continue) => (),
+                Err(StmtInterpreterErr::Break) => break,
+                rv @ Ok(_) => return rv,
+                Err(e) => return Err(e),
+            }
+
+            if let Some(inc) = increment {
+                self.tree_pass(inc)
+                    .map_err(|e| StmtInterpreterErr::Expression(e))?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn interpret_for_in_stmt(
+        &self,
+        id: &Identifier,
+        iterable: &Expr,
+        body: &Stmt,
+    ) -> StmtInterpreterResult {
+        let span = iterable.span();
+        let obj = self
+            .tree_pass(iterable)
+            .map_err(StmtInterpreterErr::Expression)?;
+        let iter = Iter::from_object(self, obj, span)
+            .map_err(|e| StmtInterpreterErr::Expression(ExprInterpreterErr::from(e).at(span)))?;
+
+        loop {
+            let item = match iter.next(self, span) {
+                Ok(Some(item)) => item,
+                Ok(None) => break,
+                Err(e) => {
+                    return Err(StmtInterpreterErr::Expression(
+                        ExprInterpreterErr::from(e).at(span),
+                    ))
+                }
+            };
+
+            // each iteration binds the loop variable in a fresh environment,
+            // so closures capture the value of their own iteration.
+            let intptr = self.with_env(Environment::from(&self.env));
+            intptr.define(id, item);
+            match intptr.tree_pass(body) {
+                Ok(None) | Err(StmtInterpreterErr::Continue) => (),
+                Err(StmtInterpreterErr::Break) => break,
                 rv @ Ok(_) => return rv,
                 Err(e) => return Err(e),
             }
@@ -1,12 +1,14 @@
 use crate::ast::expression::{AdditionExpr, Expr, MultiplicationExpr};
-use crate::interpreter::StatefulInterpreter;
+use crate::ast::span::Span;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter};
 use crate::pass::*;
 
 macro_rules! primary_number {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Number($x),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Number($x)),
+            Span::default(),
+        )
     };
 }
 
@@ -18,14 +20,20 @@ macro_rules! expr_interpret {
 
 #[test]
 fn addition_expr_should_evaluate_when_both_operands_are_numbers() {
-    let addition_expr = Expr::Addition(AdditionExpr::Add(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(2.0)),
-    ));
-    let subtraction_expr = Expr::Addition(AdditionExpr::Subtract(
-        Box::new(primary_number!(7.0)),
-        Box::new(primary_number!(2.0)),
-    ));
+    let addition_expr = Expr::Addition(
+        AdditionExpr::Add(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(2.0)),
+        ),
+        Span::default(),
+    );
+    let subtraction_expr = Expr::Addition(
+        AdditionExpr::Subtract(
+            Box::new(primary_number!(7.0)),
+            Box::new(primary_number!(2.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_number!(7.0)), expr_interpret!(addition_expr));
     assert_eq!(Ok(obj_number!(5.0)), expr_interpret!(subtraction_expr));
@@ -33,23 +41,58 @@ fn addition_expr_should_evaluate_when_both_operands_are_numbers() {
 
 #[test]
 fn addition_expr_should_maintain_operator_precedence() {
-    let expr = Expr::Addition(AdditionExpr::Add(
-        Box::new(Expr::Multiplication(MultiplicationExpr::Multiply(
-            Box::new(primary_number!(5.0)),
-            Box::new(primary_number!(-1.0)),
-        ))),
-        Box::new(primary_number!(1.0)),
-    ));
+    let expr = Expr::Addition(
+        AdditionExpr::Add(
+            Box::new(Expr::Multiplication(
+                MultiplicationExpr::Multiply(
+                    Box::new(primary_number!(5.0)),
+                    Box::new(primary_number!(-1.0)),
+                ),
+                Span::default(),
+            )),
+            Box::new(primary_number!(1.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_number!(-4.0)), expr_interpret!(expr));
 }
 
 #[test]
 fn addition_expr_should_concatenate_strings() {
-    let expr = Expr::Addition(AdditionExpr::Add(
-        Box::new(Expr::Primary(obj_str!("hello".to_string()))),
-        Box::new(Expr::Primary(obj_str!("world".to_string()))),
-    ));
+    let expr = Expr::Addition(
+        AdditionExpr::Add(
+            Box::new(Expr::Primary(
+                obj_str!("hello".to_string()),
+                Span::default(),
+            )),
+            Box::new(Expr::Primary(
+                obj_str!("world".to_string()),
+                Span::default(),
+            )),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_str!(format!("helloworld"))), expr_interpret!(expr));
 }
+
+#[test]
+fn addition_expr_should_propagate_errors_from_its_operands() {
+    let operand_span = Span::new(0, 1, 1, 1);
+    let expr = Expr::Addition(
+        AdditionExpr::Add(
+            Box::new(primary_number!(1.0)),
+            Box::new(Expr::Variable(identifier_name!("missing"), operand_span)),
+        ),
+        Span::default(),
+    );
+
+    assert_eq!(
+        Err(ExprInterpreterErr::UndefinedVariable(
+            "missing".to_string(),
+            operand_span
+        )),
+        expr_interpret!(expr)
+    );
+}
@@ -1,8 +1,11 @@
-use crate::ast::expression::Expr;
+use crate::ast::expression::{AdditionExpr, Expr};
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
+use crate::diagnostic::{Diagnostic, StackFrame};
 use crate::functions;
-use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter, StmtInterpreterErr};
+use crate::interpreter::{ExprInterpreterErr, Frame, StatefulInterpreter, StmtInterpreterErr};
 use crate::pass::*;
+use std::rc::Rc;
 
 #[test]
 fn should_return_ok_on_success() {
@@ -11,7 +14,13 @@ fn should_return_ok_on_success() {
     let f = functions::Function::new(
         interpreter.env.clone(),
         vec![],
-        Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]),
+        Stmt::Block(
+            vec![Stmt::Expression(
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
     );
 
     interpreter.env.define(
@@ -21,10 +30,14 @@ fn should_return_ok_on_success() {
 
     assert_eq!(
         Ok(None),
-        interpreter.tree_pass(vec![Stmt::Expression(Expr::Call(
-            Box::new(Expr::Variable(identifier_name!("a"))),
-            vec![]
-        ))])
+        interpreter.tree_pass(vec![Stmt::Expression(
+            Expr::Call(
+                Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+                vec![],
+                Span::default()
+            ),
+            Span::default()
+        )])
     );
 }
 
@@ -35,7 +48,13 @@ fn should_throw_error_on_arity_mismatch() {
     let f = functions::Function::new(
         interpreter.env.clone(),
         vec![],
-        Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]),
+        Stmt::Block(
+            vec![Stmt::Expression(
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
     );
 
     interpreter.env.define(
@@ -44,12 +63,91 @@ fn should_throw_error_on_arity_mismatch() {
     );
 
     assert_eq!(
-        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::CallErr(
-            "Arity".to_string()
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::CallErr(
+                "expected 0 arguments but got 1".to_string()
+            )),
+            Span::default(),
         ))),
-        interpreter.tree_pass(vec![Stmt::Expression(Expr::Call(
-            Box::new(Expr::Variable(identifier_name!("a"))),
-            vec![Expr::Primary(obj_number!(5.0))]
-        ))])
+        interpreter.tree_pass(vec![Stmt::Expression(
+            Expr::Call(
+                Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+                vec![Expr::Primary(obj_number!(5.0), Span::default())],
+                Span::default()
+            ),
+            Span::default()
+        )])
+    );
+}
+
+#[test]
+fn errors_within_a_call_should_be_traced_with_the_call_stack() {
+    let interpreter = StatefulInterpreter::new();
+    let body_span = Span::new(14, 21, 2, 3);
+    let call_site = Span::new(30, 38, 4, 1);
+
+    let f = functions::Function::new(
+        interpreter.env.clone(),
+        vec![],
+        Stmt::Block(
+            vec![Stmt::Expression(
+                Expr::Addition(
+                    AdditionExpr::Add(
+                        Box::new(Expr::Primary(obj_nil!(), Span::default())),
+                        Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    ),
+                    body_span,
+                ),
+                body_span,
+            )],
+            Span::default(),
+        ),
+    )
+    .named(&identifier_name!("fails"));
+    let callee = functions::Callable::Func(f);
+    interpreter.env.define(
+        &identifier_name!("fails"),
+        obj_call!(Box::new(callee.clone())),
+    );
+
+    let result = interpreter.tree_pass(vec![Stmt::Expression(
+        Expr::Call(
+            Box::new(Expr::Variable(identifier_name!("fails"), Span::default())),
+            vec![],
+            call_site,
+        ),
+        call_site,
+    )]);
+
+    let inner = ExprInterpreterErr::Spanned(
+        Box::new(ExprInterpreterErr::BinaryExpr(
+            "+",
+            obj_nil!(),
+            obj_number!(1.0),
+        )),
+        body_span,
+    );
+    let frames = vec![Frame {
+        name: "fails".to_string(),
+        call_site,
+        callee: Rc::new(callee),
+    }];
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Traced(
+            Box::new(inner),
+            frames
+        ))),
+        result
+    );
+    assert!(interpreter.frames().is_empty());
+
+    let diagnostic: Diagnostic = result.unwrap_err().into();
+    assert_eq!(body_span, diagnostic.span);
+    assert_eq!(
+        vec![
+            StackFrame::new("fails", body_span),
+            StackFrame::new("<script>", call_site)
+        ],
+        diagnostic.backtrace
     );
 }
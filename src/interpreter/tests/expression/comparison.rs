@@ -1,12 +1,14 @@
 use crate::ast::expression::{ComparisonExpr, Expr, MultiplicationExpr};
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 macro_rules! primary_number {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Number($x),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Number($x)),
+            Span::default(),
+        )
     };
 }
 
@@ -18,22 +20,34 @@ macro_rules! expr_interpret {
 
 #[test]
 fn comparison_expr_should_evaluate_when_both_operands_are_numbers() {
-    let less_expr = Expr::Comparison(ComparisonExpr::Less(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(2.0)),
-    ));
-    let less_equal_expr = Expr::Comparison(ComparisonExpr::LessEqual(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(5.0)),
-    ));
-    let greater_expr = Expr::Comparison(ComparisonExpr::Greater(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(5.0)),
-    ));
-    let greater_equal_expr = Expr::Comparison(ComparisonExpr::GreaterEqual(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(5.0)),
-    ));
+    let less_expr = Expr::Comparison(
+        ComparisonExpr::Less(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(2.0)),
+        ),
+        Span::default(),
+    );
+    let less_equal_expr = Expr::Comparison(
+        ComparisonExpr::LessEqual(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
+    let greater_expr = Expr::Comparison(
+        ComparisonExpr::Greater(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
+    let greater_equal_expr = Expr::Comparison(
+        ComparisonExpr::GreaterEqual(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_bool!(false)), expr_interpret!(less_expr));
     assert_eq!(Ok(obj_bool!(true)), expr_interpret!(less_equal_expr));
@@ -43,13 +57,19 @@ fn comparison_expr_should_evaluate_when_both_operands_are_numbers() {
 
 #[test]
 fn comparison_expr_should_maintain_operator_precedence() {
-    let expr = Expr::Comparison(ComparisonExpr::Less(
-        Box::new(Expr::Multiplication(MultiplicationExpr::Multiply(
-            Box::new(primary_number!(5.0)),
-            Box::new(primary_number!(-1.0)),
-        ))),
-        Box::new(primary_number!(1.0)),
-    ));
+    let expr = Expr::Comparison(
+        ComparisonExpr::Less(
+            Box::new(Expr::Multiplication(
+                MultiplicationExpr::Multiply(
+                    Box::new(primary_number!(5.0)),
+                    Box::new(primary_number!(-1.0)),
+                ),
+                Span::default(),
+            )),
+            Box::new(primary_number!(1.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_bool!(true)), expr_interpret!(expr));
 }
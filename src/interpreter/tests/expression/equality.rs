@@ -1,20 +1,23 @@
 use crate::ast::expression::{EqualityExpr, Expr, MultiplicationExpr};
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 macro_rules! primary_number {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Number($x),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Number($x)),
+            Span::default(),
+        )
     };
 }
 
 macro_rules! primary_string {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Str($x.to_string()),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Str($x.to_string())),
+            Span::default(),
+        )
     };
 }
 
@@ -26,22 +29,34 @@ macro_rules! expr_interpret {
 
 #[test]
 fn equality_expr_should_evaluate_when_both_operands_are_numbers() {
-    let less_expr = Expr::Equality(EqualityExpr::Equal(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(5.0)),
-    ));
-    let less_equal_expr = Expr::Equality(EqualityExpr::Equal(
-        Box::new(primary_number!(10.0)),
-        Box::new(primary_number!(5.0)),
-    ));
-    let greater_expr = Expr::Equality(EqualityExpr::NotEqual(
-        Box::new(primary_number!(10.0)),
-        Box::new(primary_number!(5.0)),
-    ));
-    let greater_equal_expr = Expr::Equality(EqualityExpr::NotEqual(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(5.0)),
-    ));
+    let less_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
+    let less_equal_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_number!(10.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
+    let greater_expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(primary_number!(10.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
+    let greater_equal_expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(5.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_bool!(true)), expr_interpret!(less_expr));
     assert_eq!(Ok(obj_bool!(false)), expr_interpret!(less_equal_expr));
@@ -51,22 +66,34 @@ fn equality_expr_should_evaluate_when_both_operands_are_numbers() {
 
 #[test]
 fn equality_expr_should_evaluate_when_both_operands_are_strings() {
-    let less_expr = Expr::Equality(EqualityExpr::Equal(
-        Box::new(primary_string!("hello")),
-        Box::new(primary_string!("hello")),
-    ));
-    let less_equal_expr = Expr::Equality(EqualityExpr::Equal(
-        Box::new(primary_string!("hello")),
-        Box::new(primary_string!("world")),
-    ));
-    let greater_expr = Expr::Equality(EqualityExpr::NotEqual(
-        Box::new(primary_string!("hello")),
-        Box::new(primary_string!("world")),
-    ));
-    let greater_equal_expr = Expr::Equality(EqualityExpr::NotEqual(
-        Box::new(primary_string!("hello")),
-        Box::new(primary_string!("hello")),
-    ));
+    let less_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_string!("hello")),
+            Box::new(primary_string!("hello")),
+        ),
+        Span::default(),
+    );
+    let less_equal_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_string!("hello")),
+            Box::new(primary_string!("world")),
+        ),
+        Span::default(),
+    );
+    let greater_expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(primary_string!("hello")),
+            Box::new(primary_string!("world")),
+        ),
+        Span::default(),
+    );
+    let greater_equal_expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(primary_string!("hello")),
+            Box::new(primary_string!("hello")),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_bool!(true)), expr_interpret!(less_expr));
     assert_eq!(Ok(obj_bool!(false)), expr_interpret!(less_equal_expr));
@@ -76,13 +103,84 @@ fn equality_expr_should_evaluate_when_both_operands_are_strings() {
 
 #[test]
 fn equality_expr_should_maintain_operator_precedence() {
-    let expr = Expr::Equality(EqualityExpr::NotEqual(
-        Box::new(Expr::Multiplication(MultiplicationExpr::Multiply(
+    let expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(Expr::Multiplication(
+                MultiplicationExpr::Multiply(
+                    Box::new(primary_number!(1.0)),
+                    Box::new(primary_number!(-1.0)),
+                ),
+                Span::default(),
+            )),
             Box::new(primary_number!(1.0)),
-            Box::new(primary_number!(-1.0)),
-        ))),
-        Box::new(primary_number!(1.0)),
-    ));
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_bool!(true)), expr_interpret!(expr));
 }
+
+macro_rules! primary_literal {
+    ($x:expr) => {
+        Expr::Primary($crate::object::Object::Literal($x), Span::default())
+    };
+}
+
+#[test]
+fn equality_expr_should_compare_nil_and_booleans() {
+    use crate::object::Literal;
+
+    let nil_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_literal!(Literal::Nil)),
+            Box::new(primary_literal!(Literal::Nil)),
+        ),
+        Span::default(),
+    );
+    let bool_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_literal!(Literal::Bool(true))),
+            Box::new(primary_literal!(Literal::Bool(false))),
+        ),
+        Span::default(),
+    );
+
+    assert_eq!(Ok(obj_bool!(true)), expr_interpret!(nil_expr));
+    assert_eq!(Ok(obj_bool!(false)), expr_interpret!(bool_expr));
+}
+
+#[test]
+fn equality_expr_should_treat_operands_of_different_types_as_unequal() {
+    use crate::object::Literal;
+
+    let equal_expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_number!(1.0)),
+            Box::new(primary_string!("1")),
+        ),
+        Span::default(),
+    );
+    let not_equal_expr = Expr::Equality(
+        EqualityExpr::NotEqual(
+            Box::new(primary_literal!(Literal::Bool(false))),
+            Box::new(primary_literal!(Literal::Nil)),
+        ),
+        Span::default(),
+    );
+
+    assert_eq!(Ok(obj_bool!(false)), expr_interpret!(equal_expr));
+    assert_eq!(Ok(obj_bool!(true)), expr_interpret!(not_equal_expr));
+}
+
+#[test]
+fn equality_expr_should_compare_numbers_exactly() {
+    let expr = Expr::Equality(
+        EqualityExpr::Equal(
+            Box::new(primary_number!(1e-20)),
+            Box::new(primary_number!(2e-20)),
+        ),
+        Span::default(),
+    );
+
+    assert_eq!(Ok(obj_bool!(false)), expr_interpret!(expr));
+}
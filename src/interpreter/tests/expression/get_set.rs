@@ -1,12 +1,15 @@
 use crate::ast::expression::Expr;
-use crate::class::Class;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::class::{Class, Methods};
+use crate::functions::Function;
 use crate::instance::Instance;
-use crate::interpreter::StatefulInterpreter;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter};
 use crate::pass::*;
 
 #[test]
 fn get_parameter_from_instance() {
-    let ti = Instance::new(&Class::new(&identifier_name!("test")));
+    let ti = Instance::new(&Class::new(&identifier_name!("test"), None, Methods::new()));
     ti.scope
         .clone()
         .define(&identifier_name!("test_param"), obj_bool!(true));
@@ -14,8 +17,172 @@ fn get_parameter_from_instance() {
     assert_eq!(
         Ok(obj_bool!(true)),
         StatefulInterpreter::new().tree_pass(Expr::Get(
-            Box::new(Expr::Primary(obj_instance!(ti))),
-            Box::new(Expr::Variable(identifier_name!("test_param")))
+            Box::new(Expr::Primary(obj_instance!(ti), Span::default())),
+            Box::new(Expr::Variable(
+                identifier_name!("test_param"),
+                Span::default()
+            )),
+            Span::default()
         ))
     );
 }
+
+#[test]
+fn get_method_from_instance_should_bind_this() {
+    let mut methods = Methods::new();
+    methods.insert(
+        identifier_name!("test_method"),
+        Function::new(
+            StatefulInterpreter::new().env,
+            vec![],
+            Stmt::Block(
+                vec![Stmt::Return(
+                    Some(Expr::Get(
+                        Box::new(Expr::This(Span::default())),
+                        Box::new(Expr::Variable(
+                            identifier_name!("test_param"),
+                            Span::default(),
+                        )),
+                        Span::default(),
+                    )),
+                    Span::default(),
+                )],
+                Span::default(),
+            ),
+        ),
+    );
+    let ti = Instance::new(&Class::new(&identifier_name!("test"), None, methods));
+    ti.scope
+        .clone()
+        .define(&identifier_name!("test_param"), obj_bool!(true));
+
+    assert_eq!(
+        Ok(obj_bool!(true)),
+        StatefulInterpreter::new().tree_pass(Expr::Call(
+            Box::new(Expr::Get(
+                Box::new(Expr::Primary(obj_instance!(ti), Span::default())),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_method"),
+                    Span::default()
+                )),
+                Span::default()
+            )),
+            vec![],
+            Span::default()
+        ))
+    );
+}
+
+#[test]
+fn set_parameter_on_instance() {
+    let ti = Instance::new(&Class::new(&identifier_name!("test"), None, Methods::new()));
+
+    assert_eq!(
+        Ok(obj_bool!(true)),
+        StatefulInterpreter::new().tree_pass(Expr::Set(
+            Box::new(Expr::Primary(obj_instance!(ti.clone()), Span::default())),
+            identifier_name!("test_param"),
+            Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+            Span::default()
+        ))
+    );
+    assert_eq!(
+        Some(obj_bool!(true)),
+        ti.get(&identifier_name!("test_param"))
+    );
+}
+
+#[test]
+fn set_parameter_should_be_visible_through_aliases() {
+    let interpreter = StatefulInterpreter::new();
+    let ti = Instance::new(&Class::new(&identifier_name!("test"), None, Methods::new()));
+    interpreter
+        .env
+        .define(&identifier_name!("a"), obj_instance!(ti));
+
+    let input = vec![
+        Stmt::Declaration(
+            identifier_name!("b"),
+            Expr::Variable(identifier_name!("a"), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Expression(
+            Expr::Set(
+                Box::new(Expr::Variable(identifier_name!("b"), Span::default())),
+                identifier_name!("test_param"),
+                Box::new(Expr::Primary(obj_number!(5.0), Span::default())),
+                Span::default(),
+            ),
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Get(
+                Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_param"),
+                    Span::default(),
+                )),
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(Ok(Some(obj_number!(5.0))), interpreter.tree_pass(input));
+}
+
+#[test]
+fn get_parameter_on_non_instance_should_error() {
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::Type("only instances have properties")),
+            Span::default(),
+        )),
+        StatefulInterpreter::new().tree_pass(Expr::Get(
+            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+            Box::new(Expr::Variable(identifier_name!("foo"), Span::default())),
+            Span::default()
+        ))
+    );
+}
+
+#[test]
+fn set_parameter_on_non_instance_should_error() {
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::Type("only instances have fields")),
+            Span::default(),
+        )),
+        StatefulInterpreter::new().tree_pass(Expr::Set(
+            Box::new(Expr::Primary(obj_number!(5.0), Span::default())),
+            identifier_name!("test_param"),
+            Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+            Span::default()
+        ))
+    );
+}
+
+#[cfg(feature = "math")]
+#[test]
+fn get_on_stdlib_namespace_should_return_its_member() {
+    let get = |member: &str| {
+        Expr::Get(
+            Box::new(Expr::Variable(identifier_builtin!("math"), Span::default())),
+            Box::new(Expr::Variable(identifier_name!(member), Span::default())),
+            Span::default(),
+        )
+    };
+    let interpreter = StatefulInterpreter::new();
+
+    assert_eq!(
+        Ok(obj_number!(std::f64::consts::PI)),
+        interpreter.tree_pass(get("pi"))
+    );
+    assert_eq!(
+        Err(ExprInterpreterErr::UndefinedVariable(
+            "tau".to_string(),
+            Span::default()
+        )),
+        interpreter.tree_pass(get("tau"))
+    );
+}
@@ -1,30 +1,38 @@
 use crate::ast::expression::{AdditionExpr, Expr, MultiplicationExpr};
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 macro_rules! primary_number {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Number($x),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Number($x)),
+            Span::default(),
+        )
     };
 }
 
 macro_rules! multiplication_expr {
     ($x:expr, $y:expr) => {
-        Expr::Multiplication(MultiplicationExpr::Multiply(Box::new($x), Box::new($y)))
+        Expr::Multiplication(
+            MultiplicationExpr::Multiply(Box::new($x), Box::new($y)),
+            Span::default(),
+        )
     };
 }
 
 macro_rules! addition_expr {
     ($x:expr, $y:expr) => {
-        Expr::Addition(AdditionExpr::Add(Box::new($x), Box::new($y)))
+        Expr::Addition(
+            AdditionExpr::Add(Box::new($x), Box::new($y)),
+            Span::default(),
+        )
     };
 }
 
 #[test]
 fn grouping_expr_should_interpret_to_equivalent_primary() {
-    let expr = Expr::Grouping(Box::new(primary_number!(5.0)));
+    let expr = Expr::Grouping(Box::new(primary_number!(5.0)), Span::default());
 
     assert_eq!(
         Ok(obj_number!(5.0)),
@@ -36,10 +44,10 @@ fn grouping_expr_should_interpret_to_equivalent_primary() {
 fn grouping_expr_should_maintain_operator_precedence() {
     let expr = multiplication_expr!(
         primary_number!(5.0),
-        Expr::Grouping(Box::new(addition_expr!(
-            primary_number!(-1.0),
-            primary_number!(2.0)
-        )))
+        Expr::Grouping(
+            Box::new(addition_expr!(primary_number!(-1.0), primary_number!(2.0))),
+            Span::default()
+        )
     );
 
     assert_eq!(
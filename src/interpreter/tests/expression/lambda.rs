@@ -1,15 +1,26 @@
 use crate::ast::expression::Expr;
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
 use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter, StmtInterpreterErr};
 use crate::pass::*;
 
 #[test]
 fn should_return_a_value_when_specified() {
-    let block = Stmt::Block(vec![Stmt::Return(Expr::Primary(obj_bool!(true)))]);
-    let input = vec![Stmt::Return(Expr::Call(
-        Box::new(Expr::Lambda(vec![], Box::new(block))),
-        vec![],
-    ))];
+    let block = Stmt::Block(
+        vec![Stmt::Return(
+            Some(Expr::Primary(obj_bool!(true), Span::default())),
+            Span::default(),
+        )],
+        Span::default(),
+    );
+    let input = vec![Stmt::Return(
+        Some(Expr::Call(
+            Box::new(Expr::Lambda(vec![], Box::new(block), Span::default())),
+            vec![],
+            Span::default(),
+        )),
+        Span::default(),
+    )];
 
     assert_eq!(
         Ok(Some(obj_bool!(true))),
@@ -19,15 +30,28 @@ fn should_return_a_value_when_specified() {
 
 #[test]
 fn should_throw_error_on_arity_mismatch() {
-    let block = Stmt::Block(vec![Stmt::Return(Expr::Primary(obj_bool!(true)))]);
-    let input = vec![Stmt::Expression(Expr::Call(
-        Box::new(Expr::Lambda(vec![], Box::new(block))),
-        vec![Expr::Primary(obj_number!(5.0))],
-    ))];
+    let block = Stmt::Block(
+        vec![Stmt::Return(
+            Some(Expr::Primary(obj_bool!(true), Span::default())),
+            Span::default(),
+        )],
+        Span::default(),
+    );
+    let input = vec![Stmt::Expression(
+        Expr::Call(
+            Box::new(Expr::Lambda(vec![], Box::new(block), Span::default())),
+            vec![Expr::Primary(obj_number!(5.0), Span::default())],
+            Span::default(),
+        ),
+        Span::default(),
+    )];
 
     assert_eq!(
-        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::CallErr(
-            "Arity".to_string()
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::CallErr(
+                "expected 0 arguments but got 1".to_string()
+            )),
+            Span::default(),
         ))),
         StatefulInterpreter::new().tree_pass(input)
     );
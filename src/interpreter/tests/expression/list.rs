@@ -0,0 +1,116 @@
+use crate::ast::expression::Expr;
+use crate::ast::span::Span;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter};
+use crate::list::List;
+use crate::object::Object;
+use crate::pass::*;
+
+fn list_of(items: Vec<Object>) -> Expr {
+    Expr::Primary(Object::List(List::new(items)), Span::default())
+}
+
+#[test]
+fn list_expression_should_evaluate_each_element() {
+    assert_eq!(
+        Ok(Object::List(List::new(vec![
+            obj_number!(1.0),
+            obj_bool!(true)
+        ]))),
+        StatefulInterpreter::new().tree_pass(Expr::List(
+            vec![
+                Expr::Primary(obj_number!(1.0), Span::default()),
+                Expr::Primary(obj_bool!(true), Span::default()),
+            ],
+            Span::default()
+        ))
+    );
+}
+
+#[test]
+fn index_expression_should_return_the_element_at_the_index() {
+    assert_eq!(
+        Ok(obj_number!(2.0)),
+        StatefulInterpreter::new().tree_pass(Expr::Index(
+            Box::new(list_of(vec![obj_number!(1.0), obj_number!(2.0)])),
+            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+            Span::default()
+        ))
+    );
+}
+
+#[test]
+fn index_expression_should_err_on_invalid_indices() {
+    let index = |i: Object| {
+        StatefulInterpreter::new().tree_pass(Expr::Index(
+            Box::new(list_of(vec![obj_number!(1.0)])),
+            Box::new(Expr::Primary(i, Span::default())),
+            Span::default(),
+        ))
+    };
+
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::IndexOutOfRange(1.0, 1)),
+            Span::default()
+        )),
+        index(obj_number!(1.0))
+    );
+    let err = index(obj_number!(-1.0)).unwrap_err();
+    assert_eq!("E0309", err.code());
+    assert_eq!("list index -1 out of range for length 1", err.to_string());
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::InvalidArgument(
+                "invalid argument type: list index must be an integer, found 0.5".to_string()
+            )),
+            Span::default()
+        )),
+        index(obj_number!(0.5))
+    );
+}
+
+#[test]
+fn set_index_expression_should_mutate_aliases_of_the_list() {
+    let list = List::new(vec![obj_number!(1.0)]);
+
+    assert_eq!(
+        Ok(obj_str!("updated".to_string())),
+        StatefulInterpreter::new().tree_pass(Expr::SetIndex(
+            Box::new(Expr::Primary(Object::List(list.clone()), Span::default())),
+            Box::new(Expr::Primary(obj_number!(0.0), Span::default())),
+            Box::new(Expr::Primary(
+                obj_str!("updated".to_string()),
+                Span::default()
+            )),
+            Span::default()
+        ))
+    );
+    assert_eq!(Some(obj_str!("updated".to_string())), list.get(0));
+}
+
+#[test]
+fn get_expression_should_bind_list_methods() {
+    let list = List::new(vec![]);
+    let method = |name: &str| {
+        Box::new(Expr::Get(
+            Box::new(Expr::Primary(Object::List(list.clone()), Span::default())),
+            Box::new(Expr::Variable(identifier_name!(name), Span::default())),
+            Span::default(),
+        ))
+    };
+    let interpreter = StatefulInterpreter::new();
+
+    assert_eq!(
+        Ok(obj_nil!()),
+        interpreter.tree_pass(Expr::Call(
+            method("push"),
+            vec![Expr::Primary(obj_number!(1.0), Span::default())],
+            Span::default()
+        ))
+    );
+    assert_eq!(
+        Ok(obj_number!(1.0)),
+        interpreter.tree_pass(Expr::Call(method("len"), vec![], Span::default()))
+    );
+    assert!(interpreter.tree_pass(*method("unknown")).is_err());
+}
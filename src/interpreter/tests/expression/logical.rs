@@ -1,27 +1,38 @@
 use crate::ast::expression::{Expr, LogicalExpr};
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 macro_rules! logical_truth_table {
     ($l:literal, "or", $r: literal) => {
-        Expr::Logical(LogicalExpr::Or(
-            Box::new(Expr::Primary($crate::object::Object::Literal(
-                $crate::object::Literal::Bool($l),
-            ))),
-            Box::new(Expr::Primary($crate::object::Object::Literal(
-                $crate::object::Literal::Bool($r),
-            ))),
-        ))
+        Expr::Logical(
+            LogicalExpr::Or(
+                Box::new(Expr::Primary(
+                    $crate::object::Object::Literal($crate::object::Literal::Bool($l)),
+                    Span::default(),
+                )),
+                Box::new(Expr::Primary(
+                    $crate::object::Object::Literal($crate::object::Literal::Bool($r)),
+                    Span::default(),
+                )),
+            ),
+            Span::default(),
+        )
     };
     ($l:literal, "and", $r: literal) => {
-        Expr::Logical(LogicalExpr::And(
-            Box::new(Expr::Primary($crate::object::Object::Literal(
-                $crate::object::Literal::Bool($l),
-            ))),
-            Box::new(Expr::Primary($crate::object::Object::Literal(
-                $crate::object::Literal::Bool($r),
-            ))),
-        ))
+        Expr::Logical(
+            LogicalExpr::And(
+                Box::new(Expr::Primary(
+                    $crate::object::Object::Literal($crate::object::Literal::Bool($l)),
+                    Span::default(),
+                )),
+                Box::new(Expr::Primary(
+                    $crate::object::Object::Literal($crate::object::Literal::Bool($r)),
+                    Span::default(),
+                )),
+            ),
+            Span::default(),
+        )
     };
 }
 
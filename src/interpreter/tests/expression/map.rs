@@ -0,0 +1,64 @@
+use crate::ast::expression::Expr;
+use crate::ast::span::Span;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter};
+use crate::map::Map;
+use crate::object::Object;
+use crate::pass::*;
+
+#[test]
+fn map_expression_should_evaluate_each_entry() {
+    let map = StatefulInterpreter::new()
+        .tree_pass(Expr::Map(
+            vec![(
+                Expr::Primary(obj_str!("key".to_string()), Span::default()),
+                Expr::Primary(obj_number!(1.0), Span::default()),
+            )],
+            Span::default(),
+        ))
+        .unwrap();
+
+    assert_eq!("{key: 1}", map.to_string());
+}
+
+#[test]
+fn index_expressions_should_read_and_write_map_entries() {
+    let map = Map::new();
+    let interpreter = StatefulInterpreter::new();
+    let index = |key: Object| {
+        Expr::Index(
+            Box::new(Expr::Primary(Object::Map(map.clone()), Span::default())),
+            Box::new(Expr::Primary(key, Span::default())),
+            Span::default(),
+        )
+    };
+
+    assert_eq!(
+        Ok(obj_number!(1.0)),
+        interpreter.tree_pass(Expr::SetIndex(
+            Box::new(Expr::Primary(Object::Map(map.clone()), Span::default())),
+            Box::new(Expr::Primary(obj_nil!(), Span::default())),
+            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+            Span::default()
+        ))
+    );
+    assert_eq!(
+        Ok(obj_number!(1.0)),
+        interpreter.tree_pass(index(obj_nil!()))
+    );
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::CallErr(
+                "undefined map key: true".to_string()
+            )),
+            Span::default()
+        )),
+        interpreter.tree_pass(index(obj_bool!(true)))
+    );
+    assert_eq!(
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::Unhashable(Object::Map(map.clone()))),
+            Span::default()
+        )),
+        interpreter.tree_pass(index(Object::Map(map.clone())))
+    );
+}
@@ -5,7 +5,9 @@ mod equality;
 mod get_set;
 mod grouping;
 mod lambda;
+mod list;
 mod logical;
+mod map;
 mod multiplication;
 mod primary;
 mod unary;
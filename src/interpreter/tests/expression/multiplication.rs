@@ -1,21 +1,24 @@
 use crate::ast::expression::{Expr, MultiplicationExpr, UnaryExpr};
+use crate::ast::span::Span;
 use crate::interpreter::ExprInterpreterErr;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 macro_rules! primary_number {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Number($x),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Number($x)),
+            Span::default(),
+        )
     };
 }
 
 macro_rules! primary_string {
     ($x:literal) => {
-        Expr::Primary($crate::object::Object::Literal(
-            $crate::object::Literal::Str($x.to_string()),
-        ))
+        Expr::Primary(
+            $crate::object::Object::Literal($crate::object::Literal::Str($x.to_string())),
+            Span::default(),
+        )
     };
 }
 
@@ -27,14 +30,20 @@ macro_rules! expr_interpret {
 
 #[test]
 fn multiplication_expr_should_evaluate_when_both_operands_are_numbers() {
-    let product_expr = Expr::Multiplication(MultiplicationExpr::Multiply(
-        Box::new(primary_number!(5.0)),
-        Box::new(primary_number!(2.0)),
-    ));
-    let division_expr = Expr::Multiplication(MultiplicationExpr::Divide(
-        Box::new(primary_number!(10.0)),
-        Box::new(primary_number!(2.0)),
-    ));
+    let product_expr = Expr::Multiplication(
+        MultiplicationExpr::Multiply(
+            Box::new(primary_number!(5.0)),
+            Box::new(primary_number!(2.0)),
+        ),
+        Span::default(),
+    );
+    let division_expr = Expr::Multiplication(
+        MultiplicationExpr::Divide(
+            Box::new(primary_number!(10.0)),
+            Box::new(primary_number!(2.0)),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_number!(10.0)), expr_interpret!(product_expr));
     assert_eq!(Ok(obj_number!(5.0)), expr_interpret!(division_expr));
@@ -42,15 +51,21 @@ fn multiplication_expr_should_evaluate_when_both_operands_are_numbers() {
 
 #[test]
 fn multiplication_expr_should_err_when_operands_are_not_numbers() {
-    let expr = Expr::Multiplication(MultiplicationExpr::Multiply(
-        Box::new(primary_string!("hello")),
-        Box::new(primary_string!("world")),
-    ));
+    let expr = Expr::Multiplication(
+        MultiplicationExpr::Multiply(
+            Box::new(primary_string!("hello")),
+            Box::new(primary_string!("world")),
+        ),
+        Span::default(),
+    );
     assert_eq!(
-        Err(ExprInterpreterErr::BinaryExpr(
-            "*",
-            obj_str!("hello".to_string()),
-            obj_str!("world".to_string()),
+        Err(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::BinaryExpr(
+                "*",
+                obj_str!("hello".to_string()),
+                obj_str!("world".to_string()),
+            )),
+            Span::default(),
         )),
         expr_interpret!(expr)
     );
@@ -58,12 +73,16 @@ fn multiplication_expr_should_err_when_operands_are_not_numbers() {
 
 #[test]
 fn multiplication_expr_should_maintain_operator_precedence() {
-    let expr = Expr::Multiplication(MultiplicationExpr::Multiply(
-        Box::new(primary_number!(5.0)),
-        Box::new(Expr::Unary(UnaryExpr::Minus(Box::new(primary_number!(
-            1.0
-        ))))),
-    ));
+    let expr = Expr::Multiplication(
+        MultiplicationExpr::Multiply(
+            Box::new(primary_number!(5.0)),
+            Box::new(Expr::Unary(
+                UnaryExpr::Minus(Box::new(primary_number!(1.0))),
+                Span::default(),
+            )),
+        ),
+        Span::default(),
+    );
 
     assert_eq!(Ok(obj_number!(-5.0)), expr_interpret!(expr));
 }
@@ -1,10 +1,11 @@
 use crate::ast::expression::Expr;
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 #[test]
 fn primary_expr_should_interpret_to_equivalent_primary() {
-    let expr = Expr::Primary(obj_number!(5.0));
+    let expr = Expr::Primary(obj_number!(5.0), Span::default());
 
     assert_eq!(
         Ok(obj_number!(5.0)),
@@ -1,11 +1,18 @@
 use crate::ast::expression::{Expr, UnaryExpr};
+use crate::ast::span::Span;
 use crate::interpreter::StatefulInterpreter;
 use crate::pass::*;
 
 #[test]
 fn unary_expr_should_invert_bool_with_bang_operator() {
-    let true_expr = Expr::Unary(UnaryExpr::Bang(Box::new(Expr::Primary(obj_bool!(true)))));
-    let false_expr = Expr::Unary(UnaryExpr::Bang(Box::new(Expr::Primary(obj_bool!(false)))));
+    let true_expr = Expr::Unary(
+        UnaryExpr::Bang(Box::new(Expr::Primary(obj_bool!(true), Span::default()))),
+        Span::default(),
+    );
+    let false_expr = Expr::Unary(
+        UnaryExpr::Bang(Box::new(Expr::Primary(obj_bool!(false), Span::default()))),
+        Span::default(),
+    );
 
     assert_eq!(
         Ok(obj_bool!(false)),
@@ -19,7 +26,10 @@ fn unary_expr_should_invert_bool_with_bang_operator() {
 
 #[test]
 fn unary_expr_should_negate_number_with_minus_operator() {
-    let expr = Expr::Unary(UnaryExpr::Minus(Box::new(Expr::Primary(obj_number!(1.0)))));
+    let expr = Expr::Unary(
+        UnaryExpr::Minus(Box::new(Expr::Primary(obj_number!(1.0), Span::default()))),
+        Span::default(),
+    );
 
     assert_eq!(
         Ok(obj_number!(-1.0)),
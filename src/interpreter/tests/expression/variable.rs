@@ -1,6 +1,7 @@
 use crate::ast::expression::{AdditionExpr, Expr};
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
-use crate::interpreter::StatefulInterpreter;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter};
 use crate::pass::*;
 
 #[test]
@@ -15,9 +16,27 @@ fn declaration_statement_should_set_persistent_global_symbol() {
 
     assert_eq!(
         Ok(None),
-        interpreter.tree_pass(vec![Stmt::Expression(Expr::Addition(AdditionExpr::Add(
-            Box::new(Expr::Variable(identifier_name!("a"))),
-            Box::new(Expr::Variable(identifier_name!("b"))),
-        )))])
+        interpreter.tree_pass(vec![Stmt::Expression(
+            Expr::Addition(
+                AdditionExpr::Add(
+                    Box::new(Expr::Variable(identifier_name!("a"), Span::default())),
+                    Box::new(Expr::Variable(identifier_name!("b"), Span::default())),
+                ),
+                Span::default()
+            ),
+            Span::default()
+        )])
     );
 }
+
+#[test]
+fn resolved_variable_without_a_value_should_be_reported_as_used_before_definition() {
+    let interpreter = StatefulInterpreter::new();
+    let span = Span::new(0, 1, 1, 1);
+    let err = interpreter
+        .tree_pass(Expr::Variable(identifier_resolved!(0, 0, 0), span))
+        .unwrap_err();
+
+    assert_eq!(ExprInterpreterErr::UndefinedBinding(span), err);
+    assert_eq!("variable is used before it is defined", err.to_string());
+}
@@ -1,17 +1,23 @@
-use crate::ast::expression::Expr;
+use crate::ast::expression::{AdditionExpr, ComparisonExpr, Expr, MultiplicationExpr};
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
 use crate::class;
+use crate::diagnostic::Diagnostic;
 use crate::functions;
-use crate::interpreter::StatefulInterpreter;
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter, StmtInterpreterErr};
+use crate::list::List;
+use crate::object::Object;
 use crate::pass::*;
 
 #[test]
 fn expression_stmt_should_return_ok() {
     assert_eq!(
         Ok(None),
-        StatefulInterpreter::new()
-            .tree_pass(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))])
+        StatefulInterpreter::new().tree_pass(vec![Stmt::Expression(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default()
+        )])
     );
 }
 
@@ -19,7 +25,10 @@ fn expression_stmt_should_return_ok() {
 fn print_stmt_should_return_ok() {
     assert_eq!(
         Ok(None),
-        StatefulInterpreter::new().tree_pass(vec![Stmt::Print(Expr::Primary(obj_bool!(true)))])
+        StatefulInterpreter::new().tree_pass(vec![Stmt::Print(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default()
+        )])
     );
 }
 
@@ -27,7 +36,8 @@ fn print_stmt_should_return_ok() {
 fn declaration_statement_should_set_persistent_global_symbol() {
     let stmt = Stmt::Declaration(
         Identifier::Name("test".to_string()),
-        Expr::Primary(obj_bool!(true)),
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Span::default(),
     );
     let interpreter = StatefulInterpreter::new();
     interpreter.tree_pass(vec![stmt]).unwrap();
@@ -39,7 +49,10 @@ fn declaration_statement_should_set_persistent_global_symbol() {
 
 #[test]
 fn return_statement_should_return_the_evaluated_expression_value() {
-    let stmts = Stmt::Return(Expr::Primary(obj_bool!(true)));
+    let stmts = Stmt::Return(
+        Some(Expr::Primary(obj_bool!(true), Span::default())),
+        Span::default(),
+    );
     assert_eq!(
         Ok(Some(obj_bool!(true))),
         StatefulInterpreter::new().tree_pass(stmts)
@@ -48,16 +61,31 @@ fn return_statement_should_return_the_evaluated_expression_value() {
 
 #[test]
 fn block_statement_should_set_persistent_global_symbol() {
-    let stmts = Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]);
+    let stmts = Stmt::Block(
+        vec![Stmt::Expression(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    );
     assert_eq!(Ok(None), StatefulInterpreter::new().tree_pass(stmts));
 }
 
 #[test]
 fn block_statement_with_return_should_return_value() {
-    let stmts = Stmt::Block(vec![
-        Stmt::Expression(Expr::Primary(obj_bool!(true))),
-        Stmt::Return(Expr::Primary(obj_number!(5.0))),
-    ]);
+    let stmts = Stmt::Block(
+        vec![
+            Stmt::Expression(
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Span::default(),
+            ),
+            Stmt::Return(
+                Some(Expr::Primary(obj_number!(5.0), Span::default())),
+                Span::default(),
+            ),
+        ],
+        Span::default(),
+    );
     assert_eq!(
         Ok(Some(obj_number!(5.0))),
         StatefulInterpreter::new().tree_pass(stmts)
@@ -66,32 +94,100 @@ fn block_statement_with_return_should_return_value() {
 
 #[test]
 fn class_declaration_statement_should_set_callable_instantiator() {
-    let input = Stmt::Class(identifier_name!("test"), vec![]);
+    let input = Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        None,
+        vec![],
+        Span::default(),
+    );
     let interpreter = StatefulInterpreter::new();
     interpreter.tree_pass(input).unwrap();
     assert_eq!(
         Some(obj_call!(Box::new(functions::Callable::Class(
-            class::Class::new(&identifier_name!("test"))
+            class::Class::new(&identifier_name!("test"), None, class::Methods::new())
         )))),
         interpreter.env.get(&Identifier::Name("test".to_string()))
     );
 }
 
+#[test]
+fn class_declaration_statement_should_populate_method_table() {
+    let body = Stmt::Block(
+        vec![Stmt::Return(
+            Some(Expr::Primary(obj_number!(5.0), Span::default())),
+            Span::default(),
+        )],
+        Span::default(),
+    );
+    let input = vec![
+        Stmt::Class(
+            identifier_name!("test"),
+            identifier_name!("test"),
+            None,
+            vec![Stmt::Function(
+                identifier_name!("five"),
+                identifier_name!("five"),
+                vec![],
+                Box::new(body),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Call(
+                        Box::new(Expr::Variable(identifier_name!("test"), Span::default())),
+                        vec![],
+                        Span::default(),
+                    )),
+                    Box::new(Expr::Variable(identifier_name!("five"), Span::default())),
+                    Span::default(),
+                )),
+                vec![],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Ok(Some(obj_number!(5.0))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
 #[test]
 fn function_declaration_statement_should_set_persistent_global_symbol() {
-    let block = Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]);
+    let block = Stmt::Block(
+        vec![Stmt::Expression(
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )],
+        Span::default(),
+    );
     let stmt = Stmt::Function(
+        Identifier::Name("test".to_string()),
         Identifier::Name("test".to_string()),
         vec![],
         Box::new(block),
+        Span::default(),
     );
     let interpreter = StatefulInterpreter::new();
 
     let f = functions::Function::new(
         interpreter.env.clone(),
         vec![],
-        Stmt::Block(vec![Stmt::Expression(Expr::Primary(obj_bool!(true)))]),
-    );
+        Stmt::Block(
+            vec![Stmt::Expression(
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Span::default(),
+            )],
+            Span::default(),
+        ),
+    )
+    .named(&Identifier::Name("test".to_string()));
     let expected_call = obj_call!(Box::new(functions::Callable::Func(f)));
 
     interpreter.tree_pass(vec![stmt]).unwrap();
@@ -103,17 +199,32 @@ fn function_declaration_statement_should_set_persistent_global_symbol() {
 
 #[test]
 fn function_call_should_return_a_value_when_specified() {
-    let block = Stmt::Block(vec![Stmt::Return(Expr::Primary(obj_bool!(true)))]);
+    let block = Stmt::Block(
+        vec![Stmt::Return(
+            Some(Expr::Primary(obj_bool!(true), Span::default())),
+            Span::default(),
+        )],
+        Span::default(),
+    );
     let input = vec![
         Stmt::Function(
+            Identifier::Name("test".to_string()),
             Identifier::Name("test".to_string()),
             vec![],
             Box::new(block),
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Call(
+                Box::new(Expr::Variable(
+                    Identifier::Name("test".to_string()),
+                    Span::default(),
+                )),
+                vec![],
+                Span::default(),
+            )),
+            Span::default(),
         ),
-        Stmt::Return(Expr::Call(
-            Box::new(Expr::Variable(Identifier::Name("test".to_string()))),
-            vec![],
-        )),
     ];
 
     assert_eq!(
@@ -126,15 +237,18 @@ fn function_call_should_return_a_value_when_specified() {
 fn if_statement_should_eval_to_primary_clause_if_condition_is_true() {
     let interpreter = StatefulInterpreter::new();
     let stmt = Stmt::If(
-        Expr::Primary(obj_bool!(true)),
+        Expr::Primary(obj_bool!(true), Span::default()),
         Box::new(Stmt::Declaration(
             Identifier::Name("test".to_string()),
-            Expr::Primary(obj_bool!(true)),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
         Option::Some(Box::new(Stmt::Declaration(
             Identifier::Name("test".to_string()),
-            Expr::Primary(obj_bool!(false)),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
         ))),
+        Span::default(),
     );
 
     interpreter.tree_pass(vec![stmt]).unwrap();
@@ -148,15 +262,18 @@ fn if_statement_should_eval_to_primary_clause_if_condition_is_true() {
 fn if_statement_should_eval_to_else_clause_if_condition_is_false() {
     let interpreter = StatefulInterpreter::new();
     let stmt = Stmt::If(
-        Expr::Primary(obj_bool!(false)),
+        Expr::Primary(obj_bool!(false), Span::default()),
         Box::new(Stmt::Declaration(
             Identifier::Name("test".to_string()),
-            Expr::Primary(obj_bool!(true)),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
         )),
         Option::Some(Box::new(Stmt::Declaration(
             Identifier::Name("test".to_string()),
-            Expr::Primary(obj_bool!(false)),
+            Expr::Primary(obj_bool!(false), Span::default()),
+            Span::default(),
         ))),
+        Span::default(),
     );
 
     interpreter.tree_pass(vec![stmt]).unwrap();
@@ -169,12 +286,545 @@ fn if_statement_should_eval_to_else_clause_if_condition_is_false() {
 #[test]
 fn while_statement_should_eval_until_false() {
     let stmt = Stmt::While(
-        Expr::Primary(obj_bool!(false)),
+        Expr::Primary(obj_bool!(false), Span::default()),
         Box::new(Stmt::Declaration(
             Identifier::Name("test".to_string()),
-            Expr::Primary(obj_bool!(true)),
+            Expr::Primary(obj_bool!(true), Span::default()),
+            Span::default(),
+        )),
+        None,
+        Span::default(),
+    );
+
+    assert_eq!(Ok(None), StatefulInterpreter::new().tree_pass(vec![stmt]));
+}
+
+#[test]
+fn while_statement_should_stop_on_break() {
+    let stmt = Stmt::While(
+        Expr::Primary(obj_bool!(true), Span::default()),
+        Box::new(Stmt::Block(
+            vec![Stmt::Break(Span::default())],
+            Span::default(),
         )),
+        None,
+        Span::default(),
     );
 
     assert_eq!(Ok(None), StatefulInterpreter::new().tree_pass(vec![stmt]));
 }
+
+#[test]
+fn for_in_statement_should_bind_each_element_in_a_fresh_scope() {
+    let interpreter = StatefulInterpreter::new();
+    let closures = List::new(vec![]);
+    let stmt = Stmt::ForIn(
+        identifier_name!("item"),
+        Expr::Primary(
+            Object::List(List::new(vec![obj_number!(1.0), obj_number!(2.0)])),
+            Span::default(),
+        ),
+        Box::new(Stmt::Expression(
+            Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Primary(
+                        Object::List(closures.clone()),
+                        Span::default(),
+                    )),
+                    Box::new(Expr::Variable(identifier_name!("push"), Span::default())),
+                    Span::default(),
+                )),
+                vec![Expr::Lambda(
+                    vec![],
+                    Box::new(Stmt::Return(
+                        Some(Expr::Variable(identifier_name!("item"), Span::default())),
+                        Span::default(),
+                    )),
+                    Span::default(),
+                )],
+                Span::default(),
+            ),
+            Span::default(),
+        )),
+        Span::default(),
+    );
+
+    assert_eq!(Ok(None), interpreter.tree_pass(vec![stmt]));
+    let captured: Vec<Object> = closures
+        .items()
+        .into_iter()
+        .map(|closure| match closure {
+            Object::Call(c) => c.call(&interpreter, vec![]).unwrap(),
+            obj => panic!("expected a closure, found {}", obj),
+        })
+        .collect();
+    assert_eq!(vec![obj_number!(1.0), obj_number!(2.0)], captured);
+}
+
+#[test]
+fn for_in_statement_should_err_on_non_iterables() {
+    let stmt = Stmt::ForIn(
+        identifier_name!("item"),
+        Expr::Primary(obj_nil!(), Span::default()),
+        Box::new(Stmt::Break(Span::default())),
+        Span::default(),
+    );
+
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::InvalidArgument(
+                "invalid argument type: object nil is not iterable".to_string()
+            )),
+            Span::default()
+        ))),
+        StatefulInterpreter::new().tree_pass(vec![stmt])
+    );
+}
+
+#[test]
+fn while_statement_should_run_increment_after_continue() {
+    let interpreter = StatefulInterpreter::new();
+    let test = || Box::new(Expr::Variable(identifier_name!("test"), Span::default()));
+    let stmts = vec![
+        Stmt::Declaration(
+            identifier_name!("test"),
+            Expr::Primary(obj_number!(0.0), Span::default()),
+            Span::default(),
+        ),
+        Stmt::While(
+            Expr::Comparison(
+                ComparisonExpr::Less(
+                    test(),
+                    Box::new(Expr::Primary(obj_number!(3.0), Span::default())),
+                ),
+                Span::default(),
+            ),
+            Box::new(Stmt::Block(
+                vec![
+                    Stmt::Continue(Span::default()),
+                    Stmt::Print(*test(), Span::default()),
+                ],
+                Span::default(),
+            )),
+            Some(Expr::Assignment(
+                identifier_name!("test"),
+                Box::new(Expr::Addition(
+                    AdditionExpr::Add(
+                        test(),
+                        Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    ),
+                    Span::default(),
+                )),
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(Ok(None), interpreter.tree_pass(stmts));
+    assert_eq!(
+        Some(obj_number!(3.0)),
+        interpreter.env.get(&identifier_name!("test"))
+    );
+}
+
+fn initializer_class_declaration() -> Stmt {
+    Stmt::Class(
+        identifier_name!("test"),
+        identifier_name!("test"),
+        None,
+        vec![Stmt::Function(
+            identifier_name!("init"),
+            identifier_name!("init"),
+            vec![identifier_name!("value")],
+            Box::new(Stmt::Block(
+                vec![
+                    Stmt::Expression(
+                        Expr::Set(
+                            Box::new(Expr::This(Span::default())),
+                            identifier_name!("value"),
+                            Box::new(Expr::Variable(identifier_name!("value"), Span::default())),
+                            Span::default(),
+                        ),
+                        Span::default(),
+                    ),
+                    Stmt::Return(None, Span::default()),
+                ],
+                Span::default(),
+            )),
+            Span::default(),
+        )],
+        Span::default(),
+    )
+}
+
+#[test]
+fn class_call_should_invoke_initializer_with_arguments() {
+    let input = vec![
+        initializer_class_declaration(),
+        Stmt::Return(
+            Some(Expr::Get(
+                Box::new(Expr::Call(
+                    Box::new(Expr::Variable(identifier_name!("test"), Span::default())),
+                    vec![Expr::Primary(obj_number!(5.0), Span::default())],
+                    Span::default(),
+                )),
+                Box::new(Expr::Variable(identifier_name!("value"), Span::default())),
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Ok(Some(obj_number!(5.0))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn class_call_should_enforce_initializer_arity() {
+    let input = vec![
+        initializer_class_declaration(),
+        Stmt::Expression(
+            Expr::Call(
+                Box::new(Expr::Variable(identifier_name!("test"), Span::default())),
+                vec![],
+                Span::default(),
+            ),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::CallErr(
+                "expected 1 arguments but got 0".to_string()
+            )),
+            Span::default(),
+        ))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn initializer_should_return_this_when_invoked_directly() {
+    let interpreter = StatefulInterpreter::new();
+    let input = vec![
+        initializer_class_declaration(),
+        Stmt::Declaration(
+            identifier_name!("instance"),
+            Expr::Call(
+                Box::new(Expr::Variable(identifier_name!("test"), Span::default())),
+                vec![Expr::Primary(obj_number!(5.0), Span::default())],
+                Span::default(),
+            ),
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Variable(
+                        identifier_name!("instance"),
+                        Span::default(),
+                    )),
+                    Box::new(Expr::Variable(identifier_name!("init"), Span::default())),
+                    Span::default(),
+                )),
+                vec![Expr::Primary(obj_number!(10.0), Span::default())],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    let rv = interpreter.tree_pass(input);
+    let instance = interpreter.env.get(&identifier_name!("instance"));
+
+    assert_eq!(Ok(instance), rv);
+}
+
+fn method(name: &str, body: Vec<Stmt>) -> Stmt {
+    Stmt::Function(
+        identifier_name!(name),
+        identifier_name!(name),
+        vec![],
+        Box::new(Stmt::Block(body, Span::default())),
+        Span::default(),
+    )
+}
+
+#[test]
+fn subclass_should_inherit_superclass_methods() {
+    let input = vec![
+        Stmt::Class(
+            identifier_name!("parent"),
+            identifier_name!("parent"),
+            None,
+            vec![method(
+                "test_func",
+                vec![Stmt::Return(
+                    Some(Expr::Primary(obj_number!(5.0), Span::default())),
+                    Span::default(),
+                )],
+            )],
+            Span::default(),
+        ),
+        Stmt::Class(
+            identifier_name!("child"),
+            identifier_name!("child"),
+            Some(Expr::Variable(identifier_name!("parent"), Span::default())),
+            vec![],
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Call(
+                        Box::new(Expr::Variable(identifier_name!("child"), Span::default())),
+                        vec![],
+                        Span::default(),
+                    )),
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_func"),
+                        Span::default(),
+                    )),
+                    Span::default(),
+                )),
+                vec![],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Ok(Some(obj_number!(5.0))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn super_should_invoke_the_superclass_method() {
+    let input = vec![
+        Stmt::Class(
+            identifier_name!("parent"),
+            identifier_name!("parent"),
+            None,
+            vec![method(
+                "test_func",
+                vec![Stmt::Return(
+                    Some(Expr::Primary(obj_number!(5.0), Span::default())),
+                    Span::default(),
+                )],
+            )],
+            Span::default(),
+        ),
+        Stmt::Class(
+            identifier_name!("child"),
+            identifier_name!("child"),
+            Some(Expr::Variable(identifier_name!("parent"), Span::default())),
+            vec![method(
+                "test_func",
+                vec![Stmt::Return(
+                    Some(Expr::Addition(
+                        AdditionExpr::Add(
+                            Box::new(Expr::Call(
+                                Box::new(Expr::Super(
+                                    identifier_name!("test_func"),
+                                    Span::default(),
+                                )),
+                                vec![],
+                                Span::default(),
+                            )),
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                        ),
+                        Span::default(),
+                    )),
+                    Span::default(),
+                )],
+            )],
+            Span::default(),
+        ),
+        Stmt::Return(
+            Some(Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Call(
+                        Box::new(Expr::Variable(identifier_name!("child"), Span::default())),
+                        vec![],
+                        Span::default(),
+                    )),
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_func"),
+                        Span::default(),
+                    )),
+                    Span::default(),
+                )),
+                vec![],
+                Span::default(),
+            )),
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Ok(Some(obj_number!(6.0))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn subclass_of_non_class_should_error() {
+    let input = vec![
+        Stmt::Declaration(
+            identifier_name!("parent"),
+            Expr::Primary(obj_number!(5.0), Span::default()),
+            Span::default(),
+        ),
+        Stmt::Class(
+            identifier_name!("child"),
+            identifier_name!("child"),
+            Some(Expr::Variable(identifier_name!("parent"), Span::default())),
+            vec![],
+            Span::default(),
+        ),
+    ];
+
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::Type("superclass must be a class")),
+            Span::default(),
+        ))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn runtime_errors_should_convert_to_a_diagnostic_at_the_failing_expression() {
+    let operation_span = Span::new(6, 13, 1, 7);
+    let input = vec![Stmt::Print(
+        Expr::Addition(
+            AdditionExpr::Add(
+                Box::new(Expr::Primary(obj_number!(1.0), Span::new(6, 7, 1, 7))),
+                Box::new(Expr::Primary(
+                    obj_str!("a".to_string()),
+                    Span::new(10, 13, 1, 11),
+                )),
+            ),
+            operation_span,
+        ),
+        Span::new(0, 14, 1, 1),
+    )];
+
+    let diagnostic = Diagnostic::from(StatefulInterpreter::new().tree_pass(input).unwrap_err());
+
+    assert_eq!("E0300", diagnostic.code);
+    assert_eq!(operation_span, diagnostic.span);
+}
+
+#[test]
+fn try_stmt_should_bind_runtime_errors_as_error_instances() {
+    let divide_by_zero = Expr::Multiplication(
+        MultiplicationExpr::Divide(
+            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+            Box::new(Expr::Primary(obj_number!(0.0), Span::default())),
+        ),
+        Span::new(6, 11, 2, 7),
+    );
+    let input = Stmt::Try(
+        Box::new(Stmt::Block(
+            vec![Stmt::Print(divide_by_zero, Span::default())],
+            Span::default(),
+        )),
+        Some((
+            identifier_name!("e"),
+            Box::new(Stmt::Return(
+                Some(Expr::Variable(identifier_name!("e"), Span::default())),
+                Span::default(),
+            )),
+        )),
+        None,
+        Span::default(),
+    );
+
+    let error = match StatefulInterpreter::new().tree_pass(input) {
+        Ok(Some(Object::Instance(error))) => error,
+        other => panic!("expected an error instance, found {:?}", other),
+    };
+    assert_eq!(
+        Some(obj_str!("division by zero".to_string())),
+        error.get(&identifier_name!("message"))
+    );
+    assert_eq!(Some(obj_number!(2.0)), error.get(&identifier_name!("line")));
+    assert_eq!(
+        "[<script> (line 2)]",
+        error.get(&identifier_name!("stack")).unwrap().to_string()
+    );
+}
+
+#[test]
+fn try_stmt_should_bind_thrown_values_as_thrown() {
+    let input = Stmt::Try(
+        Box::new(Stmt::Throw(
+            Expr::Primary(obj_str!("boom".to_string()), Span::default()),
+            Span::default(),
+        )),
+        Some((
+            identifier_name!("e"),
+            Box::new(Stmt::Return(
+                Some(Expr::Variable(identifier_name!("e"), Span::default())),
+                Span::default(),
+            )),
+        )),
+        None,
+        Span::default(),
+    );
+
+    assert_eq!(
+        Ok(Some(obj_str!("boom".to_string()))),
+        StatefulInterpreter::new().tree_pass(input)
+    );
+}
+
+#[test]
+fn finally_should_run_without_replacing_the_completion_unless_it_completes_abruptly() {
+    let ret = |n: f64| {
+        Stmt::Return(
+            Some(Expr::Primary(obj_number!(n), Span::default())),
+            Span::default(),
+        )
+    };
+    let throw = Stmt::Throw(
+        Expr::Primary(obj_str!("boom".to_string()), Span::default()),
+        Span::default(),
+    );
+    let try_finally = |body: Stmt, finally: Stmt| {
+        Stmt::Try(
+            Box::new(body),
+            None,
+            Some(Box::new(finally)),
+            Span::default(),
+        )
+    };
+    let interpreter = StatefulInterpreter::new();
+
+    assert_eq!(
+        Ok(Some(obj_number!(1.0))),
+        interpreter.tree_pass(try_finally(ret(1.0), Stmt::Block(vec![], Span::default())))
+    );
+    assert_eq!(
+        Ok(Some(obj_number!(2.0))),
+        interpreter.tree_pass(try_finally(ret(1.0), ret(2.0)))
+    );
+    assert_eq!(
+        Ok(Some(obj_number!(2.0))),
+        interpreter.tree_pass(try_finally(throw.clone(), ret(2.0)))
+    );
+
+    let uncaught = interpreter
+        .tree_pass(try_finally(throw, Stmt::Block(vec![], Span::default())))
+        .unwrap_err();
+    let diagnostic = Diagnostic::from(uncaught);
+    assert_eq!("E0308", diagnostic.code);
+    assert_eq!("uncaught exception: boom", diagnostic.message);
+}
@@ -0,0 +1,221 @@
+use crate::ast::span::Span;
+use crate::functions::{Arity, CallError, CallResult, Callable, NativeMethod};
+use crate::instance::Instance;
+use crate::interpreter::StatefulInterpreter;
+use crate::list::List;
+use crate::object::{Literal, Object};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// Source represents the values an iterator steps through. Lists are
+/// iterated in place, so elements pushed during iteration are visited,
+/// while maps and strings are iterated over a copy of their keys and
+/// characters.
+#[derive(Debug)]
+enum Source {
+    List(List, usize),
+    Values(Vec<Object>, usize),
+    Range { next: f64, end: f64, step: f64 },
+    Instance(Instance),
+}
+
+/// Iter represents a native iterator, as iterated by a `for-in` loop. Clones
+/// of an iterator share their position.
+#[derive(Debug, Clone)]
+pub struct Iter {
+    source: Rc<RefCell<Source>>,
+}
+
+impl Iter {
+    fn new(source: Source) -> Self {
+        Self {
+            source: Rc::new(RefCell::new(source)),
+        }
+    }
+
+    /// range returns an iterator counting from start towards, but never
+    /// reaching, end by step.
+    pub fn range(start: f64, end: f64, step: f64) -> Result<Self, CallError> {
+        if step == 0.0 {
+            Err(CallError::Native("range step must not be zero".to_string()))
+        } else {
+            Ok(Self::new(Source::Range {
+                next: start,
+                end,
+                step,
+            }))
+        }
+    }
+
+    /// from_object returns an iterator over an iterable lox value. Lists
+    /// yield their elements, maps their keys and strings their characters.
+    /// An instance is iterable if it has a `next` method, returning nil once
+    /// exhausted, or an `iter` method returning an iterable value.
+    pub fn from_object(
+        interpreter: &StatefulInterpreter,
+        obj: Object,
+        call_site: Span,
+    ) -> Result<Self, CallError> {
+        match obj {
+            Object::Iterator(iter) => Ok(iter),
+            Object::List(l) => Ok(Self::new(Source::List(l, 0))),
+            Object::Map(m) => Ok(Self::new(Source::Values(m.keys(), 0))),
+            Object::Literal(Literal::Str(s)) => Ok(Self::new(Source::Values(
+                s.chars().map(|c| obj_str!(c.to_string())).collect(),
+                0,
+            ))),
+            Object::Instance(i) if i.get(&identifier_name!("next")).is_some() => {
+                Ok(Self::new(Source::Instance(i)))
+            }
+            Object::Instance(i) => match i.get(&identifier_name!("iter")) {
+                Some(Object::Call(iter)) => {
+                    match call(interpreter, *iter, vec![], call_site)? {
+                        // an iterator returned by `iter` must iterate itself,
+                        // rather than deferring to yet another `iter`.
+                        Object::Instance(i) if i.get(&identifier_name!("next")).is_none() => {
+                            Err(not_iterable(Object::Instance(i)))
+                        }
+                        obj => Self::from_object(interpreter, obj, call_site),
+                    }
+                }
+                _ => Err(not_iterable(Object::Instance(i))),
+            },
+            obj => Err(not_iterable(obj)),
+        }
+    }
+
+    /// next advances the iterator, returning None once it is exhausted.
+    pub fn next(
+        &self,
+        interpreter: &StatefulInterpreter,
+        call_site: Span,
+    ) -> Result<Option<Object>, CallError> {
+        if let Some(item) = self.advance() {
+            return Ok(item);
+        }
+
+        // the source is released before calling into lox, which may advance
+        // this same iterator.
+        let instance = match &*self.source.borrow() {
+            Source::Instance(i) => i.clone(),
+            _ => unreachable!("only instances are advanced by calling into lox"),
+        };
+        match instance.get(&identifier_name!("next")) {
+            Some(Object::Call(next)) => match call(interpreter, *next, vec![], call_site)? {
+                Object::Literal(Literal::Nil) => Ok(None),
+                obj => Ok(Some(obj)),
+            },
+            _ => Err(not_iterable(Object::Instance(instance))),
+        }
+    }
+
+    /// advance advances an iterator over native values, returning None
+    /// rather than advancing an iterator over an instance, whose `next`
+    /// method must be called by the interpreter.
+    pub fn advance(&self) -> Option<Option<Object>> {
+        match &mut *self.source.borrow_mut() {
+            Source::List(l, pos) => {
+                let item = l.get(*pos);
+                *pos += 1;
+                Some(item)
+            }
+            Source::Values(values, pos) => {
+                let item = values.get(*pos).cloned();
+                *pos += 1;
+                Some(item)
+            }
+            Source::Range { next, end, step } => {
+                let current = *next;
+                if (*step > 0.0 && current >= *end) || (*step < 0.0 && current <= *end) {
+                    return Some(None);
+                }
+                *next += *step;
+                Some(Some(obj_number!(current)))
+            }
+            Source::Instance(_) => None,
+        }
+    }
+
+    /// is returns true if both iterators are the same object.
+    pub fn is(&self, other: &Iter) -> bool {
+        Rc::ptr_eq(&self.source, &other.source)
+    }
+
+    /// method returns the native method of the passed name bound to the
+    /// iterator, if one exists. Iterators have a single `next` method,
+    /// returning nil once exhausted.
+    pub fn method(&self, name: &str) -> Option<Callable> {
+        if name != "next" {
+            return None;
+        }
+
+        let receiver = self.clone();
+        Some(Callable::Method(NativeMethod::new(
+            name,
+            Arity::Fixed(0),
+            Object::Iterator(self.clone()),
+            move |interpreter, _| {
+                receiver
+                    .next(interpreter, interpreter.call_site())
+                    .map(|item| item.unwrap_or(obj_nil!()))
+            },
+        )))
+    }
+}
+
+impl PartialEq for Iter {
+    fn eq(&self, other: &Iter) -> bool {
+        self.is(other)
+    }
+}
+
+impl fmt::Display for Iter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<iterator>")
+    }
+}
+
+fn not_iterable(obj: Object) -> CallError {
+    CallError::Type(format!("object {} is not iterable", obj))
+}
+
+/// call invokes an iteration method defined in lox.
+fn call(
+    interpreter: &StatefulInterpreter,
+    callee: Callable,
+    args: Vec<Object>,
+    call_site: Span,
+) -> CallResult {
+    interpreter
+        .call(Rc::new(callee), args, call_site)
+        .map_err(|e| CallError::Runtime(Box::new(e)))
+}
+
+/// range is the native `range(start, end, step)` function, returning an
+/// iterator. The step defaults to 1 when omitted.
+pub fn range(args: Vec<Object>) -> CallResult {
+    let numbers = args
+        .iter()
+        .map(|arg| match arg {
+            Object::Literal(Literal::Number(n)) => Ok(*n),
+            obj => Err(CallError::Type(format!(
+                "range expects numbers, found {}",
+                obj
+            ))),
+        })
+        .collect::<Result<Vec<f64>, CallError>>()?;
+
+    match numbers.as_slice() {
+        [start, end] => Iter::range(*start, *end, 1.0),
+        [start, end, step] => Iter::range(*start, *end, *step),
+        _ => Err(CallError::Native(format!(
+            "range expects 2 or 3 arguments but got {}",
+            numbers.len()
+        ))),
+    }
+    .map(Object::Iterator)
+}
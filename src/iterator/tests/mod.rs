@@ -0,0 +1,92 @@
+use crate::ast::span::Span;
+use crate::functions::CallError;
+use crate::interpreter::StatefulInterpreter;
+use crate::iterator::{range, Iter};
+use crate::list::List;
+use crate::map::Map;
+use crate::object::Object;
+
+fn collect(obj: Object) -> Result<Vec<Object>, CallError> {
+    let interpreter = StatefulInterpreter::new();
+    let iter = Iter::from_object(&interpreter, obj, Span::default())?;
+    let mut items = Vec::new();
+    while let Some(item) = iter.next(&interpreter, Span::default())? {
+        items.push(item);
+    }
+    Ok(items)
+}
+
+#[test]
+fn iterators_should_step_through_builtin_iterables() {
+    let map = Map::new();
+    map.set(&obj_str!("b".to_string()), obj_nil!()).unwrap();
+    map.set(&obj_str!("a".to_string()), obj_nil!()).unwrap();
+
+    assert_eq!(
+        Ok(vec![obj_number!(1.0), obj_number!(2.0)]),
+        collect(Object::List(List::new(vec![
+            obj_number!(1.0),
+            obj_number!(2.0)
+        ])))
+    );
+    assert_eq!(
+        Ok(vec![obj_str!("b".to_string()), obj_str!("a".to_string())]),
+        collect(Object::Map(map))
+    );
+    assert_eq!(
+        Ok(vec![obj_str!("é".to_string()), obj_str!("!".to_string())]),
+        collect(obj_str!("é!".to_string()))
+    );
+}
+
+#[test]
+fn list_iterators_should_observe_elements_pushed_during_iteration() {
+    let interpreter = StatefulInterpreter::new();
+    let list = List::new(vec![obj_number!(1.0)]);
+    let iter =
+        Iter::from_object(&interpreter, Object::List(list.clone()), Span::default()).unwrap();
+
+    assert_eq!(
+        Ok(Some(obj_number!(1.0))),
+        iter.next(&interpreter, Span::default())
+    );
+    list.push(obj_number!(2.0));
+    assert_eq!(
+        Ok(Some(obj_number!(2.0))),
+        iter.next(&interpreter, Span::default())
+    );
+    assert_eq!(Ok(None), iter.next(&interpreter, Span::default()));
+}
+
+#[test]
+fn range_should_count_by_step_excluding_the_end() {
+    let range_of = |args: Vec<f64>| {
+        range(args.into_iter().map(|n| obj_number!(n)).collect()).and_then(collect)
+    };
+
+    assert_eq!(
+        Ok(vec![obj_number!(0.0), obj_number!(1.0), obj_number!(2.0)]),
+        range_of(vec![0.0, 3.0])
+    );
+    assert_eq!(
+        Ok(vec![obj_number!(5.0), obj_number!(3.0)]),
+        range_of(vec![5.0, 1.0, -2.0])
+    );
+    assert_eq!(Ok(vec![]), range_of(vec![3.0, 0.0]));
+    assert_eq!(
+        Err(CallError::Native("range step must not be zero".to_string())),
+        range_of(vec![0.0, 1.0, 0.0])
+    );
+    assert!(matches!(
+        range(vec![obj_number!(0.0), obj_nil!()]),
+        Err(CallError::Type(_))
+    ));
+}
+
+#[test]
+fn non_iterable_objects_should_err() {
+    assert_eq!(
+        Err(CallError::Type("object 1 is not iterable".to_string())),
+        collect(obj_number!(1.0))
+    );
+}
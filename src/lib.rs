@@ -6,14 +6,27 @@ pub mod ast;
 
 pub mod analyzer;
 pub mod class;
+pub mod compiler;
+pub mod diagnostic;
 pub mod environment;
+pub mod formatter;
 pub mod functions;
 pub mod instance;
 pub mod interpreter;
+pub mod iterator;
+pub mod list;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod map;
+pub mod module;
 pub mod parser;
 pub mod pass;
+pub mod repl;
+pub mod runtime;
 pub mod scanner;
 pub mod statics;
+pub mod stdlib;
+pub mod vm;
 
 #[cfg(test)]
 mod tests;
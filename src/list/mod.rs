@@ -0,0 +1,234 @@
+use crate::functions::{Arity, CallError, CallResult, Callable, NativeMethod};
+use crate::interpreter::StatefulInterpreter;
+use crate::object::{Literal, Object};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// List represents a lox list. The elements are reference-counted, so clones
+/// of a list are aliases that observe each other's mutations.
+#[derive(Debug, Clone, Default)]
+pub struct List {
+    items: Rc<RefCell<Vec<Object>>>,
+}
+
+impl List {
+    pub fn new(items: Vec<Object>) -> Self {
+        Self {
+            items: Rc::new(RefCell::new(items)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.borrow().is_empty()
+    }
+
+    /// get returns the element at the passed index, if it is in bounds.
+    pub fn get(&self, index: usize) -> Option<Object> {
+        self.items.borrow().get(index).cloned()
+    }
+
+    /// set overwrites the element at the passed index, returning the new
+    /// value if the index is in bounds.
+    pub fn set(&self, index: usize, value: Object) -> Option<Object> {
+        self.items.borrow_mut().get_mut(index).map(|item| {
+            *item = value.clone();
+            value
+        })
+    }
+
+    pub fn push(&self, value: Object) {
+        self.items.borrow_mut().push(value)
+    }
+
+    /// items returns a copy of the list's elements.
+    pub fn items(&self) -> Vec<Object> {
+        self.items.borrow().clone()
+    }
+
+    /// is returns true if both lists are the same object, rather than merely
+    /// lists with equal elements.
+    pub fn is(&self, other: &List) -> bool {
+        Rc::ptr_eq(&self.items, &other.items)
+    }
+
+    /// index_of converts a lox value into an index into the list, failing
+    /// if the value is not an integer or is out of bounds.
+    pub fn index_of(&self, index: &Object) -> Result<usize, CallError> {
+        to_index(index, self.len())
+    }
+
+    /// method returns the native method of the passed name bound to the
+    /// list, if one exists.
+    pub fn method(&self, name: &str) -> Option<Callable> {
+        let (arity, func): (usize, ListMethod) = match name {
+            "len" => (0, len),
+            "push" => (1, push),
+            "pop" => (0, pop),
+            "insert" => (2, insert),
+            "remove" => (1, remove),
+            "slice" => (2, slice),
+            "map" => (1, map),
+            "filter" => (1, filter),
+            "reduce" => (2, reduce),
+            _ => return None,
+        };
+
+        let receiver = self.clone();
+        Some(Callable::Method(NativeMethod::new(
+            name,
+            Arity::Fixed(arity),
+            Object::List(self.clone()),
+            move |interpreter, args| func(interpreter, &receiver, args),
+        )))
+    }
+}
+
+/// Lists compare equal when their elements are equal.
+impl PartialEq for List {
+    fn eq(&self, other: &List) -> bool {
+        self.is(other) || *self.items.borrow() == *other.items.borrow()
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}]",
+            self.items
+                .borrow()
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/// ListMethod is the signature of each native list method, taking the list
+/// the method is bound to.
+type ListMethod = fn(&StatefulInterpreter, &List, Vec<Object>) -> CallResult;
+
+/// to_index converts a lox value into the index of an element of a list of
+/// the passed length, failing if the value is not an integer or is out of
+/// range.
+fn to_index(index: &Object, len: usize) -> Result<usize, CallError> {
+    bounded(index, len, len)
+}
+
+/// to_position converts a lox value into a position between the elements of
+/// a list, from its start up to and including its end, as taken by insert
+/// and slice.
+fn to_position(index: &Object, len: usize) -> Result<usize, CallError> {
+    bounded(index, len, len + 1)
+}
+
+fn bounded(index: &Object, len: usize, bound: usize) -> Result<usize, CallError> {
+    match index {
+        Object::Literal(Literal::Number(n)) if n.fract() == 0.0 => {
+            if *n >= 0.0 && (*n as usize) < bound {
+                Ok(*n as usize)
+            } else {
+                Err(CallError::IndexOutOfRange(*n, len))
+            }
+        }
+        obj => Err(CallError::Type(format!(
+            "list index must be an integer, found {}",
+            obj
+        ))),
+    }
+}
+
+/// callback invokes a lox callable passed as an argument to a list method.
+fn callback(interpreter: &StatefulInterpreter, func: &Object, args: Vec<Object>) -> CallResult {
+    match func {
+        Object::Call(c) => interpreter
+            .call_from_native(Rc::new(*c.clone()), args)
+            .map_err(|e| CallError::Runtime(Box::new(e))),
+        obj => Err(CallError::Type(format!(
+            "expected a callable, found {}",
+            obj
+        ))),
+    }
+}
+
+fn len(_: &StatefulInterpreter, list: &List, _: Vec<Object>) -> CallResult {
+    Ok(obj_number!(list.len() as f64))
+}
+
+fn push(_: &StatefulInterpreter, list: &List, mut args: Vec<Object>) -> CallResult {
+    list.push(args.remove(0));
+    Ok(obj_nil!())
+}
+
+fn pop(_: &StatefulInterpreter, list: &List, _: Vec<Object>) -> CallResult {
+    list.items
+        .borrow_mut()
+        .pop()
+        .ok_or_else(|| CallError::Native("pop from empty list".to_string()))
+}
+
+fn insert(_: &StatefulInterpreter, list: &List, mut args: Vec<Object>) -> CallResult {
+    let value = args.remove(1);
+    let index = to_position(&args[0], list.len())?;
+    list.items.borrow_mut().insert(index, value);
+    Ok(obj_nil!())
+}
+
+fn remove(_: &StatefulInterpreter, list: &List, args: Vec<Object>) -> CallResult {
+    let index = list.index_of(&args[0])?;
+    Ok(list.items.borrow_mut().remove(index))
+}
+
+fn slice(_: &StatefulInterpreter, list: &List, args: Vec<Object>) -> CallResult {
+    let start = to_position(&args[0], list.len())?;
+    let end = to_position(&args[1], list.len())?;
+    if start > end {
+        return Err(CallError::Native(format!(
+            "slice start {} is greater than end {}",
+            start, end
+        )));
+    }
+
+    Ok(Object::List(List::new(
+        list.items.borrow()[start..end].to_vec(),
+    )))
+}
+
+// map, filter and reduce iterate over a copy of the elements, so callbacks
+// are free to mutate the list.
+
+fn map(interpreter: &StatefulInterpreter, list: &List, args: Vec<Object>) -> CallResult {
+    list.items()
+        .into_iter()
+        .map(|item| callback(interpreter, &args[0], vec![item]))
+        .collect::<Result<Vec<Object>, CallError>>()
+        .map(|items| Object::List(List::new(items)))
+}
+
+fn filter(interpreter: &StatefulInterpreter, list: &List, args: Vec<Object>) -> CallResult {
+    let mut items = Vec::new();
+    for item in list.items() {
+        let keep: bool = callback(interpreter, &args[0], vec![item.clone()])?.into();
+        if keep {
+            items.push(item);
+        }
+    }
+
+    Ok(Object::List(List::new(items)))
+}
+
+fn reduce(interpreter: &StatefulInterpreter, list: &List, mut args: Vec<Object>) -> CallResult {
+    let initial = args.remove(1);
+    list.items().into_iter().try_fold(initial, |acc, item| {
+        callback(interpreter, &args[0], vec![acc, item])
+    })
+}
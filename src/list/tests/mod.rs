@@ -0,0 +1,145 @@
+use crate::functions::{Arity, CallError, Callable, StaticFunc};
+use crate::interpreter::StatefulInterpreter;
+use crate::list::List;
+use crate::object::Object;
+
+fn numbers(ns: &[f64]) -> List {
+    List::new(ns.iter().map(|n| obj_number!(*n)).collect())
+}
+
+fn call(list: &List, name: &str, args: Vec<Object>) -> Result<Object, CallError> {
+    list.method(name)
+        .unwrap()
+        .call(&StatefulInterpreter::new(), args)
+}
+
+fn native<F>(arity: usize, func: F) -> Object
+where
+    F: Fn(Vec<Object>) -> Result<Object, CallError> + 'static,
+{
+    obj_call!(Box::new(Callable::Static(StaticFunc::new(
+        "native",
+        Arity::Fixed(arity),
+        func
+    ))))
+}
+
+#[test]
+fn list_clones_should_share_elements() {
+    let list = numbers(&[1.0]);
+    let alias = list.clone();
+
+    alias.push(obj_number!(2.0));
+    alias.set(0, obj_nil!());
+
+    assert_eq!(vec![obj_nil!(), obj_number!(2.0)], list.items());
+    assert!(list.is(&alias));
+    assert!(!list.is(&numbers(&[1.0])));
+    assert_eq!(None, list.set(2, obj_nil!()));
+}
+
+#[test]
+fn list_should_display_its_elements() {
+    assert_eq!("[]", List::new(vec![]).to_string());
+    assert_eq!(
+        "[1, a, [2]]",
+        List::new(vec![
+            obj_number!(1.0),
+            obj_str!("a".to_string()),
+            Object::List(numbers(&[2.0]))
+        ])
+        .to_string()
+    );
+}
+
+#[test]
+fn index_of_should_only_accept_integers_in_bounds() {
+    let list = numbers(&[1.0, 2.0]);
+
+    assert_eq!(Ok(1), list.index_of(&obj_number!(1.0)));
+    assert_eq!(
+        Err(CallError::IndexOutOfRange(2.0, 2)),
+        list.index_of(&obj_number!(2.0))
+    );
+    assert_eq!(
+        Err(CallError::IndexOutOfRange(-1.0, 2)),
+        list.index_of(&obj_number!(-1.0))
+    );
+    assert!(matches!(
+        list.index_of(&obj_str!("0".to_string())),
+        Err(CallError::Type(_))
+    ));
+}
+
+#[test]
+fn mutating_methods_should_update_the_list() {
+    let list = numbers(&[1.0]);
+
+    assert_eq!(Ok(obj_nil!()), call(&list, "push", vec![obj_number!(3.0)]));
+    assert_eq!(
+        Ok(obj_nil!()),
+        call(&list, "insert", vec![obj_number!(1.0), obj_number!(2.0)])
+    );
+    assert_eq!(numbers(&[1.0, 2.0, 3.0]), list);
+    assert_eq!(Ok(obj_number!(3.0)), call(&list, "len", vec![]));
+
+    assert_eq!(
+        Ok(obj_number!(1.0)),
+        call(&list, "remove", vec![obj_number!(0.0)])
+    );
+    assert_eq!(Ok(obj_number!(3.0)), call(&list, "pop", vec![]));
+    assert_eq!(Ok(obj_number!(2.0)), call(&list, "pop", vec![]));
+    assert_eq!(
+        Err(CallError::Native("pop from empty list".to_string())),
+        call(&list, "pop", vec![])
+    );
+}
+
+#[test]
+fn slice_should_copy_a_range_of_elements() {
+    let list = numbers(&[1.0, 2.0, 3.0]);
+
+    assert_eq!(
+        Ok(Object::List(numbers(&[2.0, 3.0]))),
+        call(&list, "slice", vec![obj_number!(1.0), obj_number!(3.0)])
+    );
+    assert_eq!(
+        Ok(Object::List(numbers(&[]))),
+        call(&list, "slice", vec![obj_number!(3.0), obj_number!(3.0)])
+    );
+    assert!(call(&list, "slice", vec![obj_number!(2.0), obj_number!(1.0)]).is_err());
+}
+
+#[test]
+fn higher_order_methods_should_call_back_into_lox() {
+    let list = numbers(&[1.0, 2.0, 3.0]);
+    let double = native(1, |args| match args[0] {
+        Object::Literal(crate::object::Literal::Number(n)) => Ok(obj_number!(n * 2.0)),
+        _ => Err(CallError::Unknown),
+    });
+    let is_odd = native(1, |args| Ok(obj_bool!(args[0] != obj_number!(2.0))));
+    let sum = native(2, |args| match (&args[0], &args[1]) {
+        (
+            Object::Literal(crate::object::Literal::Number(acc)),
+            Object::Literal(crate::object::Literal::Number(n)),
+        ) => Ok(obj_number!(acc + n)),
+        _ => Err(CallError::Unknown),
+    });
+
+    assert_eq!(
+        Ok(Object::List(numbers(&[2.0, 4.0, 6.0]))),
+        call(&list, "map", vec![double])
+    );
+    assert_eq!(
+        Ok(Object::List(numbers(&[1.0, 3.0]))),
+        call(&list, "filter", vec![is_odd])
+    );
+    assert_eq!(
+        Ok(obj_number!(6.0)),
+        call(&list, "reduce", vec![sum, obj_number!(0.0)])
+    );
+    assert!(matches!(
+        call(&list, "map", vec![obj_nil!()]),
+        Err(CallError::Type(_))
+    ));
+}
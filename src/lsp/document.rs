@@ -0,0 +1,553 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::expression::*;
+use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::ast::token::{self, Token, TokenType};
+use crate::diagnostic::Diagnostic;
+use crate::parser;
+use crate::pass::*;
+use crate::scanner::Scanner;
+use crate::statics;
+use crate::stdlib;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// SymbolKind represents the kind of declaration that introduced a name.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SymbolKind {
+    Variable,
+    Function,
+    Class,
+    Parameter,
+    Method,
+    Module,
+}
+
+/// Symbol represents a declaration within a document, pointing at both the
+/// name it declares and the whole declaration. A class's methods are its
+/// children.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub detail: String,
+    pub span: Span,
+    pub range: Span,
+    pub children: Vec<Symbol>,
+}
+
+/// Reference pairs a span of source naming a variable with the id of the
+/// declaration it resolves to.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Reference {
+    id: usize,
+    span: Span,
+}
+
+/// Visibility is the range of character offsets a declaration is in scope
+/// over, from its name to the end of its enclosing scope.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Visibility {
+    start: usize,
+    end: usize,
+}
+
+/// Document holds the result of analyzing a single source file: the
+/// diagnostics reported for it and an index of its declarations and the
+/// references resolved to each of them.
+#[derive(Debug, Default)]
+pub struct Document {
+    text: Vec<char>,
+    line_starts: Vec<usize>,
+    diagnostics: Vec<Diagnostic>,
+    symbols: Vec<Symbol>,
+    declarations: HashMap<usize, Symbol>,
+    visibility: HashMap<usize, Visibility>,
+    references: Vec<Reference>,
+}
+
+impl Document {
+    /// new scans, parses and analyzes source. Each top-level statement is
+    /// analyzed on its own so that an error in one doesn't hide the names
+    /// declared by the rest. Imports are located relative to the script's
+    /// path when one is passed.
+    pub fn new(source: &str, script: Option<&Path>) -> Self {
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        let mut tokens: Vec<Token> = Vec::new();
+        for lex_result in Scanner::new(source.to_string()).scan_tokens() {
+            match lex_result {
+                Ok(tok) => tokens.push(tok),
+                Err(e) => diagnostics.push(e.into()),
+            }
+        }
+
+        let (stmts, errors) = parser::parse(&tokens);
+        diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+
+        let mut analyzer = ScopeAnalyzer::new();
+        if let Some(path) = script {
+            analyzer.set_script(path);
+        }
+        let mut index = Index::new(&tokens);
+        let mut symbols = Vec::new();
+        for stmt in stmts {
+            match analyzer.tree_pass(stmt) {
+                Ok(analyzed) => symbols.extend(index.stmt(&analyzed)),
+                Err(e) => diagnostics.push(e.into()),
+            }
+        }
+
+        Document {
+            text: source.chars().collect(),
+            line_starts: line_starts(source),
+            diagnostics,
+            symbols,
+            declarations: index.declarations,
+            visibility: index.visibility,
+            references: index.references,
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// symbols returns the top-level declarations of the document, in source
+    /// order.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// definition returns the declaration of the name at the passed offset.
+    pub fn definition(&self, offset: usize) -> Option<&Symbol> {
+        self.reference_at(offset)
+            .and_then(|reference| self.declarations.get(&reference.id))
+    }
+
+    /// references returns the span of each use of the name at the passed
+    /// offset, optionally including its declaration.
+    pub fn references(&self, offset: usize, include_declaration: bool) -> Vec<Span> {
+        let id = match self.reference_at(offset) {
+            Some(reference) => reference.id,
+            None => return Vec::new(),
+        };
+        let declaration = self.declarations.get(&id).map(|symbol| symbol.span);
+
+        self.references
+            .iter()
+            .filter(|reference| reference.id == id)
+            .map(|reference| reference.span)
+            .filter(|span| include_declaration || Some(*span) != declaration)
+            .collect()
+    }
+
+    /// completions returns each keyword and each name in scope at the passed
+    /// offset, along with the natives and stdlib namespaces, without
+    /// duplicates.
+    pub fn completions(&self, offset: usize) -> Vec<(String, Option<SymbolKind>)> {
+        let mut names: Vec<(String, Option<SymbolKind>)> = Vec::new();
+        let mut push = |name: &str, kind: Option<SymbolKind>| {
+            if !names.iter().any(|(n, _)| n == name) {
+                names.push((name.to_string(), kind));
+            }
+        };
+
+        let mut declarations: Vec<&Symbol> = self
+            .declarations
+            .iter()
+            .filter(|(id, _)| {
+                self.visibility
+                    .get(id)
+                    .is_some_and(|v| v.start <= offset && offset < v.end)
+            })
+            .map(|(_, symbol)| symbol)
+            .collect();
+        declarations.sort_by_key(|symbol| symbol.span.start);
+        for symbol in declarations {
+            push(&symbol.name, Some(symbol.kind));
+        }
+        for native in statics::natives() {
+            push(native.name(), Some(SymbolKind::Function));
+        }
+        for namespace in stdlib::NAMESPACES {
+            push(namespace, Some(SymbolKind::Module));
+        }
+        for keyword in token::keywords() {
+            push(keyword, None);
+        }
+
+        names
+    }
+
+    /// offset returns the character offset of a zero-based line and column,
+    /// clamped to the end of the line. Columns count UTF-16 code units, as
+    /// the protocol's positions do by default.
+    pub fn offset(&self, line: usize, character: usize) -> usize {
+        let start = match self.line_starts.get(line) {
+            Some(start) => *start,
+            None => return self.line_starts.last().copied().unwrap_or(0),
+        };
+
+        let mut units = 0;
+        let mut offset = start;
+        for c in self.text[start..].iter().take_while(|c| **c != '\n') {
+            units += c.len_utf16();
+            if units > character {
+                break;
+            }
+            offset += 1;
+        }
+        offset
+    }
+
+    /// position returns the zero-based line and column of a character
+    /// offset, with the column counted in UTF-16 code units.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next.saturating_sub(1),
+        };
+        let start = self.line_starts.get(line).copied().unwrap_or(0);
+        let end = std::cmp::max(start, std::cmp::min(offset, self.text.len()));
+        let column = self.text[start..end].iter().map(|c| c.len_utf16()).sum();
+        (line, column)
+    }
+
+    fn reference_at(&self, offset: usize) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|reference| reference.span.start <= offset && offset <= reference.span.end)
+    }
+}
+
+/// line_starts returns the character offset each line of source begins at.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(
+            source
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '\n')
+                .map(|(i, _)| i + 1),
+        )
+        .collect()
+}
+
+/// Index walks analyzed statements, recording each resolved declaration and
+/// reference. Resolved identifiers no longer carry their names, so names and
+/// their spans are recovered from the tokens the statements were parsed from.
+/// The end of each scope being walked is tracked to record the visibility of
+/// the declarations within it, with top-level declarations visible to the
+/// end of the document.
+struct Index<'a> {
+    tokens: &'a [Token],
+    scopes: Vec<usize>,
+    declarations: HashMap<usize, Symbol>,
+    visibility: HashMap<usize, Visibility>,
+    references: Vec<Reference>,
+}
+
+impl<'a> Index<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Index {
+            tokens,
+            scopes: Vec::new(),
+            declarations: HashMap::new(),
+            visibility: HashMap::new(),
+            references: Vec::new(),
+        }
+    }
+
+    /// scoped walks part of a statement within a scope ending at the passed
+    /// offset.
+    fn scoped<T, F>(&mut self, end: usize, walk: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        self.scopes.push(end);
+        let walked = walk(self);
+        self.scopes.pop();
+        walked
+    }
+
+    /// stmt indexes a statement, returning the symbol it declares if any.
+    fn stmt(&mut self, stmt: &Stmt) -> Option<Symbol> {
+        match stmt {
+            Stmt::Expression(expr, _) | Stmt::Print(expr, _) | Stmt::Throw(expr, _) => {
+                self.expr(expr);
+                None
+            }
+            Stmt::Return(expr, _) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+                None
+            }
+            Stmt::If(cond, then, otherwise, _) => {
+                self.expr(cond);
+                self.stmt(then);
+                if let Some(otherwise) = otherwise {
+                    self.stmt(otherwise);
+                }
+                None
+            }
+            Stmt::While(cond, body, step, _) => {
+                self.expr(cond);
+                self.stmt(body);
+                if let Some(step) = step {
+                    self.expr(step);
+                }
+                None
+            }
+            Stmt::ForIn(id, iterable, body, span) => {
+                self.expr(iterable);
+                let name = self.identifier_after(span.start);
+                self.scoped(body.span().end, |index| {
+                    index.declare(id, symbol(name, SymbolKind::Variable, "var", *span));
+                    index.stmt(body);
+                });
+                None
+            }
+            Stmt::Block(stmts, span) => {
+                self.scoped(span.end, |index| {
+                    for stmt in stmts {
+                        index.stmt(stmt);
+                    }
+                });
+                None
+            }
+            Stmt::Declaration(id, expr, span) => {
+                self.expr(expr);
+                let name = self.identifier_after(span.start);
+                self.declare(id, symbol(name, SymbolKind::Variable, "var", *span))
+            }
+            Stmt::Function(id, _, params, body, span) => {
+                let name = self.identifier_after(span.start);
+                let params = self.function(name, params, body);
+                let symbol =
+                    symbol(name, SymbolKind::Function, "fun", *span).map(|symbol| Symbol {
+                        detail: format!("{}({})", symbol.detail, params),
+                        ..symbol
+                    });
+                self.declare(id, symbol)
+            }
+            Stmt::Class(id, _, superclass, methods, span) => {
+                let name = self.identifier_after(span.start);
+                let mut detail = format!("class {}", lexeme(name));
+                if let Some(superclass) = superclass {
+                    self.expr(superclass);
+                    let sc_name = self.token_at(superclass.span().start);
+                    detail = format!("{} < {}", detail, lexeme(sc_name));
+                }
+
+                let children = methods
+                    .iter()
+                    .filter_map(|method| self.method(method))
+                    .collect();
+                let symbol = symbol(name, SymbolKind::Class, "class", *span).map(|symbol| Symbol {
+                    detail,
+                    children,
+                    ..symbol
+                });
+                self.declare(id, symbol)
+            }
+            Stmt::Import(path, id, span) => {
+                let name = self.identifier_after(span.start);
+                let keyword = format!("import \"{}\" as", path);
+                self.declare(id, symbol(name, SymbolKind::Module, &keyword, *span))
+            }
+            Stmt::Try(body, catch, finally, _) => {
+                self.stmt(body);
+                if let Some((id, handler)) = catch {
+                    let name = self.identifier_after(body.span().end);
+                    let range = handler.span();
+                    self.scoped(range.end, |index| {
+                        index.declare(id, symbol(name, SymbolKind::Variable, "var", range));
+                        index.stmt(handler);
+                    });
+                }
+                if let Some(finally) = finally {
+                    self.stmt(finally);
+                }
+                None
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => None,
+        }
+    }
+
+    /// method indexes the parameters and body of a class's method, returning
+    /// its symbol. Methods are looked up by name at runtime, so they have no
+    /// declaration for references to resolve to.
+    fn method(&mut self, method: &Stmt) -> Option<Symbol> {
+        match method {
+            Stmt::Function(_, _, params, body, span) => {
+                let name = self.identifier_after(span.start);
+                let params = self.function(name, params, body);
+                symbol(name, SymbolKind::Method, "method", *span).map(|symbol| Symbol {
+                    detail: format!("{}({})", symbol.detail, params),
+                    ..symbol
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// function indexes the parameters and body of a named function,
+    /// returning its comma separated parameter names.
+    fn function(&mut self, name: Option<&Token>, params: &[Identifier], body: &Stmt) -> String {
+        let from = name.map(|tok| tok.span.end).unwrap_or_default();
+        self.scoped(body.span().end, |index| {
+            let names = index.params(params, from);
+            index.stmt(body);
+            names.join(", ")
+        })
+    }
+
+    /// params declares each parameter of a function, taken from the
+    /// identifiers following the passed offset, returning their names.
+    fn params(&mut self, params: &[Identifier], from: usize) -> Vec<String> {
+        let tokens: Vec<&'a Token> = self
+            .tokens
+            .iter()
+            .filter(|tok| tok.token_type == TokenType::Identifier && tok.span.start >= from)
+            .take(params.len())
+            .collect();
+
+        params
+            .iter()
+            .zip(tokens)
+            .map(|(param, tok)| {
+                self.declare(
+                    param,
+                    symbol(Some(tok), SymbolKind::Parameter, "param", tok.span),
+                );
+                lexeme(Some(tok)).to_string()
+            })
+            .collect()
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Assignment(id, value, span) => {
+                let name = self.token_at(span.start);
+                self.reference(id, name.map(|tok| tok.span));
+                self.expr(value);
+            }
+            Expr::Variable(id, span) => self.reference(id, Some(*span)),
+            Expr::Logical(LogicalExpr::Or(l, r), _)
+            | Expr::Logical(LogicalExpr::And(l, r), _)
+            | Expr::Equality(EqualityExpr::Equal(l, r), _)
+            | Expr::Equality(EqualityExpr::NotEqual(l, r), _)
+            | Expr::Comparison(ComparisonExpr::Less(l, r), _)
+            | Expr::Comparison(ComparisonExpr::LessEqual(l, r), _)
+            | Expr::Comparison(ComparisonExpr::Greater(l, r), _)
+            | Expr::Comparison(ComparisonExpr::GreaterEqual(l, r), _)
+            | Expr::Addition(AdditionExpr::Add(l, r), _)
+            | Expr::Addition(AdditionExpr::Subtract(l, r), _)
+            | Expr::Multiplication(MultiplicationExpr::Multiply(l, r), _)
+            | Expr::Multiplication(MultiplicationExpr::Divide(l, r), _)
+            | Expr::Get(l, r, _)
+            | Expr::Set(l, _, r, _)
+            | Expr::Index(l, r, _) => {
+                self.expr(l);
+                self.expr(r);
+            }
+            Expr::Unary(UnaryExpr::Bang(e), _)
+            | Expr::Unary(UnaryExpr::Minus(e), _)
+            | Expr::Grouping(e, _) => self.expr(e),
+            Expr::Call(callee, args, _) => {
+                self.expr(callee);
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            Expr::List(elems, _) => {
+                for elem in elems {
+                    self.expr(elem);
+                }
+            }
+            Expr::Map(entries, _) => {
+                for (key, value) in entries {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            }
+            Expr::SetIndex(target, index, value, _) => {
+                self.expr(target);
+                self.expr(index);
+                self.expr(value);
+            }
+            Expr::Lambda(params, body, span) => self.scoped(body.span().end, |index| {
+                index.params(params, span.start);
+                index.stmt(body);
+            }),
+            Expr::Primary(_, _) | Expr::This(_) | Expr::Super(_, _) => (),
+        }
+    }
+
+    /// declare records the symbol declared by a resolved identifier, visible
+    /// from its name to the end of the current scope, along with its name as
+    /// a reference to itself.
+    fn declare(&mut self, id: &Identifier, symbol: Option<Symbol>) -> Option<Symbol> {
+        match (id, symbol) {
+            (Identifier::Resolved(binding), Some(symbol)) => {
+                let visibility = Visibility {
+                    start: symbol.span.start,
+                    end: self.scopes.last().copied().unwrap_or(usize::MAX),
+                };
+                self.visibility.insert(binding.id, visibility);
+                self.declarations.insert(binding.id, symbol.clone());
+                self.references.push(Reference {
+                    id: binding.id,
+                    span: symbol.span,
+                });
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    /// reference records a use of a resolved identifier. Unresolved names,
+    /// such as properties and stdlib namespaces, aren't indexed.
+    fn reference(&mut self, id: &Identifier, span: Option<Span>) {
+        if let (Identifier::Resolved(binding), Some(span)) = (id, span) {
+            self.references.push(Reference {
+                id: binding.id,
+                span,
+            });
+        }
+    }
+
+    /// identifier_after returns the first identifier token beginning at or
+    /// after the passed offset.
+    fn identifier_after(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens
+            .iter()
+            .find(|tok| tok.token_type == TokenType::Identifier && tok.span.start >= offset)
+    }
+
+    /// token_at returns the identifier token beginning at the passed offset.
+    fn token_at(&self, offset: usize) -> Option<&'a Token> {
+        self.identifier_after(offset)
+            .filter(|tok| tok.span.start == offset)
+    }
+}
+
+/// symbol returns the symbol declared by a name token, detailed as the
+/// keyword that declared it followed by the name.
+fn symbol(name: Option<&Token>, kind: SymbolKind, keyword: &str, range: Span) -> Option<Symbol> {
+    name.map(|tok| Symbol {
+        name: lexeme(Some(tok)).to_string(),
+        kind,
+        detail: format!("{} {}", keyword, lexeme(Some(tok))),
+        span: tok.span,
+        range,
+        children: Vec::new(),
+    })
+}
+
+fn lexeme(tok: Option<&Token>) -> &str {
+    tok.and_then(|tok| tok.lexeme.as_deref())
+        .unwrap_or_default()
+}
@@ -0,0 +1,397 @@
+use crate::ast::span::Span;
+use crate::diagnostic::{Diagnostic, Severity};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+mod document;
+pub mod rpc;
+
+pub use document::{Document, Symbol, SymbolKind};
+
+#[cfg(test)]
+mod tests;
+
+/// ResponseErr represents a request the server failed to handle, reported to
+/// the client with one of the JSON-RPC error codes.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ResponseErr {
+    Parse(String),
+    InvalidRequest(String),
+    MethodNotFound(String),
+    InvalidParams(String),
+}
+
+impl ResponseErr {
+    pub fn code(&self) -> i64 {
+        match self {
+            Self::Parse(_) => -32700,
+            Self::InvalidRequest(_) => -32600,
+            Self::MethodNotFound(_) => -32601,
+            Self::InvalidParams(_) => -32602,
+        }
+    }
+}
+
+impl fmt::Display for ResponseErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid message: {}", e),
+            Self::InvalidRequest(e) => write!(f, "invalid request: {}", e),
+            Self::MethodNotFound(method) => write!(f, "unknown method '{}'", method),
+            Self::InvalidParams(e) => write!(f, "invalid params: {}", e),
+        }
+    }
+}
+
+type ResponseResult = Result<Value, ResponseErr>;
+
+/// Server implements the language server protocol over the documents opened
+/// by a client, reanalyzing a document each time it changes.
+#[derive(Debug, Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// is_exited returns true once the client has asked the server to exit.
+    pub fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// handle processes a single message from the client, returning the
+    /// messages to send back. Requests are answered with exactly one
+    /// response, while notifications may publish diagnostics.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = match message.get("method").and_then(Value::as_str) {
+            Some(method) => method,
+            None => {
+                let err = ResponseErr::InvalidRequest("missing method".to_string());
+                return vec![error_response(message.get("id"), err)];
+            }
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        match message.get("id") {
+            Some(id) => {
+                let response = match self.request(method, &params) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err(err) => error_response(Some(id), err),
+                };
+                vec![response]
+            }
+            None => self.notification(method, &params),
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> ResponseResult {
+        if self.shutdown {
+            return Err(ResponseErr::InvalidRequest(
+                "server is shutting down".to_string(),
+            ));
+        }
+
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "positionEncoding": "utf-16",
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": {
+                    "name": "rlox",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/references" => self.references(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            "textDocument/completion" => self.completion(params),
+            _ => Err(ResponseErr::MethodNotFound(method.to_string())),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        if method == "exit" {
+            self.exited = true;
+            return Vec::new();
+        }
+        let uri = match params.pointer("/textDocument/uri").and_then(Value::as_str) {
+            Some(uri) => uri.to_string(),
+            None => return Vec::new(),
+        };
+
+        let text = match method {
+            "textDocument/didOpen" => params.pointer("/textDocument/text"),
+            // documents are synchronized in full, so the last change holds
+            // the whole text.
+            "textDocument/didChange" => params
+                .get("contentChanges")
+                .and_then(Value::as_array)
+                .and_then(|changes| changes.last())
+                .and_then(|change| change.get("text")),
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish_diagnostics(&uri, Vec::new())];
+            }
+            _ => None,
+        };
+
+        match text.and_then(Value::as_str) {
+            Some(text) => {
+                let document = Document::new(text, uri_path(&uri).as_deref());
+                let diagnostics = document
+                    .diagnostics()
+                    .iter()
+                    .map(|diagnostic| diagnostic_json(&document, diagnostic))
+                    .collect();
+                self.documents.insert(uri.clone(), document);
+                vec![publish_diagnostics(&uri, diagnostics)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn definition(&self, params: &Value) -> ResponseResult {
+        let (uri, document, offset) = self.position(params)?;
+        Ok(document
+            .definition(offset)
+            .map(|symbol| location(uri, document, symbol.span))
+            .unwrap_or(Value::Null))
+    }
+
+    fn references(&self, params: &Value) -> ResponseResult {
+        let (uri, document, offset) = self.position(params)?;
+        let include_declaration = params
+            .pointer("/context/includeDeclaration")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        Ok(Value::Array(
+            document
+                .references(offset, include_declaration)
+                .into_iter()
+                .map(|span| location(uri, document, span))
+                .collect(),
+        ))
+    }
+
+    fn hover(&self, params: &Value) -> ResponseResult {
+        let (_, document, offset) = self.position(params)?;
+        Ok(document
+            .definition(offset)
+            .map(|symbol| {
+                json!({
+                    "contents": {
+                        "kind": "markdown",
+                        "value": format!("```lox\n{}\n```", symbol.detail),
+                    },
+                })
+            })
+            .unwrap_or(Value::Null))
+    }
+
+    fn document_symbols(&self, params: &Value) -> ResponseResult {
+        let (_, document) = self.document(params)?;
+        Ok(Value::Array(
+            document
+                .symbols()
+                .iter()
+                .map(|symbol| symbol_json(document, symbol))
+                .collect(),
+        ))
+    }
+
+    fn completion(&self, params: &Value) -> ResponseResult {
+        let (_, document, offset) = self.position(params)?;
+        Ok(Value::Array(
+            document
+                .completions(offset)
+                .into_iter()
+                .map(|(label, kind)| {
+                    let kind = match kind {
+                        None => 14,
+                        Some(SymbolKind::Function) | Some(SymbolKind::Method) => 3,
+                        Some(SymbolKind::Class) => 7,
+                        Some(SymbolKind::Module) => 9,
+                        Some(SymbolKind::Variable) | Some(SymbolKind::Parameter) => 6,
+                    };
+                    json!({"label": label, "kind": kind})
+                })
+                .collect(),
+        ))
+    }
+
+    /// document returns the open document named by a request's params.
+    fn document<'a>(&'a self, params: &'a Value) -> Result<(&'a str, &'a Document), ResponseErr> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ResponseErr::InvalidParams("missing textDocument.uri".to_string()))?;
+
+        self.documents
+            .get(uri)
+            .map(|document| (uri, document))
+            .ok_or_else(|| ResponseErr::InvalidParams(format!("document '{}' is not open", uri)))
+    }
+
+    /// position returns the open document named by a request's params along
+    /// with the offset of the position within it.
+    fn position<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Result<(&'a str, &'a Document, usize), ResponseErr> {
+        let (uri, document) = self.document(params)?;
+        let field = |name: &str| {
+            params
+                .pointer(&format!("/position/{}", name))
+                .and_then(Value::as_u64)
+                .map(|n| n as usize)
+                .ok_or_else(|| ResponseErr::InvalidParams(format!("missing position.{}", name)))
+        };
+
+        let offset = document.offset(field("line")?, field("character")?);
+        Ok((uri, document, offset))
+    }
+}
+
+/// run serves a client over the passed reader and writer until the client
+/// asks the server to exit or closes its end of the connection.
+pub fn run<R: BufRead, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
+    let mut server = Server::new();
+    while let Some(body) = rpc::read_message(reader)? {
+        let replies = match serde_json::from_str::<Value>(&body) {
+            Ok(message) => server.handle(&message),
+            Err(e) => vec![error_response(None, ResponseErr::Parse(e.to_string()))],
+        };
+
+        for reply in replies {
+            rpc::write_message(writer, &reply)?;
+        }
+        if server.is_exited() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// uri_path returns the path of a `file://` URI, decoding any percent-encoded
+/// bytes. Documents with any other scheme have no path.
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = match encoded[i] {
+            b'%' => encoded
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+fn error_response(id: Option<&Value>, err: ResponseErr) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id.cloned().unwrap_or(Value::Null),
+        "error": {"code": err.code(), "message": err.to_string()},
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics},
+    })
+}
+
+fn diagnostic_json(document: &Document, diagnostic: &Diagnostic) -> Value {
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+    // a span on line 0 has no known location, so is reported at the start
+    // of the document.
+    let span = match diagnostic.span.line {
+        0 => Span::default(),
+        _ => diagnostic.span,
+    };
+
+    json!({
+        "range": range(document, span),
+        "severity": severity,
+        "code": diagnostic.code,
+        "source": "rlox",
+        "message": diagnostic.message,
+    })
+}
+
+fn symbol_json(document: &Document, symbol: &Symbol) -> Value {
+    let kind = match symbol.kind {
+        SymbolKind::Module => 2,
+        SymbolKind::Class => 5,
+        SymbolKind::Method => 6,
+        SymbolKind::Function => 12,
+        SymbolKind::Variable | SymbolKind::Parameter => 13,
+    };
+
+    json!({
+        "name": symbol.name,
+        "detail": symbol.detail,
+        "kind": kind,
+        "range": range(document, symbol.range),
+        "selectionRange": range(document, symbol.span),
+        "children": symbol
+            .children
+            .iter()
+            .map(|child| symbol_json(document, child))
+            .collect::<Vec<Value>>(),
+    })
+}
+
+fn location(uri: &str, document: &Document, span: Span) -> Value {
+    json!({"uri": uri, "range": range(document, span)})
+}
+
+fn range(document: &Document, span: Span) -> Value {
+    let (start_line, start_character) = document.position(span.start);
+    let (end_line, end_character) = document.position(span.end);
+    json!({
+        "start": {"line": start_line, "character": start_character},
+        "end": {"line": end_line, "character": end_character},
+    })
+}
@@ -0,0 +1,238 @@
+use crate::lsp::{rpc, uri_path, Document, Server, SymbolKind};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+const SOURCE: &str = "class Base {}
+var count = 0;
+fun add(a, b) {
+  return a + b;
+}
+class Point < Base {
+  init(x) { this.x = x; }
+}
+count = add(count, 1);
+";
+
+#[test]
+fn definitions_should_resolve_references_to_their_declaration() {
+    let document = Document::new(SOURCE, None);
+    assert!(document.diagnostics().is_empty());
+
+    // the `count` argument on the last line.
+    let offset = document.offset(8, 13);
+    let symbol = document.definition(offset).unwrap();
+    assert_eq!(
+        ("count", SymbolKind::Variable, "var count"),
+        (symbol.name.as_str(), symbol.kind, symbol.detail.as_str())
+    );
+    assert_eq!((1, 4), document.position(symbol.span.start));
+
+    // the `b` in the function body.
+    let symbol = document.definition(document.offset(3, 13)).unwrap();
+    assert_eq!(
+        (SymbolKind::Parameter, "param b"),
+        (symbol.kind, symbol.detail.as_str())
+    );
+    assert_eq!((2, 11), document.position(symbol.span.start));
+}
+
+#[test]
+fn references_should_include_assignments_and_optionally_the_declaration() {
+    let document = Document::new(SOURCE, None);
+    let offset = document.offset(1, 5);
+
+    let positions = |spans: Vec<crate::ast::span::Span>| -> Vec<(usize, usize)> {
+        spans
+            .iter()
+            .map(|span| document.position(span.start))
+            .collect()
+    };
+
+    assert_eq!(
+        vec![(1, 4), (8, 0), (8, 12)],
+        positions(document.references(offset, true))
+    );
+    assert_eq!(
+        vec![(8, 0), (8, 12)],
+        positions(document.references(offset, false))
+    );
+}
+
+#[test]
+fn symbols_should_outline_top_level_declarations_with_class_methods() {
+    let document = Document::new(SOURCE, None);
+    let symbols: Vec<(&str, SymbolKind, &str)> = document
+        .symbols()
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.detail.as_str()))
+        .collect();
+
+    assert_eq!(
+        vec![
+            ("Base", SymbolKind::Class, "class Base"),
+            ("count", SymbolKind::Variable, "var count"),
+            ("add", SymbolKind::Function, "fun add(a, b)"),
+            ("Point", SymbolKind::Class, "class Point < Base"),
+        ],
+        symbols
+    );
+    assert_eq!("method init(x)", document.symbols()[3].children[0].detail);
+}
+
+#[test]
+fn analysis_errors_should_not_hide_the_remaining_declarations() {
+    let document = Document::new("print missing;\nvar found = 1;\nvar x = ;\n", None);
+
+    let codes: Vec<&str> = document.diagnostics().iter().map(|d| d.code).collect();
+    assert_eq!(vec!["E0101", "E0200"], codes);
+    assert_eq!("found", document.symbols()[0].name);
+}
+
+#[test]
+fn completions_should_offer_declared_names_natives_and_keywords() {
+    let document = Document::new("fun greet(name) { print name; }\n", None);
+    let completions = document.completions(document.offset(1, 0));
+
+    assert_eq!(
+        ("greet".to_string(), Some(SymbolKind::Function)),
+        completions[0]
+    );
+    assert!(completions.contains(&("clock".to_string(), Some(SymbolKind::Function))));
+    assert!(completions.contains(&("while".to_string(), None)));
+}
+
+#[test]
+fn completions_should_only_offer_names_in_scope() {
+    let document = Document::new(
+        "fun outer(param) {
+  var local = 1;
+  {
+    var inner = 2;
+  }
+  for (item in [1]) { print item; }
+}
+var after = 0;
+",
+        None,
+    );
+    let names = |line: usize, character: usize| -> Vec<String> {
+        document
+            .completions(document.offset(line, character))
+            .into_iter()
+            .filter(|(_, kind)| kind.is_some())
+            .map(|(name, _)| name)
+            .filter(|name| {
+                ["outer", "param", "local", "inner", "item", "after"].contains(&name.as_str())
+            })
+            .collect()
+    };
+
+    assert_eq!(vec!["outer", "param", "local", "inner"], names(3, 18));
+    assert_eq!(vec!["outer", "param", "local"], names(4, 3));
+    assert_eq!(vec!["outer", "param", "local", "item"], names(5, 21));
+    assert_eq!(vec!["outer", "after"], names(8, 0));
+}
+
+#[test]
+fn positions_should_count_utf16_code_units() {
+    // the emoji is a single character made of two UTF-16 code units.
+    let document = Document::new("var s = \"\u{1F600}\"; print s;\n", None);
+    let offset = document.offset(0, 20);
+
+    assert_eq!(19, offset);
+    assert_eq!((0, 20), document.position(offset));
+    assert_eq!(
+        "var s",
+        document
+            .definition(offset)
+            .map(|s| s.detail.as_str())
+            .unwrap()
+    );
+    // a column within the emoji's surrogate pair is rounded down to it.
+    assert_eq!(9, document.offset(0, 10));
+}
+
+#[test]
+fn messages_should_be_framed_by_their_content_length() {
+    let mut output = Vec::new();
+    rpc::write_message(&mut output, &json!({"id": 1})).unwrap();
+    assert_eq!(
+        "Content-Length: 8\r\n\r\n{\"id\":1}",
+        String::from_utf8(output.clone()).unwrap()
+    );
+
+    let mut input = std::io::Cursor::new(output);
+    assert_eq!(
+        Some("{\"id\":1}".to_string()),
+        rpc::read_message(&mut input).unwrap()
+    );
+    assert_eq!(None, rpc::read_message(&mut input).unwrap());
+}
+
+#[test]
+fn opening_a_document_should_publish_its_diagnostics() {
+    let mut server = Server::new();
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": "file:///a.lox", "text": "print y;"}},
+    }));
+
+    assert_eq!(1, replies.len());
+    assert_eq!("textDocument/publishDiagnostics", replies[0]["method"]);
+    let diagnostic = &replies[0]["params"]["diagnostics"][0];
+    assert_eq!("E0200", diagnostic["code"]);
+    assert_eq!(
+        json!({"start": {"line": 0, "character": 6}, "end": {"line": 0, "character": 7}}),
+        diagnostic["range"]
+    );
+}
+
+#[test]
+fn opened_documents_should_import_modules_relative_to_their_uri() {
+    let mut server = Server::new();
+    let uri = format!(
+        "file://{}/tests/corpus/imports.lox",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {
+            "uri": uri,
+            "text": "import \"modules/greeting.lox\" as greeting;\nprint greeting.farewell;\n",
+        }},
+    }));
+
+    let diagnostics = &replies[0]["params"]["diagnostics"];
+    assert_eq!(1, diagnostics.as_array().unwrap().len());
+    assert_eq!("E0212", diagnostics[0]["code"]);
+}
+
+#[test]
+fn file_uris_should_be_converted_to_paths() {
+    assert_eq!(
+        Some(PathBuf::from("/home/lox/my scripts/a.lox")),
+        uri_path("file:///home/lox/my%20scripts/a.lox")
+    );
+    assert_eq!(None, uri_path("untitled:Untitled-1"));
+}
+
+#[test]
+fn requests_should_be_answered_with_errors_once_shut_down() {
+    let mut server = Server::new();
+    let request = |id: u64, method: &str| json!({"jsonrpc": "2.0", "id": id, "method": method});
+
+    assert_eq!(
+        Value::Null,
+        server.handle(&request(1, "shutdown"))[0]["result"]
+    );
+    assert_eq!(
+        -32600,
+        server.handle(&request(2, "initialize"))[0]["error"]["code"]
+    );
+    assert!(!server.is_exited());
+
+    server.handle(&json!({"jsonrpc": "2.0", "method": "exit"}));
+    assert!(server.is_exited());
+}
@@ -1,104 +1,337 @@
 use std::env;
-use std::fs::File;
 use std::io::prelude::*;
 use std::io::{stdin, stdout};
+use std::path::Path;
 use std::process;
 
-extern crate parcel;
-use parcel::prelude::v1::*;
+use rlox::analyzer::lint::{Linter, Rule};
 use rlox::analyzer::scope::ScopeAnalyzer;
-use rlox::ast::statement::Stmt;
-use rlox::ast::token;
+use rlox::compiler::Compiler;
+use rlox::diagnostic::Diagnostic;
+use rlox::formatter;
 use rlox::interpreter::StatefulInterpreter;
-use rlox::parser::statement_parser::statements;
 use rlox::pass::*;
-use rlox::scanner;
-use rlox::statics;
+use rlox::repl::{Backend, Repl, Response};
+use rlox::runtime;
+use rlox::vm::Vm;
 
-type RuntimeResult<T> = Result<T, String>;
+type RuntimeResult<T> = Result<T, Vec<Diagnostic>>;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let args_len = args.len();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    #[cfg(feature = "lsp")]
+    {
+        if args.len() == 1 && args[0] == "lsp" {
+            return run_server();
+        }
+    }
+    if args.first().map(String::as_str) == Some("fmt") {
+        process::exit(run_fmt(&args[1..]));
+    }
+    if args.first().map(String::as_str) == Some("lint") {
+        process::exit(run_lint(&args[1..]));
+    }
+    let use_vm = match args.iter().position(|arg| arg == "--vm") {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    };
+    let backend = if use_vm {
+        Backend::Bytecode(Compiler::new(), Vm::new())
+    } else {
+        Backend::TreeWalk(StatefulInterpreter::new())
+    };
 
-    match args_len {
-        al if al > 2 => {
-            println!("Usage: rlox [script]");
+    match args.len() {
+        al if al > 1 => {
+            println!("Usage: rlox [--vm] [script] | rlox fmt [--check] [files] | rlox lint [--allow rule] [files] | rlox lsp");
             process::exit(64);
         }
-        2 => run_file(&args[1]).expect("Unable to parse file"),
-        _ => run_prompt(),
+        1 => process::exit(run_file(backend, &args[0])),
+        _ => run_prompt(backend),
     }
 }
 
-fn run_file(filename: &str) -> Result<(), String> {
-    let mut analyzer = ScopeAnalyzer::new();
-    let mut interpreter = StatefulInterpreter::new();
-    let mut f = File::open(filename).expect("file not found");
-
-    let mut contents = String::new();
-    match f.read_to_string(&mut contents) {
-        Ok(_) => {
-            run(&mut analyzer, &mut interpreter, contents).unwrap();
-            Ok(())
+/// run_server serves the language server protocol over stdio.
+#[cfg(feature = "lsp")]
+fn run_server() {
+    if let Err(e) = rlox::lsp::run(&mut stdin().lock(), &mut stdout()) {
+        eprintln!("error: {}", e);
+        process::exit(74);
+    }
+}
+
+/// run_fmt formats each passed file in place, or source read from stdin to
+/// stdout when no files are passed, returning the exit status. With
+/// `--check` nothing is written, and the status is 1 if any source isn't
+/// already formatted.
+fn run_fmt(args: &[String]) -> i32 {
+    let mut config = formatter::Config::default();
+    let mut check = false;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--indent" | "--width" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if arg == "--indent" => config.indent = n,
+                Some(n) => config.width = n,
+                None => {
+                    eprintln!("error: {} expects a number", arg);
+                    return 64;
+                }
+            },
+            file => files.push(file.to_string()),
         }
-        Err(error) => Err(format!("error: {}", error)),
     }
+
+    let mut status = 0;
+    let inputs = match files.is_empty() {
+        true => vec![None],
+        false => files.iter().map(Some).collect(),
+    };
+    for input in inputs {
+        let (name, source) = match read_input(input) {
+            Ok(read) => read,
+            Err(status) => return status,
+        };
+
+        let formatted = match formatter::format(&source, &config) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                report(name, &source, &diagnostics);
+                status = 65;
+                continue;
+            }
+        };
+        if check {
+            if formatted != source {
+                eprintln!("{} is not formatted", name);
+                status = std::cmp::max(status, 1);
+            }
+        } else if input.is_none() {
+            print!("{}", formatted);
+        } else if formatted != source {
+            if let Err(e) = std::fs::write(name, formatted) {
+                eprintln!("error: unable to write {}: {}", name, e);
+                return 74;
+            }
+        }
+    }
+
+    status
+}
+
+/// run_lint lints each passed file, or source read from stdin when no files
+/// are passed, returning the exit status. The status is 1 if any warnings
+/// are reported for rules that haven't been allowed with `--allow`.
+fn run_lint(args: &[String]) -> i32 {
+    let mut linter = Linter::new();
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--allow" => match args.next().map(|rule| rule.parse::<Rule>()) {
+                Some(Ok(rule)) => linter = linter.allow(rule),
+                Some(Err(e)) => {
+                    eprintln!("error: {}", e);
+                    return 64;
+                }
+                None => {
+                    eprintln!("error: --allow expects a rule");
+                    return 64;
+                }
+            },
+            file => files.push(file.to_string()),
+        }
+    }
+
+    let mut status = 0;
+    let inputs = match files.is_empty() {
+        true => vec![None],
+        false => files.iter().map(Some).collect(),
+    };
+    for input in inputs {
+        let (name, source) = match read_input(input) {
+            Ok(read) => read,
+            Err(status) => return status,
+        };
+
+        let stmts = match runtime::parse(&source) {
+            Ok(stmts) => stmts,
+            Err(diagnostics) => {
+                report(name, &source, &diagnostics);
+                status = 65;
+                continue;
+            }
+        };
+        // linting can't fail, its error type is Infallible.
+        let Ok(warnings) = linter.tree_pass(stmts);
+        if !warnings.is_empty() {
+            let diagnostics: Vec<Diagnostic> = warnings.into_iter().map(Diagnostic::from).collect();
+            report(name, &source, &diagnostics);
+            status = std::cmp::max(status, 1);
+        }
+    }
+
+    status
+}
+
+/// read_input reads the source of a file, or of stdin when no file is
+/// passed, along with the name to report it under. Failing to read returns
+/// the exit status to exit with.
+fn read_input(file: Option<&String>) -> Result<(&str, String), i32> {
+    let (name, read) = match file {
+        Some(file) => (file.as_str(), std::fs::read_to_string(file)),
+        None => {
+            let mut source = String::new();
+            (
+                "<stdin>",
+                stdin().read_to_string(&mut source).map(|_| source),
+            )
+        }
+    };
+
+    read.map(|source| (name, source)).map_err(|e| {
+        eprintln!("error: unable to read {}: {}", name, e);
+        74
+    })
 }
 
-fn run_prompt() {
+/// run_file runs a script, returning the exit status. Failing to read the
+/// script exits with 74, and failing to run it with 65.
+fn run_file(backend: Backend, filename: &String) -> i32 {
+    let (name, source) = match read_input(Some(filename)) {
+        Ok(read) => read,
+        Err(status) => return status,
+    };
+
     let mut analyzer = ScopeAnalyzer::new();
-    let mut interpreter = StatefulInterpreter::new();
-    loop {
-        let mut input = String::new();
-        print!("> ");
-        stdout().flush().unwrap();
+    analyzer.set_script(Path::new(name));
+    let mut backend = backend;
+    let script = match &mut backend {
+        Backend::TreeWalk(interpreter) => interpreter.set_script(name),
+        Backend::Bytecode(_, vm) => vm.set_script(name),
+    };
+    if let Err(e) = script {
+        eprintln!("error: {}", e);
+        return 74;
+    }
 
-        stdin().read_line(&mut input).expect("execution error");
-        run(&mut analyzer, &mut interpreter, input).unwrap();
+    match run(&mut analyzer, &mut backend, source.clone()) {
+        Ok(()) => 0,
+        Err(diagnostics) => {
+            report(name, &source, &diagnostics);
+            65
+        }
     }
 }
 
-fn run(
-    analyzer: &mut ScopeAnalyzer,
-    interpreter: &mut StatefulInterpreter,
-    source: String,
-) -> RuntimeResult<usize> {
-    let token_iter = scanner::Scanner::new(source).scan_tokens().into_iter();
-    let token_count = token_iter.len();
-
-    let tokens: Vec<token::Token> = token_iter
-        .map(|tok| match tok {
-            Ok(tok) => tok,
-            Err(e) => panic!("{}", e),
-        })
-        .collect();
-
-    let stmts = match statements().parse(&tokens) {
-        Ok(parcel::MatchStatus::Match((_, stmt))) => Ok(stmt),
-        Ok(parcel::MatchStatus::NoMatch(_)) => {
-            let e = "No match found".to_string();
-            println!("{}", &e);
-            Err(e)
+/// run_prompt runs an interactive session until its input is closed.
+fn run_prompt(backend: Backend) {
+    let mut repl = Repl::new(backend);
+    let mut editor = LineEditor::new();
+    while let Some(line) = editor.read_line(repl.prompt()) {
+        match line {
+            Some(line) => match repl.feed(&line) {
+                Response::Incomplete => (),
+                Response::Output(output) => print!("{}", output),
+                Response::Error(error) => eprint!("{}", error),
+            },
+            None => repl.cancel(),
         }
-        Err(e) => {
-            println!("{:?}", e);
-            Err(e)
+    }
+    editor.save();
+}
+
+/// LineEditor reads lines of REPL input with line editing, keeping a
+/// history that persists between sessions in `~/.rlox_history`.
+#[cfg(feature = "repl")]
+struct LineEditor {
+    editor: rustyline::DefaultEditor,
+    history: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "repl")]
+impl LineEditor {
+    fn new() -> Self {
+        let mut editor = rustyline::DefaultEditor::new().expect("unable to open the terminal");
+        let history =
+            env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".rlox_history"));
+        if let Some(path) = &history {
+            // there's no history to load on the first session.
+            let _ = editor.load_history(path);
         }
-    }?;
 
-    let ast = load_statics(stmts);
-    let analyzed_stmts = analyzer.tree_pass(ast).unwrap();
-    interpreter
-        .tree_pass(analyzed_stmts)
-        .map_err(|e| e.to_string())?;
+        LineEditor { editor, history }
+    }
+
+    /// read_line returns the next line of input, or None once input is
+    /// closed. An interrupted line is read as Some(None).
+    fn read_line(&mut self, prompt: &str) -> Option<Option<String>> {
+        use rustyline::error::ReadlineError;
 
-    Ok(token_count)
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = self.editor.add_history_entry(line.as_str());
+                }
+                Some(Some(line))
+            }
+            Err(ReadlineError::Interrupted) => Some(None),
+            Err(ReadlineError::Eof) => None,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                None
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        if let Some(path) = &self.history {
+            if let Err(e) = self.editor.save_history(path) {
+                eprintln!("error: unable to save history: {}", e);
+            }
+        }
+    }
+}
+
+/// LineEditor reads lines of REPL input from stdin, without line editing or
+/// history.
+#[cfg(not(feature = "repl"))]
+struct LineEditor;
+
+#[cfg(not(feature = "repl"))]
+impl LineEditor {
+    fn new() -> Self {
+        LineEditor
+    }
+
+    fn read_line(&mut self, prompt: &str) -> Option<Option<String>> {
+        print!("{}", prompt);
+        stdout().flush().ok()?;
+
+        let mut line = String::new();
+        match stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(Some(line.trim_end_matches(&['\r', '\n'][..]).to_string())),
+        }
+    }
+
+    fn save(&mut self) {}
+}
+
+/// report renders each diagnostic against its source to stderr.
+fn report(filename: &str, source: &str, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprint!("{}", diagnostic.render(filename, source));
+    }
 }
 
-fn load_statics(ast: Vec<Stmt>) -> Vec<Stmt> {
-    let mut ast = ast;
-    let mut ast_with_statics = statics::define_statics_ast();
-    ast_with_statics.append(&mut ast);
-    ast_with_statics
+fn run(analyzer: &mut ScopeAnalyzer, backend: &mut Backend, source: String) -> RuntimeResult<()> {
+    let stmts = runtime::parse(&source)?;
+    let analyzed_stmts = analyzer.tree_pass(stmts).map_err(|e| vec![e.into()])?;
+    backend.execute(analyzed_stmts)
 }
@@ -0,0 +1,222 @@
+use crate::functions::{Arity, CallError, CallResult, Callable, NativeMethod};
+use crate::interpreter::StatefulInterpreter;
+use crate::list::List;
+use crate::object::{Literal, Object};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// Key represents a lox value that can be used to key a map. Only nil,
+/// booleans, numbers and strings are hashable, with numbers keyed by their
+/// bits so that keys compare exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    Nil,
+    Bool(bool),
+    Number(u64),
+    Str(String),
+}
+
+impl TryFrom<&Object> for Key {
+    type Error = CallError;
+
+    fn try_from(obj: &Object) -> Result<Self, Self::Error> {
+        match obj {
+            Object::Literal(Literal::Nil) => Ok(Key::Nil),
+            Object::Literal(Literal::Bool(b)) => Ok(Key::Bool(*b)),
+            // -0 and 0 are equal, and so must key the same entry.
+            Object::Literal(Literal::Number(n)) if *n == 0.0 => Ok(Key::Number(0f64.to_bits())),
+            Object::Literal(Literal::Number(n)) => Ok(Key::Number(n.to_bits())),
+            Object::Literal(Literal::Str(s)) => Ok(Key::Str(s.clone())),
+            obj => Err(CallError::Unhashable(obj.clone())),
+        }
+    }
+}
+
+impl From<&Key> for Object {
+    fn from(key: &Key) -> Self {
+        match key {
+            Key::Nil => obj_nil!(),
+            Key::Bool(b) => obj_bool!(*b),
+            Key::Number(bits) => obj_number!(f64::from_bits(*bits)),
+            Key::Str(s) => obj_str!(s.clone()),
+        }
+    }
+}
+
+/// Entries holds a map's entries in insertion order, along with the position
+/// of each key's entry.
+#[derive(Debug, Default)]
+struct Entries {
+    entries: Vec<(Key, Object)>,
+    positions: HashMap<Key, usize>,
+}
+
+/// Map represents a lox map, iterated in insertion order. The entries are
+/// reference-counted, so clones of a map are aliases that observe each
+/// other's mutations.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Rc<RefCell<Entries>>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.borrow().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().entries.is_empty()
+    }
+
+    /// get returns the value keyed by the passed object, failing if the
+    /// object is unhashable.
+    pub fn get(&self, key: &Object) -> Result<Option<Object>, CallError> {
+        let key = Key::try_from(key)?;
+        let entries = self.entries.borrow();
+        Ok(entries
+            .positions
+            .get(&key)
+            .map(|&pos| entries.entries[pos].1.clone()))
+    }
+
+    /// set inserts or overwrites the value keyed by the passed object,
+    /// failing if the object is unhashable.
+    pub fn set(&self, key: &Object, value: Object) -> Result<Object, CallError> {
+        let key = Key::try_from(key)?;
+        let mut entries = self.entries.borrow_mut();
+        match entries.positions.get(&key) {
+            Some(&pos) => entries.entries[pos].1 = value.clone(),
+            None => {
+                let pos = entries.entries.len();
+                entries.positions.insert(key.clone(), pos);
+                entries.entries.push((key, value.clone()));
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// delete removes the entry keyed by the passed object, returning its
+    /// value if one existed.
+    pub fn delete(&self, key: &Object) -> Result<Option<Object>, CallError> {
+        let key = Key::try_from(key)?;
+        let mut guard = self.entries.borrow_mut();
+        let Entries { entries, positions } = &mut *guard;
+        let pos = match positions.remove(&key) {
+            Some(pos) => pos,
+            None => return Ok(None),
+        };
+
+        // each entry following the removed entry shifts down a position.
+        let (_, value) = entries.remove(pos);
+        for (i, (key, _)) in entries.iter().enumerate().skip(pos) {
+            positions.insert(key.clone(), i);
+        }
+        Ok(Some(value))
+    }
+
+    /// keys returns a copy of the map's keys, in insertion order.
+    pub fn keys(&self) -> Vec<Object> {
+        self.entries
+            .borrow()
+            .entries
+            .iter()
+            .map(|(key, _)| Object::from(key))
+            .collect()
+    }
+
+    /// values returns a copy of the map's values, in insertion order.
+    pub fn values(&self) -> Vec<Object> {
+        self.entries
+            .borrow()
+            .entries
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    /// is returns true if both maps are the same object, rather than merely
+    /// maps with equal entries.
+    pub fn is(&self, other: &Map) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    /// method returns the native method of the passed name bound to the map,
+    /// if one exists.
+    pub fn method(&self, name: &str) -> Option<Callable> {
+        let (arity, func): (usize, MapMethod) = match name {
+            "len" => (0, len),
+            "keys" => (0, keys),
+            "values" => (0, values),
+            "has" => (1, has),
+            "delete" => (1, delete),
+            _ => return None,
+        };
+
+        let receiver = self.clone();
+        Some(Callable::Method(NativeMethod::new(
+            name,
+            Arity::Fixed(arity),
+            Object::Map(self.clone()),
+            move |interpreter, args| func(interpreter, &receiver, args),
+        )))
+    }
+}
+
+/// Maps compare equal when their entries are equal, in order.
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.is(other) || self.entries.borrow().entries == other.entries.borrow().entries
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.entries
+                .borrow()
+                .entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", Object::from(key), value))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/// MapMethod is the signature of each native map method, taking the map the
+/// method is bound to.
+type MapMethod = fn(&StatefulInterpreter, &Map, Vec<Object>) -> CallResult;
+
+fn len(_: &StatefulInterpreter, map: &Map, _: Vec<Object>) -> CallResult {
+    Ok(obj_number!(map.len() as f64))
+}
+
+fn keys(_: &StatefulInterpreter, map: &Map, _: Vec<Object>) -> CallResult {
+    Ok(Object::List(List::new(map.keys())))
+}
+
+fn values(_: &StatefulInterpreter, map: &Map, _: Vec<Object>) -> CallResult {
+    Ok(Object::List(List::new(map.values())))
+}
+
+fn has(_: &StatefulInterpreter, map: &Map, args: Vec<Object>) -> CallResult {
+    map.get(&args[0]).map(|value| obj_bool!(value.is_some()))
+}
+
+fn delete(_: &StatefulInterpreter, map: &Map, args: Vec<Object>) -> CallResult {
+    map.delete(&args[0])
+        .map(|value| value.unwrap_or(obj_nil!()))
+}
@@ -0,0 +1,105 @@
+use crate::functions::CallError;
+use crate::interpreter::StatefulInterpreter;
+use crate::list::List;
+use crate::map::Map;
+use crate::object::Object;
+
+fn call(map: &Map, name: &str, args: Vec<Object>) -> Result<Object, CallError> {
+    map.method(name)
+        .unwrap()
+        .call(&StatefulInterpreter::new(), args)
+}
+
+#[test]
+fn map_should_key_entries_by_value() {
+    let map = Map::new();
+
+    assert_eq!(
+        Ok(obj_bool!(true)),
+        map.set(&obj_number!(0.0), obj_bool!(true))
+    );
+    assert_eq!(Ok(Some(obj_bool!(true))), map.get(&obj_number!(-0.0)));
+    assert_eq!(Ok(None), map.get(&obj_str!("0".to_string())));
+
+    map.set(&obj_number!(0.0), obj_nil!()).unwrap();
+    assert_eq!(Ok(Some(obj_nil!())), map.get(&obj_number!(0.0)));
+    assert_eq!(1, map.len());
+}
+
+#[test]
+fn map_should_err_on_unhashable_keys() {
+    let map = Map::new();
+    let key = Object::List(List::new(vec![]));
+
+    assert_eq!(
+        Err(CallError::Unhashable(key.clone())),
+        map.set(&key, obj_nil!())
+    );
+    assert_eq!(Err(CallError::Unhashable(key.clone())), map.get(&key));
+    assert_eq!(
+        "unhashable map key: []",
+        CallError::Unhashable(key).to_string()
+    );
+}
+
+#[test]
+fn map_should_preserve_insertion_order_across_deletes() {
+    let map = Map::new();
+    for key in &["a", "b", "c"] {
+        map.set(&obj_str!(key.to_string()), obj_str!(key.to_uppercase()))
+            .unwrap();
+    }
+
+    assert_eq!(
+        Ok(Some(obj_str!("A".to_string()))),
+        map.delete(&obj_str!("a".to_string()))
+    );
+    assert_eq!(Ok(None), map.delete(&obj_str!("a".to_string())));
+    map.set(&obj_str!("a".to_string()), obj_nil!()).unwrap();
+
+    assert_eq!("{b: B, c: C, a: nil}", map.to_string());
+    assert_eq!(
+        Ok(Some(obj_str!("C".to_string()))),
+        map.get(&obj_str!("c".to_string()))
+    );
+}
+
+#[test]
+fn map_clones_should_share_entries() {
+    let map = Map::new();
+    let alias = map.clone();
+
+    alias.set(&obj_bool!(true), obj_nil!()).unwrap();
+
+    assert_eq!(Ok(Some(obj_nil!())), map.get(&obj_bool!(true)));
+    assert!(map.is(&alias));
+    assert!(!map.is(&Map::new()));
+}
+
+#[test]
+fn native_methods_should_inspect_and_update_the_map() {
+    let map = Map::new();
+    map.set(&obj_nil!(), obj_number!(1.0)).unwrap();
+    map.set(&obj_str!("k".to_string()), obj_number!(2.0))
+        .unwrap();
+
+    assert_eq!(
+        Ok(Object::List(List::new(vec![
+            obj_nil!(),
+            obj_str!("k".to_string())
+        ]))),
+        call(&map, "keys", vec![])
+    );
+    assert_eq!(
+        Ok(Object::List(List::new(vec![
+            obj_number!(1.0),
+            obj_number!(2.0)
+        ]))),
+        call(&map, "values", vec![])
+    );
+    assert_eq!(Ok(obj_bool!(true)), call(&map, "has", vec![obj_nil!()]));
+    assert_eq!(Ok(obj_number!(1.0)), call(&map, "delete", vec![obj_nil!()]));
+    assert_eq!(Ok(obj_nil!()), call(&map, "delete", vec![obj_nil!()]));
+    assert_eq!(Ok(obj_bool!(false)), call(&map, "has", vec![obj_nil!()]));
+    assert_eq!(Ok(obj_number!(1.0)), call(&map, "len", vec![]));
+}
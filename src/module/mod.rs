@@ -0,0 +1,151 @@
+use crate::ast::statement::Stmt;
+use crate::diagnostic::Diagnostic;
+use crate::runtime;
+use crate::stdlib::Namespace;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// ImportErr represents a module that could not be imported, either because
+/// its file could not be read, it was imported while still being loaded, or
+/// it failed to run.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ImportErr {
+    Read(String, String),
+    Cycle(Vec<String>),
+    Module(String, Vec<Diagnostic>),
+}
+
+impl ImportErr {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Read(_, _) => "E0305",
+            Self::Cycle(_) => "E0306",
+            Self::Module(_, _) => "E0307",
+        }
+    }
+}
+
+impl fmt::Display for ImportErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(path, e) => write!(f, "unable to read module '{}': {}", path, e),
+            Self::Cycle(chain) => write!(f, "cyclic import: {}", chain.join(" -> ")),
+            Self::Module(path, diagnostics) => match diagnostics.first() {
+                Some(d) if d.span.line != 0 => write!(
+                    f,
+                    "error in module '{}' at line {}: {}",
+                    path, d.span.line, d.message
+                ),
+                Some(d) => write!(f, "error in module '{}': {}", path, d.message),
+                None => write!(f, "error in module '{}'", path),
+            },
+        }
+    }
+}
+
+/// Modules tracks the files loaded by import statements. Each module is run
+/// once, with later imports of the same file sharing its namespace, while the
+/// stack of files being loaded resolves relative imports and detects cycles.
+/// Namespaces are those of the tree-walking interpreter unless another type
+/// is given, as the bytecode vm does.
+#[derive(Debug)]
+pub struct Modules<N = Namespace> {
+    loaded: HashMap<PathBuf, N>,
+    loading: Vec<PathBuf>,
+}
+
+impl Modules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N> Default for Modules<N> {
+    fn default() -> Self {
+        Modules {
+            loaded: HashMap::new(),
+            loading: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone> Modules<N> {
+    /// resolve returns the canonical path of a module imported from the file
+    /// currently being loaded, or from the working directory outside of any
+    /// file.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, ImportErr> {
+        let base = self
+            .loading
+            .last()
+            .and_then(|file| file.parent())
+            .unwrap_or_else(|| Path::new("."));
+
+        fs::canonicalize(base.join(path))
+            .map_err(|e| ImportErr::Read(path.to_string(), e.to_string()))
+    }
+
+    /// get returns the namespace of a module that has already been loaded.
+    pub fn get(&self, path: &Path) -> Option<N> {
+        self.loaded.get(path).cloned()
+    }
+
+    /// enter marks a file as being loaded, failing if it is already being
+    /// loaded further up the import chain.
+    pub fn enter(&mut self, path: PathBuf) -> Result<(), ImportErr> {
+        match self.loading.iter().position(|file| *file == path) {
+            Some(start) => Err(ImportErr::Cycle(
+                self.loading[start..]
+                    .iter()
+                    .chain(std::iter::once(&path))
+                    .map(|file| display_name(file))
+                    .collect(),
+            )),
+            None => {
+                self.loading.push(path);
+                Ok(())
+            }
+        }
+    }
+
+    /// exit marks the innermost file being loaded as finished, caching its
+    /// namespace if it loaded successfully.
+    pub fn exit(&mut self, namespace: Option<N>) {
+        if let (Some(path), Some(namespace)) = (self.loading.pop(), namespace) {
+            self.loaded.insert(path, namespace);
+        }
+    }
+}
+
+/// exports returns the name of each top-level declaration of the module at
+/// the passed path, as exposed by its namespace once imported. None is
+/// returned if the module can't be read or parsed, leaving the error to be
+/// reported when it is run.
+pub fn exports(path: &Path) -> Option<HashSet<String>> {
+    let source = fs::read_to_string(path).ok()?;
+    let stmts = runtime::parse(&source).ok()?;
+
+    Some(
+        stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Declaration(id, _, _)
+                | Stmt::Function(id, _, _, _, _)
+                | Stmt::Class(id, _, _, _, _)
+                | Stmt::Import(_, id, _) => Some(id.to_string()),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// display_name returns the file name of a path, for reporting.
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
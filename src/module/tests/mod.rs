@@ -0,0 +1,60 @@
+use crate::ast::span::Span;
+use crate::diagnostic::Diagnostic;
+use crate::module::{ImportErr, Modules};
+use crate::stdlib::Namespace;
+use std::path::PathBuf;
+
+#[test]
+fn entering_a_file_already_being_loaded_should_err_with_the_cycle() {
+    let mut modules = Modules::new();
+    modules.enter(PathBuf::from("/lox/main.lox")).unwrap();
+    modules.enter(PathBuf::from("/lox/a.lox")).unwrap();
+    modules.enter(PathBuf::from("/lox/b.lox")).unwrap();
+
+    let err = modules.enter(PathBuf::from("/lox/a.lox")).unwrap_err();
+    assert_eq!(
+        ImportErr::Cycle(vec![
+            "a.lox".to_string(),
+            "b.lox".to_string(),
+            "a.lox".to_string()
+        ]),
+        err
+    );
+    assert_eq!("cyclic import: a.lox -> b.lox -> a.lox", err.to_string());
+}
+
+#[test]
+fn exiting_a_file_should_cache_its_namespace_once_loaded() {
+    let mut modules = Modules::new();
+    let path = PathBuf::from("/lox/lib.lox");
+    let namespace = Namespace::new("lib");
+
+    modules.enter(path.clone()).unwrap();
+    modules.exit(Some(namespace.clone()));
+    assert!(modules.get(&path).unwrap().is(&namespace));
+
+    // a file that failed to load can be imported again.
+    let failed = PathBuf::from("/lox/failed.lox");
+    modules.enter(failed.clone()).unwrap();
+    modules.exit(None);
+    assert_eq!(None, modules.get(&failed));
+    assert_eq!(Ok(()), modules.enter(failed));
+}
+
+#[test]
+fn module_errors_should_report_the_first_diagnostic() {
+    let err = ImportErr::Module(
+        "lib.lox".to_string(),
+        vec![Diagnostic::error(
+            "E0200",
+            "undefined variable 'x'",
+            Span::new(4, 5, 2, 1),
+        )],
+    );
+
+    assert_eq!("E0307", err.code());
+    assert_eq!(
+        "error in module 'lib.lox' at line 2: undefined variable 'x'",
+        err.to_string()
+    );
+}
@@ -1,5 +1,9 @@
 use crate::functions::Callable;
 use crate::instance::Instance;
+use crate::iterator::Iter;
+use crate::list::List;
+use crate::map::Map;
+use crate::stdlib::Namespace;
 use std::fmt;
 
 #[cfg(test)]
@@ -10,6 +14,29 @@ pub enum Object {
     Literal(Literal),
     Call(Box<Callable>),
     Instance(Instance),
+    List(List),
+    Map(Map),
+    Iterator(Iter),
+    Namespace(Namespace),
+}
+
+impl Object {
+    /// is_equal compares objects following lox's equality rules. Literals
+    /// compare by value, with numbers compared exactly, while callables,
+    /// instances, lists, maps, iterators and namespaces compare by identity.
+    /// Objects of differing types are never equal.
+    pub fn is_equal(&self, other: &Object) -> bool {
+        match (self, other) {
+            (Self::Literal(l), Self::Literal(r)) => l == r,
+            (Self::Call(l), Self::Call(r)) => l.is(r),
+            (Self::Instance(l), Self::Instance(r)) => l.is(r),
+            (Self::List(l), Self::List(r)) => l.is(r),
+            (Self::Map(l), Self::Map(r)) => l.is(r),
+            (Self::Iterator(l), Self::Iterator(r)) => l.is(r),
+            (Self::Namespace(l), Self::Namespace(r)) => l.is(r),
+            _ => false,
+        }
+    }
 }
 
 impl Into<bool> for Object {
@@ -18,6 +45,10 @@ impl Into<bool> for Object {
             Self::Literal(l) => l.into(),
             Self::Call(_) => true,
             Self::Instance(_) => true,
+            Self::List(_) => true,
+            Self::Map(_) => true,
+            Self::Iterator(_) => true,
+            Self::Namespace(_) => true,
         }
     }
 }
@@ -26,8 +57,12 @@ impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Literal(l) => write!(f, "{}", &l),
-            Self::Call(c) => write!(f, "{:?}", &c),
+            Self::Call(c) => write!(f, "{}", &c),
             Self::Instance(ref i) => write!(f, "{}", i),
+            Self::List(l) => write!(f, "{}", l),
+            Self::Map(m) => write!(f, "{}", m),
+            Self::Iterator(i) => write!(f, "{}", i),
+            Self::Namespace(n) => write!(f, "{}", n),
         }
     }
 }
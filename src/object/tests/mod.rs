@@ -1,27 +1,30 @@
 use crate::object::{Literal, Object};
 
+/// truthy converts an object into a bool, naming the type the conversion
+/// would otherwise need annotating with.
+fn truthy(obj: Object) -> bool {
+    obj.into()
+}
+
 #[test]
 fn bool_literal_object_converts_into_equivalent_primitive_bool() {
-    assert_eq!(true, Object::Literal(Literal::Bool(true)).into());
-    assert_eq!(false, Object::Literal(Literal::Bool(false)).into());
+    assert!(truthy(Object::Literal(Literal::Bool(true))));
+    assert!(!truthy(Object::Literal(Literal::Bool(false))));
 }
 
 #[test]
 fn number_literal_object_converts_into_equivalent_primitive_bool() {
-    assert_eq!(true, Object::Literal(Literal::Number(5.0)).into());
-    assert_eq!(false, Object::Literal(Literal::Number(0.0)).into());
+    assert!(truthy(Object::Literal(Literal::Number(5.0))));
+    assert!(!truthy(Object::Literal(Literal::Number(0.0))));
 }
 
 #[test]
 fn str_literal_object_converts_into_equivalent_primitive_bool() {
-    assert_eq!(
-        true,
-        Object::Literal(Literal::Str("hello".to_string())).into()
-    );
-    assert_eq!(false, Object::Literal(Literal::Str("".to_string())).into());
+    assert!(truthy(Object::Literal(Literal::Str("hello".to_string()))));
+    assert!(!truthy(Object::Literal(Literal::Str("".to_string()))));
 }
 
 #[test]
 fn nil_literal_object_converts_into_equivalent_primitive_bool() {
-    assert_eq!(false, Object::Literal(Literal::Nil).into());
+    assert!(!truthy(Object::Literal(Literal::Nil)));
 }
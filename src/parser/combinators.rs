@@ -1,5 +1,99 @@
 extern crate parcel;
+use super::ParseErr;
 use crate::ast::token::{Token, TokenType};
+use std::cell::RefCell;
+
+/// Failure records the furthest point into a token stream that a parser failed
+/// to match, along with every token type that was expected at that point.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Failure {
+    pub remaining: usize,
+    pub expected: Vec<TokenType>,
+    pub found: Token,
+}
+
+/// Recovery holds the state a parse shares between its combinators: the
+/// furthest point any parser failed to match, and the errors of statements
+/// the parser recovered from. Each parse owns its own, so that parses nested
+/// within another, such as those of imported modules, can't see its state.
+#[derive(Debug, Default)]
+pub struct Recovery {
+    failure: RefCell<Option<Failure>>,
+    errors: RefCell<Vec<ParseErr>>,
+}
+
+impl Recovery {
+    /// reset_failure clears the furthest recorded failure.
+    pub fn reset_failure(&self) {
+        self.failure.replace(None);
+    }
+
+    /// take_failure returns and clears the furthest recorded failure.
+    pub fn take_failure(&self) -> Option<Failure> {
+        self.failure.replace(None)
+    }
+
+    /// merge_failure restores a failure taken before a nested parse, keeping
+    /// whichever of it and the currently recorded failure reached further.
+    pub fn merge_failure(&self, other: Option<Failure>) {
+        let other = match other {
+            Some(other) => other,
+            None => return,
+        };
+
+        let mut failure = self.failure.borrow_mut();
+        match failure.as_mut() {
+            Some(f) if f.remaining < other.remaining => (),
+            Some(f) if f.remaining == other.remaining => {
+                for expected in other.expected {
+                    if !f.expected.contains(&expected) {
+                        f.expected.push(expected)
+                    }
+                }
+            }
+            _ => *failure = Some(other),
+        }
+    }
+
+    fn record_failure(&self, input: &[Token], expected: TokenType) {
+        let found = match input.first() {
+            Some(token) => token,
+            None => return,
+        };
+
+        let mut failure = self.failure.borrow_mut();
+        match failure.as_mut() {
+            Some(f) if f.remaining < input.len() => (),
+            Some(f) if f.remaining == input.len() => {
+                if !f.expected.contains(&expected) {
+                    f.expected.push(expected)
+                }
+            }
+            _ => {
+                *failure = Some(Failure {
+                    remaining: input.len(),
+                    expected: vec![expected],
+                    found: found.clone(),
+                })
+            }
+        }
+    }
+
+    /// record_error records the error of a statement the parser recovered
+    /// from. An error is only recorded once, as a block may be reparsed when
+    /// backtracking.
+    pub fn record_error(&self, err: ParseErr) {
+        let mut errors = self.errors.borrow_mut();
+        if !errors.iter().any(|e| e.found().span == err.found().span) {
+            errors.push(err);
+        }
+    }
+
+    /// take_errors returns the errors recorded over the parse.
+    pub fn take_errors(&self) -> Vec<ParseErr> {
+        self.errors.take()
+    }
+}
 
 pub fn unzip<A, B>(pair: Vec<(A, B)>) -> (Vec<A>, Vec<B>) {
     let mut left_vec: Vec<A> = vec![];
@@ -11,11 +105,17 @@ pub fn unzip<A, B>(pair: Vec<(A, B)>) -> (Vec<A>, Vec<B>) {
     (left_vec, right_vec)
 }
 
-pub fn token_type<'a>(expected: TokenType) -> impl parcel::Parser<'a, &'a [Token], Token> {
+pub fn token_type<'a>(
+    recovery: &'a Recovery,
+    expected: TokenType,
+) -> impl parcel::Parser<'a, &'a [Token], Token> {
     move |input: &'a [Token]| match input.get(0) {
         Some(next) if next.token_type == expected => {
             Ok(parcel::MatchStatus::Match((&input[1..], next.clone())))
         }
-        _ => Ok(parcel::MatchStatus::NoMatch(input)),
+        _ => {
+            recovery.record_failure(input, expected);
+            Ok(parcel::MatchStatus::NoMatch(input))
+        }
     }
 }
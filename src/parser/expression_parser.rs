@@ -1,8 +1,9 @@
 extern crate parcel;
 use crate::ast::expression::*;
 use crate::ast::identifier::Identifier;
+use crate::ast::span::Span;
 use crate::ast::token::{Token, TokenType};
-use crate::parser::combinators::{token_type, unzip};
+use crate::parser::combinators::{token_type, unzip, Recovery};
 use crate::parser::statement_parser::block;
 use parcel::*;
 use std::convert::TryFrom;
@@ -14,16 +15,18 @@ use std::convert::TryFrom;
 /// # Examples
 /// ```
 /// extern crate parcel;
+/// use rlox::ast::span::Span;
 /// use rlox::ast::token::{TokenType, Token};
 /// use rlox::ast::expression::*;
 /// use rlox::parser::expression_parser::*;
+/// use rlox::parser::Recovery;
 /// use rlox::object;
 /// use std::option::Option::Some;
 /// use std::convert::TryFrom;
 /// use parcel::*;
 ///
 ///
-/// let literal_token = Token::new(TokenType::Number, 0, Some("1.0".to_string()), Some(object::Object::Literal(object::Literal::Number(1.0))));
+/// let literal_token = Token::new(TokenType::Number, Span::new(0, 3, 1, 1), Some("1.0".to_string()), Some(object::Object::Literal(object::Literal::Number(1.0))));
 /// let seed_vec = vec![
 ///     literal_token.clone(),
 /// ];
@@ -31,52 +34,104 @@ use std::convert::TryFrom;
 /// assert_eq!(
 ///     Ok(MatchStatus::Match((
 ///         &seed_vec[1..],
-///         Expr::Primary(object::Object::Literal(object::Literal::Number(1.0))
+///         Expr::Primary(
+///             object::Object::Literal(object::Literal::Number(1.0)),
+///             Span::new(0, 3, 1, 1)
 ///         )
 ///     ))),
-///     expression().parse(&seed_vec)
+///     expression(&Recovery::default()).parse(&seed_vec)
 /// );
 /// ```
-pub fn expression<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
-    assignment()
+pub fn expression<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
+    assignment(recovery)
 }
 
-#[allow(clippy::redundant_closure)]
-fn assignment<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
-    join(
-        token_type(TokenType::Identifier),
-        right(join(token_type(TokenType::Equal), equality())),
-    )
-    .map(|(lhv, rhe)| Expr::Assignment(Identifier::try_from(lhv).unwrap(), Box::new(rhe)))
-    .or(|| logical_or())
+fn assignment<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
+    move |input: &'a [Token]| {
+        // the assignment value is constructed at parse time, as it recurses
+        // back into assignment.
+        let target = join(
+            logical_or(recovery),
+            optional(right(join(
+                token_type(recovery, TokenType::Equal),
+                assignment(recovery),
+            ))),
+        );
+
+        match target.parse(input) {
+            Ok(MatchStatus::Match((rest, (expr, None)))) => Ok(MatchStatus::Match((rest, expr))),
+            Ok(MatchStatus::Match((rest, (Expr::Variable(id, span), Some(rhe))))) => {
+                let span = span.merge(&rhe.span());
+                Ok(MatchStatus::Match((
+                    rest,
+                    Expr::Assignment(id, Box::new(rhe), span),
+                )))
+            }
+            Ok(MatchStatus::Match((rest, (Expr::Get(object, property, span), Some(rhe))))) => {
+                match *property {
+                    Expr::Variable(id, _) => {
+                        let span = span.merge(&rhe.span());
+                        Ok(MatchStatus::Match((
+                            rest,
+                            Expr::Set(object, id, Box::new(rhe), span),
+                        )))
+                    }
+                    _ => Ok(MatchStatus::NoMatch(input)),
+                }
+            }
+            Ok(MatchStatus::Match((rest, (Expr::Index(list, index, span), Some(rhe))))) => {
+                let span = span.merge(&rhe.span());
+                Ok(MatchStatus::Match((
+                    rest,
+                    Expr::SetIndex(list, index, Box::new(rhe), span),
+                )))
+            }
+            Ok(_) => Ok(MatchStatus::NoMatch(input)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// binary_span returns the span covering both operands of a binary
+/// expression.
+fn binary_span(lhs: &Expr, rhs: &Expr) -> Span {
+    lhs.span().merge(&rhs.span())
 }
 
 #[allow(clippy::redundant_closure)]
-fn logical_or<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn logical_or<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        logical_and(),
-        one_or_more(right(join(token_type(TokenType::Or), logical_and()))),
+        logical_and(recovery),
+        one_or_more(right(join(
+            token_type(recovery, TokenType::Or),
+            logical_and(recovery),
+        ))),
     )
     .map(|(lhe, rhe)| {
         rhe.into_iter().fold(lhe, |lhs, rhs| {
-            Expr::Logical(LogicalExpr::Or(Box::new(lhs), Box::new(rhs)))
+            let span = binary_span(&lhs, &rhs);
+            Expr::Logical(LogicalExpr::Or(Box::new(lhs), Box::new(rhs)), span)
         })
     })
-    .or(|| logical_and())
+    .or(move || logical_and(recovery))
 }
 
 #[allow(clippy::redundant_closure)]
-fn logical_and<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn logical_and<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        equality(),
-        one_or_more(right(join(token_type(TokenType::And), equality()))),
+        equality(recovery),
+        one_or_more(right(join(
+            token_type(recovery, TokenType::And),
+            equality(recovery),
+        ))),
     )
     .map(|(lhe, rhe)| {
         rhe.into_iter().fold(lhe, |lhs, rhs| {
-            Expr::Logical(LogicalExpr::And(Box::new(lhs), Box::new(rhs)))
+            let span = binary_span(&lhs, &rhs);
+            Expr::Logical(LogicalExpr::And(Box::new(lhs), Box::new(rhs)), span)
         })
     })
-    .or(|| equality())
+    .or(move || equality(recovery))
 }
 
 enum EqualityOp {
@@ -85,14 +140,16 @@ enum EqualityOp {
 }
 
 #[allow(clippy::redundant_closure)]
-fn equality<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn equality<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        comparison(),
+        comparison(recovery),
         parcel::zero_or_more(join(
-            token_type(TokenType::EqualEqual)
+            token_type(recovery, TokenType::EqualEqual)
                 .map(|_| EqualityOp::EqualEqual)
-                .or(|| token_type(TokenType::BangEqual).map(|_| EqualityOp::BangEqual)),
-            comparison(),
+                .or(move || {
+                    token_type(recovery, TokenType::BangEqual).map(|_| EqualityOp::BangEqual)
+                }),
+            comparison(recovery),
         ))
         .map(unzip),
     )
@@ -100,16 +157,16 @@ fn equality<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
         operators
             .into_iter()
             .zip(operands.into_iter())
-            .fold(first_expr, |lhs, (operator, rhs)| match operator {
-                EqualityOp::EqualEqual => {
-                    Expr::Equality(EqualityExpr::Equal(Box::new(lhs), Box::new(rhs)))
-                }
-                EqualityOp::BangEqual => {
-                    Expr::Equality(EqualityExpr::NotEqual(Box::new(lhs), Box::new(rhs)))
+            .fold(first_expr, |lhs, (operator, rhs)| {
+                let span = binary_span(&lhs, &rhs);
+                let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                match operator {
+                    EqualityOp::EqualEqual => Expr::Equality(EqualityExpr::Equal(lhs, rhs), span),
+                    EqualityOp::BangEqual => Expr::Equality(EqualityExpr::NotEqual(lhs, rhs), span),
                 }
             })
     })
-    .or(|| comparison())
+    .or(move || comparison(recovery))
 }
 
 enum ComparisonOp {
@@ -120,16 +177,21 @@ enum ComparisonOp {
 }
 
 #[allow(clippy::redundant_closure)]
-fn comparison<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn comparison<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        addition(),
+        addition(recovery),
         parcel::zero_or_more(join(
-            token_type(TokenType::Greater)
+            token_type(recovery, TokenType::Greater)
                 .map(|_| ComparisonOp::Greater)
-                .or(|| token_type(TokenType::GreaterEqual).map(|_| ComparisonOp::GreaterEqual))
-                .or(|| token_type(TokenType::Less).map(|_| ComparisonOp::Less))
-                .or(|| token_type(TokenType::LessEqual).map(|_| ComparisonOp::LessEqual)),
-            addition(),
+                .or(move || {
+                    token_type(recovery, TokenType::GreaterEqual)
+                        .map(|_| ComparisonOp::GreaterEqual)
+                })
+                .or(move || token_type(recovery, TokenType::Less).map(|_| ComparisonOp::Less))
+                .or(move || {
+                    token_type(recovery, TokenType::LessEqual).map(|_| ComparisonOp::LessEqual)
+                }),
+            addition(recovery),
         ))
         .map(unzip),
     )
@@ -137,22 +199,19 @@ fn comparison<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
         operators
             .into_iter()
             .zip(operands.into_iter())
-            .fold(first_expr, |lhs, (operator, rhs)| match operator {
-                ComparisonOp::Greater => {
-                    Expr::Comparison(ComparisonExpr::Greater(Box::new(lhs), Box::new(rhs)))
-                }
-                ComparisonOp::GreaterEqual => {
-                    Expr::Comparison(ComparisonExpr::GreaterEqual(Box::new(lhs), Box::new(rhs)))
-                }
-                ComparisonOp::Less => {
-                    Expr::Comparison(ComparisonExpr::Less(Box::new(lhs), Box::new(rhs)))
-                }
-                ComparisonOp::LessEqual => {
-                    Expr::Comparison(ComparisonExpr::LessEqual(Box::new(lhs), Box::new(rhs)))
-                }
+            .fold(first_expr, |lhs, (operator, rhs)| {
+                let span = binary_span(&lhs, &rhs);
+                let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                let comparison = match operator {
+                    ComparisonOp::Greater => ComparisonExpr::Greater(lhs, rhs),
+                    ComparisonOp::GreaterEqual => ComparisonExpr::GreaterEqual(lhs, rhs),
+                    ComparisonOp::Less => ComparisonExpr::Less(lhs, rhs),
+                    ComparisonOp::LessEqual => ComparisonExpr::LessEqual(lhs, rhs),
+                };
+                Expr::Comparison(comparison, span)
             })
     })
-    .or(|| addition())
+    .or(move || addition(recovery))
 }
 
 enum AdditionOp {
@@ -161,14 +220,14 @@ enum AdditionOp {
 }
 
 #[allow(clippy::redundant_closure)]
-fn addition<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn addition<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        multiplication(),
+        multiplication(recovery),
         parcel::zero_or_more(join(
-            token_type(TokenType::Plus)
+            token_type(recovery, TokenType::Plus)
                 .map(|_| AdditionOp::Plus)
-                .or(|| token_type(TokenType::Minus).map(|_| AdditionOp::Minus)),
-            multiplication(),
+                .or(move || token_type(recovery, TokenType::Minus).map(|_| AdditionOp::Minus)),
+            multiplication(recovery),
         ))
         .map(unzip),
     )
@@ -176,14 +235,16 @@ fn addition<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
         operators
             .into_iter()
             .zip(operands.into_iter())
-            .fold(first_expr, |lhs, (operator, rhs)| match operator {
-                AdditionOp::Plus => Expr::Addition(AdditionExpr::Add(Box::new(lhs), Box::new(rhs))),
-                AdditionOp::Minus => {
-                    Expr::Addition(AdditionExpr::Subtract(Box::new(lhs), Box::new(rhs)))
+            .fold(first_expr, |lhs, (operator, rhs)| {
+                let span = binary_span(&lhs, &rhs);
+                let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                match operator {
+                    AdditionOp::Plus => Expr::Addition(AdditionExpr::Add(lhs, rhs), span),
+                    AdditionOp::Minus => Expr::Addition(AdditionExpr::Subtract(lhs, rhs), span),
                 }
             })
     })
-    .or(|| multiplication())
+    .or(move || multiplication(recovery))
 }
 
 enum MultipliactionOp {
@@ -192,14 +253,16 @@ enum MultipliactionOp {
 }
 
 #[allow(clippy::redundant_closure)]
-fn multiplication<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn multiplication<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        unary(),
+        unary(recovery),
         parcel::zero_or_more(join(
-            token_type(TokenType::Star)
+            token_type(recovery, TokenType::Star)
                 .map(|_| MultipliactionOp::Star)
-                .or(|| token_type(TokenType::Slash).map(|_| MultipliactionOp::Slash)),
-            unary(),
+                .or(move || {
+                    token_type(recovery, TokenType::Slash).map(|_| MultipliactionOp::Slash)
+                }),
+            unary(recovery),
         ))
         .map(unzip),
     )
@@ -207,16 +270,20 @@ fn multiplication<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
         operators
             .into_iter()
             .zip(operands.into_iter())
-            .fold(first_expr, |lhs, (operator, rhs)| match operator {
-                MultipliactionOp::Star => {
-                    Expr::Multiplication(MultiplicationExpr::Multiply(Box::new(lhs), Box::new(rhs)))
-                }
-                MultipliactionOp::Slash => {
-                    Expr::Multiplication(MultiplicationExpr::Divide(Box::new(lhs), Box::new(rhs)))
+            .fold(first_expr, |lhs, (operator, rhs)| {
+                let span = binary_span(&lhs, &rhs);
+                let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                match operator {
+                    MultipliactionOp::Star => {
+                        Expr::Multiplication(MultiplicationExpr::Multiply(lhs, rhs), span)
+                    }
+                    MultipliactionOp::Slash => {
+                        Expr::Multiplication(MultiplicationExpr::Divide(lhs, rhs), span)
+                    }
                 }
             })
     })
-    .or(|| unary())
+    .or(move || unary(recovery))
 }
 
 enum UnaryOp {
@@ -225,85 +292,130 @@ enum UnaryOp {
 }
 
 #[allow(clippy::redundant_closure)]
-fn unary<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn unary<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        token_type(TokenType::Bang)
-            .map(|_| UnaryOp::Bang)
-            .or(|| token_type(TokenType::Minus).map(|_| UnaryOp::Minus)),
-        primary(),
+        token_type(recovery, TokenType::Bang)
+            .map(|token| (UnaryOp::Bang, token.span))
+            .or(move || {
+                token_type(recovery, TokenType::Minus).map(|token| (UnaryOp::Minus, token.span))
+            }),
+        primary(recovery),
     )
-    .map(|(op, lit)| {
-        Expr::Unary(match op {
+    .map(|((op, op_span), lit)| {
+        let span = op_span.merge(&lit.span());
+        let unary = match op {
             UnaryOp::Minus => UnaryExpr::Minus(Box::new(lit)),
             UnaryOp::Bang => UnaryExpr::Bang(Box::new(lit)),
-        })
+        };
+        Expr::Unary(unary, span)
     })
-    .or(|| call())
+    .or(move || call(recovery))
+}
+
+enum CallOp {
+    Arguments(Vec<Expr>, Span),
+    Property(Identifier, Span),
+    Index(Expr, Span),
 }
 
 #[allow(clippy::redundant_closure)]
-fn call<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn call<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     join(
-        primary(),
-        right(join(
-            token_type(TokenType::LeftParen),
-            left(join(
-                optional(join(
-                    expression(),
-                    zero_or_more(right(join(token_type(TokenType::Comma), expression()))),
-                )),
-                token_type(TokenType::RightParen),
-            )),
-        )),
+        lambda(recovery),
+        zero_or_more(
+            arguments(recovery)
+                .map(|(args, span)| CallOp::Arguments(args, span))
+                .or(move || {
+                    right(join(
+                        token_type(recovery, TokenType::Dot),
+                        token_type(recovery, TokenType::Identifier),
+                    ))
+                    .map(|token| {
+                        let span = token.span;
+                        CallOp::Property(Identifier::try_from(token).unwrap(), span)
+                    })
+                })
+                .or(move || {
+                    right(join(
+                        token_type(recovery, TokenType::LeftBracket),
+                        join(
+                            expression(recovery),
+                            token_type(recovery, TokenType::RightBracket),
+                        ),
+                    ))
+                    .map(|(index, rbracket)| CallOp::Index(index, rbracket.span))
+                }),
+        ),
     )
-    .map(|(callee, opt_args)| {
-        Expr::Call(
-            Box::new(callee),
-            match opt_args {
-                None => Vec::new(),
-                Some(a) => {
-                    let mut args = vec![a.0];
-                    args.extend(a.1);
-                    args
-                }
-            },
-        )
+    .map(|(callee, ops)| {
+        ops.into_iter().fold(callee, |expr, op| match op {
+            CallOp::Arguments(args, end) => {
+                let span = expr.span().merge(&end);
+                Expr::Call(Box::new(expr), args, span)
+            }
+            CallOp::Property(id, end) => {
+                let span = expr.span().merge(&end);
+                Expr::Get(Box::new(expr), Box::new(Expr::Variable(id, end)), span)
+            }
+            CallOp::Index(index, end) => {
+                let span = expr.span().merge(&end);
+                Expr::Index(Box::new(expr), Box::new(index), span)
+            }
+        })
     })
-    .or(|| get())
 }
 
-#[allow(clippy::redundant_closure)]
-fn get<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
-    join(
-        primary(),
-        right(join(token_type(TokenType::Dot), expression())),
-    )
-    .map(|(callee, param)| Expr::Get(Box::new(callee), Box::new(param)))
-    .or(|| lambda())
+/// arguments parses a parenthesized, comma-separated argument list,
+/// returning the arguments along with the span of the closing paren.
+fn arguments<'a>(
+    recovery: &'a Recovery,
+) -> impl parcel::Parser<'a, &'a [Token], (Vec<Expr>, Span)> {
+    right(join(
+        token_type(recovery, TokenType::LeftParen),
+        join(
+            optional(join(
+                expression(recovery),
+                zero_or_more(right(join(
+                    token_type(recovery, TokenType::Comma),
+                    expression(recovery),
+                ))),
+            )),
+            token_type(recovery, TokenType::RightParen),
+        ),
+    ))
+    .map(|(opt_args, rparen)| match opt_args {
+        None => (Vec::new(), rparen.span),
+        Some(a) => {
+            let mut args = vec![a.0];
+            args.extend(a.1);
+            (args, rparen.span)
+        }
+    })
 }
 
 #[allow(clippy::redundant_closure)]
-fn lambda<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
-    right(join(
-        token_type(TokenType::Fun),
+fn lambda<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
+    join(
+        token_type(recovery, TokenType::Fun),
         join(
             right(join(
-                token_type(TokenType::LeftParen),
+                token_type(recovery, TokenType::LeftParen),
                 left(join(
                     optional(join(
-                        token_type(TokenType::Identifier),
+                        token_type(recovery, TokenType::Identifier),
                         zero_or_more(right(join(
-                            token_type(TokenType::Comma),
-                            token_type(TokenType::Identifier),
+                            token_type(recovery, TokenType::Comma),
+                            token_type(recovery, TokenType::Identifier),
                         ))),
                     )),
-                    token_type(TokenType::RightParen),
+                    token_type(recovery, TokenType::RightParen),
                 )),
             )),
-            block(),
+            block(recovery),
         ),
-    ))
-    .map(|(opt_args, body)| {
+    )
+    .map(|(fun, (opt_args, body))| {
+        let span = fun.span.merge(&body.span());
         Expr::Lambda(
             opt_args.map_or(Vec::new(), |a| {
                 let mut args = vec![Identifier::try_from(a.0).unwrap()];
@@ -315,32 +427,122 @@ fn lambda<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
                 args
             }),
             Box::new(body),
+            span,
         )
     })
-    .or(|| primary())
+    .or(move || primary(recovery))
 }
 
 #[allow(clippy::redundant_closure)]
-fn primary<'a>() -> impl parcel::Parser<'a, &'a [Token], Expr> {
+fn primary<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
     parcel::one_of(vec![
-        token_type(TokenType::True),
-        token_type(TokenType::False),
-        token_type(TokenType::Nil),
-        token_type(TokenType::Number),
-        token_type(TokenType::Str),
+        token_type(recovery, TokenType::True),
+        token_type(recovery, TokenType::False),
+        token_type(recovery, TokenType::Nil),
+        token_type(recovery, TokenType::Number),
+        token_type(recovery, TokenType::Str),
     ])
-    .map(|token| Expr::Primary(token.object.unwrap()))
-    .or(|| identifier().map(|id| Expr::Variable(id)))
-    .or(|| {
+    .map(|token| Expr::Primary(token.object.unwrap(), token.span))
+    .or(move || token_type(recovery, TokenType::This).map(|token| Expr::This(token.span)))
+    .or(move || {
+        join(
+            token_type(recovery, TokenType::Super),
+            right(join(
+                token_type(recovery, TokenType::Dot),
+                token_type(recovery, TokenType::Identifier),
+            )),
+        )
+        .map(|(keyword, method)| {
+            let span = keyword.span.merge(&method.span);
+            Expr::Super(Identifier::try_from(method).unwrap(), span)
+        })
+    })
+    .or(move || {
+        token_type(recovery, TokenType::Identifier).map(|token| {
+            let span = token.span;
+            Expr::Variable(Identifier::try_from(token).unwrap(), span)
+        })
+    })
+    .or(move || {
+        join(
+            token_type(recovery, TokenType::LeftParen),
+            join(
+                expression(recovery),
+                token_type(recovery, TokenType::RightParen),
+            ),
+        )
+        .map(|(lparen, (expr, rparen))| {
+            Expr::Grouping(Box::new(expr), lparen.span.merge(&rparen.span))
+        })
+    })
+    .or(move || list(recovery))
+    .or(move || map(recovery))
+}
+
+/// map parses a braced, comma-separated map literal of colon-separated key
+/// and value pairs.
+fn map<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
+    join(
+        token_type(recovery, TokenType::LeftBrace),
+        join(
+            optional(join(
+                map_entry(recovery),
+                zero_or_more(right(join(
+                    token_type(recovery, TokenType::Comma),
+                    map_entry(recovery),
+                ))),
+            )),
+            token_type(recovery, TokenType::RightBrace),
+        ),
+    )
+    .map(|(lbrace, (opt_entries, rbrace))| {
+        let span = lbrace.span.merge(&rbrace.span);
+        let entries = opt_entries.map_or(Vec::new(), |(head, tail)| {
+            let mut entries = vec![head];
+            entries.extend(tail);
+            entries
+        });
+        Expr::Map(entries, span)
+    })
+}
+
+fn map_entry<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], (Expr, Expr)> {
+    join(
+        expression(recovery),
         right(join(
-            token_type(TokenType::LeftParen),
-            left(join(expression(), token_type(TokenType::RightParen))),
-        ))
-        .map(|expr| Expr::Grouping(Box::new(expr)))
+            token_type(recovery, TokenType::Colon),
+            expression(recovery),
+        )),
+    )
+}
+
+/// list parses a bracketed, comma-separated list literal.
+fn list<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Expr> {
+    join(
+        token_type(recovery, TokenType::LeftBracket),
+        join(
+            optional(join(
+                expression(recovery),
+                zero_or_more(right(join(
+                    token_type(recovery, TokenType::Comma),
+                    expression(recovery),
+                ))),
+            )),
+            token_type(recovery, TokenType::RightBracket),
+        ),
+    )
+    .map(|(lbracket, (opt_elems, rbracket))| {
+        let span = lbracket.span.merge(&rbracket.span);
+        let elems = opt_elems.map_or(Vec::new(), |(head, tail)| {
+            let mut elems = vec![head];
+            elems.extend(tail);
+            elems
+        });
+        Expr::List(elems, span)
     })
 }
 
 #[allow(clippy::redundant_closure)]
-pub fn identifier<'a>() -> impl parcel::Parser<'a, &'a [Token], Identifier> {
-    token_type(TokenType::Identifier).map(|token| Identifier::try_from(token).unwrap())
+pub fn identifier<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Identifier> {
+    token_type(recovery, TokenType::Identifier).map(|token| Identifier::try_from(token).unwrap())
 }
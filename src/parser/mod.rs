@@ -1,6 +1,232 @@
+extern crate parcel;
+use crate::ast::statement::Stmt;
+use crate::ast::token::{Token, TokenType};
+use crate::diagnostic::Diagnostic;
+use parcel::prelude::v1::*;
+use std::fmt;
+
 mod combinators;
+pub use combinators::Recovery;
 pub mod expression_parser;
 pub mod statement_parser;
 
 #[cfg(test)]
 mod tests;
+
+/// ParseErr represents a syntax error encountered while parsing a program.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseErr {
+    /// A token that can't begin a statement.
+    UnexpectedToken(Token),
+    /// A token that doesn't match any of the token types expected within a
+    /// statement.
+    Expected(Vec<TokenType>, Token),
+}
+
+impl ParseErr {
+    /// found returns the offending token.
+    pub fn found(&self) -> &Token {
+        match self {
+            Self::UnexpectedToken(token) | Self::Expected(_, token) => token,
+        }
+    }
+}
+
+impl fmt::Display for ParseErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedToken(token) => {
+                write!(f, "expected statement, found {}", describe(token))
+            }
+            Self::Expected(expected, token) if begins_expression(expected) => {
+                write!(f, "expected expression, found {}", describe(token))
+            }
+            Self::Expected(expected, token) => {
+                let expected = significant(expected);
+                if expected.len() == 1 {
+                    write!(f, "expected {}, ", describe_type(expected[0]))?;
+                } else {
+                    write!(
+                        f,
+                        "expected one of {}, ",
+                        expected
+                            .iter()
+                            .map(|tt| describe_type(*tt))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )?;
+                }
+
+                write!(f, "found {}", describe(token))
+            }
+        }
+    }
+}
+
+impl From<ParseErr> for Diagnostic {
+    fn from(err: ParseErr) -> Self {
+        let code = match err {
+            ParseErr::UnexpectedToken(_) => "E0100",
+            ParseErr::Expected(_, _) => "E0101",
+        };
+
+        Diagnostic::error(code, &err.to_string(), err.found().span)
+    }
+}
+
+/// begins_expression returns true if the expected token types are those that
+/// could begin any expression.
+fn begins_expression(expected: &[TokenType]) -> bool {
+    [
+        TokenType::Identifier,
+        TokenType::Number,
+        TokenType::LeftParen,
+    ]
+    .iter()
+    .all(|tt| expected.contains(tt))
+}
+
+/// significant filters the operators that could continue an expression out of
+/// the expected token types, leaving the tokens that would have terminated it.
+/// The full set is returned if nothing else was expected.
+fn significant(expected: &[TokenType]) -> Vec<TokenType> {
+    let terminators: Vec<TokenType> = expected
+        .iter()
+        .copied()
+        .filter(|tt| {
+            !matches!(
+                tt,
+                TokenType::LeftParen
+                    | TokenType::LeftBracket
+                    | TokenType::Dot
+                    | TokenType::Minus
+                    | TokenType::Plus
+                    | TokenType::Slash
+                    | TokenType::Star
+                    | TokenType::BangEqual
+                    | TokenType::Equal
+                    | TokenType::EqualEqual
+                    | TokenType::Greater
+                    | TokenType::GreaterEqual
+                    | TokenType::Less
+                    | TokenType::LessEqual
+                    | TokenType::And
+                    | TokenType::Or
+            )
+        })
+        .collect();
+
+    if terminators.is_empty() {
+        expected.to_vec()
+    } else {
+        terminators
+    }
+}
+
+/// describe returns a human readable description of a token for use in
+/// error messages.
+fn describe(token: &Token) -> String {
+    match (token.token_type, &token.lexeme) {
+        (TokenType::EOF, _) => token.token_type.to_string(),
+        (_, Some(lexeme)) => format!("`{}`", lexeme),
+        (token_type, None) => format!("`{}`", token_type),
+    }
+}
+
+/// describe_type returns a human readable description of a token type for use
+/// in error messages.
+fn describe_type(token_type: TokenType) -> String {
+    match token_type {
+        TokenType::Identifier => "identifier".to_string(),
+        TokenType::Number => "number".to_string(),
+        TokenType::Str => "string".to_string(),
+        TokenType::EOF => token_type.to_string(),
+        tt => format!("`{}`", tt),
+    }
+}
+
+/// parse parses a program from a token stream one statement at a time. When
+/// a statement fails to parse, the error is recorded and the parser
+/// synchronizes on the next statement boundary, so that every syntax error in
+/// the program is reported. Blocks recover in the same way, so an error within
+/// a function body doesn't end the function early. The statements that did
+/// parse are returned along with the errors.
+pub fn parse(tokens: &[Token]) -> (Vec<Stmt>, Vec<ParseErr>) {
+    let recovery = Recovery::default();
+    let mut stmts = Vec::new();
+    let mut position = 0;
+
+    while position < tokens.len() && tokens[position].token_type != TokenType::EOF {
+        recovery.reset_failure();
+
+        match statement_parser::statement(&recovery).parse(&tokens[position..]) {
+            Ok(parcel::MatchStatus::Match((rest, stmt))) => {
+                stmts.push(stmt);
+                position = tokens.len() - rest.len();
+            }
+            Ok(parcel::MatchStatus::NoMatch(_)) | Err(_) => {
+                position = recover(&recovery, tokens, position, false);
+            }
+        }
+    }
+
+    (stmts, recovery.take_errors())
+}
+
+/// recover records the error for a statement that failed to parse at the
+/// passed position, returning the position parsing should resume at.
+fn recover(recovery: &Recovery, tokens: &[Token], position: usize, in_block: bool) -> usize {
+    let input = &tokens[position..];
+    let failure = recovery
+        .take_failure()
+        .filter(|failure| failure.remaining < input.len());
+    let (err, error_position) = match failure {
+        Some(failure) => (
+            ParseErr::Expected(failure.expected, failure.found),
+            tokens.len() - failure.remaining,
+        ),
+        None => (ParseErr::UnexpectedToken(input[0].clone()), position),
+    };
+
+    recovery.record_error(err);
+    synchronize(
+        tokens,
+        std::cmp::max(error_position, position + 1),
+        in_block,
+    )
+}
+
+/// synchronize discards tokens from the passed position until it reaches a
+/// likely statement boundary, either following a `;` or preceding a
+/// statement keyword, returning the position parsing should resume at.
+/// Within a block, the `}` closing it is also a boundary.
+fn synchronize(tokens: &[Token], position: usize, in_block: bool) -> usize {
+    let mut position = position;
+
+    while position < tokens.len() {
+        if tokens[position - 1].token_type == TokenType::Semicolon {
+            return position;
+        }
+
+        match tokens[position].token_type {
+            TokenType::Class
+            | TokenType::Fun
+            | TokenType::Var
+            | TokenType::For
+            | TokenType::If
+            | TokenType::While
+            | TokenType::Print
+            | TokenType::Return
+            | TokenType::Break
+            | TokenType::Continue
+            | TokenType::Import
+            | TokenType::Throw
+            | TokenType::Try
+            | TokenType::EOF => return position,
+            TokenType::RightBrace if in_block => return position,
+            _ => position += 1,
+        }
+    }
+
+    position
+}
@@ -1,94 +1,134 @@
 extern crate parcel;
-use super::combinators::token_type;
+use super::combinators::{token_type, Recovery};
 use crate::ast::expression::Expr;
 use crate::ast::identifier::Identifier;
 use crate::ast::statement::Stmt;
 use crate::ast::token::{Token, TokenType};
-use crate::parser::expression_parser::{expression, identifier};
+use crate::object::{Literal, Object};
+use crate::parser::expression_parser::expression;
 use parcel::*;
 use std::convert::TryFrom;
 
-/// Represents the entrypoint for statement parsing within the lox parser and
-/// yields a Vec<Stmt> representing the program statemnts.
-pub fn statements<'a>() -> impl parcel::Parser<'a, &'a [Token], Vec<Stmt>> {
-    parcel::one_or_more(statement())
-}
-
 #[allow(clippy::redundant_closure)]
-fn statement<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    declaration_stmt()
-        .or(|| class_declaration_stmt())
-        .or(|| fun_declaration_stmt())
-        .or(|| expression_stmt())
-        .or(|| while_stmt())
-        .or(|| for_stmt())
-        .or(|| if_stmt())
-        .or(|| print_stmt())
-        .or(|| return_stmt())
-        .or(|| block())
+pub fn statement<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    declaration_stmt(recovery)
+        .or(move || class_declaration_stmt(recovery))
+        .or(move || fun_declaration_stmt(recovery))
+        .or(move || expression_stmt(recovery))
+        .or(move || while_stmt(recovery))
+        .or(move || for_in_stmt(recovery))
+        .or(move || for_stmt(recovery))
+        .or(move || if_stmt(recovery))
+        .or(move || print_stmt(recovery))
+        .or(move || return_stmt(recovery))
+        .or(move || break_stmt(recovery))
+        .or(move || continue_stmt(recovery))
+        .or(move || import_stmt(recovery))
+        .or(move || throw_stmt(recovery))
+        .or(move || try_stmt(recovery))
+        .or(move || block(recovery))
 }
 
 #[allow(clippy::redundant_closure)]
-fn expression_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    left(join(expression(), token_type(TokenType::Semicolon))).map(|expr| Stmt::Expression(expr))
+fn expression_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        expression(recovery),
+        token_type(recovery, TokenType::Semicolon),
+    )
+    .map(|(expr, semicolon)| {
+        let span = expr.span().merge(&semicolon.span);
+        Stmt::Expression(expr, span)
+    })
 }
 
 #[allow(clippy::redundant_closure)]
-fn print_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    left(right(join(
-        token_type(TokenType::Print),
-        join(expression(), token_type(TokenType::Semicolon)),
-    )))
-    .map(|expr| Stmt::Print(expr))
+fn print_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Print),
+        join(
+            expression(recovery),
+            token_type(recovery, TokenType::Semicolon),
+        ),
+    )
+    .map(|(print, (expr, semicolon))| Stmt::Print(expr, print.span.merge(&semicolon.span)))
 }
 
 #[allow(clippy::redundant_closure)]
-fn fun_declaration_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    right(join(token_type(TokenType::Fun), function()))
+fn fun_declaration_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    right(join(
+        token_type(recovery, TokenType::Fun),
+        function(recovery),
+    ))
 }
 
 #[allow(clippy::redundant_closure)]
-fn class_declaration_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    right(join(
-        token_type(TokenType::Class),
+fn class_declaration_stmt<'a>(
+    recovery: &'a Recovery,
+) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Class),
         join(
-            identifier(),
-            right(join(
-                token_type(TokenType::LeftBrace),
-                left(join(
-                    zero_or_more(function()),
-                    token_type(TokenType::RightBrace),
+            token_type(recovery, TokenType::Identifier),
+            join(
+                optional(right(join(
+                    token_type(recovery, TokenType::Less),
+                    token_type(recovery, TokenType::Identifier),
+                ))),
+                right(join(
+                    token_type(recovery, TokenType::LeftBrace),
+                    join(
+                        zero_or_more(function(recovery)),
+                        token_type(recovery, TokenType::RightBrace),
+                    ),
                 )),
-            )),
+            ),
         ),
-    ))
-    .map(|(id, funcs)| Stmt::Class(id, funcs))
+    )
+    .map(|(class, (id, (superclass, (funcs, rbrace))))| {
+        let superclass = superclass.map(|sc| {
+            let span = sc.span;
+            Expr::Variable(Identifier::try_from(sc).unwrap(), span)
+        });
+
+        let ident = Identifier::try_from(id).unwrap();
+        Stmt::Class(
+            ident.clone(),
+            ident,
+            superclass,
+            funcs,
+            class.span.merge(&rbrace.span),
+        )
+    })
 }
 
+/// function parses a named function, as used by both function and method
+/// declarations. The resulting span begins at the function's name.
 #[allow(clippy::redundant_closure)]
-fn function<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+fn function<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
     join(
-        token_type(TokenType::Identifier),
+        token_type(recovery, TokenType::Identifier),
         join(
             right(join(
-                token_type(TokenType::LeftParen),
+                token_type(recovery, TokenType::LeftParen),
                 left(join(
                     optional(join(
-                        token_type(TokenType::Identifier),
+                        token_type(recovery, TokenType::Identifier),
                         zero_or_more(right(join(
-                            token_type(TokenType::Comma),
-                            token_type(TokenType::Identifier),
+                            token_type(recovery, TokenType::Comma),
+                            token_type(recovery, TokenType::Identifier),
                         ))),
                     )),
-                    token_type(TokenType::RightParen),
+                    token_type(recovery, TokenType::RightParen),
                 )),
             )),
-            block(),
+            block(recovery),
         ),
     )
     .map(|(callee, (opt_args, body))| {
+        let span = callee.span.merge(&body.span());
         let ident = Identifier::try_from(callee).unwrap();
         Stmt::Function(
+            ident.clone(),
             ident,
             opt_args.map_or(Vec::new(), |a| {
                 let mut args = vec![Identifier::try_from(a.0).unwrap()];
@@ -100,137 +140,347 @@ fn function<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
                 args
             }),
             Box::new(body),
+            span,
         )
     })
 }
 
 #[allow(clippy::redundant_closure)]
-fn declaration_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    right(join(
-        token_type(TokenType::Var),
+fn declaration_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Var),
         join(
-            token_type(TokenType::Identifier),
+            token_type(recovery, TokenType::Identifier),
             right(join(
-                token_type(TokenType::Equal),
-                left(join(expression(), token_type(TokenType::Semicolon))),
+                token_type(recovery, TokenType::Equal),
+                join(
+                    expression(recovery),
+                    token_type(recovery, TokenType::Semicolon),
+                ),
             )),
         ),
-    ))
-    .map(|(id_tok, expr)| {
+    )
+    .map(|(var, (id_tok, (expr, semicolon)))| {
         let id = Identifier::try_from(id_tok).unwrap();
 
-        Stmt::Declaration(id, expr)
+        Stmt::Declaration(id, expr, var.span.merge(&semicolon.span))
     })
 }
 
 #[allow(clippy::redundant_closure)]
-fn return_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    left(right(join(
-        token_type(TokenType::Return),
-        join(optional(expression()), token_type(TokenType::Semicolon)),
-    )))
-    .map(|optional_expr| {
-        optional_expr.map_or(Stmt::Return(Expr::Primary(obj_nil!())), |expr| {
-            Stmt::Return(expr)
-        })
+fn return_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Return),
+        join(
+            optional(expression(recovery)),
+            token_type(recovery, TokenType::Semicolon),
+        ),
+    )
+    .map(|(ret, (optional_expr, semicolon))| {
+        Stmt::Return(optional_expr, ret.span.merge(&semicolon.span))
+    })
+}
+
+fn break_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Break),
+        token_type(recovery, TokenType::Semicolon),
+    )
+    .map(|(brk, semicolon)| Stmt::Break(brk.span.merge(&semicolon.span)))
+}
+
+fn continue_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Continue),
+        token_type(recovery, TokenType::Semicolon),
+    )
+    .map(|(cont, semicolon)| Stmt::Continue(cont.span.merge(&semicolon.span)))
+}
+
+fn import_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Import),
+        join(
+            token_type(recovery, TokenType::Str),
+            right(join(
+                token_type(recovery, TokenType::As),
+                join(
+                    token_type(recovery, TokenType::Identifier),
+                    token_type(recovery, TokenType::Semicolon),
+                ),
+            )),
+        ),
+    )
+    .map(|(import, (path, (id_tok, semicolon)))| {
+        let path = match path.object {
+            Some(Object::Literal(Literal::Str(path))) => path,
+            _ => unreachable!("string tokens always carry a string literal"),
+        };
+
+        Stmt::Import(
+            path,
+            Identifier::try_from(id_tok).unwrap(),
+            import.span.merge(&semicolon.span),
+        )
     })
 }
 
 #[allow(clippy::redundant_closure)]
-pub fn block<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+fn throw_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Throw),
+        join(
+            expression(recovery),
+            token_type(recovery, TokenType::Semicolon),
+        ),
+    )
+    .map(|(throw, (expr, semicolon))| Stmt::Throw(expr, throw.span.merge(&semicolon.span)))
+}
+
+#[allow(clippy::redundant_closure)]
+fn try_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::Try),
+        join(
+            block(recovery),
+            join(catch_clause(recovery), optional(finally_clause(recovery)))
+                .map(|(catch, finally)| (Some(catch), finally))
+                .or(move || finally_clause(recovery).map(|finally| (None, Some(finally)))),
+        ),
+    )
+    .map(|(try_tok, (body, (catch, finally)))| {
+        let end = match (&catch, &finally) {
+            (_, Some(finally)) => finally.span(),
+            (Some((_, handler)), None) => handler.span(),
+            (None, None) => body.span(),
+        };
+
+        Stmt::Try(
+            Box::new(body),
+            catch.map(|(id, handler)| (id, Box::new(handler))),
+            finally.map(Box::new),
+            try_tok.span.merge(&end),
+        )
+    })
+}
+
+#[allow(clippy::redundant_closure)]
+fn catch_clause<'a>(
+    recovery: &'a Recovery,
+) -> impl parcel::Parser<'a, &'a [Token], (Identifier, Stmt)> {
     right(join(
-        token_type(TokenType::LeftBrace),
-        left(join(statements(), token_type(TokenType::RightBrace))),
+        token_type(recovery, TokenType::Catch),
+        join(
+            right(join(
+                token_type(recovery, TokenType::LeftParen),
+                left(join(
+                    token_type(recovery, TokenType::Identifier),
+                    token_type(recovery, TokenType::RightParen),
+                )),
+            )),
+            block(recovery),
+        ),
     ))
-    .map(|stmts| Stmt::Block(stmts))
+    .map(|(id_tok, handler)| (Identifier::try_from(id_tok).unwrap(), handler))
+}
+
+#[allow(clippy::redundant_closure)]
+fn finally_clause<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    right(join(
+        token_type(recovery, TokenType::Finally),
+        block(recovery),
+    ))
+}
+
+#[allow(clippy::redundant_closure)]
+pub fn block<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        token_type(recovery, TokenType::LeftBrace),
+        join(
+            block_statements(recovery),
+            token_type(recovery, TokenType::RightBrace),
+        ),
+    )
+    .map(|(lbrace, (stmts, rbrace))| Stmt::Block(stmts, lbrace.span.merge(&rbrace.span)))
+}
+
+/// block_statements parses one or more statements up to the `}` closing a
+/// block. A statement that fails to parse is recorded as an error and the
+/// parser synchronizes within the block, as clox's `block(recovery)` does, rather
+/// than failing the enclosing statement.
+fn block_statements<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Vec<Stmt>> {
+    move |input: &'a [Token]| {
+        let mut stmts = Vec::new();
+        let mut position = 0;
+
+        loop {
+            let closed = matches!(
+                input.get(position).map(|token| token.token_type),
+                None | Some(TokenType::RightBrace) | Some(TokenType::EOF)
+            );
+            if closed && position > 0 {
+                break;
+            }
+
+            let outer = recovery.take_failure();
+            match statement(recovery).parse(&input[position..]) {
+                Ok(MatchStatus::Match((rest, stmt))) => {
+                    stmts.push(stmt);
+                    position = input.len() - rest.len();
+                }
+                _ if closed => {
+                    recovery.merge_failure(outer);
+                    return Ok(MatchStatus::NoMatch(input));
+                }
+                _ => position = super::recover(recovery, input, position, true),
+            }
+            recovery.merge_failure(outer);
+        }
+
+        Ok(MatchStatus::Match((&input[position..], stmts)))
+    }
 }
 
 #[allow(clippy::redundant_closure)]
-fn if_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+fn if_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
     join(
-        right(join(
-            token_type(TokenType::If),
+        join(
+            token_type(recovery, TokenType::If),
             left(join(
-                right(join(token_type(TokenType::LeftParen), expression())),
-                token_type(TokenType::RightParen),
+                right(join(
+                    token_type(recovery, TokenType::LeftParen),
+                    expression(recovery),
+                )),
+                token_type(recovery, TokenType::RightParen),
             )),
-        )),
+        ),
         join(
-            statement(),
-            optional(right(join(token_type(TokenType::Else), statement()))),
+            statement(recovery),
+            optional(right(join(
+                token_type(recovery, TokenType::Else),
+                statement(recovery),
+            ))),
         ),
     )
     .map(
-        |(condition, (primary_branch, secondary_branch))| match secondary_branch {
-            None => Stmt::If(condition, Box::new(primary_branch), None),
-            Some(b) => Stmt::If(condition, Box::new(primary_branch), Some(Box::new(b))),
+        |((if_tok, condition), (primary_branch, secondary_branch))| match secondary_branch {
+            None => {
+                let span = if_tok.span.merge(&primary_branch.span());
+                Stmt::If(condition, Box::new(primary_branch), None, span)
+            }
+            Some(b) => {
+                let span = if_tok.span.merge(&b.span());
+                Stmt::If(condition, Box::new(primary_branch), Some(Box::new(b)), span)
+            }
         },
     )
 }
 
 #[allow(clippy::redundant_closure)]
-pub fn while_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+pub fn while_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
     join(
-        right(join(
-            token_type(TokenType::While),
+        join(
+            token_type(recovery, TokenType::While),
             left(join(
-                right(join(token_type(TokenType::LeftParen), expression())),
-                token_type(TokenType::RightParen),
+                right(join(
+                    token_type(recovery, TokenType::LeftParen),
+                    expression(recovery),
+                )),
+                token_type(recovery, TokenType::RightParen),
             )),
-        )),
-        statement(),
+        ),
+        statement(recovery),
     )
-    .map(|(condition, stmt)| Stmt::While(condition, Box::new(stmt)))
+    .map(|((while_tok, condition), stmt)| {
+        let span = while_tok.span.merge(&stmt.span());
+        Stmt::While(condition, Box::new(stmt), None, span)
+    })
 }
 
 #[allow(clippy::redundant_closure)]
-pub fn for_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+pub fn for_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
     join(
-        right(join(
-            token_type(TokenType::For),
+        join(
+            token_type(recovery, TokenType::For),
             left(join(
                 right(join(
-                    token_type(TokenType::LeftParen),
+                    token_type(recovery, TokenType::LeftParen),
                     join(
                         optional(
-                            expression_stmt()
-                                .or(|| declaration_stmt())
-                                .or(|| nil_stmt()),
+                            expression_stmt(recovery)
+                                .or(move || declaration_stmt(recovery))
+                                .or(move || nil_stmt(recovery)),
                         ),
                         join(
                             left(join(
-                                optional(expression()),
-                                token_type(TokenType::Semicolon),
+                                optional(expression(recovery)),
+                                token_type(recovery, TokenType::Semicolon),
                             )),
-                            optional(expression()),
+                            optional(expression(recovery)),
                         ),
                     ),
                 )),
-                token_type(TokenType::RightParen),
+                token_type(recovery, TokenType::RightParen),
             )),
-        )),
-        statement(),
+        ),
+        statement(recovery),
     )
-    .map(|((initializer, (condition, incrementer)), stmt)| {
-        let mut for_block: Vec<Stmt> = Vec::new();
-        if let Some(init) = initializer {
-            for_block.push(init)
-        };
+    .map(
+        |((for_tok, (initializer, (condition, incrementer))), stmt)| {
+            let span = for_tok.span.merge(&stmt.span());
+            let mut for_block: Vec<Stmt> = Vec::new();
+            if let Some(init) = initializer {
+                for_block.push(init)
+            };
 
-        let mut while_body: Vec<Stmt> = vec![stmt];
-        if let Some(inc) = incrementer {
-            while_body.push(Stmt::Expression(inc))
-        };
+            // the incrementer is carried by the loop rather than appended to
+            // its body so that a `continue` within the body still runs it.
+            for_block.push(Stmt::While(
+                condition.unwrap_or(Expr::Primary(obj_bool!(true), span)),
+                Box::new(stmt),
+                incrementer,
+                span,
+            ));
 
-        for_block.push(Stmt::While(
-            condition.unwrap_or(Expr::Primary(obj_bool!(true))),
-            Box::new(Stmt::Block(while_body)),
-        ));
+            Stmt::Block(for_block, span)
+        },
+    )
+}
 
-        Stmt::Block(for_block)
+#[allow(clippy::redundant_closure)]
+pub fn for_in_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    join(
+        join(
+            token_type(recovery, TokenType::For),
+            right(join(
+                token_type(recovery, TokenType::LeftParen),
+                left(join(
+                    join(
+                        left(join(
+                            token_type(recovery, TokenType::Identifier),
+                            token_type(recovery, TokenType::In),
+                        )),
+                        expression(recovery),
+                    ),
+                    token_type(recovery, TokenType::RightParen),
+                )),
+            )),
+        ),
+        statement(recovery),
+    )
+    .map(|((for_tok, (var, iterable)), stmt)| {
+        let span = for_tok.span.merge(&stmt.span());
+        Stmt::ForIn(
+            Identifier::try_from(var).unwrap(),
+            iterable,
+            Box::new(stmt),
+            span,
+        )
     })
 }
 
-fn nil_stmt<'a>() -> impl parcel::Parser<'a, &'a [Token], Stmt> {
-    token_type(TokenType::Semicolon).map(|_| Stmt::Expression(Expr::Primary(obj_nil!())))
+fn nil_stmt<'a>(recovery: &'a Recovery) -> impl parcel::Parser<'a, &'a [Token], Stmt> {
+    token_type(recovery, TokenType::Semicolon).map(|semicolon| {
+        Stmt::Expression(Expr::Primary(obj_nil!(), semicolon.span), semicolon.span)
+    })
 }
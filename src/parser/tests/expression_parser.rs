@@ -2,20 +2,33 @@ extern crate parcel;
 use crate::ast::expression::{
     AdditionExpr, ComparisonExpr, EqualityExpr, Expr, LogicalExpr, MultiplicationExpr, UnaryExpr,
 };
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
-use crate::ast::token::TokenType;
+use crate::ast::token::{Token, TokenType};
 use crate::parser::expression_parser::expression;
+use crate::parser::Recovery;
+use crate::scanner::Scanner;
 use parcel::*;
 
 macro_rules! token_from_tt {
     ($tt:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::None, Option::None)
+        $crate::ast::token::Token::new($tt, Span::default(), Option::None, Option::None)
     };
     ($tt:expr, $lex:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::Some($lex.to_string()), Option::None)
+        $crate::ast::token::Token::new(
+            $tt,
+            Span::default(),
+            Option::Some($lex.to_string()),
+            Option::None,
+        )
     };
     ($tt:expr, $lex:expr, $val:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::Some($lex.to_string()), Option::Some($val))
+        $crate::ast::token::Token::new(
+            $tt,
+            Span::default(),
+            Option::Some($lex.to_string()),
+            Option::Some($val),
+        )
     };
 }
 
@@ -32,10 +45,54 @@ fn should_parse_assignment_expression() {
             &input[3..],
             Expr::Assignment(
                 identifier_name!("test"),
-                Box::new(Expr::Primary(obj_number!(1.0)))
+                Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_set_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::Identifier, "test_class"),
+        token_from_tt!(TokenType::Dot),
+        token_from_tt!(TokenType::Identifier, "test_param"),
+        token_from_tt!(TokenType::Equal),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[5..],
+            Expr::Set(
+                Box::new(Expr::Variable(
+                    identifier_name!("test_class"),
+                    Span::default()
+                )),
+                identifier_name!("test_param"),
+                Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_not_parse_assignment_to_invalid_target() {
+    let input = vec![
+        token_from_tt!(TokenType::Identifier, "test"),
+        token_from_tt!(TokenType::LeftParen),
+        token_from_tt!(TokenType::RightParen),
+        token_from_tt!(TokenType::Equal),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::NoMatch(&input[..])),
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -50,12 +107,15 @@ fn should_parse_logical_or() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Logical(LogicalExpr::Or(
-                Box::new(Expr::Primary(obj_bool!(false))),
-                Box::new(Expr::Primary(obj_bool!(true)))
-            ))
+            Expr::Logical(
+                LogicalExpr::Or(
+                    Box::new(Expr::Primary(obj_bool!(false), Span::default())),
+                    Box::new(Expr::Primary(obj_bool!(true), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -72,15 +132,21 @@ fn should_parse_multiple_logical_or() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Logical(LogicalExpr::Or(
-                Box::new(Expr::Logical(LogicalExpr::Or(
-                    Box::new(Expr::Primary(obj_bool!(false))),
-                    Box::new(Expr::Primary(obj_bool!(true)))
-                ))),
-                Box::new(Expr::Primary(obj_bool!(true))),
-            ))
+            Expr::Logical(
+                LogicalExpr::Or(
+                    Box::new(Expr::Logical(
+                        LogicalExpr::Or(
+                            Box::new(Expr::Primary(obj_bool!(false), Span::default())),
+                            Box::new(Expr::Primary(obj_bool!(true), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -95,12 +161,15 @@ fn should_parse_logical_and() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Logical(LogicalExpr::And(
-                Box::new(Expr::Primary(obj_bool!(false))),
-                Box::new(Expr::Primary(obj_bool!(true)))
-            ))
+            Expr::Logical(
+                LogicalExpr::And(
+                    Box::new(Expr::Primary(obj_bool!(false), Span::default())),
+                    Box::new(Expr::Primary(obj_bool!(true), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -117,15 +186,21 @@ fn should_parse_multiple_logical_and() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Logical(LogicalExpr::And(
-                Box::new(Expr::Logical(LogicalExpr::And(
-                    Box::new(Expr::Primary(obj_bool!(false))),
-                    Box::new(Expr::Primary(obj_bool!(true)))
-                ))),
-                Box::new(Expr::Primary(obj_bool!(true))),
-            ))
+            Expr::Logical(
+                LogicalExpr::And(
+                    Box::new(Expr::Logical(
+                        LogicalExpr::And(
+                            Box::new(Expr::Primary(obj_bool!(false), Span::default())),
+                            Box::new(Expr::Primary(obj_bool!(true), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_bool!(true), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -140,12 +215,15 @@ fn should_parse_equality_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Equality(EqualityExpr::Equal(
-                Box::new(Expr::Primary(obj_number!(1.0))),
-                Box::new(Expr::Primary(obj_number!(1.0)))
-            ))
+            Expr::Equality(
+                EqualityExpr::Equal(
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -162,15 +240,21 @@ fn should_parse_many_equality_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Equality(EqualityExpr::Equal(
-                Box::new(Expr::Equality(EqualityExpr::Equal(
-                    Box::new(Expr::Primary(obj_number!(1.0))),
-                    Box::new(Expr::Primary(obj_number!(1.0)))
-                ))),
-                Box::new(Expr::Primary(obj_number!(1.0))),
-            ))
+            Expr::Equality(
+                EqualityExpr::Equal(
+                    Box::new(Expr::Equality(
+                        EqualityExpr::Equal(
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -185,12 +269,15 @@ fn should_parse_comparison_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Comparison(ComparisonExpr::GreaterEqual(
-                Box::new(Expr::Primary(obj_number!(1.0))),
-                Box::new(Expr::Primary(obj_number!(1.0)))
-            ))
+            Expr::Comparison(
+                ComparisonExpr::GreaterEqual(
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -207,15 +294,21 @@ fn should_parse_many_comparison_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Comparison(ComparisonExpr::GreaterEqual(
-                Box::new(Expr::Comparison(ComparisonExpr::GreaterEqual(
-                    Box::new(Expr::Primary(obj_number!(1.0))),
-                    Box::new(Expr::Primary(obj_number!(1.0)))
-                ))),
-                Box::new(Expr::Primary(obj_number!(1.0))),
-            ))
+            Expr::Comparison(
+                ComparisonExpr::GreaterEqual(
+                    Box::new(Expr::Comparison(
+                        ComparisonExpr::GreaterEqual(
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -230,12 +323,15 @@ fn should_parse_addition_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Addition(AdditionExpr::Add(
-                Box::new(Expr::Primary(obj_number!(1.0))),
-                Box::new(Expr::Primary(obj_number!(1.0)))
-            ))
+            Expr::Addition(
+                AdditionExpr::Add(
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -252,15 +348,21 @@ fn should_parse_many_addition_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Addition(AdditionExpr::Add(
-                Box::new(Expr::Addition(AdditionExpr::Add(
-                    Box::new(Expr::Primary(obj_number!(1.0))),
-                    Box::new(Expr::Primary(obj_number!(1.0)))
-                ))),
-                Box::new(Expr::Primary(obj_number!(1.0))),
-            ))
+            Expr::Addition(
+                AdditionExpr::Add(
+                    Box::new(Expr::Addition(
+                        AdditionExpr::Add(
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -275,12 +377,15 @@ fn should_parse_multiplication_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Multiplication(MultiplicationExpr::Multiply(
-                Box::new(Expr::Primary(obj_number!(1.0))),
-                Box::new(Expr::Primary(obj_number!(1.0)))
-            ))
+            Expr::Multiplication(
+                MultiplicationExpr::Multiply(
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -297,15 +402,21 @@ fn should_parse_many_multiplication_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[5..],
-            Expr::Multiplication(MultiplicationExpr::Multiply(
-                Box::new(Expr::Multiplication(MultiplicationExpr::Multiply(
-                    Box::new(Expr::Primary(obj_number!(1.0))),
-                    Box::new(Expr::Primary(obj_number!(1.0)))
-                ))),
-                Box::new(Expr::Primary(obj_number!(1.0))),
-            ))
+            Expr::Multiplication(
+                MultiplicationExpr::Multiply(
+                    Box::new(Expr::Multiplication(
+                        MultiplicationExpr::Multiply(
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                            Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                        ),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                ),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -319,9 +430,12 @@ fn should_parse_unary_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[2..],
-            Expr::Unary(UnaryExpr::Bang(Box::new(Expr::Primary(obj_number!(1.0)))))
+            Expr::Unary(
+                UnaryExpr::Bang(Box::new(Expr::Primary(obj_number!(1.0), Span::default()))),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -338,11 +452,15 @@ fn should_parse_call_expression_with_single_arg() {
         Ok(MatchStatus::Match((
             &input[4..],
             Expr::Call(
-                Box::new(Expr::Variable(identifier_name!("testfunc"),)),
-                vec![Expr::Primary(obj_bool!(true))]
+                Box::new(Expr::Variable(
+                    identifier_name!("testfunc"),
+                    Span::default()
+                )),
+                vec![Expr::Primary(obj_bool!(true), Span::default())],
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -361,14 +479,18 @@ fn should_parse_call_expression_with_multiple_arg() {
         Ok(MatchStatus::Match((
             &input[6..],
             Expr::Call(
-                Box::new(Expr::Variable(identifier_name!("testfunc"),)),
+                Box::new(Expr::Variable(
+                    identifier_name!("testfunc"),
+                    Span::default()
+                )),
                 vec![
-                    Expr::Primary(obj_bool!(true)),
-                    Expr::Primary(obj_bool!(false))
-                ]
+                    Expr::Primary(obj_bool!(true), Span::default()),
+                    Expr::Primary(obj_bool!(false), Span::default())
+                ],
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -384,11 +506,144 @@ fn should_parse_get_expression() {
         Ok(MatchStatus::Match((
             &input[3..],
             Expr::Get(
-                Box::new(Expr::Variable(identifier_name!("test_class"))),
-                Box::new(Expr::Variable(identifier_name!("test_param"))),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_class"),
+                    Span::default()
+                )),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_param"),
+                    Span::default()
+                )),
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_list_literal_expression() {
+    let empty = vec![
+        token_from_tt!(TokenType::LeftBracket),
+        token_from_tt!(TokenType::RightBracket),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &empty[2..],
+            Expr::List(vec![], Span::default())
+        ))),
+        expression(&Recovery::default()).parse(&empty)
+    );
+
+    let input = vec![
+        token_from_tt!(TokenType::LeftBracket),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+        token_from_tt!(TokenType::Comma),
+        token_from_tt!(TokenType::Number, "2.0", obj_number!(2.0)),
+        token_from_tt!(TokenType::RightBracket),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[5..],
+            Expr::List(
+                vec![
+                    Expr::Primary(obj_number!(1.0), Span::default()),
+                    Expr::Primary(obj_number!(2.0), Span::default())
+                ],
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_map_literal_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::LeftBrace),
+        token_from_tt!(TokenType::Str, "key", obj_str!("key".to_string())),
+        token_from_tt!(TokenType::Colon),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+        token_from_tt!(TokenType::Comma),
+        token_from_tt!(TokenType::Nil, "nil", obj_nil!()),
+        token_from_tt!(TokenType::Colon),
+        token_from_tt!(TokenType::Number, "2.0", obj_number!(2.0)),
+        token_from_tt!(TokenType::RightBrace),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[9..],
+            Expr::Map(
+                vec![
+                    (
+                        Expr::Primary(obj_str!("key".to_string()), Span::default()),
+                        Expr::Primary(obj_number!(1.0), Span::default())
+                    ),
+                    (
+                        Expr::Primary(obj_nil!(), Span::default()),
+                        Expr::Primary(obj_number!(2.0), Span::default())
+                    )
+                ],
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_index_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::Identifier, "test_list"),
+        token_from_tt!(TokenType::LeftBracket),
+        token_from_tt!(TokenType::Number, "0.0", obj_number!(0.0)),
+        token_from_tt!(TokenType::RightBracket),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[4..],
+            Expr::Index(
+                Box::new(Expr::Variable(
+                    identifier_name!("test_list"),
+                    Span::default()
+                )),
+                Box::new(Expr::Primary(obj_number!(0.0), Span::default())),
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_index_assignment_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::Identifier, "test_list"),
+        token_from_tt!(TokenType::LeftBracket),
+        token_from_tt!(TokenType::Number, "0.0", obj_number!(0.0)),
+        token_from_tt!(TokenType::RightBracket),
+        token_from_tt!(TokenType::Equal),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[6..],
+            Expr::SetIndex(
+                Box::new(Expr::Variable(
+                    identifier_name!("test_list"),
+                    Span::default()
+                )),
+                Box::new(Expr::Primary(obj_number!(0.0), Span::default())),
+                Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -406,14 +661,109 @@ fn should_parse_nested_get_expression() {
         Ok(MatchStatus::Match((
             &input[5..],
             Expr::Get(
-                Box::new(Expr::Variable(identifier_name!("test_class"),)),
                 Box::new(Expr::Get(
-                    Box::new(Expr::Variable(identifier_name!("test_param"))),
-                    Box::new(Expr::Variable(identifier_name!("test_nested_param"))),
-                ))
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_class"),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_param"),
+                        Span::default()
+                    )),
+                    Span::default()
+                )),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_nested_param"),
+                    Span::default()
+                )),
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_method_call_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::Identifier, "test_class"),
+        token_from_tt!(TokenType::Dot),
+        token_from_tt!(TokenType::Identifier, "test_method"),
+        token_from_tt!(TokenType::LeftParen),
+        token_from_tt!(TokenType::Number, "1.0", obj_number!(1.0)),
+        token_from_tt!(TokenType::RightParen),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[6..],
+            Expr::Call(
+                Box::new(Expr::Get(
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_class"),
+                        Span::default()
+                    )),
+                    Box::new(Expr::Variable(
+                        identifier_name!("test_method"),
+                        Span::default()
+                    )),
+                    Span::default()
+                )),
+                vec![Expr::Primary(obj_number!(1.0), Span::default())],
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_this_property_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::This),
+        token_from_tt!(TokenType::Dot),
+        token_from_tt!(TokenType::Identifier, "test_param"),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[3..],
+            Expr::Get(
+                Box::new(Expr::This(Span::default())),
+                Box::new(Expr::Variable(
+                    identifier_name!("test_param"),
+                    Span::default()
+                )),
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_super_method_call_expression() {
+    let input = vec![
+        token_from_tt!(TokenType::Super),
+        token_from_tt!(TokenType::Dot),
+        token_from_tt!(TokenType::Identifier, "test_method"),
+        token_from_tt!(TokenType::LeftParen),
+        token_from_tt!(TokenType::RightParen),
+    ];
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[5..],
+            Expr::Call(
+                Box::new(Expr::Super(
+                    identifier_name!("test_method"),
+                    Span::default()
+                )),
+                vec![],
+                Span::default()
+            )
+        ))),
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -434,12 +784,17 @@ fn should_parse_lambda_expression_with_no_params() {
             &input[7..],
             Expr::Lambda(
                 vec![],
-                Box::new(Stmt::Block(vec![Stmt::Expression(Expr::Primary(
-                    obj_number!(5.0)
-                ))]))
+                Box::new(Stmt::Block(
+                    vec![Stmt::Expression(
+                        Expr::Primary(obj_number!(5.0), Span::default()),
+                        Span::default()
+                    )],
+                    Span::default()
+                )),
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -461,12 +816,17 @@ fn should_parse_lambda_expression_with_parameters() {
             &input[8..],
             Expr::Lambda(
                 vec![identifier_name!("arg_one"),],
-                Box::new(Stmt::Block(vec![Stmt::Expression(Expr::Primary(
-                    obj_number!(5.0)
-                ))]))
+                Box::new(Stmt::Block(
+                    vec![Stmt::Expression(
+                        Expr::Primary(obj_number!(5.0), Span::default()),
+                        Span::default()
+                    )],
+                    Span::default()
+                )),
+                Span::default()
             )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -481,9 +841,12 @@ fn should_parse_grouping_expression() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[3..],
-            Expr::Grouping(Box::new(Expr::Primary(obj_number!(1.0))))
+            Expr::Grouping(
+                Box::new(Expr::Primary(obj_number!(1.0), Span::default())),
+                Span::default()
+            )
         ))),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
 
@@ -497,6 +860,29 @@ fn should_throw_error_on_invalid_expression() {
 
     assert_eq!(
         Ok(MatchStatus::NoMatch(&input[..])),
-        expression().parse(&input)
+        expression(&Recovery::default()).parse(&input)
     );
 }
+
+#[test]
+fn should_span_expressions_across_their_source() {
+    let tokens: Vec<Token> = Scanner::new("a.b(1) + -2".to_string())
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap())
+        .collect();
+
+    let expr = match expression(&Recovery::default()).parse(&tokens) {
+        Ok(MatchStatus::Match((_, expr))) => expr,
+        _ => panic!("expected expression to match"),
+    };
+
+    assert_eq!(Span::new(0, 11, 1, 1), expr.span());
+    match expr {
+        Expr::Addition(AdditionExpr::Add(lhs, rhs), _) => {
+            assert_eq!(Span::new(0, 6, 1, 1), lhs.span());
+            assert_eq!(Span::new(9, 11, 1, 10), rhs.span());
+        }
+        _ => panic!("expected an addition expression"),
+    }
+}
@@ -1,2 +1,3 @@
 mod expression_parser;
+mod parse;
 mod statement_parser;
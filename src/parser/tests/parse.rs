@@ -0,0 +1,144 @@
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::ast::token::{Token, TokenType};
+use crate::diagnostic::Diagnostic;
+use crate::parser::{parse, ParseErr};
+use crate::scanner::Scanner;
+
+fn scan(source: &str) -> Vec<Token> {
+    Scanner::new(source.to_string())
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap())
+        .collect()
+}
+
+#[test]
+fn parse_should_accept_an_empty_program() {
+    assert_eq!((vec![], vec![]), parse(&scan("")));
+}
+
+#[test]
+fn parse_should_err_on_a_token_that_cannot_begin_a_statement() {
+    let tokens = scan("print 1;\n} print 2;");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(vec![ParseErr::UnexpectedToken(tokens[3].clone())], errors);
+    assert_eq!(2, stmts.len());
+}
+
+#[test]
+fn parse_should_report_the_expected_and_found_tokens() {
+    let tokens = scan("print 1;\nvar = 5;");
+    let (_, errors) = parse(&tokens);
+
+    assert_eq!(
+        vec![ParseErr::Expected(
+            vec![TokenType::Identifier],
+            tokens[4].clone()
+        )],
+        errors
+    );
+}
+
+#[test]
+fn parse_should_report_every_error_and_return_the_remaining_statements() {
+    let tokens = scan("var a = ;\nprint a;\nvar = 5;\nvar b = 2;\nprint (1 + ;");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(3, errors.len());
+    assert_eq!(
+        vec![
+            "expected expression, found `;`",
+            "expected identifier, found `=`",
+            "expected expression, found `;`"
+        ],
+        errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+    );
+    assert!(matches!(stmts[0], Stmt::Print(_, _)));
+    assert!(matches!(stmts[1], Stmt::Declaration(_, _, _)));
+    assert_eq!(2, stmts.len());
+}
+
+#[test]
+fn parse_should_synchronize_on_statement_keywords() {
+    let tokens = scan("var a = 1 print a;");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(1, errors.len());
+    assert_eq!(tokens[4], *errors[0].found());
+    assert!(matches!(stmts[..], [Stmt::Print(_, _)]));
+}
+
+#[test]
+fn parse_should_synchronize_within_a_block() {
+    let tokens = scan("fun f() { var = 1; print 2; }\nprint 3;");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(
+        vec![ParseErr::Expected(
+            vec![TokenType::Identifier],
+            tokens[6].clone()
+        )],
+        errors
+    );
+    match &stmts[..] {
+        [Stmt::Function(_, _, _, body, _), Stmt::Print(_, _)] => {
+            assert!(matches!(&**body, Stmt::Block(b, _) if matches!(b[..], [Stmt::Print(_, _)])))
+        }
+        stmts => panic!("unexpected statements {:?}", stmts),
+    }
+}
+
+#[test]
+fn parse_should_synchronize_within_nested_blocks() {
+    let tokens = scan("fun f(a) {\n  while (a) {\n    if (a print a;\n  }\n  return a;\n}");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(1, errors.len(), "{:?}", errors);
+    assert_eq!("expected `)`, found `print`", errors[0].to_string());
+    match &stmts[..] {
+        [Stmt::Function(_, _, _, body, _)] => assert!(matches!(
+            &**body,
+            Stmt::Block(b, _) if matches!(b[..], [Stmt::While(..), Stmt::Return(..)])
+        )),
+        stmts => panic!("unexpected statements {:?}", stmts),
+    }
+}
+
+#[test]
+fn parse_error_should_convert_to_a_diagnostic() {
+    let tokens = scan("print 1;\n} print 2;");
+    let (_, errors) = parse(&tokens);
+    let diagnostic = Diagnostic::from(errors[0].clone());
+
+    assert_eq!("E0100", diagnostic.code);
+    assert_eq!("expected statement, found `}`", diagnostic.message);
+    assert_eq!(Span::new(9, 10, 2, 1), diagnostic.span);
+
+    let tokens = scan("var = 5;");
+    let (_, errors) = parse(&tokens);
+    let diagnostic = Diagnostic::from(errors[0].clone());
+
+    assert_eq!("E0101", diagnostic.code);
+    assert_eq!("expected identifier, found `=`", diagnostic.message);
+    assert_eq!(Span::new(4, 5, 1, 5), diagnostic.span);
+}
+
+#[test]
+fn parse_error_should_omit_operators_that_could_continue_an_expression() {
+    let tokens = scan("print 1\nprint 2;");
+    let (stmts, errors) = parse(&tokens);
+
+    assert_eq!(
+        vec!["expected `;`, found `print`"],
+        errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+    );
+    assert_eq!(1, stmts.len());
+}
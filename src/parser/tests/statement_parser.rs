@@ -1,21 +1,31 @@
 extern crate parcel;
 use crate::ast::expression::{AdditionExpr, ComparisonExpr, Expr};
+use crate::ast::span::Span;
 use crate::ast::statement::Stmt;
-use crate::ast::token::TokenType;
-use crate::parser::statement_parser::statements;
-use parcel::prelude::v1::*;
-use parcel::MatchStatus;
+use crate::ast::token::{Token, TokenType};
+use crate::parser::parse;
+use crate::scanner::Scanner;
 use std::option::Option;
 
 macro_rules! token_from_tt {
     ($tt:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::None, Option::None)
+        $crate::ast::token::Token::new($tt, Span::default(), Option::None, Option::None)
     };
     ($tt:expr, $lex:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::Some($lex.to_string()), Option::None)
+        $crate::ast::token::Token::new(
+            $tt,
+            Span::default(),
+            Option::Some($lex.to_string()),
+            Option::None,
+        )
     };
     ($tt:expr, $lex:expr, $val:expr) => {
-        $crate::ast::token::Token::new($tt, 1, Option::Some($lex.to_string()), Option::Some($val))
+        $crate::ast::token::Token::new(
+            $tt,
+            Span::default(),
+            Option::Some($lex.to_string()),
+            Option::Some($val),
+        )
     };
 }
 
@@ -30,14 +40,15 @@ fn can_parse_declaration_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[5..],
+        (
             vec![Stmt::Declaration(
                 identifier_name!("test"),
-                Expr::Primary(obj_number!(5.0))
-            )]
-        ))),
-        statements().parse(&input)
+                Expr::Primary(obj_number!(5.0), Span::default()),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -56,17 +67,23 @@ fn can_parse_function_declaration_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[9..],
+        (
             vec![Stmt::Function(
+                identifier_name!("test"),
                 identifier_name!("test"),
                 vec![identifier_name!("arg_one")],
-                Box::new(Stmt::Block(vec![Stmt::Expression(Expr::Primary(
-                    obj_number!(5.0)
-                ))]))
-            )]
-        ))),
-        statements().parse(&input)
+                Box::new(Stmt::Block(
+                    vec![Stmt::Expression(
+                        Expr::Primary(obj_number!(5.0), Span::default()),
+                        Span::default()
+                    )],
+                    Span::default()
+                )),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -79,11 +96,14 @@ fn can_parse_print_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[3..],
-            vec![Stmt::Print(Expr::Primary(obj_number!(5.0)))]
-        ))),
-        statements().parse(&input)
+        (
+            vec![Stmt::Print(
+                Expr::Primary(obj_number!(5.0), Span::default()),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -96,11 +116,27 @@ fn can_parse_return_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[3..],
-            vec![Stmt::Return(Expr::Primary(obj_number!(5.0)))]
-        ))),
-        statements().parse(&input)
+        (
+            vec![Stmt::Return(
+                Some(Expr::Primary(obj_number!(5.0), Span::default())),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_bare_return_stmt() {
+    let input = vec![
+        token_from_tt!(TokenType::Return),
+        token_from_tt!(TokenType::Semicolon),
+    ];
+
+    assert_eq!(
+        (vec![Stmt::Return(None, Span::default())], Vec::new()),
+        parse(&input)
     );
 }
 
@@ -115,13 +151,17 @@ fn can_parse_block_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[5..],
-            vec![Stmt::Block(vec![Stmt::Print(Expr::Primary(obj_number!(
-                5.0
-            )))])]
-        ))),
-        statements().parse(&input)
+        (
+            vec![Stmt::Block(
+                vec![Stmt::Print(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default()
+                )],
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -137,15 +177,19 @@ fn can_parse_if_stmt_with_no_else_clause() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[6..],
+        (
             vec![Stmt::If(
-                Expr::Primary(obj_bool!(true)),
-                Box::new(Stmt::Expression(Expr::Primary(obj_number!(5.0)))),
-                Option::None
-            )]
-        ))),
-        statements().parse(&input)
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Box::new(Stmt::Expression(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default()
+                )),
+                Option::None,
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -164,15 +208,22 @@ fn can_parse_if_stmt_with_else_clause() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[9..],
+        (
             vec![Stmt::If(
-                Expr::Primary(obj_bool!(true)),
-                Box::new(Stmt::Expression(Expr::Primary(obj_number!(5.0)))),
-                Option::Some(Box::new(Stmt::Expression(Expr::Primary(obj_number!(5.0)))))
-            )]
-        ))),
-        statements().parse(&input)
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Box::new(Stmt::Expression(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default()
+                )),
+                Option::Some(Box::new(Stmt::Expression(
+                    Expr::Primary(obj_number!(5.0), Span::default()),
+                    Span::default()
+                ))),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -188,14 +239,143 @@ fn can_parse_while_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[6..],
+        (
             vec![Stmt::While(
-                Expr::Primary(obj_bool!(true)),
-                Box::new(Stmt::Expression(Expr::Primary(obj_bool!(true)))),
-            )]
-        ))),
-        statements().parse(&input)
+                Expr::Primary(obj_bool!(true), Span::default()),
+                Box::new(Stmt::Expression(
+                    Expr::Primary(obj_bool!(true), Span::default()),
+                    Span::default()
+                )),
+                None,
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_break_and_continue_stmts() {
+    let input = vec![
+        token_from_tt!(TokenType::Break),
+        token_from_tt!(TokenType::Semicolon),
+        token_from_tt!(TokenType::Continue),
+        token_from_tt!(TokenType::Semicolon),
+    ];
+
+    assert_eq!(
+        (
+            vec![
+                Stmt::Break(Span::default()),
+                Stmt::Continue(Span::default())
+            ],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_import_stmt() {
+    let input = vec![
+        token_from_tt!(TokenType::Import),
+        token_from_tt!(TokenType::Str, "lib.lox", obj_str!("lib.lox".to_string())),
+        token_from_tt!(TokenType::As),
+        token_from_tt!(TokenType::Identifier, "lib"),
+        token_from_tt!(TokenType::Semicolon),
+    ];
+
+    assert_eq!(
+        (
+            vec![Stmt::Import(
+                "lib.lox".to_string(),
+                identifier_name!("lib"),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_throw_and_try_stmts() {
+    let input = vec![
+        token_from_tt!(TokenType::Try),
+        token_from_tt!(TokenType::LeftBrace),
+        token_from_tt!(TokenType::Throw),
+        token_from_tt!(TokenType::Nil, "nil", obj_nil!()),
+        token_from_tt!(TokenType::Semicolon),
+        token_from_tt!(TokenType::RightBrace),
+        token_from_tt!(TokenType::Catch),
+        token_from_tt!(TokenType::LeftParen),
+        token_from_tt!(TokenType::Identifier, "e"),
+        token_from_tt!(TokenType::RightParen),
+        token_from_tt!(TokenType::LeftBrace),
+        token_from_tt!(TokenType::Break),
+        token_from_tt!(TokenType::Semicolon),
+        token_from_tt!(TokenType::RightBrace),
+        token_from_tt!(TokenType::Finally),
+        token_from_tt!(TokenType::LeftBrace),
+        token_from_tt!(TokenType::Continue),
+        token_from_tt!(TokenType::Semicolon),
+        token_from_tt!(TokenType::RightBrace),
+    ];
+
+    assert_eq!(
+        (
+            vec![Stmt::Try(
+                Box::new(Stmt::Block(
+                    vec![Stmt::Throw(
+                        Expr::Primary(obj_nil!(), Span::default()),
+                        Span::default()
+                    )],
+                    Span::default()
+                )),
+                Some((
+                    identifier_name!("e"),
+                    Box::new(Stmt::Block(
+                        vec![Stmt::Break(Span::default())],
+                        Span::default()
+                    ))
+                )),
+                Some(Box::new(Stmt::Block(
+                    vec![Stmt::Continue(Span::default())],
+                    Span::default()
+                ))),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_for_in_stmt() {
+    let input = vec![
+        token_from_tt!(TokenType::For),
+        token_from_tt!(TokenType::LeftParen),
+        token_from_tt!(TokenType::Identifier, "item"),
+        token_from_tt!(TokenType::In),
+        token_from_tt!(TokenType::Identifier, "items"),
+        token_from_tt!(TokenType::RightParen),
+        token_from_tt!(TokenType::Break),
+        token_from_tt!(TokenType::Semicolon),
+    ];
+
+    assert_eq!(
+        (
+            vec![Stmt::ForIn(
+                identifier_name!("item"),
+                Expr::Variable(identifier_name!("items"), Span::default()),
+                Box::new(Stmt::Break(Span::default())),
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -225,29 +405,48 @@ fn can_parse_for_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[20..],
-            vec![Stmt::Block(vec![
-                Stmt::Declaration(identifier_name!("test"), Expr::Primary(obj_number!(1.0))),
-                Stmt::While(
-                    Expr::Comparison(ComparisonExpr::Less(
-                        Box::new(Expr::Variable(identifier_name!("test"))),
-                        Box::new(Expr::Primary(obj_number!(5.0)))
-                    )),
-                    Box::new(Stmt::Block(vec![
-                        Stmt::Print(Expr::Variable(identifier_name!("test"))),
-                        Stmt::Expression(Expr::Assignment(
+        (
+            vec![Stmt::Block(
+                vec![
+                    Stmt::Declaration(
+                        identifier_name!("test"),
+                        Expr::Primary(obj_number!(1.0), Span::default()),
+                        Span::default()
+                    ),
+                    Stmt::While(
+                        Expr::Comparison(
+                            ComparisonExpr::Less(
+                                Box::new(Expr::Variable(identifier_name!("test"), Span::default())),
+                                Box::new(Expr::Primary(obj_number!(5.0), Span::default()))
+                            ),
+                            Span::default()
+                        ),
+                        Box::new(Stmt::Print(
+                            Expr::Variable(identifier_name!("test"), Span::default()),
+                            Span::default()
+                        )),
+                        Some(Expr::Assignment(
                             identifier_name!("test"),
-                            Box::new(Expr::Addition(AdditionExpr::Add(
-                                Box::new(Expr::Variable(identifier_name!("test"))),
-                                Box::new(Expr::Primary(obj_number!(1.0)))
-                            )))
-                        ))
-                    ]))
-                )
-            ])]
-        ))),
-        statements().parse(&input)
+                            Box::new(Expr::Addition(
+                                AdditionExpr::Add(
+                                    Box::new(Expr::Variable(
+                                        identifier_name!("test"),
+                                        Span::default()
+                                    )),
+                                    Box::new(Expr::Primary(obj_number!(1.0), Span::default()))
+                                ),
+                                Span::default()
+                            )),
+                            Span::default()
+                        )),
+                        Span::default()
+                    )
+                ],
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -261,11 +460,17 @@ fn can_parse_empty_class_declaration_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[4..],
-            vec![Stmt::Class(identifier_name!("test"), vec![])]
-        ))),
-        statements().parse(&input)
+        (
+            vec![Stmt::Class(
+                identifier_name!("test"),
+                identifier_name!("test"),
+                None,
+                vec![],
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
 
@@ -286,19 +491,75 @@ fn can_parse_nonempty_class_declaration_stmt() {
     ];
 
     assert_eq!(
-        Ok(MatchStatus::Match((
-            &input[11..],
+        (
             vec![Stmt::Class(
                 identifier_name!("test"),
+                identifier_name!("test"),
+                None,
                 vec![Stmt::Function(
+                    identifier_name!("test_func"),
                     identifier_name!("test_func"),
                     vec![],
-                    Box::new(Stmt::Block(vec![Stmt::Expression(Expr::Primary(
-                        obj_number!(5.0)
-                    ))]))
-                )]
-            )]
-        ))),
-        statements().parse(&input)
+                    Box::new(Stmt::Block(
+                        vec![Stmt::Expression(
+                            Expr::Primary(obj_number!(5.0), Span::default()),
+                            Span::default()
+                        )],
+                        Span::default()
+                    )),
+                    Span::default()
+                )],
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
+    );
+}
+
+#[test]
+fn can_parse_subclass_declaration_stmt() {
+    let input = vec![
+        token_from_tt!(TokenType::Class),
+        token_from_tt!(TokenType::Identifier, "test"),
+        token_from_tt!(TokenType::Less),
+        token_from_tt!(TokenType::Identifier, "parent"),
+        token_from_tt!(TokenType::LeftBrace),
+        token_from_tt!(TokenType::RightBrace),
+    ];
+
+    assert_eq!(
+        (
+            vec![Stmt::Class(
+                identifier_name!("test"),
+                identifier_name!("test"),
+                Some(Expr::Variable(identifier_name!("parent"), Span::default())),
+                vec![],
+                Span::default()
+            )],
+            Vec::new()
+        ),
+        parse(&input)
     );
 }
+
+#[test]
+fn should_span_statements_across_their_source() {
+    let tokens: Vec<Token> = Scanner::new("while (true) {\n  var a = 1;\n}".to_string())
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap())
+        .collect();
+
+    let (stmts, errors) = parse(&tokens);
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    assert_eq!(Span::new(0, 29, 1, 1), stmts[0].span());
+    match &stmts[0] {
+        Stmt::While(_, body, _, _) => match body.as_ref() {
+            Stmt::Block(block, _) => assert_eq!(Span::new(17, 27, 2, 3), block[0].span()),
+            _ => panic!("expected a block body"),
+        },
+        _ => panic!("expected a while statement"),
+    }
+}
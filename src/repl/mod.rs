@@ -0,0 +1,345 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::expression::{EqualityExpr, Expr};
+use crate::ast::identifier::Identifier;
+use crate::ast::statement::Stmt;
+use crate::ast::token::TokenType;
+use crate::compiler::Compiler;
+use crate::diagnostic::Diagnostic;
+use crate::interpreter::StatefulInterpreter;
+use crate::pass::*;
+use crate::runtime;
+use crate::scanner::{LexError, Scanner};
+use crate::vm::Vm;
+
+#[cfg(test)]
+mod tests;
+
+const HELP: &str = "Enter statements to run them, or an expression to print its value
+unless it is nil. The semicolon after a final expression may be left out.
+Input continues over lines until its braces are balanced and it parses.
+An empty line runs incomplete input as is.
+
+:help          print this message
+:load <file>   run a file in the session
+:ast <source>  print the syntax tree of source
+:tokens <source>
+               print the tokens of source
+:env           print each global and its value
+:reset         discard every global defined in the session
+";
+
+/// Backend represents the engine programs are executed with, either the
+/// tree-walking interpreter or the bytecode compiler and virtual machine.
+pub enum Backend {
+    TreeWalk(StatefulInterpreter),
+    Bytecode(Compiler, Vm),
+}
+
+impl Backend {
+    /// execute runs statements resolved by the ScopeAnalyzer.
+    pub fn execute(&mut self, stmts: Vec<Stmt>) -> Result<(), Vec<Diagnostic>> {
+        match self {
+            Self::TreeWalk(interpreter) => {
+                interpreter.tree_pass(stmts).map_err(|e| vec![e.into()])?;
+            }
+            Self::Bytecode(compiler, vm) => {
+                let script = compiler.tree_pass(stmts).map_err(|e| vec![e.into()])?;
+                vm.run(script).map_err(|e| vec![e.into()])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// reset returns a new backend of the same kind.
+    fn reset(&self) -> Self {
+        match self {
+            Self::TreeWalk(_) => Self::TreeWalk(StatefulInterpreter::new()),
+            Self::Bytecode(_, _) => Self::Bytecode(Compiler::new(), Vm::new()),
+        }
+    }
+}
+
+/// Response is the result of feeding a line of input to a Repl. Output is
+/// printed by the running program itself, so evaluated input responds with
+/// an empty Output.
+#[derive(Debug, PartialEq)]
+pub enum Response {
+    Incomplete,
+    Output(String),
+    Error(String),
+}
+
+/// Repl holds an interactive session, buffering lines of input until they
+/// form a complete program and then running it against the globals defined
+/// by prior input. Lines beginning with `:` outside a program are
+/// meta-commands. Bare expression statements have their values printed, as
+/// though they were passed to `print`, unless they are nil. Input that fails
+/// to run leaves the session's declarations as they were before it.
+pub struct Repl {
+    analyzer: ScopeAnalyzer,
+    backend: Backend,
+    buffer: String,
+}
+
+impl Repl {
+    pub fn new(backend: Backend) -> Self {
+        Repl {
+            analyzer: ScopeAnalyzer::new(),
+            backend,
+            buffer: String::new(),
+        }
+    }
+
+    /// prompt returns the prompt for the next line of input, `...` while
+    /// continuing incomplete input.
+    pub fn prompt(&self) -> &'static str {
+        if self.buffer.is_empty() {
+            "> "
+        } else {
+            "... "
+        }
+    }
+
+    /// cancel discards any incomplete input.
+    pub fn cancel(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// feed adds a line of input, running the buffered input once complete.
+    pub fn feed(&mut self, line: &str) -> Response {
+        if self.buffer.is_empty() {
+            if let Some(command) = line.trim_start().strip_prefix(':') {
+                return self.command(command.trim());
+            }
+        }
+
+        let forced = line.trim().is_empty();
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+        if !forced && is_incomplete(&self.buffer) {
+            return Response::Incomplete;
+        }
+
+        let source = std::mem::take(&mut self.buffer);
+        match self.eval(&source) {
+            Ok(()) => Response::Output(String::new()),
+            Err(diagnostics) => Response::Error(render("<stdin>", &source, &diagnostics)),
+        }
+    }
+
+    fn eval(&mut self, source: &str) -> Result<(), Vec<Diagnostic>> {
+        let stmts = parse(source)?.into_iter().map(echo).collect();
+        self.execute(stmts)
+    }
+
+    /// execute analyzes and runs statements, restoring the analyzer if
+    /// either fails so that names declared by the failed input are not left
+    /// declared without a value.
+    fn execute(&mut self, stmts: Vec<Stmt>) -> Result<(), Vec<Diagnostic>> {
+        let snapshot = self.analyzer.clone();
+        let result = self
+            .analyzer
+            .tree_pass(stmts)
+            .map_err(|e| vec![e.into()])
+            .and_then(|analyzed| self.backend.execute(analyzed));
+        if result.is_err() {
+            self.analyzer = snapshot;
+        }
+
+        result
+    }
+
+    fn command(&mut self, command: &str) -> Response {
+        let (name, arg) = match command.find(char::is_whitespace) {
+            Some(i) => (&command[..i], command[i..].trim()),
+            None => (command, ""),
+        };
+
+        match (name, arg) {
+            ("help", _) => Response::Output(HELP.to_string()),
+            ("load", "") => Response::Error(":load expects a file\n".to_string()),
+            ("load", file) => self.load(file),
+            ("ast", source) => ast(source),
+            ("tokens", source) => Response::Output(tokens(source)),
+            ("env", _) => Response::Output(self.env()),
+            ("reset", _) => {
+                self.analyzer = ScopeAnalyzer::new();
+                self.backend = self.backend.reset();
+                Response::Output(String::new())
+            }
+            _ => Response::Error(format!(
+                "unknown command ':{}', see :help for commands\n",
+                name
+            )),
+        }
+    }
+
+    fn load(&mut self, file: &str) -> Response {
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => return Response::Error(format!("unable to read {}: {}\n", file, e)),
+        };
+
+        match runtime::parse(&source).and_then(|stmts| self.execute(stmts)) {
+            Ok(()) => Response::Output(String::new()),
+            Err(diagnostics) => Response::Error(render(file, &source, &diagnostics)),
+        }
+    }
+
+    /// env lists each global and builtin, sorted by name, along with its
+    /// value.
+    fn env(&self) -> String {
+        let mut names: Vec<String> = self.analyzer.globals().into_keys().collect();
+        names.extend(self.analyzer.builtins());
+        names.sort();
+
+        names
+            .into_iter()
+            .map(|name| {
+                let id = self.analyzer.resolve(&identifier_name!(name.as_str()));
+                let value = match (&self.backend, id) {
+                    (Backend::TreeWalk(interpreter), Some(id)) => {
+                        interpreter.lookup(&id).map(|obj| obj.to_string())
+                    }
+                    (Backend::Bytecode(_, vm), Some(Identifier::Builtin(name))) => {
+                        vm.builtin(&name).map(|value| value.to_string())
+                    }
+                    (Backend::Bytecode(compiler, vm), Some(id)) => compiler
+                        .global(&id)
+                        .and_then(|slot| vm.global(slot))
+                        .map(|value| value.to_string()),
+                    (_, None) => None,
+                };
+                format!(
+                    "{} = {}\n",
+                    name,
+                    value.unwrap_or_else(|| "nil".to_string())
+                )
+            })
+            .collect()
+    }
+}
+
+/// is_incomplete returns true if source ends before a program it begins is
+/// complete, either with an unclosed brace, bracket, parenthesis, string or
+/// comment, or with the parser expecting more input other than the semicolon
+/// after a final expression.
+pub fn is_incomplete(source: &str) -> bool {
+    let mut tokens = Vec::new();
+    let mut depth: isize = 0;
+    for lex_result in Scanner::new(source.to_string()).scan_tokens() {
+        match lex_result {
+            Ok(token) => {
+                match token.token_type {
+                    TokenType::LeftBrace | TokenType::LeftBracket | TokenType::LeftParen => {
+                        depth += 1
+                    }
+                    TokenType::RightBrace | TokenType::RightBracket | TokenType::RightParen => {
+                        depth -= 1
+                    }
+                    _ => (),
+                }
+                tokens.push(token);
+            }
+            Err(LexError::UnclosedString(_)) | Err(LexError::InvalidComment(_)) => return true,
+            Err(_) => return false,
+        }
+    }
+
+    if depth != 0 {
+        return depth > 0;
+    }
+
+    let (_, errors) = crate::parser::parse(&tokens);
+    errors
+        .iter()
+        .any(|err| err.found().token_type == TokenType::EOF)
+        && parse(source).is_err()
+}
+
+/// parse parses source as a program, accepting a final expression statement
+/// without its trailing semicolon.
+fn parse(source: &str) -> Result<Vec<Stmt>, Vec<Diagnostic>> {
+    runtime::parse(source).or_else(|e| match runtime::parse(&format!("{};", source)) {
+        Ok(stmts) if matches!(stmts.last(), Some(Stmt::Expression(_, _))) => Ok(stmts),
+        _ => Err(e),
+    })
+}
+
+/// echo rewrites a bare expression statement into a block printing its value
+/// unless it is nil, so that calls made for their effects print nothing.
+/// Assignments are left as is, as their value is the one just written.
+fn echo(stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Expression(expr, span) => match expr {
+            Expr::Assignment(..) | Expr::Set(..) | Expr::SetIndex(..) => {
+                Stmt::Expression(expr, span)
+            }
+            expr => {
+                // the name can't be written in source, so never shadows one.
+                let value = || Expr::Variable(identifier_name!("<echo>"), span);
+                let not_nil = Expr::Equality(
+                    EqualityExpr::NotEqual(
+                        Box::new(value()),
+                        Box::new(Expr::Primary(obj_nil!(), span)),
+                    ),
+                    span,
+                );
+                Stmt::Block(
+                    vec![
+                        Stmt::Declaration(identifier_name!("<echo>"), expr, span),
+                        Stmt::If(not_nil, Box::new(Stmt::Print(value(), span)), None, span),
+                    ],
+                    span,
+                )
+            }
+        },
+        stmt => stmt,
+    }
+}
+
+/// ast prints the syntax tree of each statement in source, accepting a bare
+/// expression without its trailing semicolon.
+fn ast(source: &str) -> Response {
+    match parse(source) {
+        Ok(stmts) => Response::Output(
+            stmts
+                .iter()
+                .map(|stmt| match stmt {
+                    Stmt::Expression(expr, _) => format!("{}\n", expr),
+                    stmt => format!("{}\n", stmt),
+                })
+                .collect(),
+        ),
+        Err(diagnostics) => Response::Error(render("<stdin>", source, &diagnostics)),
+    }
+}
+
+/// tokens lists each token in source with the position it begins at.
+fn tokens(source: &str) -> String {
+    Scanner::new(source.to_string())
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| match lex_result {
+            Ok(token) => match &token.lexeme {
+                Some(lexeme) => format!(
+                    "{}:{} {:?} {}\n",
+                    token.span.line, token.span.col, token.token_type, lexeme
+                ),
+                None => format!(
+                    "{}:{} {:?}\n",
+                    token.span.line, token.span.col, token.token_type
+                ),
+            },
+            Err(e) => format!("{}:{} error: {}\n", e.span().line, e.span().col, e),
+        })
+        .collect()
+}
+
+fn render(filename: &str, source: &str, diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(filename, source))
+        .collect()
+}
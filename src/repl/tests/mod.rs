@@ -0,0 +1,178 @@
+use crate::ast::statement::Stmt;
+use crate::compiler::Compiler;
+use crate::interpreter::StatefulInterpreter;
+use crate::repl::{echo, is_incomplete, Backend, Repl, Response};
+use crate::runtime;
+use crate::vm::Vm;
+
+fn tree_walk() -> Repl {
+    Repl::new(Backend::TreeWalk(StatefulInterpreter::new()))
+}
+
+fn output(text: &str) -> Response {
+    Response::Output(text.to_string())
+}
+
+#[test]
+fn unclosed_delimiters_and_input_ending_early_should_be_incomplete() {
+    assert!(is_incomplete("fun add(a, b) {"));
+    assert!(is_incomplete("var l = [1,"));
+    assert!(is_incomplete("print 1 +"));
+    assert!(is_incomplete("var s = \"open"));
+    assert!(is_incomplete("/* open"));
+
+    assert!(!is_incomplete("print 1;"));
+    assert!(!is_incomplete("}"));
+    assert!(!is_incomplete("print ;"));
+    assert!(!is_incomplete("1 + 2"));
+    assert!(is_incomplete("print 1"));
+}
+
+#[test]
+fn lines_should_be_buffered_until_input_is_complete() {
+    let mut repl = tree_walk();
+
+    assert_eq!("> ", repl.prompt());
+    assert_eq!(Response::Incomplete, repl.feed("fun add(a, b) {"));
+    assert_eq!("... ", repl.prompt());
+    assert_eq!(Response::Incomplete, repl.feed("  return a + b;"));
+    assert_eq!(output(""), repl.feed("}"));
+    assert_eq!("> ", repl.prompt());
+
+    assert_eq!(output(""), repl.feed("var sum = add(1, 2);"));
+    assert!(repl.env().contains("sum = 3\n"));
+}
+
+#[test]
+fn an_empty_line_should_run_incomplete_input_as_is() {
+    let mut repl = tree_walk();
+
+    assert_eq!(Response::Incomplete, repl.feed("print 1 +"));
+    match repl.feed("") {
+        Response::Error(e) => assert!(e.starts_with("error[E0101]"), "{}", e),
+        other => panic!("unexpected response {:?}", other),
+    }
+    assert_eq!("> ", repl.prompt());
+}
+
+#[test]
+fn errors_should_leave_the_session_alive() {
+    let mut repl = tree_walk();
+
+    assert_eq!(output(""), repl.feed("var a = 1;"));
+    match repl.feed("print missing;") {
+        Response::Error(e) => assert!(e.contains("undefined variable 'missing'"), "{}", e),
+        other => panic!("unexpected response {:?}", other),
+    }
+    match repl.feed("a();") {
+        Response::Error(_) => (),
+        other => panic!("unexpected response {:?}", other),
+    }
+    assert_eq!(output(""), repl.feed("a = a + 1;"));
+    assert!(repl.env().contains("a = 2\n"));
+}
+
+#[test]
+fn bare_expressions_should_be_printed() {
+    let echoed = |source: &str| -> Vec<Stmt> {
+        runtime::parse(source)
+            .unwrap()
+            .into_iter()
+            .map(echo)
+            .collect()
+    };
+
+    match &echoed("f();")[0] {
+        Stmt::Block(stmts, _) => assert!(matches!(
+            stmts.as_slice(),
+            [Stmt::Declaration(_, _, _), Stmt::If(_, _, None, _)]
+        )),
+        other => panic!("unexpected statement {:?}", other),
+    }
+    assert!(matches!(echoed("a = 2;")[0], Stmt::Expression(_, _)));
+    assert!(matches!(
+        echoed("var a = 2;")[0],
+        Stmt::Declaration(_, _, _)
+    ));
+}
+
+#[test]
+fn a_final_expression_should_not_need_a_semicolon() {
+    let mut repl = tree_walk();
+
+    assert_eq!(output(""), repl.feed("var a = 1;"));
+    assert_eq!(output(""), repl.feed("a + 1"));
+    assert_eq!(Response::Incomplete, repl.feed("a = a +"));
+    assert_eq!(output(""), repl.feed("2"));
+    assert!(repl.env().contains("a = 3\n"));
+}
+
+#[test]
+fn failed_input_should_leave_its_declarations_undeclared() {
+    let mut repl = tree_walk();
+
+    assert!(matches!(repl.feed("var a = 1 / 0;"), Response::Error(_)));
+    match repl.feed("a;") {
+        Response::Error(e) => assert!(e.contains("undefined variable 'a'"), "{}", e),
+        other => panic!("unexpected response {:?}", other),
+    }
+    assert_eq!(output(""), repl.feed("var a = 2;"));
+    assert!(repl.env().contains("a = 2\n"));
+}
+
+#[test]
+fn commands_should_inspect_source_without_running_it() {
+    let mut repl = tree_walk();
+
+    assert_eq!(output("(+ 1 (* 2 3))\n"), repl.feed(":ast 1 + 2 * 3"));
+    assert_eq!(
+        output("1:1 Print\n1:7 Identifier x\n1:8 Semicolon\n1:9 EOF\n"),
+        repl.feed(":tokens print x;")
+    );
+    assert_eq!(Response::Incomplete, repl.feed("{"));
+    assert_eq!(Response::Incomplete, repl.feed(":env"));
+    repl.cancel();
+
+    match repl.feed(":unknown") {
+        Response::Error(e) => assert!(e.contains(":help")),
+        other => panic!("unexpected response {:?}", other),
+    }
+}
+
+#[test]
+fn reset_should_discard_the_sessions_globals() {
+    let mut repl = tree_walk();
+
+    repl.feed("var a = 1;");
+    assert!(repl.env().contains("a = 1\n"));
+    assert_eq!(output(""), repl.feed(":reset"));
+    assert!(!repl.env().contains("a = "));
+    assert!(repl.env().contains("clock = "));
+}
+
+#[test]
+fn env_should_read_globals_from_the_virtual_machine() {
+    let mut repl = Repl::new(Backend::Bytecode(Compiler::new(), Vm::new()));
+
+    assert_eq!(output(""), repl.feed("var a = 1; var b = \"two\";"));
+    assert_eq!(output(""), repl.feed("a = a + 2;"));
+
+    let env = repl.env();
+    assert!(env.contains("a = 3\nb = two\n"), "{}", env);
+}
+
+#[test]
+fn load_should_run_a_file_in_the_session() {
+    let path = std::env::temp_dir().join(format!("rlox-repl-{}.lox", std::process::id()));
+    std::fs::write(&path, "var loaded = 1;\n").unwrap();
+    let mut repl = tree_walk();
+
+    assert_eq!(
+        output(""),
+        repl.feed(&format!(":load {}", path.to_str().unwrap()))
+    );
+    assert!(repl.env().contains("loaded = 1\n"));
+    std::fs::remove_file(path).unwrap();
+
+    assert!(matches!(repl.feed(":load missing.lox"), Response::Error(_)));
+}
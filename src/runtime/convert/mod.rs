@@ -0,0 +1,220 @@
+use crate::list::List;
+use crate::object::{Literal, Object};
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// ConversionErr represents a lox value that could not be converted into the
+/// requested rust type.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConversionErr {
+    pub expected: &'static str,
+    pub found: Object,
+}
+
+impl ConversionErr {
+    pub fn new(expected: &'static str, found: Object) -> Self {
+        ConversionErr { expected, found }
+    }
+}
+
+impl fmt::Display for ConversionErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {}, found {} {}",
+            self.expected,
+            type_name(&self.found),
+            self.found
+        )
+    }
+}
+
+/// type_name returns the name of a value's lox type.
+fn type_name(obj: &Object) -> &'static str {
+    match obj {
+        Object::Literal(Literal::Nil) => "nil",
+        Object::Literal(Literal::Bool(_)) => "bool",
+        Object::Literal(Literal::Number(_)) => "number",
+        Object::Literal(Literal::Str(_)) => "string",
+        Object::Call(_) => "callable",
+        Object::Instance(_) => "instance",
+        Object::List(_) => "list",
+        Object::Map(_) => "map",
+        Object::Iterator(_) => "iterator",
+        Object::Namespace(_) => "namespace",
+    }
+}
+
+/// FromLox converts a lox value into a rust type, failing if the value is not
+/// of the corresponding lox type.
+pub trait FromLox: Sized {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr>;
+}
+
+/// IntoLox converts a rust type into a lox value.
+pub trait IntoLox {
+    fn into_lox(self) -> Object;
+}
+
+impl FromLox for Object {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        Ok(obj)
+    }
+}
+
+impl IntoLox for Object {
+    fn into_lox(self) -> Object {
+        self
+    }
+}
+
+impl FromLox for Literal {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(l) => Ok(l),
+            obj => Err(ConversionErr::new("literal", obj)),
+        }
+    }
+}
+
+impl IntoLox for Literal {
+    fn into_lox(self) -> Object {
+        Object::Literal(self)
+    }
+}
+
+impl FromLox for () {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(Literal::Nil) => Ok(()),
+            obj => Err(ConversionErr::new("nil", obj)),
+        }
+    }
+}
+
+impl IntoLox for () {
+    fn into_lox(self) -> Object {
+        obj_nil!()
+    }
+}
+
+impl FromLox for bool {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(Literal::Bool(b)) => Ok(b),
+            obj => Err(ConversionErr::new("bool", obj)),
+        }
+    }
+}
+
+impl IntoLox for bool {
+    fn into_lox(self) -> Object {
+        obj_bool!(self)
+    }
+}
+
+impl FromLox for f64 {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(Literal::Number(n)) => Ok(n),
+            obj => Err(ConversionErr::new("number", obj)),
+        }
+    }
+}
+
+impl IntoLox for f64 {
+    fn into_lox(self) -> Object {
+        obj_number!(self)
+    }
+}
+
+impl FromLox for String {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(Literal::Str(s)) => Ok(s),
+            obj => Err(ConversionErr::new("string", obj)),
+        }
+    }
+}
+
+impl IntoLox for String {
+    fn into_lox(self) -> Object {
+        obj_str!(self)
+    }
+}
+
+impl IntoLox for &str {
+    fn into_lox(self) -> Object {
+        obj_str!(self.to_string())
+    }
+}
+
+/// Options map nil to None, converting any other value into the wrapped
+/// type.
+impl<T: FromLox> FromLox for Option<T> {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::Literal(Literal::Nil) => Ok(None),
+            obj => T::from_lox(obj).map(Some),
+        }
+    }
+}
+
+impl<T: IntoLox> IntoLox for Option<T> {
+    fn into_lox(self) -> Object {
+        match self {
+            Some(v) => v.into_lox(),
+            None => obj_nil!(),
+        }
+    }
+}
+
+impl<T: FromLox> FromLox for Vec<T> {
+    fn from_lox(obj: Object) -> Result<Self, ConversionErr> {
+        match obj {
+            Object::List(l) => l.items().into_iter().map(T::from_lox).collect(),
+            obj => Err(ConversionErr::new("list", obj)),
+        }
+    }
+}
+
+impl<T: IntoLox> IntoLox for Vec<T> {
+    fn into_lox(self) -> Object {
+        Object::List(List::new(self.into_iter().map(IntoLox::into_lox).collect()))
+    }
+}
+
+/// IntoLoxArgs converts a rust type into the arguments of a lox function
+/// call, either from a vector of values of the same type or from a tuple of
+/// values of differing types.
+pub trait IntoLoxArgs {
+    fn into_lox_args(self) -> Vec<Object>;
+}
+
+impl<T: IntoLox> IntoLoxArgs for Vec<T> {
+    fn into_lox_args(self) -> Vec<Object> {
+        self.into_iter().map(IntoLox::into_lox).collect()
+    }
+}
+
+macro_rules! impl_into_lox_args_for_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: IntoLox),*> IntoLoxArgs for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn into_lox_args(self) -> Vec<Object> {
+                let ($($t,)*) = self;
+                vec![$($t.into_lox()),*]
+            }
+        }
+    };
+}
+
+impl_into_lox_args_for_tuple!();
+impl_into_lox_args_for_tuple!(A);
+impl_into_lox_args_for_tuple!(A, B);
+impl_into_lox_args_for_tuple!(A, B, C);
+impl_into_lox_args_for_tuple!(A, B, C, D);
+impl_into_lox_args_for_tuple!(A, B, C, D, E);
+impl_into_lox_args_for_tuple!(A, B, C, D, E, F);
@@ -0,0 +1,85 @@
+use crate::object::{Literal, Object};
+use crate::runtime::convert::{ConversionErr, FromLox, IntoLox, IntoLoxArgs};
+
+#[test]
+fn primitives_should_round_trip() {
+    assert_eq!(Ok(1.5), f64::from_lox(1.5.into_lox()));
+    assert_eq!(Ok(true), bool::from_lox(true.into_lox()));
+    assert_eq!(
+        Ok("lox".to_string()),
+        String::from_lox("lox".to_string().into_lox())
+    );
+    assert_eq!(Ok(()), <()>::from_lox(().into_lox()));
+    assert_eq!(
+        Ok(Literal::Number(2.0)),
+        Literal::from_lox(Literal::Number(2.0).into_lox())
+    );
+}
+
+#[test]
+fn str_should_convert_into_a_string() {
+    assert_eq!(obj_str!("lox".to_string()), "lox".into_lox());
+}
+
+#[test]
+fn mismatched_types_should_fail_to_convert() {
+    assert_eq!(
+        Err(ConversionErr::new("number", obj_bool!(true))),
+        f64::from_lox(obj_bool!(true))
+    );
+    assert_eq!(
+        Err(ConversionErr::new("string", obj_nil!())),
+        String::from_lox(obj_nil!())
+    );
+    assert_eq!(
+        "expected number, found bool true",
+        ConversionErr::new("number", obj_bool!(true)).to_string()
+    );
+}
+
+#[test]
+fn options_should_map_nil_to_none() {
+    assert_eq!(Ok(None), Option::<f64>::from_lox(obj_nil!()));
+    assert_eq!(Ok(Some(1.0)), Option::<f64>::from_lox(obj_number!(1.0)));
+    assert_eq!(
+        Err(ConversionErr::new("number", obj_bool!(false))),
+        Option::<f64>::from_lox(obj_bool!(false))
+    );
+
+    assert_eq!(obj_nil!(), Option::<f64>::None.into_lox());
+    assert_eq!(obj_number!(1.0), Some(1.0).into_lox());
+}
+
+#[test]
+fn vecs_and_tuples_should_convert_into_arguments() {
+    assert_eq!(
+        vec![obj_number!(1.0), obj_number!(2.0)],
+        vec![1.0, 2.0].into_lox_args()
+    );
+    assert_eq!(
+        vec![
+            obj_number!(1.0),
+            obj_str!("a".to_string()),
+            obj_nil!(),
+            obj_bool!(true)
+        ],
+        (1.0, "a", Option::<f64>::None, true).into_lox_args()
+    );
+    assert_eq!(Vec::<Object>::new(), ().into_lox_args());
+}
+
+#[test]
+fn vecs_should_round_trip_through_lists() {
+    assert_eq!(
+        Ok(vec![1.0, 2.0]),
+        Vec::<f64>::from_lox(vec![1.0, 2.0].into_lox())
+    );
+    assert_eq!(
+        Err(ConversionErr::new("number", obj_nil!())),
+        Vec::<f64>::from_lox(vec![Some(1.0), None].into_lox())
+    );
+    assert_eq!(
+        Err(ConversionErr::new("list", obj_number!(1.0))),
+        Vec::<f64>::from_lox(obj_number!(1.0))
+    );
+}
@@ -0,0 +1,176 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::diagnostic::Diagnostic;
+use crate::functions::{Arity, CallError, CallResult};
+use crate::interpreter::StatefulInterpreter;
+use crate::object::Object;
+use crate::parser;
+use crate::pass::*;
+use crate::scanner::Scanner;
+use std::fmt;
+use std::rc::Rc;
+
+pub mod convert;
+pub use convert::{ConversionErr, FromLox, IntoLox, IntoLoxArgs};
+
+#[cfg(test)]
+mod tests;
+
+/// RuntimeErr represents a failure to evaluate source, or to look up, call or
+/// convert a global from the host.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RuntimeErr {
+    Eval(Vec<Diagnostic>),
+    Undefined(String),
+    NotCallable(String),
+    Call(String, CallError),
+    Conversion(ConversionErr),
+}
+
+impl fmt::Display for RuntimeErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eval(diagnostics) => {
+                let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
+            Self::Undefined(name) => write!(f, "undefined global '{}'", name),
+            Self::NotCallable(name) => write!(f, "global '{}' is not callable", name),
+            Self::Call(name, e) => write!(f, "call to '{}' failed: {}", name, e),
+            Self::Conversion(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ConversionErr> for RuntimeErr {
+    fn from(err: ConversionErr) -> Self {
+        Self::Conversion(err)
+    }
+}
+
+pub type RuntimeResult<T> = Result<T, RuntimeErr>;
+
+/// parse scans and parses source into statements, returning a diagnostic for
+/// every lexical or syntax error found.
+pub fn parse(source: &str) -> Result<Vec<Stmt>, Vec<Diagnostic>> {
+    let mut tokens = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for lex_result in Scanner::new(source.to_string()).scan_tokens() {
+        match lex_result {
+            Ok(tok) => tokens.push(tok),
+            Err(e) => diagnostics.push(e.into()),
+        }
+    }
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    match parser::parse(&tokens) {
+        (stmts, errors) if errors.is_empty() => Ok(stmts),
+        (_, errors) => Err(errors.into_iter().map(Diagnostic::from).collect()),
+    }
+}
+
+/// Runtime embeds the tree-walking interpreter in a host program, holding the
+/// globals defined by each evaluated source so that they can be read and
+/// called from rust.
+///
+/// # Examples
+/// ```
+/// use rlox::runtime::Runtime;
+///
+/// let mut runtime = Runtime::new();
+/// runtime.eval("fun add(a, b) { return a + b; }").unwrap();
+///
+/// let sum: f64 = runtime.call("add", (1.0, 2.0)).unwrap();
+/// assert_eq!(3.0, sum);
+/// ```
+pub struct Runtime {
+    analyzer: ScopeAnalyzer,
+    interpreter: StatefulInterpreter,
+}
+
+impl Runtime {
+    /// new returns a runtime with the standard native functions defined.
+    pub fn new() -> Self {
+        Runtime {
+            analyzer: ScopeAnalyzer::new(),
+            interpreter: StatefulInterpreter::new(),
+        }
+    }
+
+    /// register_native defines a native function as a builtin callable from
+    /// evaluated source, replacing any builtin with the same name.
+    pub fn register_native<F>(&mut self, name: &str, arity: Arity, func: F)
+    where
+        F: Fn(Vec<Object>) -> CallResult + 'static,
+    {
+        self.analyzer.declare_builtin(name);
+        self.interpreter.register_native(name, arity, func);
+    }
+
+    /// eval runs source against the runtime's globals. Globals defined by the
+    /// source remain available to later calls.
+    pub fn eval(&mut self, source: &str) -> RuntimeResult<()> {
+        let stmts = parse(source).map_err(RuntimeErr::Eval)?;
+        self.execute(stmts)
+    }
+
+    fn execute(&mut self, stmts: Vec<Stmt>) -> RuntimeResult<()> {
+        // declarations are rolled back if evaluation fails, so that a global
+        // whose initializer failed is reported as undefined.
+        let snapshot = self.analyzer.clone();
+        let result = self
+            .analyzer
+            .tree_pass(stmts)
+            .map_err(|e| RuntimeErr::Eval(vec![e.into()]))
+            .and_then(|analyzed| {
+                self.interpreter
+                    .tree_pass(analyzed)
+                    .map_err(|e| RuntimeErr::Eval(vec![e.into()]))
+            });
+        if result.is_err() {
+            self.analyzer = snapshot;
+        }
+
+        result.map(|_| ())
+    }
+
+    /// get_global returns the value of a global, converted to the requested
+    /// type.
+    pub fn get_global<T: FromLox>(&self, name: &str) -> RuntimeResult<T> {
+        let obj = self.lookup(name)?;
+        Ok(T::from_lox(obj)?)
+    }
+
+    /// call invokes the function, or class, held by a global with the passed
+    /// arguments, converting its return value to the requested type.
+    pub fn call<T: FromLox, A: IntoLoxArgs>(&self, name: &str, args: A) -> RuntimeResult<T> {
+        let callable = match self.lookup(name)? {
+            Object::Call(callable) => callable,
+            _ => return Err(RuntimeErr::NotCallable(name.to_string())),
+        };
+
+        // calls from the host have no call site in source.
+        let obj = self
+            .interpreter
+            .call(Rc::new(*callable), args.into_lox_args(), Span::default())
+            .map_err(|e| RuntimeErr::Call(name.to_string(), CallError::Runtime(Box::new(e))))?;
+        Ok(T::from_lox(obj)?)
+    }
+
+    fn lookup(&self, name: &str) -> RuntimeResult<Object> {
+        self.analyzer
+            .resolve(&identifier_name!(name))
+            .and_then(|id| self.interpreter.lookup(&id))
+            .ok_or_else(|| RuntimeErr::Undefined(name.to_string()))
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
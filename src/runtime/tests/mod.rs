@@ -0,0 +1,188 @@
+use crate::ast::span::Span;
+use crate::diagnostic::Diagnostic;
+use crate::functions::{Arity, CallError};
+use crate::interpreter::ExprInterpreterErr;
+use crate::object::Object;
+use crate::runtime::{ConversionErr, Runtime, RuntimeErr};
+
+#[test]
+fn globals_should_persist_between_evals() {
+    let mut runtime = Runtime::new();
+
+    assert_eq!(Ok(()), runtime.eval("var a = 1;"));
+    assert_eq!(Ok(()), runtime.eval("var b = a + 1;"));
+    assert_eq!(Ok(2.0), runtime.get_global::<f64>("b"));
+}
+
+#[test]
+fn should_convert_globals_to_the_requested_type() {
+    let mut runtime = Runtime::new();
+    runtime
+        .eval("var name = \"lox\"; var flag = true; var empty = nil;")
+        .unwrap();
+
+    assert_eq!(Ok("lox".to_string()), runtime.get_global::<String>("name"));
+    assert_eq!(Ok(true), runtime.get_global::<bool>("flag"));
+    assert_eq!(Ok(None), runtime.get_global::<Option<f64>>("empty"));
+    assert_eq!(
+        Err(RuntimeErr::Conversion(ConversionErr::new(
+            "number",
+            obj_str!("lox".to_string())
+        ))),
+        runtime.get_global::<f64>("name")
+    );
+}
+
+#[test]
+fn should_error_on_undefined_globals() {
+    let runtime = Runtime::new();
+
+    assert_eq!(
+        Err(RuntimeErr::Undefined("missing".to_string())),
+        runtime.get_global::<Object>("missing")
+    );
+    assert_eq!(
+        Err(RuntimeErr::Undefined("missing".to_string())),
+        runtime.call::<Object, _>("missing", ())
+    );
+}
+
+#[test]
+fn globals_declared_in_blocks_should_not_be_visible() {
+    let mut runtime = Runtime::new();
+    runtime.eval("{ var local = 1; }").unwrap();
+
+    assert_eq!(
+        Err(RuntimeErr::Undefined("local".to_string())),
+        runtime.get_global::<f64>("local")
+    );
+}
+
+#[test]
+fn should_call_functions_defined_by_a_script() {
+    let mut runtime = Runtime::new();
+    runtime
+        .eval("fun greet(greeting, name) { return greeting + \", \" + name; }")
+        .unwrap();
+
+    assert_eq!(
+        Ok("hello, lox".to_string()),
+        runtime.call::<String, _>("greet", ("hello", "lox"))
+    );
+}
+
+#[test]
+fn calls_should_observe_and_mutate_globals() {
+    let mut runtime = Runtime::new();
+    runtime
+        .eval("var count = 0; fun incr(n) { count = count + n; return count; }")
+        .unwrap();
+
+    assert_eq!(Ok(2.0), runtime.call::<f64, _>("incr", vec![2.0]));
+    assert_eq!(Ok(5.0), runtime.call::<f64, _>("incr", vec![3.0]));
+    assert_eq!(Ok(5.0), runtime.get_global::<f64>("count"));
+}
+
+#[test]
+fn should_call_classes_to_construct_instances() {
+    let mut runtime = Runtime::new();
+    runtime
+        .eval("class Point { init(x) { this.x = x; } }")
+        .unwrap();
+
+    match runtime.call::<Object, _>("Point", (1.0,)) {
+        Ok(Object::Instance(_)) => (),
+        other => panic!("expected an instance, found {:?}", other),
+    }
+}
+
+#[test]
+fn calls_should_check_arity_and_callability() {
+    let mut runtime = Runtime::new();
+    runtime.eval("var a = 1; fun f(x) { return x; }").unwrap();
+
+    assert_eq!(
+        Err(RuntimeErr::NotCallable("a".to_string())),
+        runtime.call::<Object, _>("a", ())
+    );
+    assert_eq!(
+        Err(RuntimeErr::Call(
+            "f".to_string(),
+            CallError::Runtime(Box::new(ExprInterpreterErr::CallErr(
+                "expected 1 arguments but got 0".to_string()
+            )))
+        )),
+        runtime.call::<Object, _>("f", ())
+    );
+}
+
+#[test]
+fn eval_should_report_diagnostics() {
+    let mut runtime = Runtime::new();
+
+    match runtime.eval("print undefined;") {
+        Err(RuntimeErr::Eval(diagnostics)) => assert_eq!(
+            vec![Diagnostic::error(
+                "E0200",
+                "undefined variable 'undefined'",
+                Span::new(6, 15, 1, 7)
+            )],
+            diagnostics
+        ),
+        other => panic!("expected diagnostics, found {:?}", other),
+    }
+
+    // a failed eval leaves earlier globals intact.
+    runtime.eval("var a = 1;").unwrap();
+    assert!(runtime.eval("var b = ;").is_err());
+    assert_eq!(Ok(1.0), runtime.get_global::<f64>("a"));
+}
+
+#[test]
+fn failed_evals_should_not_declare_globals() {
+    let mut runtime = Runtime::new();
+
+    assert!(runtime.eval("var x = 1/0;").is_err());
+    assert_eq!(
+        Err(RuntimeErr::Undefined("x".to_string())),
+        runtime.get_global::<f64>("x")
+    );
+
+    // later source is analyzed as though x was never declared.
+    match runtime.eval("print x;") {
+        Err(RuntimeErr::Eval(diagnostics)) => assert_eq!("E0200", diagnostics[0].code),
+        other => panic!("expected diagnostics, found {:?}", other),
+    }
+}
+
+#[test]
+fn errors_raised_by_calls_should_be_traced() {
+    let mut runtime = Runtime::new();
+    runtime.eval("fun f() { return 1/0; }").unwrap();
+
+    match runtime.call::<Object, _>("f", ()) {
+        Err(RuntimeErr::Call(_, CallError::Runtime(e))) => match *e {
+            ExprInterpreterErr::Traced(_, frames) => {
+                assert_eq!(
+                    vec!["f".to_string()],
+                    frames.into_iter().map(|f| f.name).collect::<Vec<_>>()
+                )
+            }
+            other => panic!("expected a traced error, found {:?}", other),
+        },
+        other => panic!("expected a call error, found {:?}", other),
+    }
+}
+
+#[test]
+fn should_define_registered_natives() {
+    let mut runtime = Runtime::new();
+    runtime.register_native("double", Arity::Fixed(1), |args| match &args[..] {
+        [Object::Literal(crate::object::Literal::Number(n))] => Ok(obj_number!(n * 2.0)),
+        _ => Err(CallError::Type("expected a number".to_string())),
+    });
+    runtime.eval("var result = double(21);").unwrap();
+
+    assert_eq!(Ok(42.0), runtime.get_global::<f64>("result"));
+    assert_eq!(Ok(4.0), runtime.call::<f64, _>("double", (2.0,)));
+}
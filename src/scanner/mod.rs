@@ -1,6 +1,6 @@
 pub mod source_scanner;
 
-pub use source_scanner::{LexResult, Scanner};
+pub use source_scanner::{LexError, LexResult, Scanner};
 
 #[cfg(test)]
 mod tests;
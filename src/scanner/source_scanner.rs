@@ -3,13 +3,59 @@ use std::option::Option::{None, Some};
 
 use std::iter::Iterator;
 
+use crate::ast::span::Span;
 use crate::ast::token::{Token, TokenType};
+use crate::diagnostic::Diagnostic;
+use std::fmt;
+
+/// LexError represents a lexical error, carrying the span of source that
+/// could not be lexed.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexError {
+    UnexpectedCharacter(char, Span),
+    InvalidComment(Span),
+    UnclosedString(Span),
+    InvalidNumber(Span),
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::UnexpectedCharacter(_, span)
+            | Self::InvalidComment(span)
+            | Self::UnclosedString(span)
+            | Self::InvalidNumber(span) => *span,
+        }
+    }
+}
 
-type LexError = String;
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter(c, _) => write!(f, "unexpected character '{}'", c),
+            Self::InvalidComment(_) => write!(f, "invalid comment"),
+            Self::UnclosedString(_) => write!(f, "unterminated string"),
+            Self::InvalidNumber(_) => write!(f, "invalid number"),
+        }
+    }
+}
+
+impl From<LexError> for Diagnostic {
+    fn from(err: LexError) -> Self {
+        let code = match err {
+            LexError::UnexpectedCharacter(_, _) => "E0001",
+            LexError::UnclosedString(_) => "E0002",
+            LexError::InvalidNumber(_) => "E0003",
+            LexError::InvalidComment(_) => "E0004",
+        };
+
+        Diagnostic::error(code, &err.to_string(), err.span())
+    }
+}
 
 /// LexResult is an alias that represents the result of an attempt to lex a
-/// single character token. Returning either the Token or a string containing
-/// positional data for the error.
+/// single character token. Returning either the Token or a LexError
+/// containing positional data for the error.
 pub type LexResult = Result<Token, LexError>;
 
 /// Cursor stores positional data for the scanner. Actively tracking index into
@@ -42,6 +88,12 @@ impl Cursor {
         }
     }
 
+    /// span returns a Span covering the characters from the start cursor
+    /// through to, and including, the end cursor.
+    fn span(start: Cursor, end: Cursor) -> Span {
+        Span::new(start.index, end.index + 1, start.line, start.col)
+    }
+
     fn newline(cursor: Cursor) -> Cursor {
         Cursor {
             index: cursor.index,
@@ -75,8 +127,19 @@ impl Scanner {
     }
 
     pub fn scan_tokens(&self) -> Vec<LexResult> {
+        self.scan_tokens_with_comments()
+            .into_iter()
+            .filter(
+                |lex_result| !matches!(lex_result, Ok(tok) if tok.token_type == TokenType::Comment),
+            )
+            .collect()
+    }
+
+    /// scan_tokens_with_comments scans source like scan_tokens, additionally
+    /// keeping each comment as a token carrying its text.
+    pub fn scan_tokens_with_comments(&self) -> Vec<LexResult> {
         let mut tokens: Vec<LexResult> = Vec::new();
-        let mut cursor = Cursor::new(0, 0, 1);
+        let mut cursor = Cursor::new(0, 1, 1);
 
         while !self.is_at_end(cursor) {
             let (t, next_cursor) = match self.scan_token(cursor) {
@@ -91,7 +154,12 @@ impl Scanner {
             tokens.push(t);
         }
 
-        tokens.push(Ok(Token::new(TokenType::EOF, cursor.line, None, None)));
+        tokens.push(Ok(Token::new(
+            TokenType::EOF,
+            Span::new(cursor.index, cursor.index, cursor.line, cursor.col),
+            None,
+            None,
+        )));
         tokens
     }
 
@@ -104,7 +172,7 @@ impl Scanner {
             '(' => (
                 Some(Ok(Token::new(
                     TokenType::LeftParen,
-                    cursor.line,
+                    Cursor::span(cursor, cursor),
                     None,
                     None,
                 ))),
@@ -113,7 +181,7 @@ impl Scanner {
             ')' => (
                 Some(Ok(Token::new(
                     TokenType::RightParen,
-                    cursor.line,
+                    Cursor::span(cursor, cursor),
                     None,
                     None,
                 ))),
@@ -122,7 +190,7 @@ impl Scanner {
             '{' => (
                 Some(Ok(Token::new(
                     TokenType::LeftBrace,
-                    cursor.line,
+                    Cursor::span(cursor, cursor),
                     None,
                     None,
                 ))),
@@ -131,39 +199,91 @@ impl Scanner {
             '}' => (
                 Some(Ok(Token::new(
                     TokenType::RightBrace,
-                    cursor.line,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
+                cursor,
+            ),
+            '[' => (
+                Some(Ok(Token::new(
+                    TokenType::LeftBracket,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
+                cursor,
+            ),
+            ']' => (
+                Some(Ok(Token::new(
+                    TokenType::RightBracket,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
+                cursor,
+            ),
+            ':' => (
+                Some(Ok(Token::new(
+                    TokenType::Colon,
+                    Cursor::span(cursor, cursor),
                     None,
                     None,
                 ))),
                 cursor,
             ),
             ',' => (
-                Some(Ok(Token::new(TokenType::Comma, cursor.line, None, None))),
+                Some(Ok(Token::new(
+                    TokenType::Comma,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
                 cursor,
             ),
             '.' => (
-                Some(Ok(Token::new(TokenType::Dot, cursor.line, None, None))),
+                Some(Ok(Token::new(
+                    TokenType::Dot,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
                 cursor,
             ),
             '-' => (
-                Some(Ok(Token::new(TokenType::Minus, cursor.line, None, None))),
+                Some(Ok(Token::new(
+                    TokenType::Minus,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
                 cursor,
             ),
             '+' => (
-                Some(Ok(Token::new(TokenType::Plus, cursor.line, None, None))),
+                Some(Ok(Token::new(
+                    TokenType::Plus,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
                 cursor,
             ),
             ';' => (
                 Some(Ok(Token::new(
                     TokenType::Semicolon,
-                    cursor.line,
+                    Cursor::span(cursor, cursor),
                     None,
                     None,
                 ))),
                 cursor,
             ),
             '*' => (
-                Some(Ok(Token::new(TokenType::Star, cursor.line, None, None))),
+                Some(Ok(Token::new(
+                    TokenType::Star,
+                    Cursor::span(cursor, cursor),
+                    None,
+                    None,
+                ))),
                 cursor,
             ),
 
@@ -193,16 +313,21 @@ impl Scanner {
             '/' => {
                 let peek = Cursor::advance(cursor);
                 match self.char_at(peek) {
-                    Some('/') => match self.match_simple_comment(peek) {
-                        (Ok(_), next_cursor) => (None, next_cursor),
-                        (Err(e), next_cursor) => (Some(Err(e)), next_cursor),
-                    },
-                    Some('*') => match self.match_c_comment(peek) {
-                        (Ok(_), next_cursor) => (None, next_cursor),
-                        (Err(e), next_cursor) => (Some(Err(e)), next_cursor),
-                    },
+                    Some('/') => {
+                        let (lex_result, next_cursor) = self.match_simple_comment(cursor);
+                        (Some(lex_result), next_cursor)
+                    }
+                    Some('*') => {
+                        let (lex_result, next_cursor) = self.match_c_comment(cursor);
+                        (Some(lex_result), next_cursor)
+                    }
                     _ => (
-                        Some(Ok(Token::new(TokenType::Slash, cursor.line, None, None))),
+                        Some(Ok(Token::new(
+                            TokenType::Slash,
+                            Cursor::span(cursor, cursor),
+                            None,
+                            None,
+                        ))),
                         cursor,
                     ),
                 }
@@ -232,9 +357,9 @@ impl Scanner {
             }
             // Unknown lexemes
             _ => (
-                Some(Err(format!(
-                    "Lex error at line: {}, position: {}.",
-                    cursor.line, cursor.col
+                Some(Err(LexError::UnexpectedCharacter(
+                    current,
+                    Cursor::span(cursor, cursor),
                 ))),
                 cursor,
             ),
@@ -251,55 +376,81 @@ impl Scanner {
         let current = Cursor::advance(start);
         match self.char_at(current) {
             Some(_) if self.char_at(current).unwrap() == expected_next => (
-                Ok(Token::new(if_matches, current.line, None, None)),
+                Ok(Token::new(
+                    if_matches,
+                    Cursor::span(start, current),
+                    None,
+                    None,
+                )),
                 current,
             ),
-            _ => (Ok(Token::new(if_no_match, current.line, None, None)), start),
+            _ => (
+                Ok(Token::new(
+                    if_no_match,
+                    Cursor::span(start, start),
+                    None,
+                    None,
+                )),
+                start,
+            ),
         }
     }
 
+    /// match_simple_comment matches a comment running from its opening `//`
+    /// to the end of the line, or of the source.
     fn match_simple_comment(&self, start: Cursor) -> (LexResult, Cursor) {
-        let mut current = start;
+        let mut current = Cursor::advance(start);
         loop {
             current = Cursor::advance(current);
-            if let Some('\n') = self.char_at(current) {
-                return (
-                    Ok(Token::new(TokenType::Comment, start.line, None, None)),
-                    Cursor::newline(current),
-                );
+            match self.char_at(current) {
+                Some('\n') => {
+                    let end = Cursor::reverse(current);
+                    return (Ok(self.comment(start, end)), Cursor::newline(current));
+                }
+                Some(_) => continue,
+                None => {
+                    let end = Cursor::reverse(current);
+                    return (Ok(self.comment(start, end)), end);
+                }
             }
         }
     }
 
+    /// match_c_comment matches a comment running from its opening `/*` to
+    /// the next `*/`, which may span multiple lines.
     fn match_c_comment(&self, start: Cursor) -> (LexResult, Cursor) {
-        let mut current = start;
+        let mut current = Cursor::advance(start);
         loop {
             current = Cursor::advance(current);
-            if let Some('*') = self.char_at(current) {
-                let peek = Cursor::advance(current);
-                match self.char_at(peek) {
-                    Some('/') => {
-                        return (
-                            Ok(Token::new(TokenType::Comment, peek.line, None, None)),
-                            peek,
-                        );
-                    }
-                    _ => {
-                        return (
-                            Err(format!(
-                                "Invalid comment at line: {}, position: {}.",
-                                peek.line, peek.col
-                            )),
-                            peek,
-                        );
-                    }
+            match self.char_at(current) {
+                Some('*') if self.char_at(Cursor::advance(current)) == Some('/') => {
+                    let end = Cursor::advance(current);
+                    return (Ok(self.comment(start, end)), end);
+                }
+                Some('\n') => current = Cursor::newline(current),
+                Some(_) => continue,
+                None => {
+                    let end = Cursor::reverse(current);
+                    return (Err(LexError::InvalidComment(Cursor::span(start, end))), end);
                 }
             }
         }
     }
 
-    fn match_string(&self, start: Cursor) -> (LexResult, Cursor) {
-        let start = Cursor::advance(start);
+    /// comment returns a comment token holding the source between the start
+    /// and end cursors.
+    fn comment(&self, start: Cursor, end: Cursor) -> Token {
+        let text: String = self.substring(start, end).iter().collect();
+        Token::new(
+            TokenType::Comment,
+            Cursor::span(start, end),
+            Some(text.trim_end().to_string()),
+            None,
+        )
+    }
+
+    fn match_string(&self, quote: Cursor) -> (LexResult, Cursor) {
+        let start = Cursor::advance(quote);
         let mut current = start;
 
         loop {
@@ -314,7 +465,7 @@ impl Scanner {
                     return (
                         Ok(Token::new(
                             TokenType::Str,
-                            current.line,
+                            Cursor::span(quote, current),
                             Some(format!("\"{}\"", literal_str)),
                             Some(obj_str!(literal_str)),
                         )),
@@ -324,10 +475,10 @@ impl Scanner {
                 Some(_) => continue,
                 None => {
                     return (
-                        Err(format!(
-                            "Unclosed string at line: {}, position: {}",
-                            current.line, current.col
-                        )),
+                        Err(LexError::UnclosedString(Cursor::span(
+                            quote,
+                            Cursor::reverse(current),
+                        ))),
                         current,
                     )
                 }
@@ -348,20 +499,14 @@ impl Scanner {
                             '0'..='9' => (),
                             _ => {
                                 return (
-                                    Err(format!(
-                                        "Invalid number at line: {}, position: {}",
-                                        current.line, current.col
-                                    )),
+                                    Err(LexError::InvalidNumber(Cursor::span(start, current))),
                                     current,
                                 )
                             }
                         },
                         None => {
                             return (
-                                Err(format!(
-                                    "Invalid number at line: {}, position: {}",
-                                    current.line, current.col
-                                )),
+                                Err(LexError::InvalidNumber(Cursor::span(start, current))),
                                 current,
                             )
                         }
@@ -376,17 +521,14 @@ impl Scanner {
                         Ok(n) => (
                             Ok(Token::new(
                                 TokenType::Number,
-                                current.line,
+                                Cursor::span(start, current),
                                 Some(literal_num),
                                 Some(obj_number!(n)),
                             )),
                             current,
                         ),
                         Err(_) => (
-                            Err(format!(
-                                "Invalid number at line: {}, position: {}",
-                                current.line, current.col,
-                            )),
+                            Err(LexError::InvalidNumber(Cursor::span(start, current))),
                             current,
                         ),
                     };
@@ -407,7 +549,7 @@ impl Scanner {
                     let ident_literal: String = self.substring(start, current).iter().collect();
                     let t = Token::new(
                         TokenType::Identifier,
-                        current.line,
+                        Cursor::span(start, current),
                         Some(ident_literal.trim().to_string()),
                         None,
                     );
@@ -416,7 +558,7 @@ impl Scanner {
                         Some(TokenType::True) => (
                             Ok(Token::new(
                                 TokenType::True,
-                                current.line,
+                                Cursor::span(start, current),
                                 None,
                                 Some(obj_bool!(true)),
                             )),
@@ -425,7 +567,7 @@ impl Scanner {
                         Some(TokenType::False) => (
                             Ok(Token::new(
                                 TokenType::False,
-                                current.line,
+                                Cursor::span(start, current),
                                 None,
                                 Some(obj_bool!(false)),
                             )),
@@ -434,14 +576,19 @@ impl Scanner {
                         Some(TokenType::Nil) => (
                             Ok(Token::new(
                                 TokenType::Nil,
-                                current.line,
+                                Cursor::span(start, current),
                                 None,
                                 Some(obj_nil!()),
                             )),
                             current,
                         ),
                         Some(token_type) => (
-                            Ok(Token::new(token_type, current.line, None, None)),
+                            Ok(Token::new(
+                                token_type,
+                                Cursor::span(start, current),
+                                None,
+                                None,
+                            )),
                             current,
                         ),
                         None => (Ok(t), current),
@@ -1,5 +1,7 @@
+use crate::ast::span::Span;
 use crate::ast::token::{Token, TokenType};
-use crate::scanner::source_scanner::{LexResult, Scanner};
+use crate::diagnostic::Diagnostic;
+use crate::scanner::source_scanner::{LexError, LexResult, Scanner};
 use std::option::Option::{None, Some};
 
 #[test]
@@ -11,7 +13,7 @@ fn into_iter_should_return_characters_from_iterators() {
         iter.next(),
         Some(LexResult::Ok(Token {
             token_type: TokenType::Semicolon,
-            line: 1,
+            span: Span::new(0, 1, 1, 1),
             lexeme: None,
             object: None,
         }))
@@ -20,7 +22,7 @@ fn into_iter_should_return_characters_from_iterators() {
         iter.next(),
         Some(LexResult::Ok(Token {
             token_type: TokenType::Plus,
-            line: 1,
+            span: Span::new(1, 2, 1, 2),
             lexeme: None,
             object: None,
         }))
@@ -29,7 +31,7 @@ fn into_iter_should_return_characters_from_iterators() {
         iter.next(),
         Some(LexResult::Ok(Token {
             token_type: TokenType::Minus,
-            line: 1,
+            span: Span::new(2, 3, 1, 3),
             lexeme: None,
             object: None,
         }))
@@ -38,10 +40,90 @@ fn into_iter_should_return_characters_from_iterators() {
         iter.next(),
         Some(LexResult::Ok(Token {
             token_type: TokenType::EOF,
-            line: 1,
+            span: Span::new(3, 3, 1, 4),
             lexeme: None,
             object: None,
         }))
     );
     assert_eq!(iter.next(), None);
 }
+
+#[test]
+fn scan_tokens_should_record_token_spans() {
+    let s = Scanner::new("var a = \"hi\";\nprint a >= 10;".to_string());
+    let spans: Vec<Span> = s
+        .scan_tokens()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap().span)
+        .collect();
+
+    assert_eq!(
+        vec![
+            Span::new(0, 3, 1, 1),
+            Span::new(4, 5, 1, 5),
+            Span::new(6, 7, 1, 7),
+            Span::new(8, 12, 1, 9),
+            Span::new(12, 13, 1, 13),
+            Span::new(14, 19, 2, 1),
+            Span::new(20, 21, 2, 7),
+            Span::new(22, 24, 2, 9),
+            Span::new(25, 27, 2, 12),
+            Span::new(27, 28, 2, 14),
+            Span::new(28, 28, 2, 15),
+        ],
+        spans
+    );
+}
+
+#[test]
+fn lex_errors_should_convert_to_a_diagnostic() {
+    let s = Scanner::new("var a = \"hi;".to_string());
+    let err = s
+        .scan_tokens()
+        .into_iter()
+        .find_map(|lex_result| lex_result.err())
+        .unwrap();
+
+    assert_eq!(
+        Diagnostic::error("E0002", "unterminated string", Span::new(8, 12, 1, 9)),
+        Diagnostic::from(err)
+    );
+}
+
+#[test]
+fn scan_tokens_with_comments_should_keep_the_text_of_each_comment() {
+    let s = Scanner::new("/* a * b\n*/ print 1; // one".to_string());
+    let tokens: Vec<Token> = s
+        .scan_tokens_with_comments()
+        .into_iter()
+        .map(|lex_result| lex_result.unwrap())
+        .collect();
+
+    assert_eq!(
+        Token::new(
+            TokenType::Comment,
+            Span::new(0, 11, 1, 1),
+            Some("/* a * b\n*/".to_string()),
+            None
+        ),
+        tokens[0]
+    );
+    assert_eq!(Span::new(12, 17, 2, 4), tokens[1].span);
+    assert_eq!(Some("// one".to_string()), tokens[4].lexeme);
+    assert_eq!(TokenType::EOF, tokens[5].token_type);
+
+    // comments are dropped by default.
+    assert_eq!(4, s.scan_tokens().len());
+}
+
+#[test]
+fn unterminated_block_comments_should_err() {
+    let s = Scanner::new("print 1; /* never closed".to_string());
+
+    assert_eq!(
+        Some(LexError::InvalidComment(Span::new(9, 24, 1, 10))),
+        s.scan_tokens()
+            .into_iter()
+            .find_map(|lex_result| lex_result.err())
+    );
+}
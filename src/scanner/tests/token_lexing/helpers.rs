@@ -1,7 +1,18 @@
+use crate::ast::span::Span;
 use crate::ast::token::{Token, TokenType};
 use crate::object;
 use crate::scanner::*;
 
+/// span_on_line returns the span of a lexed token with its line replaced by
+/// the passed line, allowing helpers to assert on the line a token was lexed
+/// on without depending on the exact offsets of the source.
+fn span_on_line(lex_result: &LexResult, line: usize) -> Span {
+    match lex_result {
+        Ok(token) => Span { line, ..token.span },
+        Err(_) => Span::default(),
+    }
+}
+
 pub fn compare_single_token_source_helper(
     single_token_source: &str,
     expected_token_type: TokenType,
@@ -14,7 +25,7 @@ pub fn compare_single_token_source_helper(
         token_results[0],
         LexResult::Ok(Token {
             token_type: expected_token_type,
-            line: 1,
+            span: span_on_line(&token_results[0], 1),
             lexeme: None,
             object: None,
         })
@@ -35,7 +46,7 @@ pub fn compare_single_token_source_with_literal_helper(
         token_results[0],
         LexResult::Ok(Token {
             token_type: expected_token_type,
-            line: 1,
+            span: span_on_line(&token_results[0], 1),
             lexeme: if lexeme.len() > 0 {
                 Some(lexeme.trim().to_string())
             } else {
@@ -56,7 +67,7 @@ pub fn compare_single_token_source_returns_none_helper(single_token_source: &str
         token_results[0],
         LexResult::Ok(Token {
             token_type: TokenType::EOF,
-            line: line,
+            span: span_on_line(&token_results[0], line),
             lexeme: None,
             object: None,
         })
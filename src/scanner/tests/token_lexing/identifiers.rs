@@ -40,6 +40,15 @@ fn scan_tokens_should_lex_reserved_keywords() {
     compare_single_token_source_helper("fun", TokenType::Fun);
     compare_single_token_source_helper("while", TokenType::While);
     compare_single_token_source_helper("for", TokenType::For);
+    compare_single_token_source_helper("in", TokenType::In);
+    compare_single_token_source_helper("break", TokenType::Break);
+    compare_single_token_source_helper("continue", TokenType::Continue);
+    compare_single_token_source_helper("import", TokenType::Import);
+    compare_single_token_source_helper("as", TokenType::As);
+    compare_single_token_source_helper("throw", TokenType::Throw);
+    compare_single_token_source_helper("try", TokenType::Try);
+    compare_single_token_source_helper("catch", TokenType::Catch);
+    compare_single_token_source_helper("finally", TokenType::Finally);
     compare_single_token_source_helper("if", TokenType::If);
     compare_single_token_source_helper("else", TokenType::Else);
 }
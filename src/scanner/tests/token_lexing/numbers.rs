@@ -1,3 +1,4 @@
+use crate::ast::span::Span;
 use crate::ast::token::{Token, TokenType};
 use crate::scanner::*;
 
@@ -31,7 +32,7 @@ fn scan_tokens_should_not_allow_trailing_decimal() {
 
     assert_eq!(
         token_results[0],
-        LexResult::Err("Invalid number at line: 1, position: 3".to_string())
+        LexResult::Err(LexError::InvalidNumber(Span::new(0, 4, 1, 1)))
     );
 }
 
@@ -46,25 +47,25 @@ fn scan_tokens_should_allow_numbers_to_include_operators() {
         vec![
             LexResult::Ok(Token {
                 token_type: TokenType::Number,
-                line: 1,
+                span: Span::new(0, 1, 1, 1),
                 lexeme: Some("5".to_string()),
                 object: Some(obj_number!(5.0)),
             }),
             LexResult::Ok(Token {
                 token_type: TokenType::Plus,
-                line: 1,
+                span: Span::new(1, 2, 1, 2),
                 lexeme: None,
                 object: None,
             }),
             LexResult::Ok(Token {
                 token_type: TokenType::Number,
-                line: 1,
+                span: Span::new(2, 3, 1, 3),
                 lexeme: Some("5".to_string()),
                 object: Some(obj_number!(5.0)),
             }),
             LexResult::Ok(Token {
                 token_type: TokenType::EOF,
-                line: 1,
+                span: Span::new(3, 3, 1, 4),
                 lexeme: None,
                 object: None,
             })
@@ -1,3 +1,4 @@
+use crate::ast::span::Span;
 use crate::ast::token::TokenType;
 use crate::scanner::*;
 
@@ -11,6 +12,9 @@ fn scan_tokens_should_lex_single_character_lexemes() {
     compare_single_token_source_helper(")", TokenType::RightParen);
     compare_single_token_source_helper("{", TokenType::LeftBrace);
     compare_single_token_source_helper("}", TokenType::RightBrace);
+    compare_single_token_source_helper("[", TokenType::LeftBracket);
+    compare_single_token_source_helper("]", TokenType::RightBracket);
+    compare_single_token_source_helper(":", TokenType::Colon);
     compare_single_token_source_helper(",", TokenType::Comma);
     compare_single_token_source_helper(".", TokenType::Dot);
     compare_single_token_source_helper("-", TokenType::Minus);
@@ -59,6 +63,6 @@ fn lex_unknown_token_returns_error_result() {
     assert_eq!(2, token_results.len());
     assert_eq!(
         token_results[0],
-        LexResult::Err("Lex error at line: 1, position: 0.".to_string())
+        LexResult::Err(LexError::UnexpectedCharacter('%', Span::new(0, 1, 1, 1)))
     );
 }
@@ -1,25 +1,25 @@
-use crate::ast::expression::Expr;
-use crate::ast::identifier::Identifier;
-use crate::ast::statement::Stmt;
-use crate::environment::Environment;
-use crate::functions;
+use crate::functions::{Arity, CallResult, StaticFunc};
+use crate::iterator;
 use crate::object::Object;
-use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn define_statics_ast() -> Vec<Stmt> {
-    vec![Stmt::Declaration(
-        identifier_name!("clock"),
-        Expr::Primary(obj_call!(Box::new(functions::Callable::Static(
-            functions::StaticFunc::new(clock)
-        )))),
-    )]
+#[cfg(test)]
+mod tests;
+
+/// natives returns the standard native functions. These are defined as
+/// builtins of every interpreter and virtual machine, alongside any
+/// registered by the host with `register_native`.
+pub fn natives() -> Vec<StaticFunc> {
+    vec![
+        StaticFunc::new("clock", Arity::Fixed(0), clock),
+        StaticFunc::new("range", Arity::Variadic(2), iterator::range),
+    ]
 }
 
-fn clock(_env: Rc<Environment<Identifier, Object>>, _args: Vec<Object>) -> Object {
+fn clock(_args: Vec<Object>) -> CallResult {
     let t = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_millis() as f64;
-    obj_number!(t)
+    Ok(obj_number!(t))
 }
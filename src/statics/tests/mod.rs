@@ -0,0 +1,192 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::expression::Expr;
+use crate::ast::span::Span;
+use crate::ast::statement::Stmt;
+use crate::compiler::Compiler;
+use crate::functions::{Arity, CallError};
+use crate::interpreter::{ExprInterpreterErr, StatefulInterpreter, StmtInterpreterErr};
+use crate::object::{Literal, Object};
+use crate::pass::*;
+use crate::statics;
+use crate::vm::{Vm, VmErr};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn call(name: &str, args: Vec<Object>) -> Stmt {
+    Stmt::Return(
+        Some(Expr::Call(
+            Box::new(Expr::Variable(identifier_name!(name), Span::default())),
+            args.into_iter()
+                .map(|arg| Expr::Primary(arg, Span::default()))
+                .collect(),
+            Span::default(),
+        )),
+        Span::default(),
+    )
+}
+
+fn call_stmt(name: &str, args: Vec<Object>) -> Stmt {
+    match call(name, args) {
+        Stmt::Return(Some(expr), span) => Stmt::Expression(expr, span),
+        _ => unreachable!(),
+    }
+}
+
+fn run(
+    interpreter: &StatefulInterpreter,
+    program: Vec<Stmt>,
+) -> Result<Option<Object>, StmtInterpreterErr> {
+    let analyzed = interpreter.analyzer().tree_pass(program).unwrap();
+    interpreter.tree_pass(analyzed)
+}
+
+fn run_vm(vm: &mut Vm, builtins: &[&str], program: Vec<Stmt>) -> Result<(), VmErr> {
+    let mut analyzer = ScopeAnalyzer::new();
+    for name in builtins {
+        analyzer.declare_builtin(name);
+    }
+
+    let analyzed = analyzer.tree_pass(program).unwrap();
+    let script = Compiler::new().tree_pass(analyzed).unwrap();
+    vm.run(script)
+}
+
+fn sum(args: Vec<Object>) -> Result<Object, CallError> {
+    args.into_iter()
+        .try_fold(0.0, |sum, arg| match arg {
+            Object::Literal(Literal::Number(n)) => Ok(sum + n),
+            obj => Err(CallError::Type(format!("expected number, found {}", obj))),
+        })
+        .map(|sum| obj_number!(sum))
+}
+
+#[test]
+fn should_define_the_standard_natives_as_builtins() {
+    let names: Vec<String> = statics::natives()
+        .iter()
+        .map(|native| native.name().to_string())
+        .collect();
+    assert_eq!(vec!["clock", "range"], names);
+
+    assert!(matches!(
+        run(&StatefulInterpreter::new(), vec![call("clock", vec![])]),
+        Ok(Some(Object::Literal(Literal::Number(_))))
+    ));
+    assert_eq!(
+        Ok(()),
+        run_vm(&mut Vm::new(), &[], vec![call_stmt("clock", vec![])])
+    );
+}
+
+#[test]
+fn registered_natives_should_be_callable_with_arguments() {
+    let interpreter = StatefulInterpreter::new();
+    interpreter.register_native("max", Arity::Fixed(2), |args| match &args[..] {
+        [Object::Literal(Literal::Number(a)), Object::Literal(Literal::Number(b))] => {
+            Ok(obj_number!(a.max(*b)))
+        }
+        _ => Err(CallError::Type("max expects numbers".to_string())),
+    });
+
+    assert_eq!(
+        Ok(Some(obj_number!(3.0))),
+        run(
+            &interpreter,
+            vec![call("max", vec![obj_number!(3.0), obj_number!(1.0)])]
+        )
+    );
+}
+
+#[test]
+fn variadic_natives_should_accept_any_number_of_arguments() {
+    let interpreter = StatefulInterpreter::new();
+    interpreter.register_native("sum", Arity::Variadic(1), sum);
+
+    assert_eq!(
+        Ok(Some(obj_number!(6.0))),
+        run(
+            &interpreter,
+            vec![call(
+                "sum",
+                vec![obj_number!(1.0), obj_number!(2.0), obj_number!(3.0)]
+            )]
+        )
+    );
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::CallErr(
+                "expected at least 1 arguments but got 0".to_string()
+            )),
+            Span::default()
+        ))),
+        run(&interpreter, vec![call("sum", vec![])])
+    );
+}
+
+#[test]
+fn native_argument_errors_should_be_runtime_errors() {
+    let interpreter = StatefulInterpreter::new();
+    interpreter.register_native("sum", Arity::Variadic(0), sum);
+
+    assert_eq!(
+        Err(StmtInterpreterErr::Expression(ExprInterpreterErr::Spanned(
+            Box::new(ExprInterpreterErr::InvalidArgument(
+                "invalid argument type: expected number, found a".to_string()
+            )),
+            Span::default()
+        ))),
+        run(
+            &interpreter,
+            vec![call("sum", vec![obj_str!("a".to_string())])]
+        )
+    );
+}
+
+#[test]
+fn natives_should_capture_host_state() {
+    let calls = Rc::new(RefCell::new(0));
+    let counter = calls.clone();
+    let interpreter = StatefulInterpreter::new();
+    interpreter.register_native("tick", Arity::Fixed(0), move |_| {
+        *counter.borrow_mut() += 1;
+        Ok(obj_number!(*counter.borrow() as f64))
+    });
+
+    let program = vec![call_stmt("tick", vec![]), call("tick", vec![])];
+
+    assert_eq!(Ok(Some(obj_number!(2.0))), run(&interpreter, program));
+    assert_eq!(2, *calls.borrow());
+}
+
+#[test]
+fn registering_a_native_should_replace_one_with_the_same_name() {
+    let interpreter = StatefulInterpreter::new();
+    interpreter.register_native("clock", Arity::Fixed(0), |_| Ok(obj_nil!()));
+
+    assert_eq!(
+        Ok(Some(obj_nil!())),
+        run(&interpreter, vec![call("clock", vec![])])
+    );
+}
+
+#[test]
+fn native_errors_should_be_runtime_errors_in_the_vm() {
+    let mut vm = Vm::new();
+    vm.register_native("sum", Arity::Variadic(1), sum);
+
+    assert_eq!(
+        Err(VmErr::Arity(Arity::Variadic(1), 0, Span::default())),
+        run_vm(&mut vm, &["sum"], vec![call_stmt("sum", vec![])])
+    );
+    assert_eq!(
+        Err(VmErr::InvalidArgument(
+            "invalid argument type: expected number, found a".to_string(),
+            Span::default()
+        )),
+        run_vm(
+            &mut vm,
+            &["sum"],
+            vec![call_stmt("sum", vec![obj_str!("a".to_string())])]
+        )
+    );
+}
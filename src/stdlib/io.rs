@@ -0,0 +1,38 @@
+use super::{argument, Namespace};
+use crate::functions::{Arity, CallError, CallResult};
+use std::io::{self, BufRead, Write};
+
+/// namespace returns the `io` namespace of console functions.
+pub fn namespace() -> Namespace {
+    Namespace::new("io")
+        .with_native("read_line", Arity::Fixed(0), |_| {
+            read_line(&mut io::stdin().lock())
+        })
+        .with_native("write", Arity::Fixed(1), |args| {
+            let s: String = argument("io.write", &args[0])?;
+            let mut stdout = io::stdout();
+            stdout
+                .write_all(s.as_bytes())
+                .and_then(|_| stdout.flush())
+                .map_err(native_err)?;
+            Ok(obj_nil!())
+        })
+}
+
+/// read_line returns the next line from the reader, without its line ending,
+/// or nil once the reader is exhausted.
+pub(super) fn read_line<R: BufRead>(reader: &mut R) -> CallResult {
+    let mut line = String::new();
+    match reader.read_line(&mut line).map_err(native_err)? {
+        0 => Ok(obj_nil!()),
+        _ => {
+            let len = line.trim_end_matches(&['\r', '\n'][..]).len();
+            line.truncate(len);
+            Ok(obj_str!(line))
+        }
+    }
+}
+
+fn native_err(err: io::Error) -> CallError {
+    CallError::Native(err.to_string())
+}
@@ -0,0 +1,35 @@
+use super::{argument, Namespace};
+use crate::functions::{Arity, CallResult};
+use crate::object::Object;
+use std::f64::consts::PI;
+
+/// namespace returns the `math` namespace of numeric functions and constants.
+pub fn namespace() -> Namespace {
+    Namespace::new("math")
+        .with_constant("pi", obj_number!(PI))
+        .with_native("abs", Arity::Fixed(1), unary("math.abs", f64::abs))
+        .with_native("ceil", Arity::Fixed(1), unary("math.ceil", f64::ceil))
+        .with_native("floor", Arity::Fixed(1), unary("math.floor", f64::floor))
+        .with_native("round", Arity::Fixed(1), unary("math.round", f64::round))
+        .with_native("sqrt", Arity::Fixed(1), unary("math.sqrt", f64::sqrt))
+        .with_native("max", Arity::Fixed(2), binary("math.max", f64::max))
+        .with_native("min", Arity::Fixed(2), binary("math.min", f64::min))
+        .with_native("pow", Arity::Fixed(2), binary("math.pow", f64::powf))
+}
+
+/// unary wraps a function of one number as a native function.
+fn unary(name: &'static str, op: fn(f64) -> f64) -> impl Fn(Vec<Object>) -> CallResult {
+    move |args| {
+        let n: f64 = argument(name, &args[0])?;
+        Ok(obj_number!(op(n)))
+    }
+}
+
+/// binary wraps a function of two numbers as a native function.
+fn binary(name: &'static str, op: fn(f64, f64) -> f64) -> impl Fn(Vec<Object>) -> CallResult {
+    move |args| {
+        let lhv: f64 = argument(name, &args[0])?;
+        let rhv: f64 = argument(name, &args[1])?;
+        Ok(obj_number!(op(lhv, rhv)))
+    }
+}
@@ -0,0 +1,185 @@
+use crate::ast::identifier::Identifier;
+use crate::environment::Environment;
+use crate::functions::{Arity, CallResult, Callable, StaticFunc};
+use crate::object::Object;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(feature = "io")]
+mod io;
+#[cfg(feature = "math")]
+mod math;
+#[cfg(feature = "str")]
+mod string;
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(test)]
+mod tests;
+
+/// Members holds the members of a namespace. An imported module's members
+/// are the keys of its top-level declarations within the module's global
+/// environment, so that they reflect the module's current state.
+#[derive(Clone)]
+enum Members {
+    Values(HashMap<String, Object>),
+    Module(
+        Rc<Environment<Identifier, Object>>,
+        HashMap<String, Identifier>,
+    ),
+}
+
+/// Namespace represents a named group of members accessed as properties,
+/// such as the native functions and constants of `math` or the top-level
+/// declarations of an imported module. Clones of a namespace share the same
+/// members.
+#[derive(Clone)]
+pub struct Namespace {
+    name: String,
+    members: Rc<Members>,
+}
+
+impl Namespace {
+    pub fn new(name: &str) -> Self {
+        Namespace {
+            name: name.to_string(),
+            members: Rc::new(Members::Values(HashMap::new())),
+        }
+    }
+
+    /// module returns a namespace exposing the top-level declarations of a
+    /// module, given the environment it was run in and the key each
+    /// declaration's value is stored under.
+    pub fn module(
+        name: &str,
+        env: Rc<Environment<Identifier, Object>>,
+        globals: HashMap<String, Identifier>,
+    ) -> Self {
+        Namespace {
+            name: name.to_string(),
+            members: Rc::new(Members::Module(env, globals)),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// with_native adds a native function to the namespace. The function is
+    /// named after the namespace, so `sqrt` in `math` reports itself as
+    /// `math.sqrt`.
+    pub fn with_native<F>(self, name: &str, arity: Arity, func: F) -> Self
+    where
+        F: Fn(Vec<Object>) -> CallResult + 'static,
+    {
+        let func = StaticFunc::new(&format!("{}.{}", self.name, name), arity, func);
+        self.with_constant(name, obj_call!(Box::new(Callable::Static(func))))
+    }
+
+    /// with_constant adds a value to the namespace.
+    pub fn with_constant(mut self, name: &str, value: Object) -> Self {
+        match Rc::make_mut(&mut self.members) {
+            Members::Values(values) => {
+                values.insert(name.to_string(), value);
+            }
+            Members::Module(env, globals) => {
+                let key = Identifier::Name(name.to_string());
+                env.define(&key, value);
+                globals.insert(name.to_string(), key);
+            }
+        }
+        self
+    }
+
+    /// get returns the member of the namespace with the passed name.
+    pub fn get(&self, name: &str) -> Option<Object> {
+        match self.members.as_ref() {
+            Members::Values(values) => values.get(name).cloned(),
+            Members::Module(env, globals) => globals.get(name).and_then(|key| env.get(key)),
+        }
+    }
+
+    /// members returns the name and value of each member of the namespace.
+    pub fn members(&self) -> Vec<(String, Object)> {
+        match self.members.as_ref() {
+            Members::Values(values) => values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            Members::Module(env, globals) => globals
+                .iter()
+                .filter_map(|(name, key)| env.get(key).map(|value| (name.clone(), value)))
+                .collect(),
+        }
+    }
+
+    /// is returns true if both namespaces share the same members.
+    pub fn is(&self, other: &Namespace) -> bool {
+        Rc::ptr_eq(&self.members, &other.members)
+    }
+}
+
+impl fmt::Debug for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Namespace")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl PartialEq for Namespace {
+    fn eq(&self, other: &Self) -> bool {
+        self.is(other)
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<namespace {}>", self.name)
+    }
+}
+
+/// namespaces returns each namespace enabled by the crate's features. These
+/// are defined as builtins of every interpreter.
+pub fn namespaces() -> Vec<Namespace> {
+    vec![
+        #[cfg(feature = "io")]
+        io::namespace(),
+        #[cfg(feature = "math")]
+        math::namespace(),
+        #[cfg(feature = "str")]
+        string::namespace(),
+        #[cfg(feature = "time")]
+        time::namespace(),
+    ]
+}
+
+/// NAMESPACES holds the name of each namespace enabled by the crate's
+/// features.
+pub const NAMESPACES: &[&str] = &[
+    #[cfg(feature = "io")]
+    "io",
+    #[cfg(feature = "math")]
+    "math",
+    #[cfg(feature = "str")]
+    "str",
+    #[cfg(feature = "time")]
+    "time",
+];
+
+/// is_namespace returns true if a namespace with the passed name is enabled.
+pub fn is_namespace(name: &str) -> bool {
+    NAMESPACES.contains(&name)
+}
+
+/// argument converts an argument passed to the named function into the rust
+/// type the function expects.
+#[cfg(any(feature = "io", feature = "math", feature = "str"))]
+fn argument<T>(func: &str, arg: &Object) -> Result<T, crate::functions::CallError>
+where
+    T: crate::runtime::FromLox,
+{
+    T::from_lox(arg.clone())
+        .map_err(|e| crate::functions::CallError::Type(format!("{} {}", func, e)))
+}
@@ -0,0 +1,39 @@
+use super::{argument, Namespace};
+use crate::functions::{Arity, CallResult};
+use crate::object::Object;
+use crate::runtime::IntoLox;
+
+/// namespace returns the `str` namespace of string functions.
+pub fn namespace() -> Namespace {
+    Namespace::new("str")
+        .with_native("upper", Arity::Fixed(1), |args| {
+            map("str.upper", &args, |s| s.to_uppercase().into_lox())
+        })
+        .with_native("lower", Arity::Fixed(1), |args| {
+            map("str.lower", &args, |s| s.to_lowercase().into_lox())
+        })
+        .with_native("trim", Arity::Fixed(1), |args| {
+            map("str.trim", &args, |s| s.trim().into_lox())
+        })
+        .with_native("len", Arity::Fixed(1), |args| {
+            map("str.len", &args, |s| (s.chars().count() as f64).into_lox())
+        })
+        .with_native("contains", Arity::Fixed(2), |args| {
+            let s: String = argument("str.contains", &args[0])?;
+            let pattern: String = argument("str.contains", &args[1])?;
+            Ok(s.contains(&pattern).into_lox())
+        })
+        .with_native("split", Arity::Fixed(2), |args| {
+            let s: String = argument("str.split", &args[0])?;
+            let separator: String = argument("str.split", &args[1])?;
+            let parts: Vec<String> = s.split(&separator).map(String::from).collect();
+            Ok(parts.into_lox())
+        })
+}
+
+/// map applies a function to the single string argument of a native
+/// function.
+fn map(name: &str, args: &[Object], f: impl Fn(&str) -> Object) -> CallResult {
+    let s: String = argument(name, &args[0])?;
+    Ok(f(&s))
+}
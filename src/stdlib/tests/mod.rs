@@ -0,0 +1,120 @@
+use crate::functions::Arity;
+use crate::stdlib::{self, Namespace};
+
+#[cfg(any(feature = "math", feature = "str"))]
+fn call(
+    namespace: &Namespace,
+    member: &str,
+    args: Vec<crate::object::Object>,
+) -> crate::functions::CallResult {
+    use crate::functions::Callable;
+    use crate::object::Object;
+
+    match namespace.get(member) {
+        Some(Object::Call(callable)) => match *callable {
+            Callable::Static(func) => func.call(args),
+            other => panic!("expected a native function, found {}", other),
+        },
+        other => panic!("expected a native function, found {:?}", other),
+    }
+}
+
+#[test]
+fn namespaces_should_name_their_functions_after_the_namespace() {
+    let namespace =
+        Namespace::new("test").with_native("double", Arity::Fixed(1), |args| Ok(args[0].clone()));
+
+    assert_eq!(
+        "<native fn test.double>",
+        namespace.get("double").unwrap().to_string()
+    );
+    assert_eq!(None, namespace.get("triple"));
+    assert_eq!("<namespace test>", namespace.to_string());
+}
+
+#[test]
+fn namespaces_should_compare_by_identity() {
+    let namespace = Namespace::new("test");
+
+    assert!(namespace.is(&namespace.clone()));
+    assert!(!namespace.is(&Namespace::new("test")));
+}
+
+#[test]
+fn only_enabled_namespaces_should_be_registered() {
+    let names: Vec<String> = stdlib::namespaces()
+        .iter()
+        .map(|namespace| namespace.name().to_string())
+        .collect();
+
+    assert_eq!(cfg!(feature = "io"), names.contains(&"io".to_string()));
+    assert_eq!(stdlib::NAMESPACES.to_vec(), names);
+    assert_eq!(cfg!(feature = "math"), stdlib::is_namespace("math"));
+    assert!(!stdlib::is_namespace("clock"));
+}
+
+#[cfg(feature = "math")]
+#[test]
+fn math_functions_should_operate_on_numbers() {
+    let math = stdlib::math::namespace();
+
+    assert_eq!(
+        Ok(obj_number!(4.0)),
+        call(&math, "sqrt", vec![obj_number!(16.0)])
+    );
+    assert_eq!(
+        Ok(obj_number!(2.0)),
+        call(&math, "floor", vec![obj_number!(2.7)])
+    );
+    assert_eq!(
+        Ok(obj_number!(8.0)),
+        call(&math, "pow", vec![obj_number!(2.0), obj_number!(3.0)])
+    );
+    assert_eq!(
+        Err(crate::functions::CallError::Type(
+            "math.sqrt expected number, found string a".to_string()
+        )),
+        call(&math, "sqrt", vec![obj_str!("a".to_string())])
+    );
+}
+
+#[cfg(feature = "str")]
+#[test]
+fn str_functions_should_operate_on_strings() {
+    let string = stdlib::string::namespace();
+
+    assert_eq!(
+        Ok(obj_str!("ABC".to_string())),
+        call(&string, "upper", vec![obj_str!("abc".to_string())])
+    );
+    assert_eq!(
+        Ok(obj_number!(5.0)),
+        call(&string, "len", vec![obj_str!("héllo".to_string())])
+    );
+    assert_eq!(
+        "[a, b]",
+        call(
+            &string,
+            "split",
+            vec![obj_str!("a,b".to_string()), obj_str!(",".to_string())]
+        )
+        .unwrap()
+        .to_string()
+    );
+}
+
+#[cfg(feature = "io")]
+#[test]
+fn read_line_should_strip_line_endings_and_return_nil_when_exhausted() {
+    let mut input = std::io::Cursor::new("first\r\nsecond");
+
+    assert_eq!(
+        Ok(obj_str!("first".to_string())),
+        stdlib::io::read_line(&mut input)
+    );
+    assert_eq!(
+        Ok(obj_str!("second".to_string())),
+        stdlib::io::read_line(&mut input)
+    );
+    assert_eq!(Ok(obj_nil!()), stdlib::io::read_line(&mut input));
+}
@@ -0,0 +1,18 @@
+use super::Namespace;
+use crate::functions::{Arity, CallResult};
+use crate::object::Object;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// namespace returns the `time` namespace of clock functions.
+pub fn namespace() -> Namespace {
+    Namespace::new("time").with_native("now", Arity::Fixed(0), now)
+}
+
+/// now returns the milliseconds elapsed since the unix epoch.
+fn now(_args: Vec<Object>) -> CallResult {
+    let t = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as f64;
+    Ok(obj_number!(t))
+}
@@ -0,0 +1,323 @@
+use crate::ast::span::Span;
+use crate::functions::Arity;
+use crate::map::Key;
+use crate::vm::value::{
+    BoundMethod, Instance, Iteration, Map, NativeMethod, NativeMethodFn, Value,
+};
+use crate::vm::{Vm, VmErr, VmResult};
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// method returns the native method of the passed name bound to a built-in
+/// value, if one exists. The methods mirror those of the tree-walking
+/// interpreter's built-in values.
+pub fn method(receiver: &Value, name: &str) -> Option<NativeMethod> {
+    let (arity, func): (usize, NativeMethodFn) = match (receiver, name) {
+        (Value::List(_), "len") => (0, list_len),
+        (Value::List(_), "push") => (1, list_push),
+        (Value::List(_), "pop") => (0, list_pop),
+        (Value::List(_), "insert") => (2, list_insert),
+        (Value::List(_), "remove") => (1, list_remove),
+        (Value::List(_), "slice") => (2, list_slice),
+        (Value::List(_), "map") => (1, list_map),
+        (Value::List(_), "filter") => (1, list_filter),
+        (Value::List(_), "reduce") => (2, list_reduce),
+        (Value::Map(_), "len") => (0, map_len),
+        (Value::Map(_), "keys") => (0, map_keys),
+        (Value::Map(_), "values") => (0, map_values),
+        (Value::Map(_), "has") => (1, map_has),
+        (Value::Map(_), "delete") => (1, map_delete),
+        (Value::Iterator(_), "next") => (0, iterator_next),
+        _ => return None,
+    };
+
+    Some(NativeMethod::new(name, arity, receiver.clone(), func))
+}
+
+/// call invokes a native method, checking the number of arguments passed
+/// against the method's arity.
+pub fn call(vm: &mut Vm, method: &NativeMethod, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    if args.len() != method.arity {
+        return Err(VmErr::Arity(Arity::Fixed(method.arity), args.len(), span));
+    }
+
+    (method.func)(vm, &method.receiver, args, span)
+}
+
+/// property returns the value of one of an instance's fields, or one of its
+/// class's methods bound to it.
+pub fn property(instance: &Rc<Instance>, name: &str) -> Option<Value> {
+    if let Some(value) = instance.fields.borrow().get(name) {
+        return Some(value.clone());
+    }
+
+    instance.class.find_method(name).map(|method| {
+        Value::BoundMethod(Rc::new(BoundMethod::new(
+            Value::Instance(instance.clone()),
+            method,
+        )))
+    })
+}
+
+/// iterate returns an iterator over an iterable value. Lists yield their
+/// elements, maps their keys and strings their characters. An instance is
+/// iterable if it has a `next` method, returning nil once exhausted, or an
+/// `iter` method returning an iterable value.
+pub fn iterate(vm: &mut Vm, value: Value, span: Span) -> VmResult<Value> {
+    let iteration = match value {
+        Value::Iterator(_) => return Ok(value),
+        Value::List(items) => Iteration::List(items, 0),
+        Value::Map(map) => Iteration::Values(map.borrow().keys(), 0),
+        Value::Str(s) => Iteration::Values(
+            s.chars()
+                .map(|c| Value::Str(c.to_string().into()))
+                .collect(),
+            0,
+        ),
+        Value::Instance(i) if property(&i, "next").is_some() => Iteration::Instance(i),
+        Value::Instance(i) => match property(&i, "iter") {
+            Some(iter) => {
+                return match vm.call_from_native(iter, vec![], span)? {
+                    // an iterator returned by `iter` must iterate itself,
+                    // rather than deferring to yet another `iter`.
+                    Value::Instance(i) if property(&i, "next").is_none() => {
+                        Err(not_iterable(Value::Instance(i), span))
+                    }
+                    value => iterate(vm, value, span),
+                };
+            }
+            None => return Err(not_iterable(Value::Instance(i), span)),
+        },
+        value => return Err(not_iterable(value, span)),
+    };
+
+    Ok(Value::Iterator(Rc::new(RefCell::new(iteration))))
+}
+
+/// advance advances an iterator, returning None once it is exhausted.
+pub fn advance(
+    vm: &mut Vm,
+    iteration: &Rc<RefCell<Iteration>>,
+    span: Span,
+) -> VmResult<Option<Value>> {
+    let instance = match &mut *iteration.borrow_mut() {
+        Iteration::List(items, pos) => {
+            let item = items.borrow().get(*pos).cloned();
+            *pos += 1;
+            return Ok(item);
+        }
+        Iteration::Values(values, pos) => {
+            let item = values.get(*pos).cloned();
+            *pos += 1;
+            return Ok(item);
+        }
+        Iteration::Native(iter) => {
+            return Ok(iter
+                .advance()
+                .flatten()
+                .map(|item| Value::try_from(item).unwrap_or(Value::Nil)))
+        }
+        Iteration::Instance(i) => i.clone(),
+    };
+
+    // the iteration is released before calling into lox, which may advance
+    // this same iterator.
+    let next = property(&instance, "next")
+        .ok_or_else(|| not_iterable(Value::Instance(instance.clone()), span))?;
+    match vm.call_from_native(next, vec![], span)? {
+        Value::Nil => Ok(None),
+        item => Ok(Some(item)),
+    }
+}
+
+fn not_iterable(value: Value, span: Span) -> VmErr {
+    VmErr::InvalidArgument(
+        format!("invalid argument type: object {} is not iterable", value),
+        span,
+    )
+}
+
+/// to_index converts a value into the index of an element of a list of the
+/// passed length, failing if the value is not an integer or is out of range.
+pub fn to_index(index: &Value, len: usize, span: Span) -> VmResult<usize> {
+    bounded(index, len, len, span)
+}
+
+/// to_position converts a value into a position between the elements of a
+/// list, from its start up to and including its end, as taken by insert and
+/// slice.
+pub fn to_position(index: &Value, len: usize, span: Span) -> VmResult<usize> {
+    bounded(index, len, len + 1, span)
+}
+
+fn bounded(index: &Value, len: usize, bound: usize, span: Span) -> VmResult<usize> {
+    match index {
+        Value::Number(n) if n.fract() == 0.0 => {
+            if *n >= 0.0 && (*n as usize) < bound {
+                Ok(*n as usize)
+            } else {
+                Err(VmErr::IndexOutOfRange(*n, len, span))
+            }
+        }
+        value => Err(VmErr::InvalidArgument(
+            format!(
+                "invalid argument type: list index must be an integer, found {}",
+                value
+            ),
+            span,
+        )),
+    }
+}
+
+/// to_key converts a value into the key of a map entry, failing if the value
+/// is unhashable.
+pub fn to_key(key: &Value, span: Span) -> VmResult<Key> {
+    Key::try_from(key).map_err(|value| VmErr::Unhashable(value, span))
+}
+
+/// callback invokes a lox callable passed as an argument to a native method.
+fn callback(vm: &mut Vm, func: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    match func {
+        Value::Closure(_)
+        | Value::BoundMethod(_)
+        | Value::Class(_)
+        | Value::Native(_)
+        | Value::NativeMethod(_) => vm.call_from_native(func.clone(), args, span),
+        value => Err(VmErr::InvalidArgument(
+            format!(
+                "invalid argument type: expected a callable, found {}",
+                value
+            ),
+            span,
+        )),
+    }
+}
+
+fn list(receiver: &Value) -> Rc<RefCell<Vec<Value>>> {
+    match receiver {
+        Value::List(items) => items.clone(),
+        _ => unreachable!("list methods are only bound to lists"),
+    }
+}
+
+fn list_len(_: &mut Vm, receiver: &Value, _: Vec<Value>, _: Span) -> VmResult<Value> {
+    Ok(Value::Number(list(receiver).borrow().len() as f64))
+}
+
+fn list_push(_: &mut Vm, receiver: &Value, mut args: Vec<Value>, _: Span) -> VmResult<Value> {
+    list(receiver).borrow_mut().push(args.remove(0));
+    Ok(Value::Nil)
+}
+
+fn list_pop(_: &mut Vm, receiver: &Value, _: Vec<Value>, span: Span) -> VmResult<Value> {
+    list(receiver)
+        .borrow_mut()
+        .pop()
+        .ok_or_else(|| VmErr::CallErr("pop from empty list".to_string(), span))
+}
+
+fn list_insert(_: &mut Vm, receiver: &Value, mut args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let items = list(receiver);
+    let value = args.remove(1);
+    let index = to_position(&args[0], items.borrow().len(), span)?;
+    items.borrow_mut().insert(index, value);
+    Ok(Value::Nil)
+}
+
+fn list_remove(_: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let items = list(receiver);
+    let index = to_index(&args[0], items.borrow().len(), span)?;
+    let removed = items.borrow_mut().remove(index);
+    Ok(removed)
+}
+
+fn list_slice(_: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let items = list(receiver);
+    let len = items.borrow().len();
+    let start = to_position(&args[0], len, span)?;
+    let end = to_position(&args[1], len, span)?;
+    if start > end {
+        return Err(VmErr::CallErr(
+            format!("slice start {} is greater than end {}", start, end),
+            span,
+        ));
+    }
+
+    let slice = items.borrow()[start..end].to_vec();
+    Ok(Value::list(slice))
+}
+
+// map, filter and reduce iterate over a copy of the elements, so callbacks
+// are free to mutate the list.
+
+fn list_map(vm: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let items = list(receiver).borrow().clone();
+    items
+        .into_iter()
+        .map(|item| callback(vm, &args[0], vec![item], span))
+        .collect::<VmResult<Vec<Value>>>()
+        .map(Value::list)
+}
+
+fn list_filter(vm: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let mut kept = Vec::new();
+    let items = list(receiver).borrow().clone();
+    for item in items {
+        if callback(vm, &args[0], vec![item.clone()], span)?.is_truthy() {
+            kept.push(item);
+        }
+    }
+
+    Ok(Value::list(kept))
+}
+
+fn list_reduce(vm: &mut Vm, receiver: &Value, mut args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let initial = args.remove(1);
+    let items = list(receiver).borrow().clone();
+    items.into_iter().try_fold(initial, |acc, item| {
+        callback(vm, &args[0], vec![acc, item], span)
+    })
+}
+
+fn map(receiver: &Value) -> Rc<RefCell<Map>> {
+    match receiver {
+        Value::Map(map) => map.clone(),
+        _ => unreachable!("map methods are only bound to maps"),
+    }
+}
+
+fn map_len(_: &mut Vm, receiver: &Value, _: Vec<Value>, _: Span) -> VmResult<Value> {
+    Ok(Value::Number(map(receiver).borrow().len() as f64))
+}
+
+fn map_keys(_: &mut Vm, receiver: &Value, _: Vec<Value>, _: Span) -> VmResult<Value> {
+    Ok(Value::list(map(receiver).borrow().keys()))
+}
+
+fn map_values(_: &mut Vm, receiver: &Value, _: Vec<Value>, _: Span) -> VmResult<Value> {
+    Ok(Value::list(map(receiver).borrow().values()))
+}
+
+fn map_has(_: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let key = to_key(&args[0], span)?;
+    Ok(Value::Bool(map(receiver).borrow().get(&key).is_some()))
+}
+
+fn map_delete(_: &mut Vm, receiver: &Value, args: Vec<Value>, span: Span) -> VmResult<Value> {
+    let key = to_key(&args[0], span)?;
+    Ok(map(receiver)
+        .borrow_mut()
+        .delete(&key)
+        .unwrap_or(Value::Nil))
+}
+
+fn iterator_next(vm: &mut Vm, receiver: &Value, _: Vec<Value>, span: Span) -> VmResult<Value> {
+    match receiver {
+        Value::Iterator(iteration) => Ok(advance(vm, iteration, span)?.unwrap_or(Value::Nil)),
+        _ => unreachable!("iterator methods are only bound to iterators"),
+    }
+}
@@ -0,0 +1,30 @@
+use crate::ast::span::Span;
+use crate::vm::methods::*;
+use crate::vm::value::Value;
+use crate::vm::VmErr;
+
+#[test]
+fn methods_should_be_bound_to_their_receiver() {
+    let list = Value::list(vec![Value::Number(1.0)]);
+
+    let method = method(&list, "push").unwrap();
+    assert_eq!(list, method.receiver);
+    assert_eq!(1, method.arity);
+    assert!(super::method(&list, "missing").is_none());
+    assert!(super::method(&Value::Nil, "len").is_none());
+}
+
+#[test]
+fn to_index_should_accept_only_integers_within_bounds() {
+    let span = Span::default();
+
+    assert_eq!(Ok(1), to_index(&Value::Number(1.0), 2, span));
+    assert_eq!(
+        Err(VmErr::IndexOutOfRange(2.0, 2, span)),
+        to_index(&Value::Number(2.0), 2, span)
+    );
+    assert!(matches!(
+        to_index(&Value::Number(0.5), 2, span),
+        Err(VmErr::InvalidArgument(_, _))
+    ));
+}
@@ -0,0 +1,957 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::span::Span;
+use crate::compiler::chunk::{Function, FunctionKind, HandlerKind, OpCode};
+use crate::compiler::Compiler;
+use crate::diagnostic::{Diagnostic, StackFrame};
+use crate::functions::{Arity, CallError, CallResult, StaticFunc};
+use crate::module::{ImportErr, Modules};
+use crate::object::Object;
+use crate::pass::*;
+use crate::runtime;
+use crate::statics;
+use crate::stdlib;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+pub mod methods;
+pub mod value;
+use value::{BoundMethod, Class, Closure, Instance, Map, Namespace, Upvalue, Value};
+
+#[cfg(test)]
+mod tests;
+
+/// FRAMES_MAX limits the depth of nested calls before the virtual machine
+/// reports a stack overflow.
+pub const FRAMES_MAX: usize = 1024;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum VmErr {
+    Type(&'static str, Span),
+    BinaryExpr(&'static str, Value, Value, Span),
+    UndefinedProperty(String, Span),
+    CallErr(String, Span),
+    InvalidArgument(String, Span),
+    Unhashable(Value, Span),
+    IndexOutOfRange(f64, usize, Span),
+    Arity(Arity, usize, Span),
+    StackOverflow(Span),
+    DivisionByZero(Span),
+    Import(ImportErr, Span),
+    Thrown(Value, Span),
+    Traced(Box<VmErr>, Vec<StackFrame>),
+}
+
+impl VmErr {
+    /// span returns the span of source the failing instruction was compiled
+    /// from.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Type(_, span)
+            | Self::BinaryExpr(_, _, _, span)
+            | Self::UndefinedProperty(_, span)
+            | Self::CallErr(_, span)
+            | Self::InvalidArgument(_, span)
+            | Self::Unhashable(_, span)
+            | Self::IndexOutOfRange(_, _, span)
+            | Self::Arity(_, _, span)
+            | Self::StackOverflow(span)
+            | Self::DivisionByZero(span)
+            | Self::Import(_, span)
+            | Self::Thrown(_, span) => *span,
+            Self::Traced(e, _) => e.span(),
+        }
+    }
+
+    /// thrown returns the value thrown by a throw statement, if the error was
+    /// raised by one.
+    fn thrown(&self) -> Option<&Value> {
+        match self {
+            Self::Thrown(value, _) => Some(value),
+            Self::Traced(e, _) => e.thrown(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for VmErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type(e, _) => write!(f, "invalid type: {}", e),
+            Self::BinaryExpr(op, left, right, _) => write!(
+                f,
+                "invalid operand for operators: {} {} {}",
+                left, op, right
+            ),
+            Self::UndefinedProperty(name, _) => write!(f, "undefined symbol: {}", name),
+            Self::CallErr(e, _) | Self::InvalidArgument(e, _) => write!(f, "{}", e),
+            Self::Unhashable(key, _) => write!(f, "unhashable map key: {}", key),
+            Self::IndexOutOfRange(index, len, _) => {
+                write!(f, "list index {} out of range for length {}", index, len)
+            }
+            Self::Arity(expected, got, _) => {
+                write!(f, "expected {} arguments but got {}", expected, got)
+            }
+            Self::StackOverflow(_) => write!(f, "stack overflow"),
+            Self::DivisionByZero(_) => write!(f, "division by zero"),
+            Self::Import(e, _) => write!(f, "{}", e),
+            // a rethrown error instance is reported by its message.
+            Self::Thrown(Value::Instance(i), _) if i.fields.borrow().contains_key("message") => {
+                write!(f, "uncaught exception: {}", i.fields.borrow()["message"])
+            }
+            Self::Thrown(value, _) => write!(f, "uncaught exception: {}", value),
+            Self::Traced(e, _) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<VmErr> for Diagnostic {
+    fn from(err: VmErr) -> Self {
+        if let VmErr::Traced(e, backtrace) = err {
+            return Diagnostic {
+                backtrace,
+                ..Diagnostic::from(*e)
+            };
+        }
+
+        let code = match err {
+            VmErr::Type(_, _)
+            | VmErr::BinaryExpr(_, _, _, _)
+            | VmErr::InvalidArgument(_, _)
+            | VmErr::Unhashable(_, _)
+            | VmErr::DivisionByZero(_) => "E0300",
+            VmErr::UndefinedProperty(_, _) => "E0301",
+            VmErr::CallErr(_, _) | VmErr::Arity(_, _, _) => "E0302",
+            VmErr::StackOverflow(_) => "E0304",
+            VmErr::Import(ref e, _) => e.code(),
+            VmErr::Thrown(_, _) => "E0308",
+            VmErr::IndexOutOfRange(_, _, _) => "E0309",
+            VmErr::Traced(_, _) => unreachable!("traced errors are unwrapped above"),
+        };
+
+        Diagnostic::error(code, &err.to_string(), err.span())
+    }
+}
+
+pub type VmResult<T> = Result<T, VmErr>;
+
+/// CallFrame tracks the execution of a single function call, with the
+/// closure being run, the offset of the next instruction and the stack
+/// offset of the call's first slot.
+struct CallFrame {
+    closure: Rc<Closure>,
+    ip: usize,
+    base: usize,
+}
+
+/// NativeFrame tracks a call to a native method in progress. Native methods
+/// may call back into lox, so their calls are traced between the frame of
+/// their caller, at the depth they were called from, and the frames of their
+/// callbacks.
+struct NativeFrame {
+    name: String,
+    span: Span,
+    depth: usize,
+}
+
+/// Vm is a stack based virtual machine executing functions produced by the
+/// Compiler. A script's top-level variables live at the bottom of the stack
+/// and persist between runs, while builtins defined by the host are looked
+/// up by name. Imported modules are compiled and run on the same virtual
+/// machine.
+pub struct Vm {
+    stack: Vec<Value>,
+    frames: Vec<CallFrame>,
+    natives: Vec<NativeFrame>,
+    open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    builtins: HashMap<String, Value>,
+    modules: Box<Modules<Rc<Namespace>>>,
+}
+
+impl Vm {
+    /// new returns a virtual machine with the standard natives and each
+    /// enabled stdlib namespace defined as builtins.
+    pub fn new() -> Vm {
+        let mut vm = Vm {
+            stack: Vec::new(),
+            frames: Vec::new(),
+            natives: Vec::new(),
+            open_upvalues: Vec::new(),
+            builtins: HashMap::new(),
+            modules: Box::default(),
+        };
+        for native in statics::natives() {
+            vm.builtins
+                .insert(native.name().to_string(), Value::Native(Rc::new(native)));
+        }
+        for namespace in stdlib::namespaces() {
+            let value = value::Namespace::try_from(&namespace)
+                .expect("stdlib namespaces hold only natives and literals");
+            vm.builtins.insert(
+                namespace.name().to_string(),
+                Value::Namespace(Rc::new(value)),
+            );
+        }
+        vm
+    }
+
+    /// register_native defines a builtin function callable from lox under
+    /// the passed name, replacing any builtin with the same name. The name
+    /// must also be declared to the ScopeAnalyzer with `declare_builtin`.
+    pub fn register_native<F>(&mut self, name: &str, arity: Arity, func: F)
+    where
+        F: Fn(Vec<Object>) -> CallResult + 'static,
+    {
+        let native = StaticFunc::new(name, arity, func);
+        self.builtins
+            .insert(name.to_string(), Value::Native(Rc::new(native)));
+    }
+
+    /// analyzer returns a ScopeAnalyzer aware of each of the virtual
+    /// machine's builtins, for analyzing source it is about to run.
+    pub fn analyzer(&self) -> ScopeAnalyzer {
+        let mut analyzer = ScopeAnalyzer::new();
+        for name in self.builtins.keys() {
+            analyzer.declare_builtin(name);
+        }
+        analyzer
+    }
+
+    /// set_script records the file the virtual machine is running, resolving
+    /// imports relative to it rather than the working directory.
+    pub fn set_script(&mut self, path: &str) -> Result<(), ImportErr> {
+        let path = self.modules.resolve(path)?;
+        self.modules.enter(path)
+    }
+
+    /// builtin returns the value of the builtin with the passed name.
+    pub fn builtin(&self, name: &str) -> Option<&Value> {
+        self.builtins.get(name)
+    }
+
+    /// global returns the value held in a stack slot by a script's top-level
+    /// variable, as located by the Compiler.
+    pub fn global(&self, slot: usize) -> Option<&Value> {
+        self.stack.get(slot)
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vm {
+    /// run executes a compiled script. Whether or not the script completes,
+    /// the stack is left holding exactly the script's top-level variables,
+    /// with any that were never reached defined as nil.
+    pub fn run(&mut self, script: Rc<Function>) -> VmResult<()> {
+        let globals = script.globals;
+        let closure = Rc::new(Closure::new(script, Vec::new()));
+        match self.stack.first_mut() {
+            Some(slot) => *slot = Value::Closure(closure.clone()),
+            None => self.stack.push(Value::Closure(closure.clone())),
+        }
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            base: 0,
+        });
+
+        let result = self.execute(0).map_err(|e| self.trace(e));
+
+        self.frames.clear();
+        self.natives.clear();
+        self.close_upvalues(globals + 1);
+        self.stack.resize(globals + 1, Value::Nil);
+        result
+    }
+
+    /// call_from_native invokes a callable on behalf of a native method, such
+    /// as a callback passed to a list method, running it to completion and
+    /// returning its result.
+    pub fn call_from_native(
+        &mut self,
+        callee: Value,
+        args: Vec<Value>,
+        span: Span,
+    ) -> VmResult<Value> {
+        let argc = args.len();
+        let floor = self.frames.len();
+        self.push(callee);
+        self.stack.extend(args);
+
+        self.call_value(argc, span)?;
+        if self.frames.len() > floor {
+            self.execute(floor)?;
+        }
+        Ok(self.pop())
+    }
+
+    /// trace attaches a backtrace of the active call frames to an error
+    /// raised within a function call, from the innermost frame out. Stack
+    /// overflows are left untraced, as their backtrace would only repeat the
+    /// recursing calls, as are errors already traced before being rethrown
+    /// by a finally block.
+    fn trace(&self, err: VmErr) -> VmErr {
+        if self.frames.len() + self.natives.len() < 2 {
+            return err;
+        }
+        if let VmErr::StackOverflow(_) | VmErr::Traced(_, _) = err {
+            return err;
+        }
+
+        VmErr::Traced(Box::new(err), self.backtrace())
+    }
+
+    fn backtrace(&self) -> Vec<StackFrame> {
+        let mut backtrace = Vec::new();
+        for (depth, frame) in self.frames.iter().enumerate().rev() {
+            // native methods called from this frame are within it.
+            let natives = self
+                .natives
+                .iter()
+                .rev()
+                .filter(|native| native.depth == depth + 1);
+            for native in natives {
+                backtrace.push(StackFrame::new(&native.name, native.span));
+            }
+
+            let function = &frame.closure.function;
+            let name = match function.kind {
+                FunctionKind::Script => "<script>",
+                _ => &function.name,
+            };
+            backtrace.push(StackFrame::new(name, function.chunk.spans[frame.ip - 1]));
+        }
+        backtrace
+    }
+
+    fn frame(&self) -> &CallFrame {
+        self.frames.last().unwrap()
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().unwrap()
+    }
+
+    fn peek(&self, distance: usize) -> &Value {
+        &self.stack[self.stack.len() - 1 - distance]
+    }
+
+    fn constant(&self, index: usize) -> Value {
+        self.frame().closure.function.chunk.constants[index].clone()
+    }
+
+    fn name(&self, index: usize) -> Rc<str> {
+        match self.constant(index) {
+            Value::Str(name) => name,
+            _ => unreachable!("names are always compiled to string constants"),
+        }
+    }
+
+    /// execute runs instructions until the frame called at the passed depth
+    /// returns, leaving its result on the stack. The script, called at depth
+    /// zero, leaves its variables on the stack instead. An error is handled
+    /// by the innermost try statement protecting the failing instruction
+    /// within a frame at or above the passed depth, if there is one.
+    fn execute(&mut self, floor: usize) -> VmResult<()> {
+        loop {
+            match self.dispatch(floor) {
+                Err(e) => self.catch(e, floor)?,
+                result => return result,
+            }
+        }
+    }
+
+    /// dispatch runs instructions until the frame called at the passed depth
+    /// returns or an instruction fails.
+    fn dispatch(&mut self, floor: usize) -> VmResult<()> {
+        loop {
+            let (op, span) = {
+                let frame = self.frames.last_mut().unwrap();
+                let chunk = &frame.closure.function.chunk;
+                let ip = frame.ip;
+                frame.ip += 1;
+                (chunk.code[ip], chunk.spans[ip])
+            };
+
+            match op {
+                OpCode::Constant(i) => {
+                    let value = self.constant(i);
+                    self.push(value)
+                }
+                OpCode::Nil => self.push(Value::Nil),
+                OpCode::True => self.push(Value::Bool(true)),
+                OpCode::False => self.push(Value::Bool(false)),
+                OpCode::Pop => {
+                    self.pop();
+                }
+                OpCode::GetLocal(slot) => {
+                    let value = self.stack[self.frame().base + slot].clone();
+                    self.push(value)
+                }
+                OpCode::SetLocal(slot) => {
+                    let index = self.frame().base + slot;
+                    self.stack[index] = self.peek(0).clone();
+                }
+                OpCode::GetUpvalue(i) => {
+                    let upvalue = self.frame().closure.upvalues[i].clone();
+                    let value = match &*upvalue.borrow() {
+                        Upvalue::Open(index) => self.stack[*index].clone(),
+                        Upvalue::Closed(value) => value.clone(),
+                    };
+                    self.push(value)
+                }
+                OpCode::SetUpvalue(i) => {
+                    let upvalue = self.frame().closure.upvalues[i].clone();
+                    let value = self.peek(0).clone();
+                    match &mut *upvalue.borrow_mut() {
+                        Upvalue::Open(index) => self.stack[*index] = value,
+                        Upvalue::Closed(closed) => *closed = value,
+                    };
+                }
+                OpCode::GetBuiltin(i) => {
+                    let name = self.name(i);
+                    let value = self
+                        .builtins
+                        .get(&*name)
+                        .cloned()
+                        .ok_or_else(|| VmErr::UndefinedProperty(name.to_string(), span))?;
+                    self.push(value)
+                }
+                OpCode::GetProperty(i) => self.get_property(i, span)?,
+                OpCode::SetProperty(i) => {
+                    let instance = match self.peek(1) {
+                        Value::Instance(instance) => instance.clone(),
+                        _ => return Err(VmErr::Type("only instances have fields", span)),
+                    };
+                    let value = self.pop();
+                    instance
+                        .fields
+                        .borrow_mut()
+                        .insert(self.name(i).to_string(), value.clone());
+                    self.pop();
+                    self.push(value);
+                }
+                OpCode::GetSuper(i) => {
+                    let name = self.name(i);
+                    let superclass = match self.pop() {
+                        Value::Class(c) => c,
+                        _ => return Err(VmErr::Type("superclass must be a class", span)),
+                    };
+                    let receiver = self.pop();
+                    let method = superclass
+                        .find_method(&name)
+                        .ok_or_else(|| VmErr::UndefinedProperty(name.to_string(), span))?;
+                    self.push(Value::BoundMethod(Rc::new(BoundMethod::new(
+                        receiver, method,
+                    ))));
+                }
+                OpCode::Equal => {
+                    let (left, right) = self.pop_operands();
+                    self.push(Value::Bool(left == right));
+                }
+                OpCode::Greater => self.compare(">", span, |l, r| l > r)?,
+                OpCode::GreaterEqual => self.compare(">=", span, |l, r| l >= r)?,
+                OpCode::Less => self.compare("<", span, |l, r| l < r)?,
+                OpCode::LessEqual => self.compare("<=", span, |l, r| l <= r)?,
+                OpCode::Add => {
+                    let (left, right) = self.pop_operands();
+                    let sum = match (&left, &right) {
+                        (Value::Number(l), Value::Number(r)) => Value::Number(l + r),
+                        (Value::Str(l), Value::Str(r)) => Value::Str(format!("{}{}", l, r).into()),
+                        _ => return Err(VmErr::BinaryExpr("+", left, right, span)),
+                    };
+                    self.push(sum);
+                }
+                OpCode::Subtract => self.arithmetic("-", span, |l, r| l - r)?,
+                OpCode::Multiply => self.arithmetic("*", span, |l, r| l * r)?,
+                OpCode::Divide => match self.peek(0) {
+                    Value::Number(r) if *r == 0.0 && matches!(self.peek(1), Value::Number(_)) => {
+                        return Err(VmErr::DivisionByZero(span))
+                    }
+                    _ => self.arithmetic("/", span, |l, r| l / r)?,
+                },
+                OpCode::Not => {
+                    let value = self.pop();
+                    self.push(Value::Bool(!value.is_truthy()));
+                }
+                OpCode::Negate => match self.pop() {
+                    Value::Number(n) => self.push(Value::Number(-n)),
+                    _ => return Err(VmErr::Type("operand must be a number", span)),
+                },
+                OpCode::Print => println!("{}", self.pop()),
+                OpCode::Jump(target) => self.frames.last_mut().unwrap().ip = target,
+                OpCode::JumpIfFalse(target) => {
+                    if !self.peek(0).is_truthy() {
+                        self.frames.last_mut().unwrap().ip = target;
+                    }
+                }
+                OpCode::Call(argc) => self.call_value(argc, span)?,
+                OpCode::Closure(i) => {
+                    let function = match self.constant(i) {
+                        Value::Function(function) => function,
+                        _ => unreachable!("closures are always compiled from functions"),
+                    };
+                    let base = self.frame().base;
+                    let upvalues = function
+                        .upvalues
+                        .iter()
+                        .map(|upvalue| match upvalue.is_local {
+                            true => self.capture_upvalue(base + upvalue.index),
+                            false => self.frame().closure.upvalues[upvalue.index].clone(),
+                        })
+                        .collect();
+                    self.push(Value::Closure(Rc::new(Closure::new(function, upvalues))));
+                }
+                OpCode::CloseUpvalue => {
+                    self.close_upvalues(self.stack.len() - 1);
+                    self.pop();
+                }
+                OpCode::Return => {
+                    let result = self.pop();
+                    let frame = self.frames.pop().unwrap();
+                    // the variables of a script, or of a module it imports,
+                    // remain on the stack once it returns.
+                    if frame.closure.function.kind == FunctionKind::Script {
+                        return Ok(());
+                    }
+
+                    self.close_upvalues(frame.base);
+                    self.stack.truncate(frame.base);
+                    self.push(result);
+                    if self.frames.len() == floor {
+                        return Ok(());
+                    }
+                }
+                OpCode::Class(i) => {
+                    let name = self.name(i);
+                    self.push(Value::Class(Rc::new(Class::new(&name))));
+                }
+                OpCode::Inherit => {
+                    let superclass = match self.peek(1) {
+                        Value::Class(c) => c.clone(),
+                        _ => return Err(VmErr::Type("superclass must be a class", span)),
+                    };
+                    if let Value::Class(subclass) = self.pop() {
+                        subclass
+                            .methods
+                            .borrow_mut()
+                            .extend(superclass.methods.borrow().clone());
+                    }
+                }
+                OpCode::List(len) => {
+                    let items = self.stack.split_off(self.stack.len() - len);
+                    self.push(Value::list(items));
+                }
+                OpCode::Map(len) => {
+                    let mut map = Map::new();
+                    let entries = self.stack.split_off(self.stack.len() - len * 2);
+                    for entry in entries.chunks(2) {
+                        map.set(methods::to_key(&entry[0], span)?, entry[1].clone());
+                    }
+                    self.push(Value::Map(Rc::new(RefCell::new(map))));
+                }
+                OpCode::Index => {
+                    let index = self.pop();
+                    let value = match self.pop() {
+                        Value::List(items) => {
+                            let i = methods::to_index(&index, items.borrow().len(), span)?;
+                            items.borrow()[i].clone()
+                        }
+                        Value::Map(map) => map
+                            .borrow()
+                            .get(&methods::to_key(&index, span)?)
+                            .ok_or_else(|| {
+                                VmErr::CallErr(format!("undefined map key: {}", index), span)
+                            })?,
+                        _ => return Err(VmErr::Type("only lists and maps can be indexed", span)),
+                    };
+                    self.push(value);
+                }
+                OpCode::SetIndex => {
+                    let value = self.pop();
+                    let index = self.pop();
+                    match self.pop() {
+                        Value::List(items) => {
+                            let i = methods::to_index(&index, items.borrow().len(), span)?;
+                            items.borrow_mut()[i] = value.clone();
+                        }
+                        Value::Map(map) => {
+                            let key = methods::to_key(&index, span)?;
+                            map.borrow_mut().set(key, value.clone());
+                        }
+                        _ => return Err(VmErr::Type("only lists and maps can be indexed", span)),
+                    };
+                    self.push(value);
+                }
+                OpCode::Iterate => {
+                    let value = self.pop();
+                    let iterator = methods::iterate(self, value, span)?;
+                    self.push(iterator);
+                }
+                OpCode::IterNext(target) => {
+                    let iteration = match self.peek(0) {
+                        Value::Iterator(iteration) => iteration.clone(),
+                        _ => unreachable!("for-in loops always hold an iterator"),
+                    };
+                    match methods::advance(self, &iteration, span)? {
+                        Some(item) => self.push(item),
+                        None => self.frames.last_mut().unwrap().ip = target,
+                    }
+                }
+                OpCode::Import(i) => {
+                    let path = self.name(i);
+                    let namespace = self.import(&path).map_err(|e| VmErr::Import(e, span))?;
+                    self.push(Value::Namespace(namespace));
+                }
+                OpCode::Throw => {
+                    let value = self.pop();
+                    return Err(VmErr::Thrown(value, span));
+                }
+                OpCode::Rethrow => match self.pop() {
+                    Value::Raised(e) => return Err((*e).clone()),
+                    _ => unreachable!("finally blocks always rethrow their pending error"),
+                },
+                OpCode::Method(i) => {
+                    let name = self.name(i);
+                    let method = match self.pop() {
+                        Value::Closure(c) => c,
+                        _ => unreachable!("methods are always compiled to closures"),
+                    };
+                    if let Value::Class(class) = self.peek(0) {
+                        class.methods.borrow_mut().insert(name.to_string(), method);
+                    }
+                }
+            }
+        }
+    }
+
+    /// catch unwinds the stack to the innermost handler protecting the
+    /// failing instruction of a frame at or above the floor, returning the
+    /// error if no handler does. As with the tree-walking interpreter, errors
+    /// importing a module can't be caught.
+    fn catch(&mut self, err: VmErr, floor: usize) -> VmResult<()> {
+        if let VmErr::Import(_, _) = err {
+            return Err(err);
+        }
+
+        let caught = self
+            .frames
+            .iter()
+            .enumerate()
+            .skip(floor)
+            .rev()
+            .find_map(|(depth, frame)| {
+                frame
+                    .closure
+                    .function
+                    .chunk
+                    .handlers
+                    .iter()
+                    .find(|handler| handler.covers(frame.ip - 1))
+                    .map(|handler| (depth, *handler))
+            });
+        let (depth, handler) = match caught {
+            Some(caught) => caught,
+            None => return Err(err),
+        };
+
+        // the error is traced before the frames it unwound through are
+        // discarded.
+        let value = match handler.kind {
+            HandlerKind::Catch => self.error_value(err),
+            HandlerKind::Finally => Value::Raised(Rc::new(self.trace(err))),
+        };
+        self.frames.truncate(depth + 1);
+        self.natives.retain(|native| native.depth <= depth);
+
+        let frame = self.frames.last_mut().unwrap();
+        frame.ip = handler.target;
+        let base = frame.base + handler.depth;
+        self.close_upvalues(base);
+        self.stack.truncate(base);
+        self.push(value);
+        Ok(())
+    }
+
+    /// error_value returns the value a catch clause binds for an error.
+    /// Thrown values are bound as they were thrown, while runtime errors are
+    /// bound as an Error instance with `message`, `line` and `stack` fields.
+    fn error_value(&self, err: VmErr) -> Value {
+        if let Some(value) = err.thrown() {
+            return value.clone();
+        }
+
+        // errors that have not been rethrown are traced with the calls still
+        // in progress.
+        let (err, backtrace) = match err {
+            VmErr::Traced(e, backtrace) => (*e, backtrace),
+            e => (e, self.backtrace()),
+        };
+        let stack = backtrace
+            .iter()
+            .map(|frame| Value::Str(format!("{} (line {})", frame.name, frame.span.line).into()))
+            .collect();
+
+        let error = Instance::new(Rc::new(Class::new("Error")));
+        {
+            let mut fields = error.fields.borrow_mut();
+            fields.insert("message".to_string(), Value::Str(err.to_string().into()));
+            fields.insert("line".to_string(), Value::Number(err.span().line as f64));
+            fields.insert("stack".to_string(), Value::list(stack));
+        }
+        Value::Instance(Rc::new(error))
+    }
+
+    fn pop_operands(&mut self) -> (Value, Value) {
+        let right = self.pop();
+        let left = self.pop();
+        (left, right)
+    }
+
+    fn arithmetic(&mut self, op: &'static str, span: Span, f: fn(f64, f64) -> f64) -> VmResult<()> {
+        match self.pop_operands() {
+            (Value::Number(l), Value::Number(r)) => {
+                self.push(Value::Number(f(l, r)));
+                Ok(())
+            }
+            (left, right) => Err(VmErr::BinaryExpr(op, left, right, span)),
+        }
+    }
+
+    fn compare(&mut self, op: &'static str, span: Span, f: fn(f64, f64) -> bool) -> VmResult<()> {
+        match self.pop_operands() {
+            (Value::Number(l), Value::Number(r)) => {
+                self.push(Value::Bool(f(l, r)));
+                Ok(())
+            }
+            (left, right) => Err(VmErr::BinaryExpr(op, left, right, span)),
+        }
+    }
+
+    /// get_property replaces the instance on top of the stack with the value
+    /// of one of its fields, or one of its class's methods bound to it. The
+    /// properties of a built-in value are its native methods.
+    fn get_property(&mut self, name: usize, span: Span) -> VmResult<()> {
+        let name = self.name(name);
+        let instance = match self.pop() {
+            Value::Instance(instance) => instance,
+            Value::Namespace(ns) => {
+                let member = ns
+                    .get(&name)
+                    .ok_or_else(|| VmErr::UndefinedProperty(name.to_string(), span))?;
+                self.push(member);
+                return Ok(());
+            }
+            obj @ Value::List(_) | obj @ Value::Map(_) | obj @ Value::Iterator(_) => {
+                let method = methods::method(&obj, &name)
+                    .ok_or_else(|| VmErr::UndefinedProperty(name.to_string(), span))?;
+                self.push(Value::NativeMethod(Rc::new(method)));
+                return Ok(());
+            }
+            _ => return Err(VmErr::Type("only instances have properties", span)),
+        };
+
+        let value = methods::property(&instance, &name)
+            .ok_or_else(|| VmErr::UndefinedProperty(name.to_string(), span))?;
+        self.push(value);
+        Ok(())
+    }
+
+    fn call_value(&mut self, argc: usize, span: Span) -> VmResult<()> {
+        let callee_slot = self.stack.len() - argc - 1;
+        match self.stack[callee_slot].clone() {
+            Value::Closure(closure) => self.call(closure, argc, span),
+            Value::BoundMethod(bound) => {
+                self.stack[callee_slot] = bound.receiver.clone();
+                self.call(bound.method.clone(), argc, span)
+            }
+            Value::Class(class) => {
+                self.stack[callee_slot] = Value::Instance(Rc::new(Instance::new(class.clone())));
+                match class.find_method("init") {
+                    Some(init) => self.call(init, argc, span),
+                    None if argc != 0 => Err(VmErr::Arity(Arity::Fixed(0), argc, span)),
+                    None => Ok(()),
+                }
+            }
+            Value::Native(native) => {
+                let args = self
+                    .stack
+                    .split_off(callee_slot + 1)
+                    .into_iter()
+                    .map(Object::try_from)
+                    .collect::<Result<Vec<Object>, Value>>()
+                    .map_err(|v| {
+                        VmErr::CallErr(format!("object {} can't be passed to a static", v), span)
+                    })?;
+                let result = native.call(args).map_err(|e| match e {
+                    CallError::Arity(arity, got) => VmErr::Arity(arity, got, span),
+                    e @ CallError::Type(_) => VmErr::InvalidArgument(e.to_string(), span),
+                    e => VmErr::CallErr(e.to_string(), span),
+                })?;
+
+                self.stack.truncate(callee_slot);
+                self.push(Value::try_from(result).unwrap_or(Value::Nil));
+                Ok(())
+            }
+            Value::NativeMethod(method) => {
+                let args = self.stack.split_off(callee_slot + 1);
+                self.natives.push(NativeFrame {
+                    name: method.name.clone(),
+                    span,
+                    depth: self.frames.len(),
+                });
+                let result = methods::call(self, &method, args, span)?;
+                self.natives.pop();
+
+                self.stack.truncate(callee_slot);
+                self.push(result);
+                Ok(())
+            }
+            callee => Err(VmErr::CallErr(
+                format!("object {} is not callable", callee),
+                span,
+            )),
+        }
+    }
+
+    fn call(&mut self, closure: Rc<Closure>, argc: usize, span: Span) -> VmResult<()> {
+        if closure.function.arity != argc {
+            return Err(VmErr::Arity(
+                Arity::Fixed(closure.function.arity),
+                argc,
+                span,
+            ));
+        }
+        if self.frames.len() >= FRAMES_MAX {
+            return Err(VmErr::StackOverflow(span));
+        }
+
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            base: self.stack.len() - argc - 1,
+        });
+        Ok(())
+    }
+
+    /// import returns the namespace of the module at the passed path, running
+    /// the module the first time it is imported.
+    fn import(&mut self, path: &str) -> Result<Rc<Namespace>, ImportErr> {
+        let resolved = self.modules.resolve(path)?;
+        if let Some(namespace) = self.modules.get(&resolved) {
+            return Ok(namespace);
+        }
+
+        self.modules.enter(resolved.clone())?;
+        let namespace = self.load(path, &resolved);
+        self.modules.exit(namespace.clone().ok());
+        namespace
+    }
+
+    /// load compiles and runs a module above the stack of the running
+    /// script. Once the module returns, each of its top-level variables is
+    /// closed over by its namespace, sharing the variable with any closure
+    /// that captured it.
+    fn load(&mut self, path: &str, resolved: &Path) -> Result<Rc<Namespace>, ImportErr> {
+        let source = fs::read_to_string(resolved)
+            .map_err(|e| ImportErr::Read(path.to_string(), e.to_string()))?;
+        let module_err = |diagnostics| ImportErr::Module(path.to_string(), diagnostics);
+
+        let mut analyzer = self.analyzer();
+        analyzer.set_script(resolved);
+        let stmts = runtime::parse(&source).map_err(module_err)?;
+        let analyzed = analyzer
+            .tree_pass(stmts)
+            .map_err(|e| module_err(vec![e.into()]))?;
+        let mut compiler = Compiler::new();
+        let script = compiler
+            .tree_pass(analyzed)
+            .map_err(|e| module_err(vec![e.into()]))?;
+
+        let floor = self.frames.len();
+        let base = self.stack.len();
+        let closure = Rc::new(Closure::new(script, Vec::new()));
+        self.push(Value::Closure(closure.clone()));
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            base,
+        });
+
+        // cycles are reported as a whole at the import that closed them,
+        // rather than wrapped by each module along the chain.
+        let result = self.execute(floor).map_err(|e| match e {
+            VmErr::Import(cycle @ ImportErr::Cycle(_), _) => cycle,
+            e => module_err(vec![e.into()]),
+        });
+        self.frames.truncate(floor);
+        self.natives.retain(|native| native.depth <= floor);
+
+        let name = resolved
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let mut namespace = Namespace::new(&name);
+        if result.is_ok() {
+            for name in analyzer.globals().into_keys() {
+                let id = analyzer.resolve(&identifier_name!(name));
+                if let Some(slot) = id.and_then(|id| compiler.global(&id)) {
+                    namespace.define(&name, self.capture_upvalue(base + slot));
+                }
+            }
+        }
+        self.close_upvalues(base);
+        self.stack.truncate(base);
+
+        result.map(|_| Rc::new(namespace))
+    }
+
+    /// capture_upvalue returns the open upvalue referencing a stack slot,
+    /// creating one if the slot hasn't already been captured.
+    fn capture_upvalue(&mut self, index: usize) -> Rc<RefCell<Upvalue>> {
+        let existing = self
+            .open_upvalues
+            .iter()
+            .find(|upvalue| *upvalue.borrow() == Upvalue::Open(index));
+
+        match existing {
+            Some(upvalue) => upvalue.clone(),
+            None => {
+                let upvalue = Rc::new(RefCell::new(Upvalue::Open(index)));
+                self.open_upvalues.push(upvalue.clone());
+                upvalue
+            }
+        }
+    }
+
+    /// close_upvalues closes every open upvalue referencing a stack slot at
+    /// or above the passed index, moving the slot's value into the upvalue.
+    fn close_upvalues(&mut self, from: usize) {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|upvalue| {
+            let index = match *upvalue.borrow() {
+                Upvalue::Open(index) => index,
+                Upvalue::Closed(_) => return false,
+            };
+            if index < from {
+                return true;
+            }
+
+            let value = stack.get(index).cloned().unwrap_or(Value::Nil);
+            upvalue.replace(Upvalue::Closed(value));
+            false
+        });
+    }
+}
@@ -0,0 +1,435 @@
+use crate::analyzer::scope::ScopeAnalyzer;
+use crate::ast::span::Span;
+use crate::compiler::Compiler;
+use crate::diagnostic::Diagnostic;
+use crate::functions::Arity;
+use crate::module::ImportErr;
+use crate::parser;
+use crate::pass::*;
+use crate::scanner::Scanner;
+use crate::vm::value::Value;
+use crate::vm::{Vm, VmErr};
+
+/// Session holds the state required to compile and run successive programs
+/// against the same virtual machine.
+struct Session {
+    analyzer: ScopeAnalyzer,
+    compiler: Compiler,
+    vm: Vm,
+}
+
+impl Session {
+    fn new() -> Self {
+        Session {
+            analyzer: ScopeAnalyzer::new(),
+            compiler: Compiler::new(),
+            vm: Vm::new(),
+        }
+    }
+
+    fn run(&mut self, source: &str) -> Result<(), VmErr> {
+        let tokens: Vec<_> = Scanner::new(source.to_string())
+            .scan_tokens()
+            .into_iter()
+            .map(|lex_result| lex_result.unwrap())
+            .collect();
+        let (stmts, errors) = parser::parse(&tokens);
+        assert!(errors.is_empty());
+
+        let analyzed = self.analyzer.tree_pass(stmts).unwrap();
+        let script = self.compiler.tree_pass(analyzed).unwrap();
+        self.vm.run(script)
+    }
+
+    /// globals returns the values of the top-level variables, skipping the
+    /// script occupying the first slot.
+    fn globals(&self) -> &[Value] {
+        &self.vm.stack[1..]
+    }
+}
+
+#[test]
+fn should_evaluate_expressions_into_variables() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Ok(()),
+        session.run("var a = (1 + 2) * 3; var b = \"a\" + \"b\"; var c = !(a > 5);")
+    );
+    assert_eq!(
+        &[
+            Value::Number(9.0),
+            Value::Str("ab".into()),
+            Value::Bool(false)
+        ],
+        session.globals()
+    );
+}
+
+#[test]
+fn should_leave_only_top_level_variables_on_the_stack() {
+    let mut session = Session::new();
+
+    session
+        .run("var a = 0; { var b = 1; while (a < 3) { var c = a; a = c + b; } }")
+        .unwrap();
+
+    assert_eq!(&[Value::Number(3.0)], session.globals());
+    assert!(session.vm.frames.is_empty());
+}
+
+#[test]
+fn should_call_functions_and_return_values() {
+    let mut session = Session::new();
+
+    session
+        .run("fun fib(n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); } var a = fib(10);")
+        .unwrap();
+
+    assert_eq!(Value::Number(55.0), session.globals()[1]);
+}
+
+#[test]
+fn closures_should_share_captured_variables() {
+    let mut session = Session::new();
+
+    session
+        .run(
+            "fun make() { var n = 0; fun inc() { n = n + 1; return n; } return inc; }
+             var inc = make(); inc(); var a = inc();",
+        )
+        .unwrap();
+
+    assert_eq!(Value::Number(2.0), session.globals()[2]);
+    assert!(session.vm.open_upvalues.is_empty());
+}
+
+#[test]
+fn should_call_initializers_and_methods() {
+    let mut session = Session::new();
+
+    session
+        .run(
+            "class A { init(v) { this.v = v; } get() { return this.v; } }
+             class B < A { get() { return super.get() * 2; } }
+             var a = B(21).get();",
+        )
+        .unwrap();
+
+    assert_eq!(Value::Number(42.0), session.globals()[2]);
+}
+
+#[test]
+fn top_level_variables_should_persist_between_runs() {
+    let mut session = Session::new();
+
+    session.run("var a = 1; fun get() { return a; }").unwrap();
+    session.run("a = 2; var b = get();").unwrap();
+
+    assert_eq!(
+        &[
+            Value::Number(2.0),
+            session.globals()[1].clone(),
+            Value::Number(2.0)
+        ],
+        session.globals()
+    );
+}
+
+#[test]
+fn should_define_unreached_variables_as_nil_after_an_error() {
+    let mut session = Session::new();
+
+    assert!(session
+        .run("var a = 1; var b = a + nil; var c = 3;")
+        .is_err());
+    assert_eq!(
+        &[Value::Number(1.0), Value::Nil, Value::Nil],
+        session.globals()
+    );
+
+    session.run("var d = 4;").unwrap();
+    assert_eq!(Value::Number(4.0), session.globals()[3]);
+}
+
+#[test]
+fn should_error_with_the_span_of_the_failing_instruction() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Err(VmErr::BinaryExpr(
+            "-",
+            Value::Str("a".into()),
+            Value::Number(1.0),
+            Span::new(6, 13, 1, 7)
+        )),
+        session.run("print \"a\" - 1;")
+    );
+}
+
+#[test]
+fn should_error_on_arity_mismatch() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Err(VmErr::Arity(Arity::Fixed(1), 2, Span::new(23, 30, 1, 24))),
+        session.run("fun f(a) { return a; } f(1, 2);")
+    );
+}
+
+#[test]
+fn should_error_on_unbounded_recursion() {
+    let mut session = Session::new();
+
+    match session.run("fun f() { return f(); } f();") {
+        Err(VmErr::StackOverflow(_)) => (),
+        other => panic!("expected stack overflow, found {:?}", other),
+    }
+}
+
+#[test]
+fn should_error_on_undefined_properties() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Err(VmErr::UndefinedProperty(
+            "missing".to_string(),
+            Span::new(24, 33, 1, 25)
+        )),
+        session.run("class A {} var a = A(); a.missing;")
+    );
+    assert_eq!(
+        Err(VmErr::Type(
+            "only instances have properties",
+            Span::new(17, 22, 1, 18)
+        )),
+        session.run("var n = 1; print n.foo;")
+    );
+}
+
+#[test]
+fn errors_within_calls_should_be_traced_with_the_call_frames() {
+    let mut session = Session::new();
+    let source = "fun f() {\n  return nil + 1;\n}\nfun g() {\n  return f();\n}\ng();";
+
+    let err = session.run(source).unwrap_err();
+    let diagnostic = Diagnostic::from(err);
+    let names: Vec<(String, usize)> = diagnostic
+        .backtrace
+        .iter()
+        .map(|frame| (frame.name.clone(), frame.span.line))
+        .collect();
+
+    assert_eq!(
+        vec![
+            ("f".to_string(), 2),
+            ("g".to_string(), 5),
+            ("<script>".to_string(), 7)
+        ],
+        names
+    );
+    assert_eq!(2, diagnostic.span.line);
+}
+
+#[test]
+fn errors_outside_of_calls_should_not_be_traced() {
+    let mut session = Session::new();
+
+    match session.run("print nil + 1;") {
+        Err(VmErr::BinaryExpr(_, _, _, _)) => (),
+        other => panic!("expected an untraced error, found {:?}", other),
+    }
+}
+
+#[test]
+fn should_error_on_division_by_zero() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Err(VmErr::DivisionByZero(Span::new(6, 11, 1, 7))),
+        session.run("print 1 / 0;")
+    );
+}
+
+#[test]
+fn globals_should_be_located_through_the_compiler_by_their_resolved_identifier() {
+    let mut session = Session::new();
+    session.run("var a = 1; { var b = 2; }").unwrap();
+    session.run("var c = a + 1; a = 3;").unwrap();
+
+    let value = |name: &str| {
+        session
+            .analyzer
+            .resolve(&identifier_name!(name))
+            .and_then(|id| session.compiler.global(&id))
+            .and_then(|slot| session.vm.global(slot))
+            .cloned()
+    };
+    assert_eq!(Some(Value::Number(3.0)), value("a"));
+    assert_eq!(Some(Value::Number(2.0)), value("c"));
+    assert_eq!(None, value("b"));
+}
+
+#[test]
+fn lists_should_be_indexed_and_shared_by_aliases() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Ok(()),
+        session.run("var xs = [1, 2]; var ys = xs; ys[0] = 3; ys.push(xs[0] + xs[1]);")
+    );
+    assert_eq!("[3, 2, 5]", session.globals()[0].to_string());
+    assert_eq!(session.globals()[0], session.globals()[1]);
+    assert_eq!(
+        Err(VmErr::IndexOutOfRange(3.0, 3, Span::new(6, 11, 1, 7))),
+        session.run("print xs[3];")
+    );
+}
+
+#[test]
+fn native_methods_should_be_traced_between_their_caller_and_callbacks() {
+    let mut session = Session::new();
+    let source = "fun f(n) {\n  return n + nil;\n}\n[1].map(f);";
+
+    let diagnostic = Diagnostic::from(session.run(source).unwrap_err());
+    let names: Vec<(String, usize)> = diagnostic
+        .backtrace
+        .iter()
+        .map(|frame| (frame.name.clone(), frame.span.line))
+        .collect();
+
+    assert_eq!(
+        vec![
+            ("f".to_string(), 2),
+            ("map".to_string(), 4),
+            ("<script>".to_string(), 4)
+        ],
+        names
+    );
+    assert_eq!(2, session.vm.stack.len());
+}
+
+#[test]
+fn maps_should_keep_their_entries_in_insertion_order() {
+    let mut session = Session::new();
+
+    assert_eq!(
+        Ok(()),
+        session.run("var m = {\"b\": 1, 0: 2}; m[-0] = 3; m[\"a\"] = m[\"b\"]; m.delete(\"b\");")
+    );
+    assert_eq!("{0: 3, a: 1}", session.globals()[0].to_string());
+    assert_eq!(
+        Err(VmErr::CallErr(
+            "undefined map key: b".to_string(),
+            Span::new(6, 12, 1, 7)
+        )),
+        session.run("print m[\"b\"];")
+    );
+    match session.run("m[[]] = 1;") {
+        Err(VmErr::Unhashable(Value::List(_), _)) => (),
+        other => panic!("expected an unhashable key, found {:?}", other),
+    }
+}
+
+#[test]
+fn for_in_should_bind_each_item_afresh_and_unwind_on_break() {
+    let mut session = Session::new();
+    let source = "var fs = []; var seen = 0;
+        for (i in range(0, 5)) {
+            var j = i;
+            if (i == 1) continue;
+            if (i == 3) break;
+            fs.push(fun () { return j; });
+            seen = seen + 1;
+        }
+        var first = fs[0](); var second = fs[1]();";
+
+    assert_eq!(Ok(()), session.run(source));
+    assert_eq!(
+        &[Value::Number(2.0), Value::Number(0.0), Value::Number(2.0)],
+        &session.globals()[1..]
+    );
+}
+
+#[test]
+fn errors_should_unwind_to_the_innermost_handler() {
+    let mut session = Session::new();
+    let source = "fun risky(n) { return [n].map(fun (x) { return x / 0; }); }
+        var log = [];
+        fun guarded() {
+            try {
+                var local = 1;
+                try { risky(1); } finally { log.push(\"inner\"); }
+            } catch (e) {
+                log.push(e.message);
+                return e.stack;
+            } finally {
+                log.push(\"outer\");
+            }
+        }
+        var stack = guarded();";
+
+    assert_eq!(Ok(()), session.run(source));
+    assert_eq!(
+        "[inner, division by zero, outer]",
+        session.globals()[1].to_string()
+    );
+    assert_eq!(
+        "[lambda (line 1), map (line 1), risky (line 1), guarded (line 6), <script> (line 14)]",
+        session.globals()[3].to_string()
+    );
+    // the unwound frames leave only the script's variables on the stack.
+    assert_eq!(4, session.globals().len());
+}
+
+#[test]
+fn uncaught_throws_should_be_reported_with_their_value() {
+    let mut session = Session::new();
+    let err = session
+        .run("try { throw \"first\"; } finally { print \"cleanup\"; }")
+        .unwrap_err();
+
+    assert_eq!(
+        VmErr::Thrown(Value::Str("first".into()), Span::new(6, 20, 1, 7)),
+        err
+    );
+    let diagnostic = Diagnostic::from(err);
+    assert_eq!("E0308", diagnostic.code);
+    assert_eq!("uncaught exception: first", diagnostic.message);
+}
+
+#[cfg(feature = "math")]
+#[test]
+fn stdlib_namespaces_should_be_builtins() {
+    let mut session = Session::new();
+
+    assert_eq!(Ok(()), session.run("var root = math.sqrt(16);"));
+    assert_eq!(&[Value::Number(4.0)], session.globals());
+    assert_eq!(
+        Err(VmErr::UndefinedProperty(
+            "cbrt".to_string(),
+            Span::new(6, 15, 1, 7)
+        )),
+        session.run("print math.cbrt;")
+    );
+}
+
+#[test]
+fn imported_modules_should_share_their_variables_through_their_namespace() {
+    let mut session = Session::new();
+    let source = "import \"tests/corpus/modules/counter.lox\" as counter;
+        import \"tests/corpus/modules/counter.lox\" as shared;
+        counter.bump();
+        shared.bump();
+        var count = counter.count;";
+
+    assert_eq!(Ok(()), session.run(source));
+    assert_eq!(Value::Number(2.0), session.globals()[2]);
+    assert_eq!(session.globals()[0], session.globals()[1]);
+    match session.run("import \"tests/corpus/modules/missing.lox\" as missing;") {
+        Err(VmErr::Import(ImportErr::Read(path, _), _)) => {
+            assert_eq!("tests/corpus/modules/missing.lox", path)
+        }
+        other => panic!("expected an unreadable module, found {:?}", other),
+    }
+}
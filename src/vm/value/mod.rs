@@ -0,0 +1,454 @@
+use crate::ast::span::Span;
+use crate::compiler::chunk::Function;
+use crate::functions::{Callable, StaticFunc};
+use crate::iterator::Iter;
+use crate::list::List;
+use crate::map::Key;
+use crate::object::{Literal, Object};
+use crate::stdlib;
+use crate::vm::{Vm, VmErr, VmResult};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// Value represents a value on the virtual machine's stack. Heap allocated
+/// values are reference counted, so clones are aliases of the same object.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    Str(Rc<str>),
+    Function(Rc<Function>),
+    Closure(Rc<Closure>),
+    Native(Rc<StaticFunc>),
+    Class(Rc<Class>),
+    Instance(Rc<Instance>),
+    BoundMethod(Rc<BoundMethod>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    Iterator(Rc<RefCell<Iteration>>),
+    NativeMethod(Rc<NativeMethod>),
+    Namespace(Rc<Namespace>),
+    /// Raised holds an error raised within a try statement while its finally
+    /// block runs, in a slot lox code cannot refer to.
+    Raised(Rc<VmErr>),
+}
+
+impl Value {
+    /// list returns a new list holding the passed elements.
+    pub fn list(items: Vec<Value>) -> Self {
+        Self::List(Rc::new(RefCell::new(items)))
+    }
+
+    /// is_truthy follows the truthiness rules of the tree-walking
+    /// interpreter, treating nil, false, empty strings and zero as false.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Nil => false,
+            Self::Bool(b) => *b,
+            Self::Str(s) => !s.is_empty(),
+            Self::Number(n) => n.abs() > f64::EPSILON,
+            _ => true,
+        }
+    }
+}
+
+/// Heap allocated values are equal only when they are the same object.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Nil, Self::Nil) => true,
+            (Self::Bool(l), Self::Bool(r)) => l == r,
+            (Self::Number(l), Self::Number(r)) => l == r,
+            (Self::Str(l), Self::Str(r)) => l == r,
+            (Self::Function(l), Self::Function(r)) => Rc::ptr_eq(l, r),
+            (Self::Closure(l), Self::Closure(r)) => Rc::ptr_eq(l, r),
+            (Self::Native(l), Self::Native(r)) => l == r,
+            (Self::Class(l), Self::Class(r)) => Rc::ptr_eq(l, r),
+            (Self::Instance(l), Self::Instance(r)) => Rc::ptr_eq(l, r),
+            (Self::BoundMethod(l), Self::BoundMethod(r)) => Rc::ptr_eq(l, r),
+            (Self::List(l), Self::List(r)) => Rc::ptr_eq(l, r),
+            (Self::Map(l), Self::Map(r)) => Rc::ptr_eq(l, r),
+            (Self::Iterator(l), Self::Iterator(r)) => Rc::ptr_eq(l, r),
+            (Self::Namespace(l), Self::Namespace(r)) => Rc::ptr_eq(l, r),
+            (Self::NativeMethod(l), Self::NativeMethod(r)) => {
+                l.name == r.name && l.receiver == r.receiver
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nil => write!(f, "nil"),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Str(s) => write!(f, "{}", s),
+            Self::Function(func) => write!(f, "{}", func),
+            Self::Closure(c) => write!(f, "{}", c.function),
+            Self::Native(native) => write!(f, "<native fn {}>", native.name()),
+            Self::Class(c) => write!(f, "{}", c),
+            Self::Instance(i) => write!(f, "Instance({})", i.class),
+            Self::BoundMethod(m) => write!(f, "{}", m.method.function),
+            Self::List(items) => write!(
+                f,
+                "[{}]",
+                items
+                    .borrow()
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::Map(map) => write!(
+                f,
+                "{{{}}}",
+                map.borrow()
+                    .entries
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", Value::from(key), value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::Iterator(_) => write!(f, "<iterator>"),
+            Self::NativeMethod(m) => write!(f, "<native fn {}>", m.name),
+            Self::Namespace(n) => write!(f, "<namespace {}>", n.name),
+            Self::Raised(e) => write!(f, "<error {}>", e),
+        }
+    }
+}
+
+impl From<Literal> for Value {
+    fn from(literal: Literal) -> Self {
+        match literal {
+            Literal::Nil => Self::Nil,
+            Literal::Bool(b) => Self::Bool(b),
+            Literal::Str(s) => Self::Str(s.into()),
+            Literal::Number(n) => Self::Number(n),
+        }
+    }
+}
+
+/// Values key maps as the tree-walking interpreter's objects do, with only
+/// nil, booleans, numbers and strings hashable.
+impl std::convert::TryFrom<&Value> for Key {
+    type Error = Value;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(Key::Nil),
+            Value::Bool(b) => Ok(Key::Bool(*b)),
+            // -0 and 0 are equal, and so must key the same entry.
+            Value::Number(n) if *n == 0.0 => Ok(Key::Number(0f64.to_bits())),
+            Value::Number(n) => Ok(Key::Number(n.to_bits())),
+            Value::Str(s) => Ok(Key::Str(s.to_string())),
+            value => Err(value.clone()),
+        }
+    }
+}
+
+impl From<&Key> for Value {
+    fn from(key: &Key) -> Self {
+        match key {
+            Key::Nil => Self::Nil,
+            Key::Bool(b) => Self::Bool(*b),
+            Key::Number(bits) => Self::Number(f64::from_bits(*bits)),
+            Key::Str(s) => Self::Str(s.as_str().into()),
+        }
+    }
+}
+
+/// Converts a tree-walking interpreter object to a value. Only literals,
+/// static functions, lists and namespaces of these and native iterators,
+/// such as those returned by `range`, have an equivalent value.
+impl std::convert::TryFrom<Object> for Value {
+    type Error = Object;
+
+    fn try_from(obj: Object) -> Result<Self, Self::Error> {
+        match obj {
+            Object::Literal(l) => Ok(l.into()),
+            Object::List(ref l) => l
+                .items()
+                .into_iter()
+                .map(Self::try_from)
+                .collect::<Result<Vec<Value>, Object>>()
+                .map(Self::list)
+                .map_err(|_| obj),
+            Object::Iterator(iter) => Ok(Self::Iterator(Rc::new(RefCell::new(Iteration::Native(
+                iter,
+            ))))),
+            Object::Call(c) => match *c {
+                Callable::Static(f) => Ok(Self::Native(Rc::new(f))),
+                c => Err(obj_call!(Box::new(c))),
+            },
+            obj => Err(obj),
+        }
+    }
+}
+
+/// Converts a value to a tree-walking interpreter object for passing to
+/// static functions. Only literal values and lists of these have an
+/// equivalent object, with lists converted into a copy.
+impl std::convert::TryFrom<Value> for Object {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(obj_nil!()),
+            Value::Bool(b) => Ok(obj_bool!(b)),
+            Value::Number(n) => Ok(obj_number!(n)),
+            Value::Str(s) => Ok(obj_str!(s.to_string())),
+            Value::List(ref items) => {
+                let items = items.borrow().clone();
+                items
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<Vec<Object>, Value>>()
+                    .map(|items| Object::List(List::new(items)))
+                    .map_err(|_| value)
+            }
+            v => Err(v),
+        }
+    }
+}
+
+/// Upvalue represents a variable captured by a closure. An upvalue is open
+/// while the variable still lives on the stack, referencing its slot, and is
+/// closed over a copy of the value once the variable goes out of scope.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Upvalue {
+    Open(usize),
+    Closed(Value),
+}
+
+/// Closure pairs a compiled function with the variables it captures.
+#[derive(Debug)]
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+}
+
+impl Closure {
+    pub fn new(function: Rc<Function>, upvalues: Vec<Rc<RefCell<Upvalue>>>) -> Self {
+        Closure { function, upvalues }
+    }
+}
+
+/// Class represents a runtime class. Inherited methods are copied into the
+/// method table of a subclass when it is defined.
+#[derive(Debug)]
+pub struct Class {
+    pub name: String,
+    pub methods: RefCell<HashMap<String, Rc<Closure>>>,
+}
+
+impl Class {
+    pub fn new(name: &str) -> Self {
+        Class {
+            name: name.to_string(),
+            methods: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<Rc<Closure>> {
+        self.methods.borrow().get(name).cloned()
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Class {}", self.name)
+    }
+}
+
+/// Instance represents an instantiated class and its fields.
+#[derive(Debug)]
+pub struct Instance {
+    pub class: Rc<Class>,
+    pub fields: RefCell<HashMap<String, Value>>,
+}
+
+impl Instance {
+    pub fn new(class: Rc<Class>) -> Self {
+        Instance {
+            class,
+            fields: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+/// BoundMethod represents a method accessed from an instance, carrying the
+/// instance to bind to `this` when called.
+#[derive(Debug)]
+pub struct BoundMethod {
+    pub receiver: Value,
+    pub method: Rc<Closure>,
+}
+
+impl BoundMethod {
+    pub fn new(receiver: Value, method: Rc<Closure>) -> Self {
+        BoundMethod { receiver, method }
+    }
+}
+
+/// Map holds the entries of a map in insertion order, along with the
+/// position of each key's entry.
+#[derive(Debug, Default)]
+pub struct Map {
+    entries: Vec<(Key, Value)>,
+    positions: HashMap<Key, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &Key) -> Option<Value> {
+        self.positions
+            .get(key)
+            .map(|&pos| self.entries[pos].1.clone())
+    }
+
+    /// set inserts or overwrites the value of the passed key.
+    pub fn set(&mut self, key: Key, value: Value) {
+        match self.positions.get(&key) {
+            Some(&pos) => self.entries[pos].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    /// delete removes the entry of the passed key, returning its value if
+    /// one existed.
+    pub fn delete(&mut self, key: &Key) -> Option<Value> {
+        let pos = self.positions.remove(key)?;
+
+        // each entry following the removed entry shifts down a position.
+        let (_, value) = self.entries.remove(pos);
+        for (i, (key, _)) in self.entries.iter().enumerate().skip(pos) {
+            self.positions.insert(key.clone(), i);
+        }
+        Some(value)
+    }
+
+    /// keys returns the map's keys, in insertion order.
+    pub fn keys(&self) -> Vec<Value> {
+        self.entries
+            .iter()
+            .map(|(key, _)| Value::from(key))
+            .collect()
+    }
+
+    /// values returns the map's values, in insertion order.
+    pub fn values(&self) -> Vec<Value> {
+        self.entries
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+}
+
+/// Namespace represents a named group of members accessed as properties,
+/// such as the natives of a stdlib namespace. Each member is held in a cell
+/// that may be shared with the closures capturing it.
+#[derive(Debug)]
+pub struct Namespace {
+    pub name: String,
+    members: HashMap<String, Rc<RefCell<Upvalue>>>,
+}
+
+impl Namespace {
+    pub fn new(name: &str) -> Self {
+        Namespace {
+            name: name.to_string(),
+            members: HashMap::new(),
+        }
+    }
+
+    /// define adds a member held in the passed cell to the namespace.
+    pub fn define(&mut self, name: &str, cell: Rc<RefCell<Upvalue>>) {
+        self.members.insert(name.to_string(), cell);
+    }
+
+    /// get returns the current value of the member with the passed name.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.members.get(name).map(|cell| match &*cell.borrow() {
+            Upvalue::Closed(value) => value.clone(),
+            Upvalue::Open(_) => unreachable!("namespace members are always closed"),
+        })
+    }
+}
+
+/// Converts a namespace of the tree-walking interpreter, such as a stdlib
+/// namespace, into a namespace of constant members.
+impl std::convert::TryFrom<&stdlib::Namespace> for Namespace {
+    type Error = Object;
+
+    fn try_from(ns: &stdlib::Namespace) -> Result<Self, Self::Error> {
+        let mut namespace = Namespace::new(ns.name());
+        for (name, member) in ns.members() {
+            let value = Value::try_from(member)?;
+            namespace.define(&name, Rc::new(RefCell::new(Upvalue::Closed(value))));
+        }
+
+        Ok(namespace)
+    }
+}
+
+/// Iteration represents the values an iterator steps through. Lists are
+/// iterated in place, so elements pushed during iteration are visited, while
+/// maps and strings are iterated over a copy of their keys and characters.
+/// Iterators returned by natives are advanced natively, while an instance is
+/// advanced by calling its `next` method.
+#[derive(Debug)]
+pub enum Iteration {
+    List(Rc<RefCell<Vec<Value>>>, usize),
+    Values(Vec<Value>, usize),
+    Native(Iter),
+    Instance(Rc<Instance>),
+}
+
+/// NativeMethodFn is the signature of each method of a built-in value,
+/// taking the virtual machine making the call, so that methods may call back
+/// into lox, along with the value the method is bound to, the arguments
+/// passed and the span of the call.
+pub type NativeMethodFn = fn(&mut Vm, &Value, Vec<Value>, Span) -> VmResult<Value>;
+
+/// NativeMethod represents a method of a built-in value, such as a list,
+/// bound to the value it was accessed on.
+#[derive(Debug)]
+pub struct NativeMethod {
+    pub name: String,
+    pub arity: usize,
+    pub receiver: Value,
+    pub func: NativeMethodFn,
+}
+
+impl NativeMethod {
+    pub fn new(name: &str, arity: usize, receiver: Value, func: NativeMethodFn) -> Self {
+        NativeMethod {
+            name: name.to_string(),
+            arity,
+            receiver,
+            func,
+        }
+    }
+}
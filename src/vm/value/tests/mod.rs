@@ -0,0 +1,60 @@
+use crate::object::Object;
+use crate::vm::value::*;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+#[test]
+fn truthiness_should_match_the_tree_walking_interpreter() {
+    let values = vec![
+        Value::Nil,
+        Value::Bool(false),
+        Value::Bool(true),
+        Value::Number(0.0),
+        Value::Number(1.0),
+        Value::Str("".into()),
+        Value::Str("a".into()),
+        Value::Class(Rc::new(Class::new("A"))),
+    ];
+
+    for value in values {
+        let obj_truthiness = match Object::try_from(value.clone()) {
+            Ok(obj) => obj.into(),
+            Err(_) => true,
+        };
+
+        assert_eq!(obj_truthiness, value.is_truthy(), "{:?}", value);
+    }
+}
+
+#[test]
+fn heap_values_should_be_equal_only_to_themselves() {
+    let class = Rc::new(Class::new("A"));
+    let first = Value::Instance(Rc::new(Instance::new(class.clone())));
+    let second = Value::Instance(Rc::new(Instance::new(class)));
+
+    assert_eq!(first, first.clone());
+    assert_ne!(first, second);
+    assert_eq!(Value::Str("a".into()), Value::Str("a".into()));
+}
+
+#[test]
+fn values_should_convert_to_and_from_literal_objects() {
+    assert_eq!(Ok(obj_number!(1.5)), Object::try_from(Value::Number(1.5)));
+    assert_eq!(
+        Ok(Value::Str("a".into())),
+        Value::try_from(obj_str!("a".to_string()))
+    );
+    assert!(Object::try_from(Value::Class(Rc::new(Class::new("A")))).is_err());
+}
+
+#[test]
+fn values_should_print_like_objects() {
+    let class = Rc::new(Class::new("0"));
+
+    assert_eq!("1.5", Value::Number(1.5).to_string());
+    assert_eq!("nil", Value::Nil.to_string());
+    assert_eq!(
+        "Instance(Class 0)",
+        Value::Instance(Rc::new(Instance::new(class))).to_string()
+    );
+}
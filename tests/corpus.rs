@@ -0,0 +1,129 @@
+//! Runs each program in the corpus with both the tree-walking interpreter and
+//! the bytecode virtual machine, checking their output against the
+//! expectations embedded in the program's comments.
+//!
+//! A `// expect: <line>` comment expects a line of output, in order, while a
+//! `// expect runtime error: <message>` comment expects the program to fail
+//! with the message after printing its expected output. Each
+//! `// expect trace: <function>:<line>` comment expects a frame of the
+//! error's backtrace, from the innermost frame out.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const EXPECT_OUTPUT: &str = "// expect: ";
+const EXPECT_ERROR: &str = "// expect runtime error: ";
+const EXPECT_TRACE: &str = "// expect trace: ";
+
+struct Expectation {
+    output: Vec<String>,
+    error: Option<String>,
+    trace: Vec<String>,
+}
+
+impl Expectation {
+    fn parse(source: &str) -> Self {
+        let mut output = Vec::new();
+        let mut error = None;
+        let mut trace = Vec::new();
+        for line in source.lines() {
+            if let Some(i) = line.find(EXPECT_OUTPUT) {
+                output.push(line[i + EXPECT_OUTPUT.len()..].to_string());
+            } else if let Some(i) = line.find(EXPECT_ERROR) {
+                error = Some(line[i + EXPECT_ERROR.len()..].to_string());
+            } else if let Some(i) = line.find(EXPECT_TRACE) {
+                trace.push(line[i + EXPECT_TRACE.len()..].to_string());
+            }
+        }
+
+        Expectation {
+            output,
+            error,
+            trace,
+        }
+    }
+}
+
+/// backtrace extracts each `at <function> (<file>:<line>)` frame reported on
+/// stderr as `<function>:<line>`.
+fn backtrace(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("at "))
+        .filter_map(|frame| {
+            let (name, location) = frame.split_once(" (")?;
+            let line = location.trim_end_matches(')').rsplit(':').next()?;
+            Some(format!("{}:{}", name, line))
+        })
+        .collect()
+}
+
+fn corpus() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut programs: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lox"))
+        .collect();
+    programs.sort();
+    programs
+}
+
+fn check(program: &Path, flags: &[&str]) -> Result<(), String> {
+    let expected = Expectation::parse(&fs::read_to_string(program).unwrap());
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(flags)
+        .arg(program)
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<String> = stdout.lines().map(String::from).collect();
+    if lines != expected.output {
+        return Err(format!(
+            "expected output {:?}, got {:?}",
+            expected.output, lines
+        ));
+    }
+
+    let trace = backtrace(&stderr);
+    if trace != expected.trace {
+        return Err(format!(
+            "expected backtrace {:?}, got {:?}",
+            expected.trace, trace
+        ));
+    }
+
+    match (expected.error, output.status.success()) {
+        (None, true) => Ok(()),
+        (None, false) => Err(format!("unexpected error: {}", stderr)),
+        (Some(_), true) => Err("expected a runtime error".to_string()),
+        (Some(message), false) if stderr.contains(&message) => Ok(()),
+        (Some(message), false) => Err(format!("expected error {:?}, got {}", message, stderr)),
+    }
+}
+
+fn run_corpus(flags: &[&str]) {
+    let failures: Vec<String> = corpus()
+        .iter()
+        .filter_map(|program| {
+            check(program, flags)
+                .err()
+                .map(|e| format!("{}: {}", program.display(), e))
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn tree_walking_interpreter_should_pass_the_corpus() {
+    run_corpus(&[]);
+}
+
+#[test]
+fn bytecode_vm_should_pass_the_corpus() {
+    run_corpus(&["--vm"]);
+}
@@ -0,0 +1,68 @@
+//! Runs `rlox fmt` against files on disk, checking that files are rewritten
+//! in place and that `--check` reports unformatted files through its exit
+//! status without modifying them.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn scratch(name: &str, source: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("rlox-fmt-{}-{}.lox", std::process::id(), name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn fmt(args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg("fmt")
+        .args(args)
+        .output()
+        .expect("unable to run rlox fmt")
+        .status
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn check_should_fail_on_unformatted_files_without_rewriting_them() {
+    let unformatted = scratch("unformatted", "print 1+2;");
+    let formatted = scratch("formatted", "print 1 + 2;\n");
+    let path = unformatted.to_str().unwrap();
+
+    assert_eq!(0, fmt(&["--check", formatted.to_str().unwrap()]));
+    assert_eq!(1, fmt(&["--check", path]));
+    assert_eq!("print 1+2;", fs::read_to_string(&unformatted).unwrap());
+
+    assert_eq!(0, fmt(&[path]));
+    assert_eq!("print 1 + 2;\n", fs::read_to_string(&unformatted).unwrap());
+    assert_eq!(0, fmt(&["--check", path]));
+
+    fs::remove_file(unformatted).unwrap();
+    fs::remove_file(formatted).unwrap();
+}
+
+#[test]
+fn comments_within_parameter_lists_should_stay_beside_their_parameter() {
+    let commented = scratch("parameters", "fun f(a, /* p */ b) { return a; }");
+    let path = commented.to_str().unwrap();
+
+    assert_eq!(0, fmt(&[path]));
+    assert_eq!(
+        "fun f(\n    a, /* p */\n    b\n) {\n    return a;\n}\n",
+        fs::read_to_string(&commented).unwrap()
+    );
+    assert_eq!(0, fmt(&["--check", path]));
+
+    fs::remove_file(commented).unwrap();
+}
+
+#[test]
+fn files_with_syntax_errors_should_be_reported_and_left_untouched() {
+    let invalid = scratch("invalid", "print ;");
+
+    assert_eq!(65, fmt(&[invalid.to_str().unwrap()]));
+    assert_eq!("print ;", fs::read_to_string(&invalid).unwrap());
+
+    fs::remove_file(invalid).unwrap();
+}
@@ -0,0 +1,166 @@
+//! Drives `rlox lsp` over stdio with a scripted JSON-RPC client, checking the
+//! server's replies to a session of document edits and navigation requests.
+
+#![cfg(feature = "lsp")]
+
+use rlox::lsp::rpc;
+use serde_json::{json, Value};
+use std::io::BufReader;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const URI: &str = "file:///session.lox";
+
+const SOURCE: &str = "fun square(n) {
+  return n * n;
+}
+var total = square(3);
+total = total + square(4);
+print total;
+";
+
+struct Client {
+    server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_rlox"))
+            .arg("lsp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("unable to start the language server");
+        let stdin = server.stdin.take().unwrap();
+        let stdout = BufReader::new(server.stdout.take().unwrap());
+
+        Client {
+            server,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    /// request sends a request, returning the result of its response.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+
+        let response = self.receive();
+        assert_eq!(json!(id), response["id"], "response to {}", method);
+        assert_eq!(None, response.get("error"), "response to {}", method);
+        response["result"].clone()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    /// diagnostics returns the codes of the next diagnostics published.
+    fn diagnostics(&mut self) -> Vec<String> {
+        let message = self.receive();
+        assert_eq!("textDocument/publishDiagnostics", message["method"]);
+        assert_eq!(URI, message["params"]["uri"]);
+
+        message["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["code"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn send(&mut self, message: Value) {
+        rpc::write_message(&mut self.stdin, &message).unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let body = rpc::read_message(&mut self.stdout)
+            .unwrap()
+            .expect("the server closed its output");
+        serde_json::from_str(&body).unwrap()
+    }
+}
+
+fn at(line: u64, character: u64) -> Value {
+    json!({"textDocument": {"uri": URI}, "position": {"line": line, "character": character}})
+}
+
+fn range(line: u64, start: u64, end: u64) -> Value {
+    json!({"start": {"line": line, "character": start}, "end": {"line": line, "character": end}})
+}
+
+#[test]
+fn a_scripted_session_should_be_answered_from_the_analyzed_document() {
+    let mut client = Client::start();
+
+    let init = client.request("initialize", json!({"capabilities": {}}));
+    assert_eq!(json!(true), init["capabilities"]["definitionProvider"]);
+    client.notify("initialized", json!({}));
+
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {"uri": URI, "languageId": "lox", "version": 1, "text": SOURCE}}),
+    );
+    assert_eq!(Vec::<String>::new(), client.diagnostics());
+
+    // `total` on the last line.
+    let definition = client.request("textDocument/definition", at(5, 7));
+    assert_eq!(json!({"uri": URI, "range": range(3, 4, 9)}), definition);
+
+    let mut params = at(3, 5);
+    params["context"] = json!({"includeDeclaration": false});
+    let references = client.request("textDocument/references", params);
+    let lines: Vec<&Value> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| &location["range"]["start"]["line"])
+        .collect();
+    assert_eq!(vec![&json!(4), &json!(4), &json!(5)], lines);
+
+    // the `n` parameter within the body.
+    let hover = client.request("textDocument/hover", at(1, 9));
+    assert_eq!("```lox\nparam n\n```", hover["contents"]["value"]);
+    let hover = client.request("textDocument/hover", at(3, 14));
+    assert_eq!("```lox\nfun square(n)\n```", hover["contents"]["value"]);
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({"textDocument": {"uri": URI}}),
+    );
+    let names: Vec<&Value> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| &symbol["name"])
+        .collect();
+    assert_eq!(vec![&json!("square"), &json!("total")], names);
+
+    let completions = client.request("textDocument/completion", at(5, 0));
+    let labels: Vec<&str> = completions
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|item| item["label"].as_str())
+        .collect();
+    assert!(labels.contains(&"square"));
+    assert!(labels.contains(&"return"));
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": URI, "version": 2},
+            "contentChanges": [{"text": "print missing;\nvar = 1;\n"}],
+        }),
+    );
+    assert_eq!(vec!["E0101", "E0200"], client.diagnostics());
+
+    assert_eq!(Value::Null, client.request("shutdown", Value::Null));
+    client.notify("exit", Value::Null);
+    assert!(client.server.wait().unwrap().success());
+}
@@ -0,0 +1,56 @@
+//! Pipes a session into `rlox` without a script, checking that input is
+//! continued over lines, that errors leave the session running and that the
+//! session's history is saved.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SESSION: &str = "fun add(a, b) {
+  return a + b;
+}
+add(1, 2)
+;
+print missing;
+var total = add(3, 4);
+:env
+";
+
+#[test]
+fn a_piped_session_should_run_each_complete_input() {
+    let home = env::temp_dir().join(format!("rlox-repl-home-{}", std::process::id()));
+    fs::create_dir_all(&home).unwrap();
+
+    let mut repl = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("unable to start the repl");
+    repl.stdin
+        .take()
+        .unwrap()
+        .write_all(SESSION.as_bytes())
+        .unwrap();
+    let output = repl.wait_with_output().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert!(stdout.contains("3\n"), "{}", stdout);
+    assert!(stdout.contains("total = 7\n"), "{}", stdout);
+    assert!(
+        stderr.contains("undefined variable 'missing'"),
+        "{}",
+        stderr
+    );
+
+    #[cfg(feature = "repl")]
+    {
+        let history = fs::read_to_string(home.join(".rlox_history")).unwrap();
+        assert!(history.contains("var total = add(3, 4);"), "{}", history);
+    }
+    fs::remove_dir_all(home).unwrap();
+}